@@ -1,7 +1,6 @@
 extern crate gl_winit;
 extern crate image;
 extern crate polygon;
-extern crate tobj;
 extern crate winit;
 
 use gl_winit::CreateContext;
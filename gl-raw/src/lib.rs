@@ -43,6 +43,17 @@ pub fn buffer_data<T>(target: BufferTarget, data: &[T], usage: BufferUsage) {
     }
 }
 
+pub fn buffer_sub_data<T>(target: BufferTarget, offset: usize, data: &[T]) {
+    unsafe {
+        buffer_sub_data_raw(
+            target,
+            offset as isize,
+            (data.len() * mem::size_of::<T>()) as isize,
+            data.as_ptr() as *const _,
+        );
+    }
+}
+
 pub fn gen_buffer() -> Option<BufferName> {
     let mut buffer_name = BufferName::null();
     unsafe {
@@ -225,6 +236,33 @@ gl_proc!(glBindBuffer:
     ///   `gen_buffers`.
     fn bind_buffer(target: BufferTarget, buffer: BufferName));
 
+gl_proc!(glBindBufferBase:
+    /// Binds a buffer object to an indexed buffer target.
+    ///
+    /// [Wiki page](https://www.opengl.org/wiki/GLAPI/glBindBufferBase)
+    ///
+    /// Core since version 3.0
+    ///
+    /// Binds the buffer object `buffer` to the binding point at index `index` of the array of
+    /// targets specified by `target`. Each `target` represents an indexed array of buffer binding
+    /// points, as well as a single general binding point that can be used by other buffer
+    /// manipulation functions such as `bind_buffer` or `map_buffer`. In addition to binding
+    /// `buffer` to the indexed buffer binding target, `bind_buffer_base` also binds `buffer` to
+    /// the generic buffer binding point specified by `target`.
+    ///
+    /// For `BufferTarget::Uniform` the indexed binding points are the ones that uniform blocks
+    /// are associated with by calling `uniform_block_binding`.
+    ///
+    /// # Errors
+    ///
+    /// - `GL_INVALID_ENUM` is generated if `target` is not `AtomicCounter`, `TransformFeedback`,
+    ///   `Uniform` or `ShaderStorage`.
+    /// - `GL_INVALID_VALUE` is generated if `index` is greater than or equal to the number of
+    ///   target-specific indexed binding points (e.g. `GL_MAX_UNIFORM_BUFFER_BINDINGS`).
+    /// - `GL_INVALID_VALUE` is generated if `buffer` does not have an associated data store, or if
+    ///   the size of that store is zero.
+    fn bind_buffer_base(target: BufferTarget, index: u32, buffer: BufferName));
+
 gl_proc!(glBindTexture:
     /// Binds a named texture to a texturing target.
     ///
@@ -434,6 +472,38 @@ gl_proc!(glBufferData:
     ///   specified size​.
     fn buffer_data_raw(target: BufferTarget, size: isize, data: *const (), usage: BufferUsage));
 
+gl_proc!(glBufferSubData:
+    /// Updates a subset of a buffer object's data store.
+    ///
+    /// [Wiki page](https://www.opengl.org/wiki/GLAPI/glBufferSubData)
+    ///
+    /// Core since version 1.5
+    ///
+    /// Redefines some or all of the data store for the buffer object currently bound to `target`.
+    /// Data starting at byte offset `offset` and extending for `size` bytes is copied to the data
+    /// store from the memory pointed to by `data`. An error is thrown if `offset` and `size`
+    /// together define a range beyond the bounds of the buffer object's data store.
+    ///
+    /// # Notes
+    ///
+    /// - When replacing the entire data store, consider using `buffer_sub_data` rather than
+    ///   completely recreating the data store with `buffer_data`. This avoids the cost of
+    ///   reallocating the data store.
+    /// - Consider using multiple buffer objects to avoid stalling the rendering pipeline during
+    ///   data store updates. If any rendering in the pipeline makes reference to data in the
+    ///   buffer object being updated by `buffer_sub_data`, especially from the specific region
+    ///   being updated, that rendering must drain from the pipeline before the data store can be
+    ///   updated.
+    ///
+    /// # Errors
+    ///
+    /// - `GL_INVALID_VALUE` is generated if `offset` or `size` is negative, or if together they
+    ///   define a region of memory that extends beyond the buffer object's allocated data store.
+    /// - `GL_INVALID_OPERATION` is generated if the reserved buffer object name 0 is bound to
+    ///   `target`.
+    /// - `GL_INVALID_OPERATION` is generated if the buffer object being updated is mapped.
+    fn buffer_sub_data_raw(target: BufferTarget, offset: isize, size: isize, data: *const ()));
+
 gl_proc!(glClear:
     /// Clears buffers to preset values.
     ///
@@ -825,6 +895,28 @@ gl_proc!(glDrawArrays:
     ///    object.
     fn draw_arrays(mode: DrawMode, first: i32, count: i32));
 
+gl_proc!(glDrawArraysInstanced:
+    /// Draws multiple instances of a range of elements.
+    ///
+    /// [Wiki page](https://www.opengl.org/wiki/GLAPI/glDrawArraysInstanced)
+    ///
+    /// Core since version 3.1
+    ///
+    /// Behaves identically to `draw_arrays` except that `instance_count` instances of the range
+    /// of elements are executed and the value of the internal counter `instanceID` advances for
+    /// each iteration. `instanceID` is an internal 32-bit integer counter that may be read by a
+    /// vertex shader as `gl_InstanceID`.
+    ///
+    /// Vertex attributes with a non-zero divisor (see `vertex_attrib_divisor`) advance once every
+    /// `divisor` instances rather than once per vertex.
+    ///
+    /// # Errors
+    ///
+    /// - `GL_INVALID_VALUE` is generated if `count` or `instance_count` is negative.
+    /// - `GL_INVALID_OPERATION` is generated if a non-zero buffer object name is bound to an
+    ///   enabled array and the buffer object's data store is currently mapped.
+    fn draw_arrays_instanced(mode: DrawMode, first: i32, count: i32, instance_count: i32));
+
 gl_proc!(glDrawElements:
     /// Renders primitives from array data.
     ///
@@ -866,6 +958,37 @@ gl_proc!(glDrawElements:
     ///   mapped.
     fn draw_elements(mode: DrawMode, count: i32, index_type: IndexType, offset: usize));
 
+gl_proc!(glDrawElementsInstanced:
+    /// Draws multiple instances of a set of elements.
+    ///
+    /// [Wiki page](https://www.opengl.org/wiki/GLAPI/glDrawElementsInstanced)
+    ///
+    /// Core since version 3.1
+    ///
+    /// Behaves identically to `draw_elements` except that `instance_count` instances of the set
+    /// of elements are executed and the value of the internal counter `instanceID` advances for
+    /// each iteration. `instanceID` is an internal 32-bit integer counter that may be read by a
+    /// vertex shader as `gl_InstanceID`.
+    ///
+    /// Vertex attributes with a non-zero divisor (see `vertex_attrib_divisor`) advance once every
+    /// `divisor` instances rather than once per vertex.
+    ///
+    /// # Errors
+    ///
+    /// - `GL_INVALID_VALUE` is generated if `count` or `instance_count` is negative.
+    /// - `GL_INVALID_OPERATION` is generated if a geometry shader is active and `mode` is
+    ///   incompatible with the input primitive type of the geometry shader in the currently
+    ///   installed program object.
+    /// - `GL_INVALID_OPERATION` is generated if a non-zero buffer object name is bound to an
+    ///   enabled array or the element array and the buffer object's data store is currently
+    ///   mapped.
+    fn draw_elements_instanced(
+        mode: DrawMode,
+        count: i32,
+        index_type: IndexType,
+        offset: usize,
+        instance_count: i32));
+
 gl_proc!(glEnable:
     /// Enables server-side GL capabilities.
     ///
@@ -1073,6 +1196,8 @@ gl_proc!(glGetIntegerv:
     ///   supported by the current context.
     /// - GL_NUM_EXTENSIONS - `params` returns one value, the number of extensions supported by
     ///   the GL implementation for the current context. See `get_string`.
+    /// - `MaxVertexAttribs` - `params` returns one value, the maximum number of 4-component
+    ///   generic vertex attributes accessible to a vertex shader. The value must be at least 16.
     fn get_integers(name: IntegerName, params: *mut i32));
 
 gl_proc!(glGetProgramInfoLog:
@@ -1882,6 +2007,56 @@ gl_proc!(glUniformMatrix3fv:
         transpose: Boolean,
         values: *const f32));
 
+gl_proc!(glUniformBlockBinding:
+    /// Assigns a binding point to an active uniform block.
+    ///
+    /// [Wiki page](https://www.opengl.org/wiki/GLAPI/glUniformBlockBinding)
+    ///
+    /// Core since version 3.1
+    ///
+    /// Binding points for active uniform blocks are assigned using `uniform_block_binding`. Each
+    /// of a program's active uniform blocks has a corresponding uniform buffer binding point.
+    /// `program` is the name of a program object for which the command `link_program` has been
+    /// issued in the past. If successful, `uniform_block_binding` specifies that `program` will
+    /// use the data store of the buffer object bound to the binding point `uniform_block_binding`
+    /// to extract the values of the uniforms in the uniform block identified by
+    /// `uniform_block_index`.
+    ///
+    /// When a program object is linked or re-linked, the uniform buffer object binding point
+    /// assigned to each of its active uniform blocks is reset to zero.
+    ///
+    /// # Errors
+    ///
+    /// - `GL_INVALID_VALUE` is generated if `uniform_block_index` is not an active uniform block
+    ///   index of `program`.
+    /// - `GL_INVALID_VALUE` is generated if `uniform_block_binding` is greater than or equal to
+    ///   the value of `GL_MAX_UNIFORM_BUFFER_BINDINGS`.
+    /// - `GL_INVALID_VALUE` is generated if `program` is not the name of a program object
+    ///   generated by the GL.
+    fn uniform_block_binding(
+        program: ProgramObject,
+        uniform_block_index: u32,
+        uniform_block_binding: u32));
+
+gl_proc!(glGetUniformBlockIndex:
+    /// Retrieves the index of a named uniform block.
+    ///
+    /// [Wiki page](https://www.opengl.org/wiki/GLAPI/glGetUniformBlockIndex)
+    ///
+    /// Core since version 3.1
+    ///
+    /// Retrieves the index of a uniform block within `program`. `uniform_block_name` must be a
+    /// null terminated string. If `uniform_block_name` does not identify an active uniform block
+    /// of `program`, `get_uniform_block_index` returns the special identifier `INVALID_INDEX`.
+    /// Indices of the active uniform blocks of a program are assigned in consecutive order,
+    /// beginning with zero.
+    ///
+    /// # Errors
+    ///
+    /// - `GL_INVALID_OPERATION` is generated if `program` is not the name of a program object for
+    ///   which `link_program` has been called in the past.
+    fn get_uniform_block_index(program: ProgramObject, uniform_block_name: *const u8) -> u32);
+
 gl_proc!(glGetUniformLocation:
     /// Returns the location of a uniform variable.
     ///
@@ -2315,6 +2490,27 @@ gl_proc!(glUseProgram:
     /// - `GL_INVALID_OPERATION` is generated if transform feedback mode is active.
     fn use_program(program: ProgramObject));
 
+gl_proc!(glVertexAttribDivisor:
+    /// Modifies the rate at which generic vertex attributes advance during instanced rendering.
+    ///
+    /// [Wiki page](https://www.opengl.org/wiki/GLAPI/glVertexAttribDivisor)
+    ///
+    /// Core since version 3.3
+    ///
+    /// Modifies the rate at which generic vertex attributes advance when rendering multiple
+    /// instances of primitives in a single draw call. If `divisor` is zero, the attribute at slot
+    /// `attrib` advances once per vertex. If `divisor` is non-zero, the attribute advances once
+    /// per `divisor` instances of the set(s) of vertices being rendered. An attribute is referred
+    /// to as instanced if its divisor value is non-zero.
+    ///
+    /// The divisor is part of the state of the currently bound vertex array object.
+    ///
+    /// # Errors
+    ///
+    /// - `GL_INVALID_VALUE` is generated if the index represented by `attrib` is greater than or
+    ///   equal to `GL_MAX_VERTEX_ATTRIBS`.
+    fn vertex_attrib_divisor(attrib: AttributeLocation, divisor: u32));
+
 gl_proc!(glVertexAttribPointer:
     /// Defines an array of generic vertex attribute data.
     ///
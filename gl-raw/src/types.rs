@@ -286,6 +286,9 @@ pub enum Integer64Name {
 #[repr(u32)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum IntegerName {
+    // Version 2.0
+    MaxVertexAttribs = 0x8869,
+
     // Version 3.0
     MajorVersion = 0x821B,
     MinorVersion = 0x821C,
@@ -686,6 +689,10 @@ impl UniformLocation {
     }
 }
 
+/// The value returned by `get_uniform_block_index` when the named block isn't an active uniform
+/// block of the program.
+pub const INVALID_INDEX: u32 = 0xFFFFFFFF;
+
 /// TODO: Use NonZero here so that Option<VertexArrayName>::None can be used instead of 0.
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -98,6 +98,16 @@ impl Context {
         unsafe { gl::clear(ClearBufferMask::Color | ClearBufferMask::Depth); }
     }
 
+    /// Gets the maximum number of vertex attribute locations available to vertex shaders.
+    ///
+    /// OpenGL guarantees that at least 16 locations are available.
+    pub fn max_vertex_attribs(&self) -> u32 {
+        let _guard = ::context::ContextGuard::new(self.raw);
+        let mut max = 0;
+        unsafe { gl::get_integers(IntegerName::MaxVertexAttribs, &mut max); }
+        max as u32
+    }
+
     pub fn swap_buffers(&self) {
         let _guard = ::context::ContextGuard::new(self.raw);
         unsafe { gl::platform::swap_buffers(self.raw); }
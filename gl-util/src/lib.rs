@@ -13,16 +13,19 @@ use gl::*;
 use shader::Program;
 use std::mem;
 use std::cell::RefCell;
+use std::ops::Range;
 use std::collections::HashMap;
 use std::rc::Rc;
 use texture::Texture2d;
 
 pub use gl::{
     AttributeLocation,
+    BufferUsage,
     Comparison,
     DestFactor,
     DrawMode,
     Face,
+    IndexType,
     PolygonMode,
     ShaderType,
     SourceFactor,
@@ -32,6 +35,7 @@ pub use gl::{
 pub mod context;
 pub mod shader;
 pub mod texture;
+pub mod uniform_buffer;
 
 /// Describes the layout of vertex data in a `VertexBuffer`.
 ///
@@ -73,13 +77,28 @@ pub struct VertexArray {
     /// Used to can determine how many vertices are in the buffer.
     elements_per_vertex: usize,
 
+    /// The usage hint given to GL for the vertex and index buffers.
+    usage: BufferUsage,
+
     context: Rc<RefCell<ContextInner>>,
 }
 
 impl VertexArray {
     /// Creates a new VAO and vertex buffer, filling the buffer with the provided data.
+    ///
+    /// The buffer is created with `BufferUsage::StaticDraw`, use `with_usage()` for vertex data
+    /// that will be updated after it's created.
     // TODO: Is this operation fallible? If so it should return a `Result<T>`.
     pub fn new(context: &Context, vertex_data: &[f32]) -> VertexArray {
+        VertexArray::with_usage(context, vertex_data, BufferUsage::StaticDraw)
+    }
+
+    /// Creates a new VAO and vertex buffer with the specified usage hint.
+    ///
+    /// `BufferUsage::DynamicDraw` should be used for data that is updated often with
+    /// `set_vertex_data()` or `update_vertex_data()`, and `BufferUsage::StreamDraw` for data that
+    /// is replaced every frame.
+    pub fn with_usage(context: &Context, vertex_data: &[f32], usage: BufferUsage) -> VertexArray {
         let context_inner = context.inner();
 
         let (vertex_buffer_name, vertex_array_name) = unsafe {
@@ -98,7 +117,7 @@ impl VertexArray {
             gl::buffer_data(
                 BufferTarget::Array,
                 vertex_data,
-                BufferUsage::StaticDraw,
+                usage,
             );
 
             (buffer_name, vertex_array)
@@ -112,35 +131,114 @@ impl VertexArray {
             vertex_primitive_len: vertex_data.len(),
             elements_per_vertex: 0,
 
+            usage: usage,
+
             context: context_inner,
         }
     }
 
     /// Creates a new VAO with the provided vertex and index data.
-    pub fn with_index_buffer(context: &Context, vertex_data: &[f32], index_data: &[u32]) -> VertexArray {
-        let mut vertex_array = VertexArray::new(context, vertex_data);
+    ///
+    /// Index data may be either `u16` or `u32`, and draws using the vertex array will use the
+    /// matching `IndexType`. 16-bit indices use half the memory, so they should be preferred
+    /// when there are few enough vertices in the buffer.
+    pub fn with_index_buffer<I: Index>(context: &Context, vertex_data: &[f32], index_data: &[I]) -> VertexArray {
+        VertexArray::with_index_buffer_and_usage(context, vertex_data, index_data, BufferUsage::StaticDraw)
+    }
+
+    /// Creates a new VAO with the provided vertex and index data, using the specified usage hint
+    /// for both buffers.
+    ///
+    /// See `with_usage()` and `with_index_buffer()` for more information.
+    pub fn with_index_buffer_and_usage<I: Index>(
+        context: &Context,
+        vertex_data: &[f32],
+        index_data: &[I],
+        usage: BufferUsage,
+    ) -> VertexArray {
+        let mut vertex_array = VertexArray::with_usage(context, vertex_data, usage);
+        vertex_array.set_index_data(index_data);
+        vertex_array
+    }
+
+    /// Gets the usage hint of the vertex array's buffers.
+    pub fn usage(&self) -> BufferUsage {
+        self.usage
+    }
+
+    /// Replaces the contents of the vertex buffer.
+    ///
+    /// The buffer gets a new data store, so the old data is orphaned rather than overwritten and
+    /// the update doesn't have to wait for earlier draws using the old data to finish. The new
+    /// data may be a different size than the old data, but attributes declared with
+    /// `set_attrib()` keep their layout, so they must still describe the new data.
+    pub fn set_vertex_data(&mut self, vertex_data: &[f32]) {
+        let context = self.context.borrow();
+        let _guard = ::context::ContextGuard::new(context.raw());
 
+        unsafe { gl::bind_buffer(BufferTarget::Array, self.vertex_buffer_name); }
+        gl::buffer_data(BufferTarget::Array, vertex_data, self.usage);
+
+        self.vertex_primitive_len = vertex_data.len();
+    }
+
+    /// Overwrites part of the vertex buffer, starting `offset` elements from the start.
+    ///
+    /// Only the updated range is uploaded, which is cheaper than `set_vertex_data()` for small
+    /// changes to large buffers. Unlike `set_vertex_data()` the existing data store is written
+    /// to, so GL may have to wait for earlier draws using the buffer to finish first.
+    ///
+    /// # Panics
+    ///
+    /// - If the range being updated extends past the end of the vertex buffer.
+    pub fn update_vertex_data(&mut self, offset: usize, vertex_data: &[f32]) {
+        assert!(
+            offset <= self.vertex_primitive_len && vertex_data.len() <= self.vertex_primitive_len - offset,
+            "Cannot update {} elements at offset {} in a vertex buffer of {} elements",
+            vertex_data.len(),
+            offset,
+            self.vertex_primitive_len,
+        );
+
+        let context = self.context.borrow();
+        let _guard = ::context::ContextGuard::new(context.raw());
+
+        unsafe { gl::bind_buffer(BufferTarget::Array, self.vertex_buffer_name); }
+        gl::buffer_sub_data(BufferTarget::Array, offset * mem::size_of::<f32>(), vertex_data);
+    }
+
+    /// Replaces the contents of the index buffer, creating the index buffer if the vertex array
+    /// doesn't have one.
+    ///
+    /// Like `set_vertex_data()` this orphans the old data store. The new indices may be a
+    /// different type than the old ones.
+    pub fn set_index_data<I: Index>(&mut self, index_data: &[I]) {
         let index_buffer_name = unsafe {
-            let context = vertex_array.context.borrow_mut();
+            let mut context = self.context.borrow_mut();
             let _guard = ::context::ContextGuard::new(context.raw());
 
-            let buffer_name = gl::gen_buffer().expect("Failed to generate buffer object");
+            // The index buffer binding is part of the VAO's state, so the VAO must be bound.
+            context.bind_vertex_array(self.vertex_array_name);
+
+            let buffer_name = match self.index_buffer {
+                Some(ref index_buffer) => index_buffer.name,
+                None => gl::gen_buffer().expect("Failed to generate buffer object"),
+            };
             gl::bind_buffer(BufferTarget::ElementArray, buffer_name);
             gl::buffer_data(
                 BufferTarget::ElementArray,
                 index_data,
-                BufferUsage::StaticDraw,
+                self.usage,
             );
 
             buffer_name
         };
 
-        vertex_array.index_buffer = Some(IndexBuffer {
+        self.index_buffer = Some(IndexBuffer {
             name: index_buffer_name,
             primitive_len: index_data.len(),
+            index_type: I::INDEX_TYPE,
         });
-
-        vertex_array
     }
 
     /// Declares a vetex attribute within the vertex buffer.
@@ -178,6 +276,78 @@ impl VertexArray {
     }
 }
 
+impl Drop for VertexArray {
+    fn drop(&mut self) {
+        let mut context = self.context.borrow_mut();
+        let _guard = ::context::ContextGuard::new(context.raw());
+
+        // Deleting a bound VAO reverts the binding to 0, so the cached binding has to be cleared
+        // before the name can be reused by a new VAO.
+        context.unbind_vertex_array(self.vertex_array_name);
+
+        unsafe {
+            gl::delete_vertex_arrays(1, &self.vertex_array_name);
+            gl::delete_buffers(1, &self.vertex_buffer_name);
+            if let Some(ref index_buffer) = self.index_buffer {
+                gl::delete_buffers(1, &index_buffer.name);
+            }
+        }
+    }
+}
+
+/// A buffer of per-instance vertex data used for instanced rendering.
+///
+/// Attributes sourced from an instance buffer advance once per instance rather than once per
+/// vertex. Instance attributes are specified for each draw with `DrawBuilder::instance_attrib()`,
+/// so the same instance buffer can be used to draw any `VertexArray`.
+#[derive(Debug)]
+pub struct InstanceBuffer {
+    buffer_name: BufferName,
+
+    context: Rc<RefCell<ContextInner>>,
+}
+
+impl InstanceBuffer {
+    /// Creates a new, empty instance buffer.
+    pub fn new(context: &Context) -> InstanceBuffer {
+        let context_inner = context.inner();
+
+        let buffer_name = {
+            let context = context_inner.borrow();
+            let _guard = ::context::ContextGuard::new(context.raw());
+
+            gl::gen_buffer().expect("Failed to create buffer object")
+        };
+
+        InstanceBuffer {
+            buffer_name: buffer_name,
+
+            context: context_inner,
+        }
+    }
+
+    /// Replaces the contents of the instance buffer.
+    ///
+    /// A new data store is created each time the data is set, so the buffer can safely be
+    /// refilled between draws without waiting for earlier draws using the old data to finish.
+    pub fn set_data(&self, instance_data: &[f32]) {
+        let context = self.context.borrow();
+        let _guard = ::context::ContextGuard::new(context.raw());
+
+        unsafe { gl::bind_buffer(BufferTarget::Array, self.buffer_name); }
+        gl::buffer_data(BufferTarget::Array, instance_data, BufferUsage::StreamDraw);
+    }
+}
+
+impl Drop for InstanceBuffer {
+    fn drop(&mut self) {
+        let context = self.context.borrow();
+        let _guard = ::context::ContextGuard::new(context.raw());
+
+        unsafe { gl::delete_buffers(1, &self.buffer_name); }
+    }
+}
+
 /// Represents a buffer of index data used to index into a `VertexBuffer` when drawing.
 #[derive(Debug, Clone, Copy)]
 struct IndexBuffer {
@@ -188,6 +358,26 @@ struct IndexBuffer {
     /// This does not reflect number of primitive shapes described by the index buffer, e.g. an
     /// index length of 3 may only describe a single triangle.
     primitive_len: usize,
+
+    /// The type of the indices in the buffer.
+    index_type: IndexType,
+}
+
+/// A primitive type that can be used as index data in an index buffer.
+///
+/// This is implemented for `u16` and `u32`, the two index types supported by all versions of
+/// OpenGL that `gl-util` targets.
+pub trait Index: Copy {
+    /// The `IndexType` that GL uses to read indices of this type.
+    const INDEX_TYPE: IndexType;
+}
+
+impl Index for u16 {
+    const INDEX_TYPE: IndexType = IndexType::UnsignedShort;
+}
+
+impl Index for u32 {
+    const INDEX_TYPE: IndexType = IndexType::UnsignedInt;
 }
 
 /// A configuration object for specifying all of the various configurable options for a draw call.
@@ -202,6 +392,10 @@ pub struct DrawBuilder<'a> {
     winding_order: WindingOrder,
     blend: (SourceFactor, DestFactor),
     uniforms: HashMap<UniformLocation, UniformValue<'a>>,
+    vertex_attribs: Vec<(AttributeLocation, AttribLayout)>,
+    instance_attribs: Vec<(AttributeLocation, &'a InstanceBuffer, AttribLayout)>,
+    instance_count: Option<usize>,
+    range: Option<Range<usize>>,
 
     context: Rc<RefCell<ContextInner>>,
 }
@@ -220,6 +414,10 @@ impl<'a> DrawBuilder<'a> {
             winding_order: WindingOrder::default(),
             blend: Default::default(),
             uniforms: HashMap::new(),
+            vertex_attribs: Vec::new(),
+            instance_attribs: Vec::new(),
+            instance_count: None,
+            range: None,
 
             context: context.inner(),
         }
@@ -264,6 +462,78 @@ impl<'a> DrawBuilder<'a> {
         self
     }
 
+    /// Declares a vertex attribute sourced from the vertex array's vertex buffer.
+    ///
+    /// Unlike attributes declared with `VertexArray::set_attrib()`, the attribute is only active
+    /// for this draw. This allows the same vertex data to be drawn with programs that read it
+    /// from different attribute locations.
+    pub fn vertex_attrib(
+        &mut self,
+        attrib_location: AttributeLocation,
+        layout: AttribLayout,
+    ) -> &mut DrawBuilder<'a> {
+        assert!(
+            layout.elements <= 4,
+            "Layout elements must not be more than 4 (was actually {})",
+            layout.elements,
+        );
+
+        self.vertex_attribs.push((attrib_location, layout));
+        self
+    }
+
+    /// Declares a per-instance vertex attribute sourced from an instance buffer.
+    ///
+    /// Instance attributes are only active for this draw, the vertex array's own attributes are
+    /// left unchanged once the draw completes.
+    pub fn instance_attrib(
+        &mut self,
+        attrib_location: AttributeLocation,
+        instance_buffer: &'a InstanceBuffer,
+        layout: AttribLayout,
+    ) -> &mut DrawBuilder<'a> {
+        assert!(
+            layout.elements <= 4,
+            "Layout elements must not be more than 4 (was actually {})",
+            layout.elements,
+        );
+
+        self.instance_attribs.push((attrib_location, instance_buffer, layout));
+        self
+    }
+
+    /// Draws `count` instances of the vertex array in a single draw call.
+    pub fn instances(&mut self, count: usize) -> &mut DrawBuilder<'a> {
+        self.instance_count = Some(count);
+        self
+    }
+
+    /// Limits the draw to a range of the vertex array's elements.
+    ///
+    /// The range is a range of indices if the vertex array has an index buffer, and a range of
+    /// vertices otherwise. By default the whole vertex array is drawn. The range can be changed
+    /// between calls to `draw()` to draw several parts of the same vertex array with the same
+    /// settings.
+    ///
+    /// # Panics
+    ///
+    /// - If the end of the range is past the end of the index buffer or vertex buffer.
+    pub fn range(&mut self, range: Range<usize>) -> &mut DrawBuilder<'a> {
+        let len = match self.vertex_array.index_buffer {
+            Some(ref indices) => indices.primitive_len,
+            None => self.vertex_array.vertex_primitive_len / self.vertex_array.elements_per_vertex,
+        };
+        assert!(
+            range.start <= range.end && range.end <= len,
+            "Draw range {:?} is out of bounds for {} elements",
+            range,
+            len,
+        );
+
+        self.range = Some(range);
+        self
+    }
+
     /// Sets the value of a uniform variable in the shader program.
     ///
     /// `uniform()` will silently ignore uniform variables that do not exist in the shader program,
@@ -334,20 +604,89 @@ impl<'a> DrawBuilder<'a> {
             // first.
             context.bind_vertex_array(self.vertex_array.vertex_array_name);
 
-            if let Some(indices) = self.vertex_array.index_buffer.as_ref() {
-                gl::draw_elements(
-                    self.draw_mode,
-                    indices.primitive_len as i32,
-                    IndexType::UnsignedInt,
-                    0,
+            // Setup the attributes declared for this draw only.
+            gl::bind_buffer(BufferTarget::Array, self.vertex_array.vertex_buffer_name);
+            for &(attrib_location, layout) in &self.vertex_attribs {
+                gl::enable_vertex_attrib_array(attrib_location);
+                gl::vertex_attrib_pointer(
+                    attrib_location,
+                    layout.elements as i32,
+                    GlType::Float,
+                    False,
+                    (layout.stride * mem::size_of::<f32>()) as i32,
+                    layout.offset * mem::size_of::<f32>(),
                 );
-            } else {
-                let vertex_len = self.vertex_array.vertex_primitive_len / self.vertex_array.elements_per_vertex;
-                gl::draw_arrays(
-                    self.draw_mode,
-                    0,
-                    vertex_len as i32,
+            }
+
+            // Setup per-instance attributes.
+            for &(attrib_location, instance_buffer, layout) in &self.instance_attribs {
+                gl::bind_buffer(BufferTarget::Array, instance_buffer.buffer_name);
+                gl::enable_vertex_attrib_array(attrib_location);
+                gl::vertex_attrib_pointer(
+                    attrib_location,
+                    layout.elements as i32,
+                    GlType::Float,
+                    False,
+                    (layout.stride * mem::size_of::<f32>()) as i32,
+                    layout.offset * mem::size_of::<f32>(),
                 );
+                gl::vertex_attrib_divisor(attrib_location, 1);
+            }
+
+            match (self.vertex_array.index_buffer.as_ref(), self.instance_count) {
+                (Some(indices), instance_count) => {
+                    let range = self.range.clone().unwrap_or(0..indices.primitive_len);
+                    let index_size = match indices.index_type {
+                        IndexType::UnsignedByte => 1,
+                        IndexType::UnsignedShort => 2,
+                        IndexType::UnsignedInt => 4,
+                    };
+
+                    // The offset into the index buffer is given in bytes.
+                    match instance_count {
+                        Some(instance_count) => gl::draw_elements_instanced(
+                            self.draw_mode,
+                            range.len() as i32,
+                            indices.index_type,
+                            range.start * index_size,
+                            instance_count as i32,
+                        ),
+                        None => gl::draw_elements(
+                            self.draw_mode,
+                            range.len() as i32,
+                            indices.index_type,
+                            range.start * index_size,
+                        ),
+                    }
+                }
+
+                (None, instance_count) => {
+                    let vertex_len = self.vertex_array.vertex_primitive_len / self.vertex_array.elements_per_vertex;
+                    let range = self.range.clone().unwrap_or(0..vertex_len);
+                    match instance_count {
+                        Some(instance_count) => gl::draw_arrays_instanced(
+                            self.draw_mode,
+                            range.start as i32,
+                            range.len() as i32,
+                            instance_count as i32,
+                        ),
+                        None => gl::draw_arrays(
+                            self.draw_mode,
+                            range.start as i32,
+                            range.len() as i32,
+                        ),
+                    }
+                }
+            }
+
+            // Disable the per-draw and instance attributes so that they don't affect later draws
+            // using the same vertex array.
+            for &(attrib_location, _) in &self.vertex_attribs {
+                gl::disable_vertex_attrib_array(attrib_location);
+            }
+            for &(attrib_location, _, _) in &self.instance_attribs {
+                gl::vertex_attrib_divisor(attrib_location, 0);
+                gl::disable_vertex_attrib_array(attrib_location);
             }
         }
     }
@@ -5,7 +5,11 @@ use std::ffi::CString;
 use std::mem;
 
 /// Represents a single shader which can be used to create a `Program`.
-#[derive(Debug, Clone)]
+///
+/// The shader object is deleted when the `Shader` is dropped. Programs keep working after the
+/// shaders they were linked from are deleted, so shaders can be dropped as soon as the program
+/// has been created.
+#[derive(Debug)]
 pub struct Shader {
     shader_object: ShaderObject,
     shader_type: ShaderType,
@@ -46,10 +50,23 @@ impl Shader {
             }),
             ShaderCompileStatus::Failure => {
                 let log = shader_log(shader_object);
+                unsafe { gl::delete_shader(shader_object); }
                 Err(ShaderError::CompileError(log))
             }
         }
     }
+
+    /// Gets the stage of the pipeline the shader is used for.
+    pub fn shader_type(&self) -> ShaderType {
+        self.shader_type
+    }
+}
+
+impl Drop for Shader {
+    fn drop(&mut self) {
+        let _guard = ::context::ContextGuard::new(self.context);
+        unsafe { gl::delete_shader(self.shader_object); }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -184,6 +201,28 @@ impl Program {
         }
     }
 
+    /// Maps a uniform block in the program to a uniform buffer binding point.
+    ///
+    /// Returns `false` if the program has no active uniform block with the specified name. As
+    /// with uniforms, it's always safe to speculatively bind blocks that the program may not use.
+    pub fn bind_uniform_block(&self, name: &str, binding: u32) -> bool {
+        let _guard = ::context::ContextGuard::new(self.context);
+
+        let mut null_terminated = String::from(name);
+        null_terminated.push('\0');
+
+        let block_index = unsafe {
+            gl::get_uniform_block_index(self.inner(), null_terminated.as_ptr())
+        };
+
+        if block_index == INVALID_INDEX {
+            return false;
+        }
+
+        unsafe { gl::uniform_block_binding(self.inner(), block_index, binding); }
+        true
+    }
+
     pub(crate) fn get_uniform_location(&self, name: &str) -> Option<UniformLocation> {
         let _guard = ::context::ContextGuard::new(self.context);
 
@@ -207,6 +246,15 @@ impl Program {
     }
 }
 
+impl Drop for Program {
+    fn drop(&mut self) {
+        // A program that's still in use is only flagged for deletion, GL deletes it once it's no
+        // longer the current program.
+        let _guard = ::context::ContextGuard::new(self.context);
+        unsafe { gl::delete_program(self.program_object); }
+    }
+}
+
 #[repr(i32)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(dead_code)]
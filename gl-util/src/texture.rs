@@ -105,6 +105,14 @@ impl Texture2d {
     }
 }
 
+impl Drop for Texture2d {
+    fn drop(&mut self) {
+        // Empty textures have the null texture object, which `delete_textures` ignores.
+        let _guard = ::context::ContextGuard::new(self.context);
+        unsafe { gl::delete_textures(1, &mut self.texture_object); }
+    }
+}
+
 pub trait TextureData {
     const DATA_TYPE: TextureDataType;
     const ELEMENTS: usize;
@@ -0,0 +1,235 @@
+//! Uniform buffer objects and helpers for writing data in the std140 layout.
+//!
+//! Uniform blocks let a group of uniforms be backed by a single buffer object, so values that
+//! are shared by many draws (camera transforms, lighting, etc.) can be uploaded once and then
+//! used by every program that declares a matching block. A block is connected to a buffer via an
+//! indexed binding point: The program maps the block to a binding point with
+//! `Program::bind_uniform_block()` and the buffer is attached to the same binding point with
+//! `UniformBuffer::bind()`.
+
+use context::Context;
+use gl;
+use gl::*;
+use GlMatrix;
+use std::mem;
+
+/// A buffer object used as the data store for uniform blocks.
+#[derive(Debug)]
+pub struct UniformBuffer {
+    buffer_name: BufferName,
+
+    /// The size in bytes of the buffer's data store.
+    len: usize,
+
+    context: ::gl::Context,
+}
+
+impl UniformBuffer {
+    /// Creates a new uniform buffer with no data store.
+    ///
+    /// Data must be set with `set_data()` before the buffer can be bound.
+    pub fn new(context: &Context) -> UniformBuffer {
+        let context = context.raw();
+        let _guard = ::context::ContextGuard::new(context);
+
+        let buffer_name = gl::gen_buffer().expect("Failed to create buffer object");
+
+        UniformBuffer {
+            buffer_name: buffer_name,
+            len: 0,
+
+            context: context,
+        }
+    }
+
+    /// Replaces the contents of the buffer with `data`.
+    ///
+    /// If `data` is the same size as the current data store the store is updated in place,
+    /// otherwise a new data store is created.
+    pub fn set_data<T>(&mut self, data: &[T]) {
+        let _guard = ::context::ContextGuard::new(self.context);
+
+        let len = data.len() * mem::size_of::<T>();
+
+        unsafe { gl::bind_buffer(BufferTarget::Uniform, self.buffer_name); }
+        if len == self.len {
+            gl::buffer_sub_data(BufferTarget::Uniform, 0, data);
+        } else {
+            gl::buffer_data(BufferTarget::Uniform, data, BufferUsage::DynamicDraw);
+            self.len = len;
+        }
+        unsafe { gl::bind_buffer(BufferTarget::Uniform, BufferName::null()); }
+    }
+
+    /// Attaches the buffer to the uniform buffer binding point at `binding`.
+    ///
+    /// Every uniform block mapped to `binding` will read its values from this buffer until
+    /// another buffer is bound to the same binding point.
+    ///
+    /// # Panics
+    ///
+    /// - If no data has been set with `set_data()`.
+    pub fn bind(&self, binding: u32) {
+        assert!(self.len > 0, "Cannot bind a uniform buffer that has no data");
+
+        let _guard = ::context::ContextGuard::new(self.context);
+        unsafe { gl::bind_buffer_base(BufferTarget::Uniform, binding, self.buffer_name); }
+    }
+}
+
+impl Drop for UniformBuffer {
+    fn drop(&mut self) {
+        let _guard = ::context::ContextGuard::new(self.context);
+        unsafe { gl::delete_buffers(1, &self.buffer_name); }
+    }
+}
+
+/// A value that can be written into a std140 uniform block.
+///
+/// Scalars are aligned to 4 bytes, 2-component vectors to 8 bytes, and 3- and 4-component
+/// vectors to 16 bytes.
+pub trait Std140: Copy {
+    /// The base alignment of the type in bytes.
+    const ALIGNMENT: usize;
+
+    /// Appends the raw 4-byte words for the value to `words`.
+    fn write_words(self, words: &mut Vec<u32>);
+}
+
+impl Std140 for f32 {
+    const ALIGNMENT: usize = 4;
+
+    fn write_words(self, words: &mut Vec<u32>) {
+        words.push(self.to_bits());
+    }
+}
+
+impl Std140 for i32 {
+    const ALIGNMENT: usize = 4;
+
+    fn write_words(self, words: &mut Vec<u32>) {
+        words.push(self as u32);
+    }
+}
+
+impl Std140 for u32 {
+    const ALIGNMENT: usize = 4;
+
+    fn write_words(self, words: &mut Vec<u32>) {
+        words.push(self);
+    }
+}
+
+impl Std140 for [f32; 2] {
+    const ALIGNMENT: usize = 8;
+
+    fn write_words(self, words: &mut Vec<u32>) {
+        words.extend(self.iter().map(|value| value.to_bits()));
+    }
+}
+
+impl Std140 for [f32; 3] {
+    const ALIGNMENT: usize = 16;
+
+    fn write_words(self, words: &mut Vec<u32>) {
+        words.extend(self.iter().map(|value| value.to_bits()));
+    }
+}
+
+impl Std140 for [f32; 4] {
+    const ALIGNMENT: usize = 16;
+
+    fn write_words(self, words: &mut Vec<u32>) {
+        words.extend(self.iter().map(|value| value.to_bits()));
+    }
+}
+
+/// Builds the contents of a uniform block following the std140 layout rules.
+///
+/// Members must be pushed in the same order they are declared in the block. Padding is inserted
+/// as needed to satisfy each member's alignment:
+///
+/// - Arrays have each element aligned to 16 bytes, regardless of the element type.
+/// - Matrices are stored as an array of column vectors, so a `mat3` takes up 48 bytes.
+/// - The block as a whole is padded out to a multiple of 16 bytes by `finish()`.
+#[derive(Debug, Clone, Default)]
+pub struct Std140Buffer {
+    words: Vec<u32>,
+}
+
+impl Std140Buffer {
+    pub fn new() -> Std140Buffer {
+        Std140Buffer {
+            words: Vec::new(),
+        }
+    }
+
+    /// Removes all data from the buffer, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.words.clear();
+    }
+
+    /// Appends a single value to the block.
+    pub fn push<T: Std140>(&mut self, value: T) -> &mut Std140Buffer {
+        self.align_to(T::ALIGNMENT);
+        value.write_words(&mut self.words);
+        self
+    }
+
+    /// Appends an array of values to the block.
+    pub fn push_array<T: Std140>(&mut self, values: &[T]) -> &mut Std140Buffer {
+        for value in values {
+            self.align_to(16);
+            value.write_words(&mut self.words);
+        }
+
+        // The member following an array is aligned to 16 bytes.
+        self.align_to(16);
+        self
+    }
+
+    /// Appends a 3x3 or 4x4 matrix to the block.
+    ///
+    /// `matrix.transpose` has the same meaning as when setting a matrix uniform: If it's `true`
+    /// the data is treated as being in row-major order and is transposed to the column-major
+    /// order expected by the block.
+    ///
+    /// # Panics
+    ///
+    /// - If the matrix data doesn't contain 9 or 16 elements.
+    pub fn push_matrix(&mut self, matrix: GlMatrix) -> &mut Std140Buffer {
+        let size = match matrix.data.len() {
+            16 => 4,
+            9 => 3,
+            _ => panic!("Unsupported matrix data length: {}", matrix.data.len()),
+        };
+
+        for col in 0..size {
+            self.align_to(16);
+            for row in 0..size {
+                let value = if matrix.transpose {
+                    matrix.data[row * size + col]
+                } else {
+                    matrix.data[col * size + row]
+                };
+                self.words.push(value.to_bits());
+            }
+        }
+
+        self.align_to(16);
+        self
+    }
+
+    /// Pads the block to its final size and returns the raw block data.
+    pub fn finish(&mut self) -> &[u32] {
+        self.align_to(16);
+        &*self.words
+    }
+
+    fn align_to(&mut self, alignment: usize) {
+        let alignment_words = alignment / mem::size_of::<u32>();
+        while self.words.len() % alignment_words != 0 {
+            self.words.push(0);
+        }
+    }
+}
@@ -99,6 +99,8 @@ impl<'a> Lexer<'a> {
 
                 let token = match word {
                     "property" => Token::Property,
+                    "instance" => Token::Instance,
+                    "attribute" => Token::Attribute,
                     "program" => Token::Program,
                     _ => Token::Identifier,
                 };
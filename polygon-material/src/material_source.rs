@@ -9,6 +9,19 @@ use std::path::Path;
 #[derive(Debug, PartialEq, Eq)]
 pub struct MaterialSource {
     pub properties: Vec<PropertySource>,
+
+    /// Properties that are set per mesh instance rather than per material.
+    ///
+    /// Instance properties are declared with the `instance` keyword and are read in shader
+    /// programs as `@instance.<name>`. Textures cannot be used as instance properties.
+    pub instance_properties: Vec<PropertySource>,
+
+    /// Custom vertex attributes read from the mesh being rendered.
+    ///
+    /// Attributes are declared with the `attribute` keyword and are read in the vertex program
+    /// as `vertex_<name>`. They're matched by name with the custom attributes of the mesh.
+    pub attributes: Vec<AttributeSource>,
+
     pub programs: Vec<ProgramSource>,
 }
 
@@ -76,6 +89,34 @@ pub enum PropertyType {
     Vector3,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AttributeSource {
+    pub name: String,
+    pub attribute_type: AttributeType,
+}
+
+/// The type of a custom vertex attribute, which can have between 1 and 4 components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(bad_style)]
+pub enum AttributeType {
+    f32,
+    Vector2,
+    Vector3,
+    Vector4,
+}
+
+impl AttributeType {
+    /// Gets the number of components in the attribute type.
+    pub fn elements(self) -> usize {
+        match self {
+            AttributeType::f32 => 1,
+            AttributeType::Vector2 => 2,
+            AttributeType::Vector3 => 3,
+            AttributeType::Vector4 => 4,
+        }
+    }
+}
+
 /// Represents an error in parsing a material source file.
 #[derive(Debug)]
 pub enum Error {
@@ -1,5 +1,5 @@
 use lexer::{Lexer, Error as TokenError};
-use material_source::{MaterialSource, ProgramSource, PropertySource, PropertyType};
+use material_source::{AttributeSource, AttributeType, MaterialSource, ProgramSource, PropertySource, PropertyType};
 use token::*;
 
 #[derive(Debug)]
@@ -18,13 +18,17 @@ impl<'a> Parser<'a> {
 
     pub fn parse(&mut self) -> Result<MaterialSource, Error> {
         let mut properties = Vec::new();
+        let mut instance_properties = Vec::new();
+        let mut attributes = Vec::new();
         let mut programs = Vec::new();
 
         loop {
             let (token, span) = self.lexer.next()?;
             match token {
                 Token::Program => programs.push(self.parse_program(span)?),
-                Token::Property => properties.push(self.parse_property(span)?),
+                Token::Property => properties.push(self.parse_property(span, true)?),
+                Token::Instance => instance_properties.push(self.parse_property(span, false)?),
+                Token::Attribute => attributes.push(self.parse_attribute(span)?),
 
                 Token::EndOfFile => break,
 
@@ -34,16 +38,21 @@ impl<'a> Parser<'a> {
 
         Ok(MaterialSource {
             properties: properties,
+            instance_properties: instance_properties,
+            attributes: attributes,
             programs: programs,
         })
     }
 
-    /// Parses a property item.
+    /// Parses a property or instance item.
+    ///
+    /// Instance items share the property syntax, but can't have texture types so
+    /// `allow_textures` should be `false` when parsing them.
     ///
     /// # Preconditions
     ///
-    /// - The "property" keyword was already pulled from the lexer.
-    fn parse_property(&mut self, _start_span: Span) -> Result<PropertySource, Error> {
+    /// - The "property" or "instance" keyword was already pulled from the lexer.
+    fn parse_property(&mut self, _start_span: Span, allow_textures: bool) -> Result<PropertySource, Error> {
         let (token, span) = self.lexer.next()?;
         let ident = match token {
             Token::Identifier => self.source[span].into(),
@@ -60,7 +69,7 @@ impl<'a> Parser<'a> {
         let property_type = match token {
             Token::Identifier => match &self.source[span] {
                 "Color" => PropertyType::Color,
-                "Texture2d" => PropertyType::Texture2d,
+                "Texture2d" if allow_textures => PropertyType::Texture2d,
                 "f32" => PropertyType::f32,
                 "Vector3" => PropertyType::Vector3,
                 _ => return Err(Error::BadPropertyType(span)),
@@ -80,6 +89,48 @@ impl<'a> Parser<'a> {
         })
     }
 
+    /// Parses an attribute item.
+    ///
+    /// # Preconditions
+    ///
+    /// - The "attribute" keyword was already pulled from the lexer.
+    fn parse_attribute(&mut self, _start_span: Span) -> Result<AttributeSource, Error> {
+        let (token, span) = self.lexer.next()?;
+        let ident = match token {
+            Token::Identifier => self.source[span].into(),
+            _ => return Err(Error::ExpectedIdent(span)),
+        };
+
+        let (token, span) = self.lexer.next()?;
+        match token {
+            Token::Colon => {},
+            _ => return Err(Error::ExpectedColon(span)),
+        }
+
+        let (token, span) = self.lexer.next()?;
+        let attribute_type = match token {
+            Token::Identifier => match &self.source[span] {
+                "f32" => AttributeType::f32,
+                "Vector2" => AttributeType::Vector2,
+                "Vector3" => AttributeType::Vector3,
+                "Vector4" => AttributeType::Vector4,
+                _ => return Err(Error::BadAttributeType(span)),
+            },
+            _ => return Err(Error::ExpectedIdent(span)),
+        };
+
+        let (token, span) = self.lexer.next()?;
+        match token {
+            Token::SemiColon => {},
+            _ => return Err(Error::ExpectedSemiColon(span)),
+        }
+
+        Ok(AttributeSource {
+            name: ident,
+            attribute_type: attribute_type,
+        })
+    }
+
     /// Parses a program item.
     ///
     /// # Preconditions
@@ -116,6 +167,7 @@ pub enum Error {
     ExpectedProgramLiteral(Span),
     ExpectedSemiColon(Span),
     BadPropertyType(Span),
+    BadAttributeType(Span),
     BadProgramType(Span),
 }
 
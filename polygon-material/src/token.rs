@@ -6,6 +6,8 @@ pub enum Token {
     /* Keywords */
     Program,
     Property,
+    Instance,
+    Attribute,
 
     /* Operator symbols */
     Eq,
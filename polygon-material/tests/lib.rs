@@ -1,7 +1,7 @@
 extern crate polygon_material as material;
 
 use material::lexer::{Error as TokenError, ErrorData, Lexer};
-use material::material_source::{PropertySource, PropertyType, ProgramSource, MaterialSource, Error as MaterialSourceError};
+use material::material_source::{AttributeSource, AttributeType, PropertySource, PropertyType, ProgramSource, MaterialSource, Error as MaterialSourceError};
 use material::parser::Error as ParseError;
 use material::token::*;
 
@@ -74,12 +74,150 @@ fn lex_properties() {
                 property_type: PropertyType::Vector3,
             }
         ],
+        instance_properties: vec![],
+        attributes: vec![],
         programs: vec![],
     });
 
     verify_lexer(SOURCE, EXPECTED_TOKENS, expected_material);
 }
 
+#[test]
+fn lex_instance_properties() {
+    static SOURCE: &'static str = r#"
+        property surface_color: Color;
+        instance tint: Color;
+        instance scale : f32 ;
+    "#;
+
+    static EXPECTED_TOKENS: &'static [Result<(Token, &'static str), (ErrorData, &'static str)>] = &[
+        Ok((Token::Property, "property")),
+        Ok((Token::Identifier, "surface_color")),
+        Ok((Token::Colon, ":")),
+        Ok((Token::Identifier, "Color")),
+        Ok((Token::SemiColon, ";")),
+
+        Ok((Token::Instance, "instance")),
+        Ok((Token::Identifier, "tint")),
+        Ok((Token::Colon, ":")),
+        Ok((Token::Identifier, "Color")),
+        Ok((Token::SemiColon, ";")),
+
+        Ok((Token::Instance, "instance")),
+        Ok((Token::Identifier, "scale")),
+        Ok((Token::Colon, ":")),
+        Ok((Token::Identifier, "f32")),
+        Ok((Token::SemiColon, ";")),
+
+        Ok((Token::EndOfFile, "")),
+    ];
+
+    let expected_material = Ok(MaterialSource {
+        properties: vec![
+            PropertySource {
+                name: "surface_color".to_string(),
+                property_type: PropertyType::Color,
+            },
+        ],
+        instance_properties: vec![
+            PropertySource {
+                name: "tint".to_string(),
+                property_type: PropertyType::Color,
+            },
+            PropertySource {
+                name: "scale".to_string(),
+                property_type: PropertyType::f32,
+            },
+        ],
+        attributes: vec![],
+        programs: vec![],
+    });
+
+    verify_lexer(SOURCE, EXPECTED_TOKENS, expected_material);
+}
+
+#[test]
+fn instance_texture_error() {
+    static SOURCE: &'static str = r#"
+        instance tex: Texture2d;
+    "#;
+
+    static EXPECTED_TOKENS: &'static [Result<(Token, &'static str), (ErrorData, &'static str)>] = &[
+        Ok((Token::Instance, "instance")),
+        Ok((Token::Identifier, "tex")),
+        Ok((Token::Colon, ":")),
+        Ok((Token::Identifier, "Texture2d")),
+        Ok((Token::SemiColon, ";")),
+        Ok((Token::EndOfFile, "")),
+    ];
+
+    let expected_material = Err(MaterialSourceError::ParseError(ParseError::BadPropertyType(Span::new(23, 32))));
+
+    verify_lexer(SOURCE, EXPECTED_TOKENS, expected_material);
+}
+
+#[test]
+fn lex_attributes() {
+    static SOURCE: &'static str = r#"
+        attribute wind: f32;
+        attribute blend_weights : Vector4 ;
+    "#;
+
+    static EXPECTED_TOKENS: &'static [Result<(Token, &'static str), (ErrorData, &'static str)>] = &[
+        Ok((Token::Attribute, "attribute")),
+        Ok((Token::Identifier, "wind")),
+        Ok((Token::Colon, ":")),
+        Ok((Token::Identifier, "f32")),
+        Ok((Token::SemiColon, ";")),
+
+        Ok((Token::Attribute, "attribute")),
+        Ok((Token::Identifier, "blend_weights")),
+        Ok((Token::Colon, ":")),
+        Ok((Token::Identifier, "Vector4")),
+        Ok((Token::SemiColon, ";")),
+
+        Ok((Token::EndOfFile, "")),
+    ];
+
+    let expected_material = Ok(MaterialSource {
+        properties: vec![],
+        instance_properties: vec![],
+        attributes: vec![
+            AttributeSource {
+                name: "wind".to_string(),
+                attribute_type: AttributeType::f32,
+            },
+            AttributeSource {
+                name: "blend_weights".to_string(),
+                attribute_type: AttributeType::Vector4,
+            },
+        ],
+        programs: vec![],
+    });
+
+    verify_lexer(SOURCE, EXPECTED_TOKENS, expected_material);
+}
+
+#[test]
+fn attribute_type_error() {
+    static SOURCE: &'static str = r#"
+        attribute tint: Color;
+    "#;
+
+    static EXPECTED_TOKENS: &'static [Result<(Token, &'static str), (ErrorData, &'static str)>] = &[
+        Ok((Token::Attribute, "attribute")),
+        Ok((Token::Identifier, "tint")),
+        Ok((Token::Colon, ":")),
+        Ok((Token::Identifier, "Color")),
+        Ok((Token::SemiColon, ";")),
+        Ok((Token::EndOfFile, "")),
+    ];
+
+    let expected_material = Err(MaterialSourceError::ParseError(ParseError::BadAttributeType(Span::new(25, 30))));
+
+    verify_lexer(SOURCE, EXPECTED_TOKENS, expected_material);
+}
+
 #[test]
 fn lex_sybmol_error() {
     static SOURCE: &'static str = r#"
@@ -127,6 +265,8 @@ fn lex_program() {
 
     let expected_material = Ok(MaterialSource {
         properties: vec![],
+        instance_properties: vec![],
+        attributes: vec![],
         programs: vec![
             ProgramSource::Vertex(" foo.bar(); ".to_string()),
             ProgramSource::Fragment("\n            fn program keyworkds do_stuff() {\n                bar.foo();\n            }\n        ".to_string()),
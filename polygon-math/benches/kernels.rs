@@ -0,0 +1,70 @@
+//! Benchmarks for the math kernels.
+//!
+//! Run with `cargo bench` for the scalar kernels and `cargo bench --features simd` for the SIMD
+//! kernels. Each benchmark reports the average time per operation.
+
+extern crate polygon_math;
+
+use polygon_math::*;
+use polygon_math::kernels::scalar;
+use std::hint::black_box;
+use std::time::Instant;
+
+const ITERATIONS: u32 = 1_000_000;
+const POINT_COUNT: usize = 10_000;
+
+fn bench<F: FnMut()>(name: &str, iterations: u32, mut f: F) {
+    // Warm up so the first measurement isn't skewed by cold caches.
+    for _ in 0..iterations / 10 {
+        f();
+    }
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    let elapsed = start.elapsed();
+
+    let nanos = elapsed.as_secs() as f64 * 1e9 + elapsed.subsec_nanos() as f64;
+    println!("{:<32} {:>10.2} ns/iter", name, nanos / iterations as f64);
+}
+
+fn main() {
+    let first = Matrix4::translation(1.0, 2.0, 3.0) * Matrix4::rotation(0.3, 0.2, 0.1);
+    let second = Matrix4::scale(2.0, 3.0, 4.0) * Matrix4::rotation(-0.5, 1.0, 0.25);
+    let point = Point::new(1.0, -2.0, 3.0);
+    let q1 = Orientation::from_eulers(0.1, 0.2, 0.3).0;
+    let q2 = Orientation::from_eulers(-0.7, 0.5, 1.3).0;
+
+    println!("simd feature enabled: {}", cfg!(feature = "simd"));
+
+    bench("scalar Matrix4 * Matrix4", ITERATIONS, || {
+        black_box(scalar::mul_matrix4(black_box(&first), black_box(&second)));
+    });
+    bench("Matrix4 * Matrix4", ITERATIONS, || {
+        black_box(black_box(first) * black_box(second));
+    });
+
+    bench("scalar Point * Matrix4", ITERATIONS, || {
+        let point = black_box(point);
+        black_box(scalar::transform(black_box(&first), [point.x, point.y, point.z, point.w]));
+    });
+    bench("Point * Matrix4", ITERATIONS, || {
+        black_box(black_box(point) * black_box(first));
+    });
+
+    let mut points = vec![point; POINT_COUNT];
+    bench("scalar transform 10k points", 1_000, || {
+        scalar::transform_points(black_box(&first), black_box(&mut points));
+    });
+    bench("Matrix4::transform_points 10k", 1_000, || {
+        black_box(&first).transform_points(black_box(&mut points));
+    });
+
+    bench("scalar Quaternion * Quaternion", ITERATIONS, || {
+        black_box(scalar::mul_quaternion(black_box(q1), black_box(q2)));
+    });
+    bench("Quaternion * Quaternion", ITERATIONS, || {
+        black_box(black_box(q1) * black_box(q2));
+    });
+}
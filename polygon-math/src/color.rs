@@ -1,4 +1,8 @@
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
 use std::slice;
+use std::str::FromStr;
+
+use Lerp;
 
 pub const RED:   Color = Color { r: 1.0, b: 0.0, g: 0.0, a: 1.0 };
 pub const WHITE: Color = Color { r: 1.0, b: 1.0, g: 1.0, a: 1.0 };
@@ -8,12 +12,15 @@ pub const BLUE:  Color = Color { r: 0.0, b: 1.0, g: 0.0, a: 1.0 };
 ///
 /// Colors have a red, green, blue, and alpha component. If alpha is not needed used
 /// `Color::rgb()` and the alpha component will default to `1.0`, effectively behaving as if there
-/// were no alpha. Color components are represented in linear color space. If non-linear color
-/// computations are needed use one of the other color types.
+/// were no alpha. Color components are represented in linear color space.
 ///
-/// TODO: Add other color types -___-;
+/// Colors coming from art tools are almost always sRGB encoded, so the hex, HSV, HSL, and 8-bit
+/// conversions all treat their input as sRGB and convert it to linear. Use `Color::from_srgb()`
+/// for sRGB values given as floats, and `to_srgb()` to convert back when a non-linear
+/// representation is needed. Alpha is always linear.
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(C)]
+#[cfg_attr(feature = "serde-impls", derive(Serialize, Deserialize))]
 pub struct Color {
     pub r: f32,
     pub g: f32,
@@ -47,6 +54,192 @@ impl Color {
         }
     }
 
+    /// Constructs a new `Color` from sRGB encoded red, green, and blue components.
+    ///
+    /// The color components are converted to linear color space, alpha is used as-is.
+    pub fn from_srgb(r: f32, g: f32, b: f32, a: f32) -> Color {
+        Color {
+            r: srgb_to_linear(r),
+            g: srgb_to_linear(g),
+            b: srgb_to_linear(b),
+            a: a,
+        }
+    }
+
+    /// Converts the color to sRGB, returning the components as `[r, g, b, a]`.
+    ///
+    /// Alpha is not affected by the conversion.
+    pub fn to_srgb(self) -> [f32; 4] {
+        [linear_to_srgb(self.r), linear_to_srgb(self.g), linear_to_srgb(self.b), self.a]
+    }
+
+    /// Constructs a new `Color` from hue, saturation, and value.
+    ///
+    /// `hue` is in degrees and wraps around, so any value is valid. `saturation` and `value` are
+    /// in the range `[0, 1]`. HSV is defined on the sRGB encoded values, so the result is converted
+    /// from sRGB to linear. The alpha of the color is `1.0`.
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Color {
+        let chroma = value * saturation;
+        let (r, g, b) = hue_to_rgb(hue, chroma);
+        let offset = value - chroma;
+        Color::from_srgb(r + offset, g + offset, b + offset, 1.0)
+    }
+
+    /// Converts the color to hue, saturation, and value, returned as `(h, s, v)`.
+    ///
+    /// Hue is in degrees in the range `[0, 360)`. The conversion is done on the sRGB encoded color
+    /// and alpha is ignored. Achromatic colors have a hue of `0.0`.
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let [r, g, b, _] = self.to_srgb();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+
+        let saturation = if max > 0.0 { chroma / max } else { 0.0 };
+        (rgb_to_hue(r, g, b, max, chroma), saturation, max)
+    }
+
+    /// Constructs a new `Color` from hue, saturation, and lightness.
+    ///
+    /// `hue` is in degrees and wraps around, so any value is valid. `saturation` and `lightness`
+    /// are in the range `[0, 1]`. HSL is defined on the sRGB encoded values, so the result is
+    /// converted from sRGB to linear. The alpha of the color is `1.0`.
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Color {
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let (r, g, b) = hue_to_rgb(hue, chroma);
+        let offset = lightness - chroma * 0.5;
+        Color::from_srgb(r + offset, g + offset, b + offset, 1.0)
+    }
+
+    /// Converts the color to hue, saturation, and lightness, returned as `(h, s, l)`.
+    ///
+    /// Hue is in degrees in the range `[0, 360)`. The conversion is done on the sRGB encoded color
+    /// and alpha is ignored. Achromatic colors have a hue of `0.0`.
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let [r, g, b, _] = self.to_srgb();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+        let lightness = (max + min) * 0.5;
+
+        let saturation = if lightness > 0.0 && lightness < 1.0 {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        } else {
+            0.0
+        };
+        (rgb_to_hue(r, g, b, max, chroma), saturation, lightness)
+    }
+
+    /// Parses an sRGB hex color string such as `"#ff8800"`.
+    ///
+    /// The leading `#` is optional. The short forms `rgb` and `rgba` and the long forms `rrggbb`
+    /// and `rrggbbaa` are all accepted. If alpha isn't specified it defaults to fully opaque.
+    pub fn from_hex(hex: &str) -> Result<Color, ParseHexError> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+
+        let mut values = [0u8; 8];
+        let mut count = 0;
+        for digit in digits.chars() {
+            if count == values.len() {
+                return Err(ParseHexError::InvalidLength(digits.chars().count()));
+            }
+
+            values[count] = digit.to_digit(16).ok_or(ParseHexError::InvalidDigit(digit))? as u8;
+            count += 1;
+        }
+
+        let bytes = match count {
+            3 | 4 => {
+                // Each digit is repeated, so `#f80` is the same as `#ff8800`.
+                let alpha = if count == 4 { values[3] * 17 } else { 255 };
+                [values[0] * 17, values[1] * 17, values[2] * 17, alpha]
+            }
+
+            6 | 8 => {
+                let alpha = if count == 8 { values[6] << 4 | values[7] } else { 255 };
+                [values[0] << 4 | values[1], values[2] << 4 | values[3], values[4] << 4 | values[5], alpha]
+            }
+
+            _ => return Err(ParseHexError::InvalidLength(count)),
+        };
+
+        Ok(Color::from_srgba8(bytes))
+    }
+
+    /// Formats the color as an sRGB hex string such as `"#ff8800"`.
+    ///
+    /// The alpha component is only included (as `"#rrggbbaa"`) if the color isn't fully opaque.
+    pub fn to_hex(self) -> String {
+        let [r, g, b, a] = self.to_srgba8();
+        if a == 255 {
+            format!("#{:02x}{:02x}{:02x}", r, g, b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+        }
+    }
+
+    /// Constructs a new `Color` from sRGB encoded 8-bit components ordered `[r, g, b, a]`.
+    pub fn from_srgba8(bytes: [u8; 4]) -> Color {
+        let [r, g, b, a] = bytes;
+        Color::from_srgb(
+            r as f32 / 255.0,
+            g as f32 / 255.0,
+            b as f32 / 255.0,
+            a as f32 / 255.0,
+        )
+    }
+
+    /// Converts the color to sRGB encoded 8-bit components ordered `[r, g, b, a]`.
+    ///
+    /// Components outside of `[0, 1]` are clamped.
+    pub fn to_srgba8(self) -> [u8; 4] {
+        let [r, g, b, a] = self.to_srgb();
+        [quantize(r), quantize(g), quantize(b), quantize(a)]
+    }
+
+    /// Constructs a new `Color` from an sRGB encoded color packed as `0xRRGGBBAA`.
+    pub fn from_packed_srgba8(packed: u32) -> Color {
+        Color::from_srgba8([
+            (packed >> 24) as u8,
+            (packed >> 16) as u8,
+            (packed >> 8) as u8,
+            packed as u8,
+        ])
+    }
+
+    /// Converts the color to sRGB and packs it as `0xRRGGBBAA`.
+    pub fn to_packed_srgba8(self) -> u32 {
+        let [r, g, b, a] = self.to_srgba8();
+        (r as u32) << 24 | (g as u32) << 16 | (b as u32) << 8 | a as u32
+    }
+
+    /// Returns the color with its red, green, and blue components multiplied by alpha.
+    pub fn premultiplied(self) -> Color {
+        Color {
+            r: self.r * self.a,
+            g: self.g * self.a,
+            b: self.b * self.a,
+            a: self.a,
+        }
+    }
+
+    /// Reverses `premultiplied()`, dividing the red, green, and blue components by alpha.
+    ///
+    /// A fully transparent color has no recoverable color information, so transparent black is
+    /// returned in that case.
+    pub fn unpremultiplied(self) -> Color {
+        if self.a == 0.0 {
+            return Color::new(0.0, 0.0, 0.0, 0.0);
+        }
+
+        Color {
+            r: self.r / self.a,
+            g: self.g / self.a,
+            b: self.b / self.a,
+            a: self.a,
+        }
+    }
+
     pub fn as_slice_of_arrays(colors: &[Color]) -> &[[f32; 4]] {
         let ptr = colors.as_ptr() as *const _;
         unsafe { slice::from_raw_parts(ptr, colors.len()) }
@@ -144,3 +337,192 @@ impl AsRef<[f32]> for Color {
         unsafe { slice::from_raw_parts(ptr, 4) }
     }
 }
+
+impl FromStr for Color {
+    type Err = ParseHexError;
+
+    fn from_str(hex: &str) -> Result<Color, ParseHexError> {
+        Color::from_hex(hex)
+    }
+}
+
+impl Lerp for Color {
+    /// Interpolates linearly between two colors.
+    ///
+    /// Interpolation is done in linear color space, which is physically correct for blending
+    /// light but doesn't look perceptually even. Convert to sRGB first for perceptual blends.
+    fn lerp(t: f32, from: Color, to: Color) -> Color {
+        from + (to - from) * t
+    }
+}
+
+impl AddAssign for Color {
+    fn add_assign(&mut self, rhs: Color) {
+        self.r += rhs.r;
+        self.g += rhs.g;
+        self.b += rhs.b;
+        self.a += rhs.a;
+    }
+}
+
+impl Add for Color {
+    type Output = Color;
+
+    fn add(mut self, rhs: Color) -> Color {
+        self += rhs;
+        self
+    }
+}
+
+impl SubAssign for Color {
+    fn sub_assign(&mut self, rhs: Color) {
+        self.r -= rhs.r;
+        self.g -= rhs.g;
+        self.b -= rhs.b;
+        self.a -= rhs.a;
+    }
+}
+
+impl Sub for Color {
+    type Output = Color;
+
+    fn sub(mut self, rhs: Color) -> Color {
+        self -= rhs;
+        self
+    }
+}
+
+/// Multiplies two colors component-wise, e.g. to tint one color by another.
+impl MulAssign for Color {
+    fn mul_assign(&mut self, rhs: Color) {
+        self.r *= rhs.r;
+        self.g *= rhs.g;
+        self.b *= rhs.b;
+        self.a *= rhs.a;
+    }
+}
+
+impl Mul for Color {
+    type Output = Color;
+
+    fn mul(mut self, rhs: Color) -> Color {
+        self *= rhs;
+        self
+    }
+}
+
+impl MulAssign<f32> for Color {
+    fn mul_assign(&mut self, rhs: f32) {
+        self.r *= rhs;
+        self.g *= rhs;
+        self.b *= rhs;
+        self.a *= rhs;
+    }
+}
+
+impl Mul<f32> for Color {
+    type Output = Color;
+
+    fn mul(mut self, rhs: f32) -> Color {
+        self *= rhs;
+        self
+    }
+}
+
+impl Mul<Color> for f32 {
+    type Output = Color;
+
+    fn mul(self, rhs: Color) -> Color {
+        rhs * self
+    }
+}
+
+impl DivAssign<f32> for Color {
+    fn div_assign(&mut self, rhs: f32) {
+        self.r /= rhs;
+        self.g /= rhs;
+        self.b /= rhs;
+        self.a /= rhs;
+    }
+}
+
+impl Div<f32> for Color {
+    type Output = Color;
+
+    fn div(mut self, rhs: f32) -> Color {
+        self /= rhs;
+        self
+    }
+}
+
+/// An error parsing a hex color string with `Color::from_hex()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseHexError {
+    /// The string didn't have 3, 4, 6, or 8 hex digits. Contains the number of digits found.
+    InvalidLength(usize),
+
+    /// The string contained a character that isn't a hex digit.
+    InvalidDigit(char),
+}
+
+/// Converts a single sRGB encoded component to linear color space.
+///
+/// This uses the exact piecewise sRGB transfer function rather than a gamma approximation.
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a single linear component to sRGB encoding.
+///
+/// This is the inverse of `srgb_to_linear()`.
+pub fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Converts a component in the range `[0, 1]` to a byte, rounding to the nearest value.
+fn quantize(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Builds the red, green, and blue components for a hue and chroma, shared by HSV and HSL.
+///
+/// The caller adds the offset needed to reach the desired value or lightness.
+fn hue_to_rgb(hue: f32, chroma: f32) -> (f32, f32, f32) {
+    let sector = (((hue % 360.0) + 360.0) % 360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+
+    match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    }
+}
+
+/// Calculates the hue in degrees of an RGB color, given its largest component and chroma.
+fn rgb_to_hue(r: f32, g: f32, b: f32, max: f32, chroma: f32) -> f32 {
+    if chroma == 0.0 {
+        return 0.0;
+    }
+
+    let hue = if max == r {
+        ((g - b) / chroma) % 6.0
+    } else if max == g {
+        (b - r) / chroma + 2.0
+    } else {
+        (r - g) / chroma + 4.0
+    };
+
+    let degrees = hue * 60.0;
+    if degrees < 0.0 { degrees + 360.0 } else { degrees }
+}
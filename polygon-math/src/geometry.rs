@@ -0,0 +1,809 @@
+//! Geometric primitives used for bounding volumes and intersection tests.
+//!
+//! All primitives live in 3D space and use the same conventions as the rest of the crate: Points
+//! are transformed by a `Matrix4` with `point * matrix`, which applies the matrix as if the point
+//! were a column vector on the right.
+//!
+//! Intersection tests that produce a hit distance (e.g. `Ray::intersect_aabb()`) return the
+//! distance along the ray as a multiple of the ray's direction vector. Since `Ray::new()`
+//! normalizes the direction this is the distance in world units unless the ray's fields were set
+//! directly.
+
+use {Dot, EPSILON, IsZero};
+use matrix::{Matrix3, Matrix4};
+use point::Point;
+use std::f32;
+use vector::Vector3;
+
+/// Gets the component-wise minimum of two points.
+fn min_point(lhs: Point, rhs: Point) -> Point {
+    Point::new(lhs.x.min(rhs.x), lhs.y.min(rhs.y), lhs.z.min(rhs.z))
+}
+
+/// Gets the component-wise maximum of two points.
+fn max_point(lhs: Point, rhs: Point) -> Point {
+    Point::new(lhs.x.max(rhs.x), lhs.y.max(rhs.y), lhs.z.max(rhs.z))
+}
+
+/// Calculates the inverse-transpose of the upper 3x3 portion of `matrix`, scaled by an
+/// arbitrary positive factor.
+///
+/// This is the matrix used to transform normals. The result isn't scaled by the determinant, so
+/// normals transformed by it need to be renormalized, but it doesn't fail for singular matrices.
+fn normal_matrix(matrix: Matrix4) -> Matrix3 {
+    let m = |row: usize, col: usize| matrix[row][col];
+
+    // The cofactor matrix is the inverse-transpose scaled by the determinant.
+    let mut cofactor = Matrix3::identity();
+    for row in 0..3 {
+        for col in 0..3 {
+            let (row_0, row_1) = ((row + 1) % 3, (row + 2) % 3);
+            let (col_0, col_1) = ((col + 1) % 3, (col + 2) % 3);
+            cofactor[row][col] = m(row_0, col_0) * m(row_1, col_1) - m(row_0, col_1) * m(row_1, col_0);
+        }
+    }
+
+    // Flip the result if the determinant is negative so that it's scaled by a positive factor.
+    let determinant = m(0, 0) * cofactor[0][0] + m(0, 1) * cofactor[0][1] + m(0, 2) * cofactor[0][2];
+    if determinant < 0.0 {
+        for row in 0..3 {
+            for col in 0..3 {
+                cofactor[row][col] = -cofactor[row][col];
+            }
+        }
+    }
+
+    cofactor
+}
+
+// AABB
+// ================================================================================================
+
+/// An axis-aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-impls", derive(Serialize, Deserialize))]
+pub struct Aabb {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Aabb {
+    /// Creates a new bounding box from its minimum and maximum corners.
+    ///
+    /// # Panics
+    ///
+    /// - If any coordinate of `min` is greater than the corresponding coordinate of `max`.
+    pub fn new(min: Point, max: Point) -> Aabb {
+        assert!(
+            min.x <= max.x && min.y <= max.y && min.z <= max.z,
+            "Min corner {:?} must not be greater than max corner {:?}",
+            min,
+            max,
+        );
+
+        Aabb {
+            min: min,
+            max: max,
+        }
+    }
+
+    /// Creates a bounding box from its center and its half-size along each axis.
+    pub fn from_center_extents(center: Point, extents: Vector3) -> Aabb {
+        Aabb::new(center - extents, center + extents)
+    }
+
+    /// Creates the smallest bounding box containing all of the points.
+    ///
+    /// Returns `None` if `points` is empty.
+    pub fn from_points(points: &[Point]) -> Option<Aabb> {
+        let (first, rest) = points.split_first()?;
+        let mut aabb = Aabb::new(*first, *first);
+        for point in rest {
+            aabb = aabb.expand_to_point(*point);
+        }
+
+        Some(aabb)
+    }
+
+    /// Gets the point at the center of the bounding box.
+    pub fn center(&self) -> Point {
+        Point::new(
+            (self.min.x + self.max.x) * 0.5,
+            (self.min.y + self.max.y) * 0.5,
+            (self.min.z + self.max.z) * 0.5,
+        )
+    }
+
+    /// Gets the half-size of the bounding box along each axis.
+    pub fn extents(&self) -> Vector3 {
+        (self.max - self.min) * 0.5
+    }
+
+    /// Gets the size of the bounding box along each axis.
+    pub fn size(&self) -> Vector3 {
+        self.max - self.min
+    }
+
+    /// Gets the eight corners of the bounding box.
+    pub fn corners(&self) -> [Point; 8] {
+        let Aabb { min, max } = *self;
+        [
+            Point::new(min.x, min.y, min.z),
+            Point::new(max.x, min.y, min.z),
+            Point::new(min.x, max.y, min.z),
+            Point::new(max.x, max.y, min.z),
+            Point::new(min.x, min.y, max.z),
+            Point::new(max.x, min.y, max.z),
+            Point::new(min.x, max.y, max.z),
+            Point::new(max.x, max.y, max.z),
+        ]
+    }
+
+    /// Creates the smallest bounding box containing both this box and `point`.
+    pub fn expand_to_point(&self, point: Point) -> Aabb {
+        Aabb {
+            min: min_point(self.min, point),
+            max: max_point(self.max, point),
+        }
+    }
+
+    /// Creates the smallest bounding box containing both bounding boxes.
+    pub fn merge(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: min_point(self.min, other.min),
+            max: max_point(self.max, other.max),
+        }
+    }
+
+    /// Finds the point inside the bounding box that is closest to `point`.
+    pub fn closest_point(&self, point: Point) -> Point {
+        min_point(max_point(point, self.min), self.max)
+    }
+
+    /// Checks if the point is inside the bounding box, including its boundary.
+    pub fn contains_point(&self, point: Point) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x
+            && point.y >= self.min.y && point.y <= self.max.y
+            && point.z >= self.min.z && point.z <= self.max.z
+    }
+
+    /// Checks if `other` is entirely inside this bounding box.
+    pub fn contains_aabb(&self, other: &Aabb) -> bool {
+        self.contains_point(other.min) && self.contains_point(other.max)
+    }
+
+    /// Checks if the two bounding boxes overlap, including if they only touch.
+    pub fn intersects_aabb(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x && self.max.x >= other.min.x
+            && self.min.y <= other.max.y && self.max.y >= other.min.y
+            && self.min.z <= other.max.z && self.max.z >= other.min.z
+    }
+
+    /// Checks if the bounding box and sphere overlap.
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.closest_point(sphere.center).distance_sqr(&sphere.center) <= sphere.radius * sphere.radius
+    }
+
+    /// Calculates the axis-aligned bounding box containing this box after it has been
+    /// transformed.
+    ///
+    /// The result is generally larger than the original box when `transform` includes a
+    /// rotation, since the rotated box has to be enclosed in a new axis-aligned box. Use
+    /// `Obb::from_aabb()` to get an exact transformed box.
+    pub fn transform(&self, transform: Matrix4) -> Aabb {
+        let center = self.center() * transform;
+        let extents = self.extents();
+
+        let mut new_extents = Vector3::ZERO;
+        for row in 0..3 {
+            new_extents[row] =
+                transform[row][0].abs() * extents.x
+              + transform[row][1].abs() * extents.y
+              + transform[row][2].abs() * extents.z;
+        }
+
+        Aabb::from_center_extents(center, new_extents)
+    }
+}
+
+// SPHERE
+// ================================================================================================
+
+/// A sphere, usually used as a bounding volume.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-impls", derive(Serialize, Deserialize))]
+pub struct Sphere {
+    pub center: Point,
+    pub radius: f32,
+}
+
+impl Sphere {
+    /// Creates a new sphere.
+    ///
+    /// # Panics
+    ///
+    /// - If `radius` is negative.
+    pub fn new(center: Point, radius: f32) -> Sphere {
+        assert!(radius >= 0.0, "Sphere radius must not be negative: {}", radius);
+
+        Sphere {
+            center: center,
+            radius: radius,
+        }
+    }
+
+    /// Creates a sphere containing all of the points.
+    ///
+    /// The sphere is centered on the center of the points' bounding box, which doesn't always
+    /// give the smallest possible sphere but is fast to calculate and usually close. Returns
+    /// `None` if `points` is empty.
+    pub fn from_points(points: &[Point]) -> Option<Sphere> {
+        let center = Aabb::from_points(points)?.center();
+        let radius_sqr = points
+            .iter()
+            .map(|point| center.distance_sqr(point))
+            .fold(0.0, f32::max);
+
+        Some(Sphere::new(center, radius_sqr.sqrt()))
+    }
+
+    /// Checks if the point is inside the sphere, including its surface.
+    pub fn contains_point(&self, point: Point) -> bool {
+        self.center.distance_sqr(&point) <= self.radius * self.radius
+    }
+
+    /// Checks if `other` is entirely inside this sphere.
+    pub fn contains_sphere(&self, other: &Sphere) -> bool {
+        self.center.distance(&other.center) + other.radius <= self.radius
+    }
+
+    /// Checks if the two spheres overlap, including if they only touch.
+    pub fn intersects_sphere(&self, other: &Sphere) -> bool {
+        let radius_sum = self.radius + other.radius;
+        self.center.distance_sqr(&other.center) <= radius_sum * radius_sum
+    }
+
+    /// Creates the smallest sphere containing both spheres.
+    pub fn merge(&self, other: &Sphere) -> Sphere {
+        let offset = other.center - self.center;
+        let distance = offset.magnitude();
+
+        if distance + other.radius <= self.radius {
+            return *self;
+        }
+
+        if distance + self.radius <= other.radius {
+            return *other;
+        }
+
+        // The new sphere spans from the far side of one sphere to the far side of the other.
+        let radius = (distance + self.radius + other.radius) * 0.5;
+        let center = self.center + offset * ((radius - self.radius) / distance);
+        Sphere::new(center, radius)
+    }
+
+    /// Calculates a sphere containing this sphere after it has been transformed.
+    ///
+    /// The radius is scaled by the largest scale factor in `transform`, so non-uniform scaling
+    /// produces a sphere that is larger than strictly necessary.
+    pub fn transform(&self, transform: Matrix4) -> Sphere {
+        let max_scale = [transform.x_part(), transform.y_part(), transform.z_part()]
+            .iter()
+            .map(Vector3::magnitude)
+            .fold(0.0, f32::max);
+
+        Sphere::new(self.center * transform, self.radius * max_scale)
+    }
+}
+
+// PLANE
+// ================================================================================================
+
+/// An infinite plane.
+///
+/// The plane is made up of all points `p` where `normal.dot(p) + distance == 0`. The normal
+/// points towards the front of the plane, so points in front of the plane have a positive signed
+/// distance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-impls", derive(Serialize, Deserialize))]
+pub struct Plane {
+    pub normal: Vector3,
+    pub distance: f32,
+}
+
+impl Plane {
+    /// Creates a new plane, normalizing the plane equation.
+    ///
+    /// # Panics
+    ///
+    /// - If `normal` is zero.
+    pub fn new(normal: Vector3, distance: f32) -> Plane {
+        let length = normal.magnitude();
+        assert!(!length.is_zero(), "Plane normal must not be zero");
+
+        Plane {
+            normal: normal / length,
+            distance: distance / length,
+        }
+    }
+
+    /// Creates the plane passing through `point` with the specified normal.
+    pub fn from_point_normal(point: Point, normal: Vector3) -> Plane {
+        let normal = normal.normalized();
+        Plane {
+            normal: normal,
+            distance: -normal.dot(point.as_vector3()),
+        }
+    }
+
+    /// Creates the plane passing through three points.
+    ///
+    /// The normal faces towards the side from which the points are in counter-clockwise order.
+    /// Returns `None` if the points are collinear.
+    pub fn from_points(a: Point, b: Point, c: Point) -> Option<Plane> {
+        let normal = Vector3::cross(b - a, c - a);
+        if normal.magnitude_squared() < EPSILON * EPSILON {
+            return None;
+        }
+
+        Some(Plane::from_point_normal(a, normal))
+    }
+
+    /// Calculates the signed distance from the plane to the point.
+    ///
+    /// The distance is positive if the point is in front of the plane and negative if it's
+    /// behind it.
+    pub fn signed_distance(&self, point: Point) -> f32 {
+        self.normal.dot(point.as_vector3()) + self.distance
+    }
+
+    /// Projects the point onto the plane, giving the closest point on the plane.
+    pub fn project_point(&self, point: Point) -> Point {
+        point - self.normal * self.signed_distance(point)
+    }
+
+    /// Transforms the plane by an affine transform.
+    pub fn transform(&self, transform: Matrix4) -> Plane {
+        let point = Point::origin() - self.normal * self.distance;
+        let normal = self.normal * normal_matrix(transform);
+        Plane::from_point_normal(point * transform, normal)
+    }
+}
+
+// RAY
+// ================================================================================================
+
+/// A half-infinite line starting at `origin` and extending along `direction`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-impls", derive(Serialize, Deserialize))]
+pub struct Ray {
+    pub origin: Point,
+    pub direction: Vector3,
+}
+
+impl Ray {
+    /// Creates a new ray, normalizing `direction`.
+    pub fn new(origin: Point, direction: Vector3) -> Ray {
+        Ray {
+            origin: origin,
+            direction: direction.normalized(),
+        }
+    }
+
+    /// Gets the point at `distance` along the ray.
+    pub fn at(&self, distance: f32) -> Point {
+        self.origin + self.direction * distance
+    }
+
+    /// Transforms the ray.
+    ///
+    /// The direction is renormalized after it's transformed, so distances along the
+    /// transformed ray are in the transformed space's units.
+    pub fn transform(&self, transform: Matrix4) -> Ray {
+        Ray::new(self.origin * transform, self.direction * transform)
+    }
+
+    /// Finds the distance along the ray at which it crosses the plane.
+    ///
+    /// Returns `None` if the ray is parallel to the plane or points away from it.
+    pub fn intersect_plane(&self, plane: &Plane) -> Option<f32> {
+        let denominator = plane.normal.dot(self.direction);
+        if denominator.is_zero() {
+            return None;
+        }
+
+        let distance = -plane.signed_distance(self.origin) / denominator;
+        if distance >= 0.0 {
+            Some(distance)
+        } else {
+            None
+        }
+    }
+
+    /// Finds the distance along the ray at which it enters the bounding box.
+    ///
+    /// Returns `Some(0.0)` if the ray starts inside the box, and `None` if the ray misses.
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        let mut t_min = 0.0f32;
+        let mut t_max = f32::INFINITY;
+
+        for axis in 0..3 {
+            let origin = self.origin.as_vector3()[axis];
+            let direction = self.direction[axis];
+            let (min, max) = (aabb.min.as_vector3()[axis], aabb.max.as_vector3()[axis]);
+
+            if direction.is_zero() {
+                // The ray is parallel to the slab, so it must start inside of it.
+                if origin < min || origin > max {
+                    return None;
+                }
+            } else {
+                let inverse = 1.0 / direction;
+                let t_0 = (min - origin) * inverse;
+                let t_1 = (max - origin) * inverse;
+
+                t_min = t_min.max(t_0.min(t_1));
+                t_max = t_max.min(t_0.max(t_1));
+                if t_min > t_max {
+                    return None;
+                }
+            }
+        }
+
+        Some(t_min)
+    }
+
+    /// Finds the distance along the ray at which it enters the sphere.
+    ///
+    /// Returns `Some(0.0)` if the ray starts inside the sphere, and `None` if the ray misses.
+    pub fn intersect_sphere(&self, sphere: &Sphere) -> Option<f32> {
+        let offset = self.origin - sphere.center;
+        let a = self.direction.magnitude_squared();
+        let b = offset.dot(self.direction);
+        let c = offset.magnitude_squared() - sphere.radius * sphere.radius;
+
+        if c <= 0.0 {
+            return Some(0.0);
+        }
+
+        let discriminant = b * b - a * c;
+        if b > 0.0 || discriminant < 0.0 {
+            return None;
+        }
+
+        Some((-b - discriminant.sqrt()) / a)
+    }
+
+    /// Finds the distance along the ray at which it hits the triangle.
+    ///
+    /// Both sides of the triangle can be hit. Returns `None` if the ray misses the triangle or
+    /// is parallel to it.
+    pub fn intersect_triangle(&self, triangle: &Triangle) -> Option<f32> {
+        // Möller–Trumbore intersection.
+        let edge_1 = triangle.b - triangle.a;
+        let edge_2 = triangle.c - triangle.a;
+
+        // The determinant scales with the size of the triangle, so the tolerance for treating the
+        // ray as parallel has to as well.
+        let p = Vector3::cross(self.direction, edge_2);
+        let determinant = edge_1.dot(p);
+        if determinant.abs() <= EPSILON * edge_1.magnitude() * edge_2.magnitude() {
+            return None;
+        }
+
+        let inverse_determinant = 1.0 / determinant;
+        let offset = self.origin - triangle.a;
+
+        let u = offset.dot(p) * inverse_determinant;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let q = Vector3::cross(offset, edge_1);
+        let v = self.direction.dot(q) * inverse_determinant;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let distance = edge_2.dot(q) * inverse_determinant;
+        if distance >= 0.0 {
+            Some(distance)
+        } else {
+            None
+        }
+    }
+}
+
+// TRIANGLE
+// ================================================================================================
+
+/// A triangle defined by its three corners.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-impls", derive(Serialize, Deserialize))]
+pub struct Triangle {
+    pub a: Point,
+    pub b: Point,
+    pub c: Point,
+}
+
+impl Triangle {
+    pub fn new(a: Point, b: Point, c: Point) -> Triangle {
+        Triangle {
+            a: a,
+            b: b,
+            c: c,
+        }
+    }
+
+    /// Calculates the unit normal of the triangle.
+    ///
+    /// The normal faces towards the side from which the corners are in counter-clockwise order.
+    /// The result isn't meaningful if the triangle is degenerate.
+    pub fn normal(&self) -> Vector3 {
+        Vector3::cross(self.b - self.a, self.c - self.a).normalized()
+    }
+
+    /// Calculates the area of the triangle.
+    pub fn area(&self) -> f32 {
+        Vector3::cross(self.b - self.a, self.c - self.a).magnitude() * 0.5
+    }
+
+    /// Gets the average of the triangle's corners.
+    pub fn centroid(&self) -> Point {
+        Point::new(
+            (self.a.x + self.b.x + self.c.x) / 3.0,
+            (self.a.y + self.b.y + self.c.y) / 3.0,
+            (self.a.z + self.b.z + self.c.z) / 3.0,
+        )
+    }
+
+    /// Gets the plane containing the triangle, or `None` if the triangle is degenerate.
+    pub fn plane(&self) -> Option<Plane> {
+        Plane::from_points(self.a, self.b, self.c)
+    }
+
+    /// Checks if the triangle has (nearly) zero area.
+    pub fn is_degenerate(&self) -> bool {
+        self.plane().is_none()
+    }
+
+    /// Calculates the barycentric coordinates `(u, v, w)` of `point` projected onto the
+    /// triangle's plane, such that the projected point is `a * u + b * v + c * w`.
+    ///
+    /// The projected point is inside the triangle when all three coordinates are in `[0, 1]`.
+    /// Returns `None` if the triangle is degenerate.
+    pub fn barycentric(&self, point: Point) -> Option<(f32, f32, f32)> {
+        let edge_0 = self.b - self.a;
+        let edge_1 = self.c - self.a;
+        let offset = point - self.a;
+
+        let d00 = edge_0.dot(edge_0);
+        let d01 = edge_0.dot(edge_1);
+        let d11 = edge_1.dot(edge_1);
+        let d20 = offset.dot(edge_0);
+        let d21 = offset.dot(edge_1);
+
+        let denominator = d00 * d11 - d01 * d01;
+        if denominator.abs() < EPSILON * EPSILON {
+            return None;
+        }
+
+        let v = (d11 * d20 - d01 * d21) / denominator;
+        let w = (d00 * d21 - d01 * d20) / denominator;
+        Some((1.0 - v - w, v, w))
+    }
+
+    /// Gets the bounding box of the triangle.
+    pub fn aabb(&self) -> Aabb {
+        Aabb::new(self.a, self.a)
+            .expand_to_point(self.b)
+            .expand_to_point(self.c)
+    }
+
+    /// Transforms each corner of the triangle.
+    pub fn transform(&self, transform: Matrix4) -> Triangle {
+        Triangle::new(self.a * transform, self.b * transform, self.c * transform)
+    }
+}
+
+// OBB
+// ================================================================================================
+
+/// An oriented bounding box.
+///
+/// The box is centered on `center` and extends `extents[i]` units in either direction along
+/// `axes[i]`. The axes are unit length and perpendicular to each other.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-impls", derive(Serialize, Deserialize))]
+pub struct Obb {
+    pub center: Point,
+    pub axes: [Vector3; 3],
+    pub extents: Vector3,
+}
+
+impl Obb {
+    /// Creates an oriented box matching an axis-aligned bounding box.
+    pub fn from_aabb(aabb: &Aabb) -> Obb {
+        Obb {
+            center: aabb.center(),
+            axes: [Vector3::RIGHT, Vector3::UP, Vector3::BACK],
+            extents: aabb.extents(),
+        }
+    }
+
+    /// Gets the eight corners of the box.
+    pub fn corners(&self) -> [Point; 8] {
+        let mut corners = [self.center; 8];
+        for (index, corner) in corners.iter_mut().enumerate() {
+            for axis in 0..3 {
+                let sign = if index & (1 << axis) == 0 { -1.0 } else { 1.0 };
+                *corner += self.axes[axis] * (self.extents[axis] * sign);
+            }
+        }
+
+        corners
+    }
+
+    /// Gets the axis-aligned bounding box containing the oriented box.
+    pub fn aabb(&self) -> Aabb {
+        let mut extents = Vector3::ZERO;
+        for axis in 0..3 {
+            for world_axis in 0..3 {
+                extents[world_axis] += self.axes[axis][world_axis].abs() * self.extents[axis];
+            }
+        }
+
+        Aabb::from_center_extents(self.center, extents)
+    }
+
+    /// Checks if the point is inside the box, including its boundary.
+    pub fn contains_point(&self, point: Point) -> bool {
+        let offset = point - self.center;
+        (0..3).all(|axis| offset.dot(self.axes[axis]).abs() <= self.extents[axis] + EPSILON)
+    }
+
+    /// Checks if the two boxes overlap using the separating axis test.
+    pub fn intersects_obb(&self, other: &Obb) -> bool {
+        let offset = other.center - self.center;
+
+        // Project both boxes onto the axis and check if the projections are separated.
+        let is_separated = |axis: Vector3| {
+            if axis.magnitude_squared() < EPSILON {
+                // The cross product of two (nearly) parallel axes isn't a valid separating axis.
+                return false;
+            }
+
+            let self_radius: f32 = (0..3).map(|index| self.axes[index].dot(axis).abs() * self.extents[index]).sum();
+            let other_radius: f32 = (0..3).map(|index| other.axes[index].dot(axis).abs() * other.extents[index]).sum();
+            offset.dot(axis).abs() > self_radius + other_radius
+        };
+
+        for index in 0..3 {
+            if is_separated(self.axes[index]) || is_separated(other.axes[index]) {
+                return false;
+            }
+        }
+
+        for self_axis in &self.axes {
+            for other_axis in &other.axes {
+                if is_separated(Vector3::cross(*self_axis, *other_axis)) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Checks if the oriented box overlaps an axis-aligned bounding box.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.intersects_obb(&Obb::from_aabb(aabb))
+    }
+
+    /// Transforms the box by an affine transform.
+    ///
+    /// Any scaling in `transform` is applied to the extents. Shearing transforms can't be
+    /// represented exactly by an oriented box, so the result only approximates them.
+    pub fn transform(&self, transform: Matrix4) -> Obb {
+        let mut axes = self.axes;
+        let mut extents = self.extents;
+        for axis in 0..3 {
+            let transformed = self.axes[axis] * transform;
+            let scale = transformed.magnitude();
+
+            axes[axis] = transformed / scale;
+            extents[axis] *= scale;
+        }
+
+        Obb {
+            center: self.center * transform,
+            axes: axes,
+            extents: extents,
+        }
+    }
+}
+
+// FRUSTUM
+// ================================================================================================
+
+/// The volume of space visible to a camera, represented as six inward-facing planes.
+///
+/// Points inside the frustum are in front of all six planes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-impls", derive(Serialize, Deserialize))]
+pub struct Frustum {
+    /// The planes of the frustum, ordered left, right, bottom, top, near, far.
+    pub planes: [Plane; 6],
+}
+
+impl Frustum {
+    /// Extracts the frustum planes from a combined view-projection matrix.
+    ///
+    /// The matrix is expected to map the visible volume to OpenGL's clip space, where visible
+    /// points have `-w <= x, y, z <= w`. The resulting planes are in world space; passing in just
+    /// a projection matrix gives the frustum in view space instead.
+    pub fn from_matrix(view_projection: Matrix4) -> Frustum {
+        let plane = |row: usize, sign: f32| {
+            let last = view_projection[3];
+            let other = view_projection[row];
+            Plane::new(
+                Vector3::new(
+                    last[0] + other[0] * sign,
+                    last[1] + other[1] * sign,
+                    last[2] + other[2] * sign,
+                ),
+                last[3] + other[3] * sign,
+            )
+        };
+
+        Frustum {
+            planes: [
+                plane(0, 1.0),
+                plane(0, -1.0),
+                plane(1, 1.0),
+                plane(1, -1.0),
+                plane(2, 1.0),
+                plane(2, -1.0),
+            ],
+        }
+    }
+
+    /// Checks if the point is inside the frustum.
+    pub fn contains_point(&self, point: Point) -> bool {
+        self.planes.iter().all(|plane| plane.signed_distance(point) >= 0.0)
+    }
+
+    /// Checks if the sphere is entirely inside the frustum.
+    pub fn contains_sphere(&self, sphere: &Sphere) -> bool {
+        self.planes.iter().all(|plane| plane.signed_distance(sphere.center) >= sphere.radius)
+    }
+
+    /// Checks if the bounding box is entirely inside the frustum.
+    pub fn contains_aabb(&self, aabb: &Aabb) -> bool {
+        aabb.corners().iter().all(|&corner| self.contains_point(corner))
+    }
+
+    /// Checks if any part of the sphere may be inside the frustum.
+    ///
+    /// This test is conservative: Spheres near the corners of the frustum may be reported as
+    /// intersecting even though they're just outside of it.
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.planes.iter().all(|plane| plane.signed_distance(sphere.center) >= -sphere.radius)
+    }
+
+    /// Checks if any part of the bounding box may be inside the frustum.
+    ///
+    /// This test is conservative: Boxes near the corners of the frustum may be reported as
+    /// intersecting even though they're just outside of it.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        let center = aabb.center();
+        let extents = aabb.extents();
+        self.planes.iter().all(|plane| {
+            let radius =
+                plane.normal.x.abs() * extents.x
+              + plane.normal.y.abs() * extents.y
+              + plane.normal.z.abs() * extents.z;
+            plane.signed_distance(center) >= -radius
+        })
+    }
+}
@@ -0,0 +1,22 @@
+//! Low-level kernels for the hot paths in matrix and quaternion math.
+//!
+//! The operator implementations on `Matrix4`, `Point`, and `Quaternion` forward to the kernels
+//! re-exported from this module. By default these are the portable implementations in
+//! [`scalar`]. When the `simd` feature is enabled and the target is x86_64 the SSE2
+//! implementations in `sse2` are used instead. SSE2 is part of the x86_64 baseline, so no runtime
+//! feature detection is needed.
+//!
+//! Both sets of kernels are public so that they can be tested and benchmarked against each other.
+//! The results agree to within floating point rounding, but are not guaranteed to be
+//! bit-identical since the SIMD versions sum their products in a different order.
+
+pub mod scalar;
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+pub mod sse2;
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+pub use self::sse2::{mul_matrix4, mul_quaternion, transform, transform_points};
+
+#[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+pub use self::scalar::{mul_matrix4, mul_quaternion, transform, transform_points};
@@ -0,0 +1,55 @@
+//! Portable implementations of the math kernels.
+
+use matrix::Matrix4;
+use point::Point;
+use quaternion::Quaternion;
+use vector::Vector3;
+use Dot;
+
+/// Multiplies two matrices, returning `first * second`.
+#[inline]
+pub fn mul_matrix4(first: &Matrix4, second: &Matrix4) -> Matrix4 {
+    let mut result = Matrix4::new();
+
+    for row in 0..4 {
+        for col in 0..4 {
+            result[row][col] =
+                (first[row][0] * second[0][col]) +
+                (first[row][1] * second[1][col]) +
+                (first[row][2] * second[2][col]) +
+                (first[row][3] * second[3][col]);
+        }
+    }
+
+    result
+}
+
+/// Transforms a 4-component column vector by the matrix, returning `matrix * vector`.
+#[inline]
+pub fn transform(matrix: &Matrix4, vector: [f32; 4]) -> [f32; 4] {
+    let [x, y, z, w] = vector;
+    let mut result = [0.0; 4];
+    for (row, value) in result.iter_mut().enumerate() {
+        *value = matrix[row][0] * x + matrix[row][1] * y + matrix[row][2] * z + matrix[row][3] * w;
+    }
+
+    result
+}
+
+/// Transforms each point in `points` by the matrix in place.
+#[inline]
+pub fn transform_points(matrix: &Matrix4, points: &mut [Point]) {
+    for point in points {
+        let [x, y, z, w] = transform(matrix, [point.x, point.y, point.z, point.w]);
+        *point = Point { x: x, y: y, z: z, w: w };
+    }
+}
+
+/// Calculates the Hamilton product of two quaternions.
+#[inline]
+pub fn mul_quaternion(first: Quaternion, second: Quaternion) -> Quaternion {
+    Quaternion {
+        v: Vector3::cross(first.v, second.v) + second.w * first.v + first.w * second.v,
+        w: first.w * second.w - Vector3::dot(first.v, second.v),
+    }
+}
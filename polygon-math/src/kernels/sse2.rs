@@ -0,0 +1,160 @@
+//! SSE2 implementations of the math kernels.
+//!
+//! Only compiled when the `simd` feature is enabled on x86_64.
+
+use std::arch::x86_64::*;
+
+use matrix::Matrix4;
+use point::Point;
+use quaternion::Quaternion;
+use vector::Vector3;
+
+/// Multiplies two matrices, returning `first * second`.
+///
+/// Each row of the result is a linear combination of the rows of `second`, weighted by the
+/// elements of the matching row of `first`.
+#[inline]
+pub fn mul_matrix4(first: &Matrix4, second: &Matrix4) -> Matrix4 {
+    let mut result = Matrix4::new();
+
+    unsafe {
+        let rows = [
+            _mm_loadu_ps(second[0].as_ptr()),
+            _mm_loadu_ps(second[1].as_ptr()),
+            _mm_loadu_ps(second[2].as_ptr()),
+            _mm_loadu_ps(second[3].as_ptr()),
+        ];
+
+        for row in 0..4 {
+            let weights = &first[row];
+            let mut sum = _mm_mul_ps(_mm_set1_ps(weights[0]), rows[0]);
+            sum = _mm_add_ps(sum, _mm_mul_ps(_mm_set1_ps(weights[1]), rows[1]));
+            sum = _mm_add_ps(sum, _mm_mul_ps(_mm_set1_ps(weights[2]), rows[2]));
+            sum = _mm_add_ps(sum, _mm_mul_ps(_mm_set1_ps(weights[3]), rows[3]));
+            _mm_storeu_ps(result[row].as_mut_ptr(), sum);
+        }
+    }
+
+    result
+}
+
+/// Transforms a 4-component column vector by the matrix, returning `matrix * vector`.
+#[inline]
+pub fn transform(matrix: &Matrix4, vector: [f32; 4]) -> [f32; 4] {
+    let mut result = [0.0; 4];
+
+    unsafe {
+        let vector = _mm_loadu_ps(vector.as_ptr());
+
+        // Multiply each row by the vector, then transpose the products so that summing the
+        // columns gives the dot product of each row with the vector.
+        let (first, second, third, fourth) = transpose(
+            _mm_mul_ps(_mm_loadu_ps(matrix[0].as_ptr()), vector),
+            _mm_mul_ps(_mm_loadu_ps(matrix[1].as_ptr()), vector),
+            _mm_mul_ps(_mm_loadu_ps(matrix[2].as_ptr()), vector),
+            _mm_mul_ps(_mm_loadu_ps(matrix[3].as_ptr()), vector),
+        );
+        let sum = _mm_add_ps(_mm_add_ps(first, second), _mm_add_ps(third, fourth));
+        _mm_storeu_ps(result.as_mut_ptr(), sum);
+    }
+
+    result
+}
+
+/// Transforms each point in `points` by the matrix in place.
+///
+/// The matrix is transposed once up front, after which each point only needs four multiplies
+/// and three adds.
+#[inline]
+pub fn transform_points(matrix: &Matrix4, points: &mut [Point]) {
+    unsafe {
+        let (x_column, y_column, z_column, w_column) = transpose(
+            _mm_loadu_ps(matrix[0].as_ptr()),
+            _mm_loadu_ps(matrix[1].as_ptr()),
+            _mm_loadu_ps(matrix[2].as_ptr()),
+            _mm_loadu_ps(matrix[3].as_ptr()),
+        );
+
+        for point in points {
+            // `Point` is `#[repr(C)]` with four `f32` fields, so it can be loaded directly.
+            let ptr = point as *mut Point as *mut f32;
+            let value = _mm_loadu_ps(ptr);
+
+            let x = _mm_shuffle_ps::<{ shuffle(0, 0, 0, 0) }>(value, value);
+            let y = _mm_shuffle_ps::<{ shuffle(1, 1, 1, 1) }>(value, value);
+            let z = _mm_shuffle_ps::<{ shuffle(2, 2, 2, 2) }>(value, value);
+            let w = _mm_shuffle_ps::<{ shuffle(3, 3, 3, 3) }>(value, value);
+
+            let xy = _mm_add_ps(_mm_mul_ps(x_column, x), _mm_mul_ps(y_column, y));
+            let zw = _mm_add_ps(_mm_mul_ps(z_column, z), _mm_mul_ps(w_column, w));
+            _mm_storeu_ps(ptr, _mm_add_ps(xy, zw));
+        }
+    }
+}
+
+/// Calculates the Hamilton product of two quaternions.
+#[inline]
+pub fn mul_quaternion(first: Quaternion, second: Quaternion) -> Quaternion {
+    let mut result = [0.0; 4];
+
+    unsafe {
+        // Lanes are ordered `(x, y, z, w)`.
+        let a = _mm_set_ps(first.w, first.v.z, first.v.y, first.v.x);
+        let b = _mm_set_ps(second.w, second.v.z, second.v.y, second.v.x);
+
+        // Flips the sign of the `w` lane.
+        let negate_w = _mm_set_ps(-0.0, 0.0, 0.0, 0.0);
+
+        // (w1 x2, w1 y2, w1 z2, w1 w2)
+        let a_w = _mm_shuffle_ps::<{ shuffle(3, 3, 3, 3) }>(a, a);
+        let mut sum = _mm_mul_ps(a_w, b);
+
+        // (x1 w2, y1 w2, z1 w2, -x1 x2)
+        let a_xyzx = _mm_shuffle_ps::<{ shuffle(0, 1, 2, 0) }>(a, a);
+        let b_wwwx = _mm_shuffle_ps::<{ shuffle(3, 3, 3, 0) }>(b, b);
+        sum = _mm_add_ps(sum, _mm_xor_ps(_mm_mul_ps(a_xyzx, b_wwwx), negate_w));
+
+        // (y1 z2, z1 x2, x1 y2, -y1 y2)
+        let a_yzxy = _mm_shuffle_ps::<{ shuffle(1, 2, 0, 1) }>(a, a);
+        let b_zxyy = _mm_shuffle_ps::<{ shuffle(2, 0, 1, 1) }>(b, b);
+        sum = _mm_add_ps(sum, _mm_xor_ps(_mm_mul_ps(a_yzxy, b_zxyy), negate_w));
+
+        // (z1 y2, x1 z2, y1 x2, z1 z2), subtracted.
+        let a_zxyz = _mm_shuffle_ps::<{ shuffle(2, 0, 1, 2) }>(a, a);
+        let b_yzxz = _mm_shuffle_ps::<{ shuffle(1, 2, 0, 2) }>(b, b);
+        sum = _mm_sub_ps(sum, _mm_mul_ps(a_zxyz, b_yzxz));
+
+        _mm_storeu_ps(result.as_mut_ptr(), sum);
+    }
+
+    let [x, y, z, w] = result;
+    Quaternion {
+        v: Vector3::new(x, y, z),
+        w: w,
+    }
+}
+
+/// Builds the immediate for `_mm_shuffle_ps()` that selects the given lane for each output lane.
+///
+/// The lanes are listed in output order, so `shuffle(2, 0, 1, 3)` produces `(z, x, y, w)` when
+/// both inputs are the same vector. The first two lanes come from the first argument to
+/// `_mm_shuffle_ps()` and the last two from the second.
+const fn shuffle(first: i32, second: i32, third: i32, fourth: i32) -> i32 {
+    first | second << 2 | third << 4 | fourth << 6
+}
+
+/// Transposes four rows into four columns.
+#[inline(always)]
+unsafe fn transpose(first: __m128, second: __m128, third: __m128, fourth: __m128) -> (__m128, __m128, __m128, __m128) {
+    let low_01 = _mm_unpacklo_ps(first, second);
+    let low_23 = _mm_unpacklo_ps(third, fourth);
+    let high_01 = _mm_unpackhi_ps(first, second);
+    let high_23 = _mm_unpackhi_ps(third, fourth);
+
+    (
+        _mm_movelh_ps(low_01, low_23),
+        _mm_movehl_ps(low_23, low_01),
+        _mm_movelh_ps(high_01, high_23),
+        _mm_movehl_ps(high_23, high_01),
+    )
+}
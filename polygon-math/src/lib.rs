@@ -6,19 +6,24 @@ extern crate serde;
 extern crate serde_derive;
 
 pub mod color;
+pub mod geometry;
+pub mod kernels;
 pub mod matrix;
 pub mod orientation;
 pub mod point;
 pub mod quaternion;
+pub mod transform;
 pub mod vector;
 
 pub use color::Color;
+pub use geometry::{Aabb, Frustum, Obb, Plane, Ray, Sphere, Triangle};
 pub use matrix::{Matrix3, Matrix4};
 pub use orientation::Orientation;
 pub use point::Point;
 pub use quaternion::Quaternion;
+pub use transform::Transform;
 pub use std::f32::consts::PI;
-pub use vector::{Vector2, Vector3};
+pub use vector::{IVec2, IVec3, UVec2, UVec3, Vector2, Vector3, Vector4};
 
 use std::ops::{Rem, Add};
 
@@ -2,10 +2,11 @@ use std::ops::{Index, IndexMut, Mul};
 use std::fmt::{Debug, Formatter, Error};
 use std::cmp::PartialEq;
 
-use vector::Vector3;
+use vector::{Vector3, Vector4};
 use orientation::Orientation;
 use point::Point;
 use quaternion::Quaternion;
+use kernels;
 use super::{IsZero, Dot};
 
 /// A 4x4 matrix that can be used to represent a combination of translation, rotation, and scale.
@@ -145,6 +146,218 @@ impl Matrix4 {
         // because the layout in memory is exactly the same.
         unsafe { ::std::mem::transmute(&self.0) }
     }
+
+    /// Transforms each point in `points` by the matrix in place.
+    ///
+    /// This gives the same results as `*point = *point * matrix` for each point, but is
+    /// significantly faster for large batches when the `simd` feature is enabled.
+    #[inline]
+    pub fn transform_points(&self, points: &mut [Point]) {
+        kernels::transform_points(self, points);
+    }
+
+    /// Creates a view matrix for a camera at `eye` looking towards `target`.
+    ///
+    /// The view matrix converts from world space to view space, where the camera looks down the
+    /// negative z axis (`Vector3::FORWARD`) with `up` pointing roughly along the positive y axis.
+    ///
+    /// # Panics
+    ///
+    /// - If `eye` and `target` are the same point, or if `up` is parallel to the view direction.
+    pub fn look_at(eye: Point, target: Point, up: Vector3) -> Matrix4 {
+        let forward = (target - eye).normalized();
+        let right = Vector3::cross(forward, up);
+        assert!(
+            !right.is_zero() && !forward.is_zero(),
+            "Cannot create look-at matrix with eye {:?}, target {:?}, and up {:?}",
+            eye,
+            target,
+            up,
+        );
+        let right = right.normalized();
+        let up = Vector3::cross(right, forward);
+
+        let eye = eye.as_vector3();
+        Matrix4([
+            [right.x,    right.y,    right.z,    -right.dot(eye)  ],
+            [up.x,       up.y,       up.z,       -up.dot(eye)     ],
+            [-forward.x, -forward.y, -forward.z, forward.dot(eye) ],
+            [0.0,        0.0,        0.0,        1.0              ],
+        ])
+    }
+
+    /// Creates a perspective projection matrix.
+    ///
+    /// `fov_y` is the vertical field of view in radians, and `aspect` is the ratio of the
+    /// viewport's width to its height. The matrix maps the view frustum to OpenGL's clip space,
+    /// with the near plane mapped to a depth of -1 and the far plane to 1.
+    pub fn perspective(fov_y: f32, aspect: f32, near: f32, far: f32) -> Matrix4 {
+        let focal_length = 1.0 / (fov_y * 0.5).tan();
+
+        Matrix4([
+            [focal_length / aspect, 0.0,          0.0,                          0.0                               ],
+            [0.0,                   focal_length, 0.0,                          0.0                               ],
+            [0.0,                   0.0,          -(far + near) / (far - near), -2.0 * far * near / (far - near)  ],
+            [0.0,                   0.0,          -1.0,                         0.0                               ],
+        ])
+    }
+
+    /// Creates an orthographic projection matrix.
+    ///
+    /// The box bounded by `left`, `right`, `bottom`, and `top` in x and y and spanning from
+    /// `-near` to `-far` in z is mapped to OpenGL's clip space.
+    pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Matrix4 {
+        let width = right - left;
+        let height = top - bottom;
+        let depth = far - near;
+
+        Matrix4([
+            [2.0 / width, 0.0,          0.0,          -(right + left) / width ],
+            [0.0,         2.0 / height, 0.0,          -(top + bottom) / height],
+            [0.0,         0.0,          -2.0 / depth, -(far + near) / depth   ],
+            [0.0,         0.0,          0.0,          1.0                     ],
+        ])
+    }
+
+    /// Calculates the determinant of the matrix.
+    pub fn determinant(&self) -> f32 {
+        let (sub_lower, sub_upper) = self.sub_determinants();
+
+        sub_upper[0] * sub_lower[5] - sub_upper[1] * sub_lower[4] + sub_upper[2] * sub_lower[3]
+      + sub_upper[3] * sub_lower[2] - sub_upper[4] * sub_lower[1] + sub_upper[5] * sub_lower[0]
+    }
+
+    /// Calculates the inverse of the matrix.
+    ///
+    /// Returns `None` if the matrix is singular and has no inverse. If the matrix is known to be
+    /// an affine transform (e.g. a combination of translation, rotation, and scale) then
+    /// `affine_inverse()` is faster.
+    pub fn inverse(&self) -> Option<Matrix4> {
+        let m = &self.0;
+        let (lower, upper) = self.sub_determinants();
+
+        let determinant =
+            upper[0] * lower[5] - upper[1] * lower[4] + upper[2] * lower[3]
+          + upper[3] * lower[2] - upper[4] * lower[1] + upper[5] * lower[0];
+        let inverse_determinant = 1.0 / determinant;
+        if determinant == 0.0 || !inverse_determinant.is_finite() {
+            return None;
+        }
+
+        let mut result = Matrix4([
+            [
+                m[1][1] * lower[5] - m[1][2] * lower[4] + m[1][3] * lower[3],
+                -m[0][1] * lower[5] + m[0][2] * lower[4] - m[0][3] * lower[3],
+                m[3][1] * upper[5] - m[3][2] * upper[4] + m[3][3] * upper[3],
+                -m[2][1] * upper[5] + m[2][2] * upper[4] - m[2][3] * upper[3],
+            ],
+            [
+                -m[1][0] * lower[5] + m[1][2] * lower[2] - m[1][3] * lower[1],
+                m[0][0] * lower[5] - m[0][2] * lower[2] + m[0][3] * lower[1],
+                -m[3][0] * upper[5] + m[3][2] * upper[2] - m[3][3] * upper[1],
+                m[2][0] * upper[5] - m[2][2] * upper[2] + m[2][3] * upper[1],
+            ],
+            [
+                m[1][0] * lower[4] - m[1][1] * lower[2] + m[1][3] * lower[0],
+                -m[0][0] * lower[4] + m[0][1] * lower[2] - m[0][3] * lower[0],
+                m[3][0] * upper[4] - m[3][1] * upper[2] + m[3][3] * upper[0],
+                -m[2][0] * upper[4] + m[2][1] * upper[2] - m[2][3] * upper[0],
+            ],
+            [
+                -m[1][0] * lower[3] + m[1][1] * lower[1] - m[1][2] * lower[0],
+                m[0][0] * lower[3] - m[0][1] * lower[1] + m[0][2] * lower[0],
+                -m[3][0] * upper[3] + m[3][1] * upper[1] - m[3][2] * upper[0],
+                m[2][0] * upper[3] - m[2][1] * upper[1] + m[2][2] * upper[0],
+            ],
+        ]);
+
+        for row in 0..4 {
+            for col in 0..4 {
+                result[row][col] *= inverse_determinant;
+            }
+        }
+
+        Some(result)
+    }
+
+    /// Checks if the bottom row of the matrix is `[0, 0, 0, 1]`.
+    ///
+    /// Any combination of translation, rotation, scale, and shear is affine. Projection matrices
+    /// are not.
+    pub fn is_affine(&self) -> bool {
+        self[3] == [0.0, 0.0, 0.0, 1.0]
+    }
+
+    /// Calculates the inverse of an affine transform.
+    ///
+    /// This is faster than `inverse()` since it only has to invert the upper 3x3 portion of the
+    /// matrix. Returns `None` if the matrix is singular.
+    ///
+    /// # Panics
+    ///
+    /// - In debug builds, if the matrix isn't affine (see `is_affine()`).
+    pub fn affine_inverse(&self) -> Option<Matrix4> {
+        debug_assert!(self.is_affine(), "Matrix must be affine to use affine_inverse(): {:?}", self);
+
+        let inverse_linear = Matrix3::from(*self).inverse()?;
+        let translation = -self.translation_part().as_vector3() * inverse_linear;
+
+        let mut result = Matrix4::from_matrix3(inverse_linear);
+        result[0][3] = translation.x;
+        result[1][3] = translation.y;
+        result[2][3] = translation.z;
+        Some(result)
+    }
+
+    /// Decomposes an affine transform into its translation, rotation, and scale.
+    ///
+    /// The components are such that `translation * orientation * scale` reproduces the
+    /// original matrix. If the matrix mirrors space (i.e. it has a negative determinant) the
+    /// mirroring is represented by making the x scale negative. Any shearing in the matrix is
+    /// lost. Returns `None` if the matrix has a zero scale on any axis.
+    ///
+    /// # Panics
+    ///
+    /// - In debug builds, if the matrix isn't affine (see `is_affine()`).
+    pub fn decompose(&self) -> Option<(Point, Orientation, Vector3)> {
+        debug_assert!(self.is_affine(), "Matrix must be affine to decompose it: {:?}", self);
+
+        let linear = Matrix3::from(*self);
+        let mut scale = Vector3::new(
+            linear.x_part().magnitude(),
+            linear.y_part().magnitude(),
+            linear.z_part().magnitude(),
+        );
+        if scale.x.is_zero() || scale.y.is_zero() || scale.z.is_zero() {
+            return None;
+        }
+
+        if linear.determinant() < 0.0 {
+            scale.x = -scale.x;
+        }
+
+        let rotation = linear * Matrix3::from_scale_vector(Vector3::new(1.0 / scale.x, 1.0 / scale.y, 1.0 / scale.z));
+        Some((self.translation_part(), Orientation::from(rotation), scale))
+    }
+
+    /// Calculates the determinants of the 2x2 sub-matrices formed from the lower and upper two
+    /// rows of the matrix.
+    ///
+    /// The six sub-matrices for each pair of rows are taken from column pairs (0, 1), (0, 2),
+    /// (0, 3), (1, 2), (1, 3), and (2, 3) in that order.
+    fn sub_determinants(&self) -> ([f32; 6], [f32; 6]) {
+        let m = &self.0;
+        let pairs = [(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)];
+
+        let mut lower = [0.0; 6];
+        let mut upper = [0.0; 6];
+        for (index, &(first, second)) in pairs.iter().enumerate() {
+            lower[index] = m[2][first] * m[3][second] - m[2][second] * m[3][first];
+            upper[index] = m[0][first] * m[1][second] - m[0][second] * m[1][first];
+        }
+
+        (lower, upper)
+    }
 }
 
 impl From<Matrix3> for Matrix4 {
@@ -208,56 +421,28 @@ impl IndexMut<usize> for Matrix4 {
 impl Mul<Matrix4> for Matrix4 {
     type Output = Matrix4;
 
+    #[inline]
     fn mul(self, other: Matrix4) -> Matrix4 {
-        let mut result: Matrix4 = unsafe { ::std::mem::uninitialized() };
-
-        // for row in 0..4 {
-        //     for col in 0..4 {
-        //         result[row][col] = {
-        //             let mut dot_product = 0.0;
-        //             for offset in 0..4 {
-        //                 dot_product +=
-        //                     self[row][offset] *
-        //                     other[offset][col];
-        //             }
-        //             dot_product
-        //         };
-        //     }
-        // }
-
-        result[0][0] = (self[0][0] * other[0][0]) + (self[0][1] * other[1][0]) + (self[0][2] * other[2][0]) + (self[0][3] * other[3][0]);
-        result[0][1] = (self[0][0] * other[0][1]) + (self[0][1] * other[1][1]) + (self[0][2] * other[2][1]) + (self[0][3] * other[3][1]);
-        result[0][2] = (self[0][0] * other[0][2]) + (self[0][1] * other[1][2]) + (self[0][2] * other[2][2]) + (self[0][3] * other[3][2]);
-        result[0][3] = (self[0][0] * other[0][3]) + (self[0][1] * other[1][3]) + (self[0][2] * other[2][3]) + (self[0][3] * other[3][3]);
-        result[1][0] = (self[1][0] * other[0][0]) + (self[1][1] * other[1][0]) + (self[1][2] * other[2][0]) + (self[1][3] * other[3][0]);
-        result[1][1] = (self[1][0] * other[0][1]) + (self[1][1] * other[1][1]) + (self[1][2] * other[2][1]) + (self[1][3] * other[3][1]);
-        result[1][2] = (self[1][0] * other[0][2]) + (self[1][1] * other[1][2]) + (self[1][2] * other[2][2]) + (self[1][3] * other[3][2]);
-        result[1][3] = (self[1][0] * other[0][3]) + (self[1][1] * other[1][3]) + (self[1][2] * other[2][3]) + (self[1][3] * other[3][3]);
-        result[2][0] = (self[2][0] * other[0][0]) + (self[2][1] * other[1][0]) + (self[2][2] * other[2][0]) + (self[2][3] * other[3][0]);
-        result[2][1] = (self[2][0] * other[0][1]) + (self[2][1] * other[1][1]) + (self[2][2] * other[2][1]) + (self[2][3] * other[3][1]);
-        result[2][2] = (self[2][0] * other[0][2]) + (self[2][1] * other[1][2]) + (self[2][2] * other[2][2]) + (self[2][3] * other[3][2]);
-        result[2][3] = (self[2][0] * other[0][3]) + (self[2][1] * other[1][3]) + (self[2][2] * other[2][3]) + (self[2][3] * other[3][3]);
-        result[3][0] = (self[3][0] * other[0][0]) + (self[3][1] * other[1][0]) + (self[3][2] * other[2][0]) + (self[3][3] * other[3][0]);
-        result[3][1] = (self[3][0] * other[0][1]) + (self[3][1] * other[1][1]) + (self[3][2] * other[2][1]) + (self[3][3] * other[3][1]);
-        result[3][2] = (self[3][0] * other[0][2]) + (self[3][1] * other[1][2]) + (self[3][2] * other[2][2]) + (self[3][3] * other[3][2]);
-        result[3][3] = (self[3][0] * other[0][3]) + (self[3][1] * other[1][3]) + (self[3][2] * other[2][3]) + (self[3][3] * other[3][3]);
+        kernels::mul_matrix4(&self, &other)
+    }
+}
 
-        result
+impl Mul<Vector4> for Matrix4 {
+    type Output = Vector4;
+
+    #[inline]
+    fn mul(self, rhs: Vector4) -> Vector4 {
+        kernels::transform(&self, rhs.into()).into()
     }
 }
 
 impl Mul<Matrix4> for Point {
     type Output = Point;
 
+    #[inline]
     fn mul(self, rhs: Matrix4) -> Point {
-        let Point { x, y, z, w } = self;
-
-        Point {
-            x: rhs[0][0] * x + rhs[0][1] * y + rhs[0][2] * z + rhs[0][3] * w,
-            y: rhs[1][0] * x + rhs[1][1] * y + rhs[1][2] * z + rhs[1][3] * w,
-            z: rhs[2][0] * x + rhs[2][1] * y + rhs[2][2] * z + rhs[2][3] * w,
-            w: rhs[3][0] * x + rhs[3][1] * y + rhs[3][2] * z + rhs[3][3] * w,
-        }
+        let [x, y, z, w] = kernels::transform(&rhs, [self.x, self.y, self.z, self.w]);
+        Point { x: x, y: y, z: z, w: w }
     }
 }
 
@@ -379,6 +564,49 @@ impl Matrix3 {
 
         unsafe { &*(data as *const _ as *const [f32; 9]) }
     }
+
+    /// Calculates the determinant of the matrix.
+    pub fn determinant(&self) -> f32 {
+        let cofactors = self.cofactors();
+        self[0][0] * cofactors[0][0] + self[0][1] * cofactors[0][1] + self[0][2] * cofactors[0][2]
+    }
+
+    /// Calculates the inverse of the matrix.
+    ///
+    /// Returns `None` if the matrix is singular and has no inverse.
+    pub fn inverse(&self) -> Option<Matrix3> {
+        let cofactors = self.cofactors();
+        let determinant = self[0][0] * cofactors[0][0] + self[0][1] * cofactors[0][1] + self[0][2] * cofactors[0][2];
+        let inverse_determinant = 1.0 / determinant;
+        if determinant == 0.0 || !inverse_determinant.is_finite() {
+            return None;
+        }
+
+        // The inverse is the transpose of the cofactor matrix divided by the determinant.
+        let mut result = cofactors.transpose();
+        for row in 0..3 {
+            for col in 0..3 {
+                result[row][col] *= inverse_determinant;
+            }
+        }
+
+        Some(result)
+    }
+
+    /// Calculates the matrix of cofactors.
+    fn cofactors(&self) -> Matrix3 {
+        let m = &self.0;
+        let mut cofactors = Matrix3::identity();
+        for row in 0..3 {
+            for col in 0..3 {
+                let (row_0, row_1) = ((row + 1) % 3, (row + 2) % 3);
+                let (col_0, col_1) = ((col + 1) % 3, (col + 2) % 3);
+                cofactors[row][col] = m[row_0][col_0] * m[row_1][col_1] - m[row_0][col_1] * m[row_1][col_0];
+            }
+        }
+
+        cofactors
+    }
 }
 
 impl From<Matrix4> for Matrix3 {
@@ -403,6 +631,77 @@ impl From<Orientation> for Matrix3 {
     }
 }
 
+impl PartialEq for Matrix3 {
+    fn ne(&self, other: &Matrix3) -> bool {
+        let our_data = self.raw_data();
+        let their_data = other.raw_data();
+        for (ours, theirs) in our_data.iter().zip(their_data.iter()) {
+            if !(ours - theirs).is_zero() {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn eq(&self, other: &Matrix3) -> bool {
+        !(self != other)
+    }
+}
+
+impl From<Matrix3> for Orientation {
+    /// Converts a pure rotation matrix into an orientation.
+    ///
+    /// The result is unspecified if the matrix contains scaling or shearing.
+    fn from(m: Matrix3) -> Orientation {
+        // Pick the largest of w, x, y, and z to calculate first to keep the result stable.
+        let trace = m[0][0] + m[1][1] + m[2][2];
+        let quaternion = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Quaternion::new(
+                Vector3::new(
+                    (m[2][1] - m[1][2]) / s,
+                    (m[0][2] - m[2][0]) / s,
+                    (m[1][0] - m[0][1]) / s,
+                ),
+                0.25 * s,
+            )
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+            Quaternion::new(
+                Vector3::new(
+                    0.25 * s,
+                    (m[0][1] + m[1][0]) / s,
+                    (m[0][2] + m[2][0]) / s,
+                ),
+                (m[2][1] - m[1][2]) / s,
+            )
+        } else if m[1][1] > m[2][2] {
+            let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+            Quaternion::new(
+                Vector3::new(
+                    (m[0][1] + m[1][0]) / s,
+                    0.25 * s,
+                    (m[1][2] + m[2][1]) / s,
+                ),
+                (m[0][2] - m[2][0]) / s,
+            )
+        } else {
+            let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+            Quaternion::new(
+                Vector3::new(
+                    (m[0][2] + m[2][0]) / s,
+                    (m[1][2] + m[2][1]) / s,
+                    0.25 * s,
+                ),
+                (m[1][0] - m[0][1]) / s,
+            )
+        };
+
+        Orientation(quaternion.normalized())
+    }
+}
+
 impl Index<usize> for Matrix3 {
     type Output = [f32; 3];
 
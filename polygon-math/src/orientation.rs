@@ -1,6 +1,6 @@
 use quaternion::Quaternion;
 use std::ops::{Add, AddAssign, Sub, SubAssign, Div, DivAssign, Mul, MulAssign};
-use super::{IsZero, Dot, PI};
+use super::{IsZero, Dot, Lerp, PI};
 use vector::Vector3;
 
 /// An orientation in 3D space.
@@ -81,6 +81,85 @@ impl Orientation {
         }
     }
 
+    /// Creates the smallest rotation that rotates the direction `from` onto the direction `to`.
+    ///
+    /// Neither vector needs to be normalized, but both must be non-zero. If the two vectors point
+    /// in opposite directions the rotation is 180 degrees around an arbitrary axis perpendicular
+    /// to `from`.
+    pub fn between(from: Vector3, to: Vector3) -> Orientation {
+        assert!(!from.is_zero());
+        assert!(!to.is_zero());
+
+        let from = from.normalized();
+        let to = to.normalized();
+        let dot = from.dot(to);
+
+        if (dot + 1.0).is_zero() {
+            // Pick whichever cardinal axis is least parallel to `from` to build a perpendicular.
+            let other = if from.x.abs() < 0.9 { Vector3::RIGHT } else { Vector3::UP };
+            let axis = Vector3::cross(from, other).normalized();
+            return Orientation::axis_angle(axis, PI);
+        }
+
+        // Using the half-way vector avoids any trigonometry: the quaternion for a rotation of
+        // `2 * theta` is `(cross, dot)` for vectors `theta` apart.
+        let q = Quaternion::new(Vector3::cross(from, to), 1.0 + dot).normalized();
+        Orientation(q)
+    }
+
+    /// Interpolates between two orientations along the shortest path and normalizes the result.
+    ///
+    /// See `Quaternion::nlerp()` for the tradeoffs compared to `slerp()`.
+    pub fn nlerp(from: Orientation, to: Orientation, t: f32) -> Orientation {
+        Orientation(Quaternion::nlerp(from.0, to.0, t))
+    }
+
+    /// Spherically interpolates between two orientations along the shortest path.
+    pub fn slerp(from: Orientation, to: Orientation, t: f32) -> Orientation {
+        Orientation(Quaternion::slerp(from.0, to.0, t))
+    }
+
+    /// Interpolates smoothly from `from` to `to` using spherical quadrangle interpolation.
+    ///
+    /// `before` and `after` are the keyframes on either side of the segment being interpolated,
+    /// and are used to make the curve continuous across keyframes. See `Quaternion::squad()`.
+    pub fn squad(before: Orientation, from: Orientation, to: Orientation, after: Orientation, t: f32) -> Orientation {
+        // Keep the keyframes on the same hemisphere so the curve doesn't take the long way.
+        let from = from.0;
+        let to = if Quaternion::dot(from, to.0) < 0.0 { to.0 * -1.0 } else { to.0 };
+
+        let from_control = Quaternion::squad_control_point(before.0, from, to);
+        let to_control = Quaternion::squad_control_point(from, to, after.0);
+        Orientation(Quaternion::squad(from, to, from_control, to_control, t))
+    }
+
+    /// Rotates towards `target` by at most `max_angle` radians.
+    ///
+    /// If `target` is within `max_angle` of the orientation then `target` is returned. A negative
+    /// `max_angle` rotates away from `target`, but never past the opposite orientation.
+    pub fn rotate_towards(self, target: Orientation, max_angle: f32) -> Orientation {
+        let angle = self.angle_between(target);
+        if angle.is_zero() {
+            return target;
+        }
+
+        let t = f32::min(1.0, max_angle / angle);
+        if t >= 1.0 {
+            return target;
+        }
+
+        // `slerp()` extrapolates correctly for negative `t`, but not past the antipode.
+        let t = f32::max(t, 1.0 - PI / angle);
+        Orientation::slerp(self, target, t)
+    }
+
+    /// Calculates the angle in radians of the smallest rotation between two orientations.
+    ///
+    /// The result is always in the range `[0, PI]`.
+    pub fn angle_between(self, other: Orientation) -> f32 {
+        Quaternion::angle_between(self.0, other.0)
+    }
+
     /// Creates a quaternion from a set of euler angles.
     pub fn from_eulers(x: f32, y: f32, z: f32) -> Orientation {
         Orientation::axis_angle(Vector3::new(1.0, 0.0, 0.0), x)
@@ -195,6 +274,12 @@ impl Default for Orientation {
     }
 }
 
+impl Lerp for Orientation {
+    fn lerp(t: f32, from: Orientation, to: Orientation) -> Orientation {
+        Orientation::slerp(from, to, t)
+    }
+}
+
 impl Add for Orientation {
     type Output = Orientation;
 
@@ -12,11 +12,16 @@
 ///
 /// [hamilton product]: https://en.wikipedia.org/wiki/Quaternion#Hamilton_product
 
+use kernels;
 use orientation::Orientation;
 use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
 
 use vector::Vector3;
-use super::{IsZero, Dot};
+use super::{IsZero, Dot, PI};
+
+/// How close the dot product of two quaternions must be to 1 before `slerp()` falls back to
+/// normalized linear interpolation.
+const SLERP_THRESHOLD: f32 = 1e-4;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 #[cfg_attr(feature = "serde-impls", derive(Serialize, Deserialize))]
@@ -100,6 +105,147 @@ impl Quaternion {
         first + (second - first) * t
     }
 
+    /// Interpolates between two rotation quaternions and normalizes the result.
+    ///
+    /// `nlerp()` always interpolates along the shortest path between the two rotations. It is
+    /// cheaper than `slerp()` but doesn't interpolate at a constant angular velocity, with the
+    /// rotation speeding up towards the middle of the interpolation. The difference is
+    /// negligible when the two rotations are close together.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the interpolated quaternion has a length of zero, which can only happen if the
+    /// inputs aren't normalized.
+    pub fn nlerp(first: Quaternion, second: Quaternion, t: f32) -> Quaternion {
+        let second = if Quaternion::dot(first, second) < 0.0 { second * -1.0 } else { second };
+        Quaternion::lerp(first, second, t).normalized()
+    }
+
+    /// Spherically interpolates between two rotation quaternions.
+    ///
+    /// `slerp()` interpolates along the shortest arc between the two rotations at a constant
+    /// angular velocity. Both quaternions should be normalized. When the two rotations are
+    /// nearly identical this falls back to `nlerp()` to avoid dividing by a vanishingly small
+    /// sine.
+    pub fn slerp(first: Quaternion, second: Quaternion, t: f32) -> Quaternion {
+        let mut cos_theta = Quaternion::dot(first, second);
+
+        // `q` and `-q` represent the same rotation, so flip the target onto the same hemisphere
+        // as the source in order to take the short way around.
+        let second = if cos_theta < 0.0 {
+            cos_theta = -cos_theta;
+            second * -1.0
+        } else {
+            second
+        };
+
+        if cos_theta > 1.0 - SLERP_THRESHOLD {
+            return Quaternion::lerp(first, second, t).normalized();
+        }
+
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+        let first_weight = ((1.0 - t) * theta).sin() / sin_theta;
+        let second_weight = (t * theta).sin() / sin_theta;
+
+        first * first_weight + second * second_weight
+    }
+
+    /// Performs spherical quadrangle interpolation between `first` and `second`.
+    ///
+    /// `squad()` produces a smooth (C1 continuous) curve through a sequence of rotations, where
+    /// `first_control` and `second_control` are the inner control points for `first` and
+    /// `second` respectively. Use `squad_control_point()` to calculate the control points from
+    /// the neighboring keyframes.
+    pub fn squad(
+        first: Quaternion,
+        second: Quaternion,
+        first_control: Quaternion,
+        second_control: Quaternion,
+        t: f32,
+    ) -> Quaternion {
+        let outer = Quaternion::slerp_no_invert(first, second, t);
+        let inner = Quaternion::slerp_no_invert(first_control, second_control, t);
+        Quaternion::slerp_no_invert(outer, inner, 2.0 * t * (1.0 - t))
+    }
+
+    /// Calculates the inner control point used by `squad()` for the keyframe `current`.
+    ///
+    /// `previous` and `next` are the keyframes on either side of `current`. For the first and
+    /// last keyframes of a sequence pass `current` as the missing neighbor.
+    pub fn squad_control_point(previous: Quaternion, current: Quaternion, next: Quaternion) -> Quaternion {
+        // Keep the neighbors on the same hemisphere as `current` so the tangent follows the
+        // shortest path to each of them.
+        let previous = if Quaternion::dot(current, previous) < 0.0 { previous * -1.0 } else { previous };
+        let next = if Quaternion::dot(current, next) < 0.0 { next * -1.0 } else { next };
+
+        let inverse = current.conjugate();
+        let to_next = (inverse * next).ln();
+        let to_previous = (inverse * previous).ln();
+        let tangent = (to_next + to_previous) * -0.25;
+
+        (current * tangent.exp()).normalized()
+    }
+
+    /// Calculates the angle in radians between the rotations represented by two unit quaternions.
+    ///
+    /// The result is the angle of the smallest rotation taking one to the other, and is always in
+    /// the range `[0, PI]`.
+    pub fn angle_between(first: Quaternion, second: Quaternion) -> f32 {
+        let dot = Quaternion::dot(first, second).abs().min(1.0);
+        2.0 * dot.acos()
+    }
+
+    /// Calculates the natural logarithm of a unit quaternion.
+    ///
+    /// The result is a pure quaternion (`w` is zero) whose vector part is the rotation axis
+    /// scaled by half the rotation angle.
+    pub fn ln(self) -> Quaternion {
+        let sin_half_angle = self.v.magnitude();
+        if sin_half_angle.is_zero() {
+            return Quaternion::new(Vector3::ZERO, 0.0);
+        }
+
+        let half_angle = f32::atan2(sin_half_angle, self.w);
+        Quaternion::new(self.v * (half_angle / sin_half_angle), 0.0)
+    }
+
+    /// Calculates the exponential of a pure quaternion.
+    ///
+    /// This is the inverse of `ln()`, and takes a pure quaternion back to a unit quaternion.
+    pub fn exp(self) -> Quaternion {
+        let half_angle = self.v.magnitude();
+        if half_angle.is_zero() {
+            return Quaternion::identity();
+        }
+
+        Quaternion::new(self.v * (half_angle.sin() / half_angle), half_angle.cos())
+    }
+
+    /// Spherically interpolates without flipping `second` onto the shortest path.
+    ///
+    /// `squad()` depends on the control points being interpolated exactly as given, so it can't
+    /// use `slerp()` directly.
+    fn slerp_no_invert(first: Quaternion, second: Quaternion, t: f32) -> Quaternion {
+        let cos_theta = Quaternion::dot(first, second);
+        if cos_theta > 1.0 - SLERP_THRESHOLD {
+            return Quaternion::lerp(first, second, t).normalized();
+        }
+
+        if cos_theta < -1.0 + SLERP_THRESHOLD {
+            // `second` is (nearly) opposite `first`, so `sin_theta` is too small to divide by and
+            // every great circle between them is equally short. Rotate halfway around the circle
+            // through a quaternion perpendicular to `first` instead.
+            let perpendicular = Quaternion::new(Vector3::new(-first.v.y, first.v.x, -first.w), first.v.z);
+            let angle = t * PI;
+            return first * angle.cos() + perpendicular * angle.sin();
+        }
+
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+        first * (((1.0 - t) * theta).sin() / sin_theta) + second * ((t * theta).sin() / sin_theta)
+    }
+
     pub fn inverse(self) -> Quaternion {
         (1.0 / self.len_sqr()) * self.conjugate()
     }
@@ -162,11 +308,9 @@ impl SubAssign for Quaternion {
 impl Mul for Quaternion {
     type Output = Quaternion;
 
+    #[inline]
     fn mul(self, rhs: Quaternion) -> Quaternion {
-        Quaternion {
-            v: Vector3::cross(self.v, rhs.v) + rhs.w * self.v + self.w * rhs.v,
-            w: self.w * rhs.w - Vector3::dot(self.v, rhs.v),
-        }
+        kernels::mul_quaternion(self, rhs)
     }
 }
 
@@ -0,0 +1,223 @@
+use std::ops::{Mul, MulAssign};
+
+use matrix::{Matrix3, Matrix4};
+use orientation::Orientation;
+use point::Point;
+use vector::Vector3;
+use Lerp;
+
+/// A position, orientation, and scale in 3D space.
+///
+/// A `Transform` represents the matrix `translation * rotation * scale`, i.e. scale is applied
+/// first, then rotation, then translation. Keeping the components separate (rather than storing a
+/// `Matrix4`) makes it cheap to modify them individually and to interpolate between transforms.
+///
+/// Transforms compose with `*`, where `parent * child` gives a transform that first applies
+/// `child` and then `parent`, matching the equivalent matrix product. A transform with
+/// non-uniform scale combined with rotation can produce shear, which can't be represented by a
+/// `Transform`. In that case composition and `inverse()` approximate the result by combining the
+/// scales component-wise; use the matrix form when exact results are needed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-impls", derive(Serialize, Deserialize))]
+pub struct Transform {
+    pub position: Point,
+    pub orientation: Orientation,
+    pub scale: Vector3,
+}
+
+impl Transform {
+    /// Creates the identity transform.
+    pub fn new() -> Transform {
+        Transform {
+            position: Point::origin(),
+            orientation: Orientation::new(),
+            scale: Vector3::ONE,
+        }
+    }
+
+    /// Creates a transform from a position, orientation, and scale.
+    pub fn from_parts(position: Point, orientation: Orientation, scale: Vector3) -> Transform {
+        Transform {
+            position: position,
+            orientation: orientation,
+            scale: scale,
+        }
+    }
+
+    /// Creates a transform that only translates to `position`.
+    pub fn from_position(position: Point) -> Transform {
+        Transform { position: position, .. Transform::new() }
+    }
+
+    /// Creates a transform that only rotates by `orientation`.
+    pub fn from_orientation(orientation: Orientation) -> Transform {
+        Transform { orientation: orientation, .. Transform::new() }
+    }
+
+    /// Creates a transform that only scales by `scale`.
+    pub fn from_scale(scale: Vector3) -> Transform {
+        Transform { scale: scale, .. Transform::new() }
+    }
+
+    /// Decomposes an affine matrix into a transform.
+    ///
+    /// Returns `None` if the matrix can't be decomposed, see `Matrix4::decompose()` for details.
+    /// Any shear in the matrix is lost.
+    pub fn from_matrix(matrix: Matrix4) -> Option<Transform> {
+        matrix
+            .decompose()
+            .map(|(position, orientation, scale)| Transform::from_parts(position, orientation, scale))
+    }
+
+    /// Calculates the matrix to convert from the transform's local space to its parent space.
+    pub fn matrix(&self) -> Matrix4 {
+        let position = Matrix4::from_point(self.position);
+        let orientation = Matrix4::from(self.orientation);
+        let scale = Matrix4::from_scale_vector(self.scale);
+
+        position * (orientation * scale)
+    }
+
+    /// Calculates the matrix to convert from the transform's parent space to its local space.
+    ///
+    /// Unlike `inverse().matrix()` this is exact even with non-uniform scale. If any component of
+    /// the scale is zero the result contains infinities.
+    pub fn inverse_matrix(&self) -> Matrix4 {
+        let inv_scale = Matrix4::from_scale_vector(1.0 / self.scale);
+        let inv_orientation = Matrix4::from(self.orientation).transpose();
+        let inv_translation = Matrix4::from_point(-self.position);
+
+        inv_scale * (inv_orientation * inv_translation)
+    }
+
+    /// Calculates the matrix used to transform normals from local space to parent space.
+    ///
+    /// This is the inverse transpose of the upper 3x3 of `matrix()`. If the scale collapses an
+    /// axis the matrix has no inverse, in which case the orientation matrix is used instead.
+    pub fn normal_matrix(&self) -> Matrix3 {
+        Matrix3::from(self.matrix())
+            .inverse()
+            .map(|inverse| inverse.transpose())
+            .unwrap_or_else(|| Matrix3::from(self.orientation))
+    }
+
+    /// Calculates the inverse transform.
+    ///
+    /// Composing a transform with its inverse gives the identity. The result is only exact if the
+    /// scale is uniform, see the type-level documentation. Any zero component of the scale
+    /// results in infinities.
+    pub fn inverse(&self) -> Transform {
+        let orientation = Orientation(self.orientation.0.conjugate());
+        let scale = 1.0 / self.scale;
+        let position = Point::origin() + scale * (orientation * -self.position.as_vector3());
+
+        Transform {
+            position: position,
+            orientation: orientation,
+            scale: scale,
+        }
+    }
+
+    /// Transforms a point from local space to parent space.
+    pub fn transform_point(&self, point: Point) -> Point {
+        self.position + self.transform_vector(point.as_vector3())
+    }
+
+    /// Transforms a direction from local space to parent space.
+    ///
+    /// Vectors are scaled and rotated but not translated. The result is not normalized.
+    pub fn transform_vector(&self, vector: Vector3) -> Vector3 {
+        self.orientation * (self.scale * vector)
+    }
+
+    /// Transforms a surface normal from local space to parent space.
+    ///
+    /// Normals are scaled by the inverse of the scale so that they remain perpendicular to the
+    /// surface, and the result is normalized.
+    pub fn transform_normal(&self, normal: Vector3) -> Vector3 {
+        (self.orientation * (normal / self.scale)).normalized()
+    }
+
+    /// Transforms a point from parent space to local space.
+    ///
+    /// This is the inverse of `transform_point()`, and is exact even with non-uniform scale.
+    pub fn inverse_transform_point(&self, point: Point) -> Point {
+        Point::origin() + self.inverse_transform_vector(point - self.position)
+    }
+
+    /// Transforms a direction from parent space to local space.
+    ///
+    /// This is the inverse of `transform_vector()`.
+    pub fn inverse_transform_vector(&self, vector: Vector3) -> Vector3 {
+        let inv_orientation = Orientation(self.orientation.0.conjugate());
+        (inv_orientation * vector) / self.scale
+    }
+
+    /// Interpolates between two transforms, using `nlerp()` for the orientation.
+    ///
+    /// Position and scale are interpolated linearly. This is cheaper than `slerp()` and is
+    /// accurate enough when the orientations are close together, e.g. between animation frames.
+    pub fn lerp(from: Transform, to: Transform, t: f32) -> Transform {
+        Transform {
+            position: from.position + (to.position - from.position) * t,
+            orientation: Orientation::nlerp(from.orientation, to.orientation, t),
+            scale: Vector3::lerp(t, from.scale, to.scale),
+        }
+    }
+
+    /// Interpolates between two transforms, using `slerp()` for the orientation.
+    ///
+    /// Position and scale are interpolated linearly.
+    pub fn slerp(from: Transform, to: Transform, t: f32) -> Transform {
+        Transform {
+            position: from.position + (to.position - from.position) * t,
+            orientation: Orientation::slerp(from.orientation, to.orientation, t),
+            scale: Vector3::lerp(t, from.scale, to.scale),
+        }
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Transform {
+        Transform::new()
+    }
+}
+
+impl From<Transform> for Matrix4 {
+    fn from(from: Transform) -> Matrix4 {
+        from.matrix()
+    }
+}
+
+impl Lerp for Transform {
+    fn lerp(t: f32, from: Transform, to: Transform) -> Transform {
+        Transform::slerp(from, to, t)
+    }
+}
+
+impl Mul for Transform {
+    type Output = Transform;
+
+    /// Composes two transforms, applying `rhs` first and then `self`.
+    fn mul(self, rhs: Transform) -> Transform {
+        Transform {
+            position: self.transform_point(rhs.position),
+            orientation: self.orientation + rhs.orientation,
+            scale: self.scale * rhs.scale,
+        }
+    }
+}
+
+impl MulAssign for Transform {
+    fn mul_assign(&mut self, rhs: Transform) {
+        *self = *self * rhs;
+    }
+}
+
+impl Mul<Point> for Transform {
+    type Output = Point;
+
+    fn mul(self, rhs: Point) -> Point {
+        self.transform_point(rhs)
+    }
+}
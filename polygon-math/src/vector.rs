@@ -3,6 +3,331 @@ use std::ops::*;
 use std::fmt::{self, Debug, Formatter};
 use std::slice;
 
+// OPERATOR MACROS
+// ================================================================================================
+
+/// Expands to `$scalar` once for each field, used to build tuple types matching a vector.
+macro_rules! field_type {
+    ($field:ident, $scalar:ty) => { $scalar }
+}
+
+/// Implements the component-wise operator suite shared by all vector types.
+///
+/// This covers arithmetic against other vectors and against scalars, indexing, the dot product,
+/// component-wise `min()` and `max()`, and conversions to and from arrays and tuples.
+macro_rules! vector_ops {
+    ($vector:ident, $scalar:ty, $len:expr, { $($field:ident: $index:expr),+ }) => {
+        impl $vector {
+            /// Creates a vector with every component set to `value`.
+            pub fn splat(value: $scalar) -> $vector {
+                $vector { $($field: value),+ }
+            }
+
+            /// Returns the component-wise minimum of two vectors.
+            pub fn min(self, other: $vector) -> $vector {
+                $vector { $($field: if other.$field < self.$field { other.$field } else { self.$field }),+ }
+            }
+
+            /// Returns the component-wise maximum of two vectors.
+            pub fn max(self, other: $vector) -> $vector {
+                $vector { $($field: if other.$field > self.$field { other.$field } else { self.$field }),+ }
+            }
+
+            /// Converts the vector into an array of its components.
+            pub fn into_array(self) -> [$scalar; $len] {
+                [$(self.$field),+]
+            }
+        }
+
+        impl Dot for $vector {
+            type Output = $scalar;
+
+            fn dot(self, rhs: $vector) -> $scalar {
+                [$(self.$field * rhs.$field),+].iter().sum()
+            }
+        }
+
+        impl AddAssign for $vector {
+            fn add_assign(&mut self, rhs: $vector) {
+                $(self.$field += rhs.$field;)+
+            }
+        }
+
+        impl Add for $vector {
+            type Output = $vector;
+
+            fn add(mut self, rhs: $vector) -> $vector {
+                self += rhs;
+                self
+            }
+        }
+
+        impl SubAssign for $vector {
+            fn sub_assign(&mut self, rhs: $vector) {
+                $(self.$field -= rhs.$field;)+
+            }
+        }
+
+        impl Sub for $vector {
+            type Output = $vector;
+
+            fn sub(mut self, rhs: $vector) -> $vector {
+                self -= rhs;
+                self
+            }
+        }
+
+        impl MulAssign for $vector {
+            fn mul_assign(&mut self, rhs: $vector) {
+                $(self.$field *= rhs.$field;)+
+            }
+        }
+
+        impl Mul for $vector {
+            type Output = $vector;
+
+            fn mul(mut self, rhs: $vector) -> $vector {
+                self *= rhs;
+                self
+            }
+        }
+
+        impl MulAssign<$scalar> for $vector {
+            fn mul_assign(&mut self, rhs: $scalar) {
+                $(self.$field *= rhs;)+
+            }
+        }
+
+        impl Mul<$scalar> for $vector {
+            type Output = $vector;
+
+            fn mul(mut self, rhs: $scalar) -> $vector {
+                self *= rhs;
+                self
+            }
+        }
+
+        impl Mul<$vector> for $scalar {
+            type Output = $vector;
+
+            fn mul(self, rhs: $vector) -> $vector {
+                rhs * self
+            }
+        }
+
+        impl DivAssign for $vector {
+            fn div_assign(&mut self, rhs: $vector) {
+                $(self.$field /= rhs.$field;)+
+            }
+        }
+
+        impl Div for $vector {
+            type Output = $vector;
+
+            fn div(mut self, rhs: $vector) -> $vector {
+                self /= rhs;
+                self
+            }
+        }
+
+        impl DivAssign<$scalar> for $vector {
+            fn div_assign(&mut self, rhs: $scalar) {
+                $(self.$field /= rhs;)+
+            }
+        }
+
+        impl Div<$scalar> for $vector {
+            type Output = $vector;
+
+            fn div(mut self, rhs: $scalar) -> $vector {
+                self /= rhs;
+                self
+            }
+        }
+
+        impl Div<$vector> for $scalar {
+            type Output = $vector;
+
+            /// Divides the scalar by each component of the vector.
+            fn div(self, rhs: $vector) -> $vector {
+                $vector { $($field: self / rhs.$field),+ }
+            }
+        }
+
+        // TODO: Is `usize` an appropriate index? Especially considering the valid values are 0..N?
+        impl Index<usize> for $vector {
+            type Output = $scalar;
+
+            fn index(&self, index: usize) -> &$scalar {
+                match index {
+                    $($index => &self.$field,)+
+                    _ => panic!("Index {} is out of bounds for {}", index, stringify!($vector)),
+                }
+            }
+        }
+
+        impl IndexMut<usize> for $vector {
+            fn index_mut(&mut self, index: usize) -> &mut $scalar {
+                match index {
+                    $($index => &mut self.$field,)+
+                    _ => panic!("Index {} is out of bounds for {}", index, stringify!($vector)),
+                }
+            }
+        }
+
+        impl From<[$scalar; $len]> for $vector {
+            fn from(from: [$scalar; $len]) -> $vector {
+                $vector { $($field: from[$index]),+ }
+            }
+        }
+
+        impl<'a> From<&'a [$scalar; $len]> for $vector {
+            fn from(from: &'a [$scalar; $len]) -> $vector {
+                $vector { $($field: from[$index]),+ }
+            }
+        }
+
+        impl<'a> From<&'a [$scalar]> for $vector {
+            fn from(from: &'a [$scalar]) -> $vector {
+                assert_eq!(
+                    from.len(),
+                    $len,
+                    "Wrong number of elements to convert to `{}`",
+                    stringify!($vector),
+                );
+
+                $vector { $($field: from[$index]),+ }
+            }
+        }
+
+        impl From<($(field_type!($field, $scalar)),+)> for $vector {
+            fn from(from: ($(field_type!($field, $scalar)),+)) -> $vector {
+                let ($($field),+) = from;
+                $vector { $($field: $field),+ }
+            }
+        }
+
+        impl From<$vector> for [$scalar; $len] {
+            fn from(from: $vector) -> [$scalar; $len] {
+                from.into_array()
+            }
+        }
+
+        impl From<$vector> for ($(field_type!($field, $scalar)),+) {
+            fn from(from: $vector) -> ($(field_type!($field, $scalar)),+) {
+                ($(from.$field),+)
+            }
+        }
+    };
+}
+
+/// Implements negation and `abs()` for vectors with signed components.
+macro_rules! signed_vector_ops {
+    ($vector:ident, { $($field:ident),+ }) => {
+        impl $vector {
+            /// Returns a vector containing the absolute value of each component.
+            pub fn abs(self) -> $vector {
+                $vector { $($field: self.$field.abs()),+ }
+            }
+        }
+
+        impl Neg for $vector {
+            type Output = $vector;
+
+            fn neg(self) -> $vector {
+                $vector { $($field: -self.$field),+ }
+            }
+        }
+    };
+}
+
+/// Implements the operations that only make sense for floating point vectors.
+macro_rules! float_vector_ops {
+    ($vector:ident, { $($field:ident),+ }) => {
+        impl $vector {
+            /// Normalizes the vector, returning the old length.
+            ///
+            /// If the vector is the zero vector it is not altered.
+            pub fn normalize(&mut self) -> f32 {
+                if self.is_zero() {
+                    0.0
+                } else {
+                    let magnitude = self.magnitude();
+                    let one_over_magnitude = 1.0 / magnitude;
+                    *self *= one_over_magnitude;
+
+                    magnitude
+                }
+            }
+
+            /// Returns the normalized version of the vector.
+            ///
+            /// If the vector is the zero vector a copy is returned.
+            pub fn normalized(&self) -> $vector {
+                let mut copy = *self;
+                copy.normalize();
+                copy
+            }
+
+            pub fn is_normalized(&self) -> bool {
+                (self.dot(*self) - 1.0).is_zero()
+            }
+
+            pub fn magnitude(&self) -> f32 {
+                self.magnitude_squared().sqrt()
+            }
+
+            pub fn magnitude_squared(&self) -> f32 {
+                self.dot(*self)
+            }
+
+            /// Returns a vector containing the largest integer less than or equal to each component.
+            pub fn floor(self) -> $vector {
+                $vector { $($field: self.$field.floor()),+ }
+            }
+
+            /// Returns a vector containing the smallest integer greater than or equal to each
+            /// component.
+            pub fn ceil(self) -> $vector {
+                $vector { $($field: self.$field.ceil()),+ }
+            }
+
+            /// Returns a vector with each component rounded to the nearest integer.
+            ///
+            /// Half-way cases are rounded away from zero.
+            pub fn round(self) -> $vector {
+                $vector { $($field: self.$field.round()),+ }
+            }
+        }
+
+        impl IsZero for $vector {
+            fn is_zero(self) -> bool {
+                self.dot(self).is_zero()
+            }
+        }
+
+        impl Lerp for $vector {
+            fn lerp(t: f32, from: $vector, to: $vector) -> $vector {
+                from + (to - from) * t
+            }
+        }
+    };
+}
+
+/// Generates swizzle accessors, e.g. `zyx()`, which build a new vector from the named components.
+macro_rules! swizzles {
+    ($vector:ident => $output:ident { $($name:ident: ($($field:ident),+)),+ $(,)* }) => {
+        impl $vector {
+            $(
+                /// Builds a new vector from the components named by the method.
+                pub fn $name(self) -> $output {
+                    $output::new($(self.$field),+)
+                }
+            )+
+        }
+    };
+}
+
 // VECTOR 3
 // ================================================================================================
 
@@ -68,48 +393,6 @@ impl Vector3 {
         self
     }
 
-    /// Normalizes the vector, returning the old length.
-    ///
-    /// If the vector is the zero vector it is not altered.
-    pub fn normalize(&mut self) -> f32 {
-        if self.is_zero() {
-            0.0
-        } else {
-            let magnitude = self.magnitude();
-            let one_over_magnitude = 1.0 / magnitude;
-            self.x *= one_over_magnitude;
-            self.y *= one_over_magnitude;
-            self.z *= one_over_magnitude;
-
-            magnitude
-        }
-    }
-
-    /// Returns the normalized version of the vector.
-    ///
-    /// If the vector is the zero vector a copy is returned.
-    pub fn normalized(&self) -> Vector3 {
-        if self.is_zero() {
-            *self
-        } else {
-            let mut copy = *self;
-            copy.normalize();
-            copy
-        }
-    }
-
-    pub fn is_normalized(&self) -> bool {
-        (self.dot(self) - 1.0).is_zero()
-    }
-
-    pub fn magnitude(&self) -> f32 {
-        self.magnitude_squared().sqrt()
-    }
-
-    pub fn magnitude_squared(&self) -> f32 {
-        self.x * self.x + self.y * self.y + self.z * self.z
-    }
-
     /// Safely reinterprets a slice of Vector3s to a slice of f32s. This is a cheap operation and
     /// does not copy any data.
     pub fn as_ref(vectors: &[Vector3]) -> &[f32] {
@@ -125,15 +408,6 @@ impl Vector3 {
         unsafe { slice::from_raw_parts(ptr, vecs.len()) }
     }
 
-    /// Converts the `Vector3` into a 3 element array.
-    ///
-    /// In the returned array, the `x` coordinate is at index 0, the `y` coordinate is at index 1,
-    /// and the `z` coordinate is at index 2.
-    pub fn into_array(self) -> [f32; 3] {
-        let Vector3 { x, y, z } = self;
-        [x, y, z]
-    }
-
     // pub fn cross(&self, rhs: Vector3) -> Vector3 {
     //     Vector3::new(
     //         self.y * rhs.z - self.z * rhs.y,
@@ -169,13 +443,17 @@ impl Debug for Vector3 {
     }
 }
 
-impl Dot for Vector3 {
-    type Output = f32;
+vector_ops!(Vector3, f32, 3, { x: 0, y: 1, z: 2 });
+signed_vector_ops!(Vector3, { x, y, z });
+float_vector_ops!(Vector3, { x, y, z });
 
-    fn dot(self, rhs: Vector3) -> f32 {
-        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
-    }
-}
+swizzles!(Vector3 => Vector2 {
+    xy: (x, y), xz: (x, z), yx: (y, x), yz: (y, z), zx: (z, x), zy: (z, y),
+});
+
+swizzles!(Vector3 => Vector3 {
+    xzy: (x, z, y), yxz: (y, x, z), yzx: (y, z, x), zxy: (z, x, y), zyx: (z, y, x),
+});
 
 impl Dot<[f32; 3]> for Vector3 {
     type Output = f32;
@@ -193,188 +471,6 @@ impl Dot<Vector3> for [f32; 3] {
     }
 }
 
-// impl<'a> Dot<&'a [f32; 3]> for Vector3 {
-//     type Output = f32;
-//
-//     fn dot(self, rhs: &[f32; 3]) -> f32 {
-//         self.dot(*rhs)
-//     }
-// }
-
-impl AddAssign for Vector3 {
-    fn add_assign(&mut self, rhs: Vector3) {
-        self.x += rhs.x;
-        self.y += rhs.y;
-        self.z += rhs.z;
-    }
-}
-
-impl Add for Vector3 {
-    type Output = Vector3;
-
-    fn add(mut self, rhs: Vector3) -> Vector3 {
-        self += rhs;
-        self
-    }
-}
-
-impl SubAssign for Vector3 {
-    fn sub_assign(&mut self, rhs: Vector3) {
-        self.x -= rhs.x;
-        self.y -= rhs.y;
-        self.z -= rhs.z;
-    }
-}
-
-impl Sub for Vector3 {
-    type Output = Vector3;
-
-    fn sub(mut self, rhs: Vector3) -> Vector3 {
-        self -= rhs;
-        self
-    }
-}
-
-impl MulAssign for Vector3 {
-    fn mul_assign(&mut self, rhs: Vector3) {
-        self.x *= rhs.x;
-        self.y *= rhs.y;
-        self.z *= rhs.z;
-    }
-}
-
-impl Mul for Vector3 {
-    type Output = Vector3;
-
-    fn mul(mut self, rhs: Vector3) -> Vector3 {
-        self *= rhs;
-        self
-    }
-}
-
-impl MulAssign<f32> for Vector3 {
-    fn mul_assign(&mut self, rhs: f32) {
-        self.x *= rhs;
-        self.y *= rhs;
-        self.z *= rhs;
-    }
-}
-
-impl Mul<f32> for Vector3 {
-    type Output = Vector3;
-
-    fn mul(mut self, rhs: f32) -> Vector3 {
-        self *= rhs;
-        self
-    }
-}
-
-impl Mul<Vector3> for f32 {
-    type Output = Vector3;
-
-    fn mul(self, rhs: Vector3) -> Vector3 {
-        rhs * self
-    }
-}
-
-impl Neg for Vector3 {
-    type Output = Vector3;
-
-    fn neg(self) -> Vector3 {
-        Vector3::new(-self.x, -self.y, -self.z)
-    }
-}
-
-impl DivAssign<f32> for Vector3 {
-    fn div_assign(&mut self, rhs: f32) {
-        self.x /= rhs;
-        self.y /= rhs;
-        self.z /= rhs;
-    }
-}
-
-impl Div<f32> for Vector3 {
-    type Output = Vector3;
-
-    fn div(mut self, rhs: f32) -> Vector3 {
-        self /= rhs;
-        self
-    }
-}
-
-impl Div<Vector3> for f32 {
-    type Output = Vector3;
-
-    fn div(self, rhs: Vector3) -> Vector3 {
-        rhs / self
-    }
-}
-
-impl IsZero for Vector3 {
-    fn is_zero(self) -> bool {
-        self.dot(self).is_zero()
-    }
-}
-
-// TODO: Is `usize` an appropriate index? Especially considering the valid values are 0..3?
-impl Index<usize> for Vector3 {
-    type Output = f32;
-
-    fn index(&self, index: usize) -> &f32 {
-        match index {
-            0 => &self.x,
-            1 => &self.y,
-            2 => &self.z,
-            // TODO: Use `unreachable()` intrinsic in release mode.
-            _ => panic!("Index {} is out of bounds for Vector3", index),
-        }
-    }
-}
-
-impl IndexMut<usize> for Vector3 {
-    fn index_mut(&mut self, index: usize) -> &mut f32 {
-        match index {
-            0 => &mut self.x,
-            1 => &mut self.y,
-            2 => &mut self.z,
-            // TODO: Use `unreachable()` intrinsic in release mode.
-            _ => panic!("Index {} is out of bounds for Vector3", index),
-        }
-    }
-}
-
-impl<'a> From<&'a [f32]> for Vector3 {
-    fn from(from: &[f32]) -> Vector3 {
-        assert_eq!(from.len(), 3, "Wrong number of elements to convert to `Vector3`");
-
-        Vector3 {
-            x: from[0],
-            y: from[1],
-            z: from[2],
-        }
-    }
-}
-
-impl From<[f32; 3]> for Vector3 {
-    fn from(from: [f32; 3]) -> Vector3 {
-        Vector3 {
-            x: from[0],
-            y: from[1],
-            z: from[2],
-        }
-    }
-}
-
-impl<'a> From<&'a [f32; 3]> for Vector3 {
-    fn from(from: &'a [f32; 3]) -> Vector3 {
-        Vector3 {
-            x: from[0],
-            y: from[1],
-            z: from[2],
-        }
-    }
-}
-
 impl From<Point> for Vector3 {
     /// Creates a new `Vector3` from a `Point`.
     ///
@@ -390,25 +486,68 @@ impl From<Point> for Vector3 {
     }
 }
 
-impl From<(f32, f32, f32)> for Vector3 {
-    fn from(from: (f32, f32, f32)) -> Vector3 {
-        Vector3 {
-            x: from.0,
-            y: from.1,
-            z: from.2,
+// VECTOR 4
+// ================================================================================================
+
+/// A 4-component vector, mainly used for homogeneous coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[repr(C)]
+#[cfg_attr(feature = "serde-impls", derive(Serialize, Deserialize))]
+pub struct Vector4 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Vector4 {
+    pub const ZERO: Vector4 = Vector4 { x: 0.0, y: 0.0, z: 0.0, w: 0.0 };
+    pub const ONE: Vector4 = Vector4 { x: 1.0, y: 1.0, z: 1.0, w: 1.0 };
+
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Vector4 {
+        Vector4 {
+            x: x,
+            y: y,
+            z: z,
+            w: w,
         }
     }
-}
 
-impl Into<[f32; 3]> for Vector3 {
-    fn into(self) -> [f32; 3] {
-        [self.x, self.y, self.z]
+    /// Creates a vector from the `x`, `y`, and `z` components of a `Vector3` and a `w` component.
+    pub fn from_vector3(from: Vector3, w: f32) -> Vector4 {
+        Vector4::new(from.x, from.y, from.z, w)
+    }
+
+    /// Safely reinterprets a slice of Vector4s to a slice of f32s. This is a cheap operation and
+    /// does not copy any data.
+    pub fn as_ref(vectors: &[Vector4]) -> &[f32] {
+        unsafe {
+            slice::from_raw_parts(
+                vectors.as_ptr() as *const f32,
+                vectors.len() * 4)
+        }
     }
 }
 
-impl Into<(f32, f32, f32)> for Vector3 {
-    fn into(self) -> (f32, f32, f32) {
-        (self.x, self.y, self.z)
+vector_ops!(Vector4, f32, 4, { x: 0, y: 1, z: 2, w: 3 });
+signed_vector_ops!(Vector4, { x, y, z, w });
+float_vector_ops!(Vector4, { x, y, z, w });
+
+swizzles!(Vector4 => Vector2 {
+    xy: (x, y), xz: (x, z), yz: (y, z), zw: (z, w),
+});
+
+swizzles!(Vector4 => Vector3 {
+    xyz: (x, y, z), xyw: (x, y, w), xzw: (x, z, w), yzw: (y, z, w), zyx: (z, y, x),
+});
+
+swizzles!(Vector4 => Vector4 {
+    wzyx: (w, z, y, x),
+});
+
+impl From<Point> for Vector4 {
+    fn from(from: Point) -> Vector4 {
+        Vector4::new(from.x, from.y, from.z, from.w)
     }
 }
 
@@ -416,13 +555,17 @@ impl Into<(f32, f32, f32)> for Vector3 {
 // ================================================================================================
 
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
-#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+#[repr(C)]
+#[cfg_attr(feature = "serde-impls", derive(Serialize, Deserialize))]
 pub struct Vector2 {
     pub x: f32,
     pub y: f32,
 }
 
 impl Vector2 {
+    pub const ZERO: Vector2 = Vector2 { x: 0.0, y: 0.0 };
+    pub const ONE: Vector2 = Vector2 { x: 1.0, y: 1.0 };
+
     pub fn new(x: f32, y: f32) -> Vector2 {
         Vector2 {
             x: x,
@@ -446,9 +589,20 @@ impl Vector2 {
         Vector2::new(0.0, -1.0)
     }
 
-    pub fn as_ref(vectors: &[Vector2]) -> &[f32] {
-        use std::slice;
+    /// Calculates the z component of the cross product of the two vectors extended into 3D.
+    ///
+    /// The result is positive if `second` is counter-clockwise from `first`, and its magnitude is
+    /// the area of the parallelogram spanned by the two vectors.
+    pub fn cross(first: Vector2, second: Vector2) -> f32 {
+        first.x * second.y - first.y * second.x
+    }
 
+    /// Returns the vector rotated 90 degrees counter-clockwise.
+    pub fn perpendicular(self) -> Vector2 {
+        Vector2::new(-self.y, self.x)
+    }
+
+    pub fn as_ref(vectors: &[Vector2]) -> &[f32] {
         unsafe {
             slice::from_raw_parts(
                 vectors.as_ptr() as *const f32,
@@ -457,8 +611,6 @@ impl Vector2 {
     }
 
     pub fn slice_from_f32_slice(data: &[f32]) -> &[Vector2] {
-        use std::slice;
-
         assert!(data.len() % 2 == 0, "Slice must have an even number of elements to be converted to a slice of Vector2");
         unsafe {
             slice::from_raw_parts(
@@ -478,88 +630,179 @@ impl Default for Vector2 {
     }
 }
 
-impl From<[f32; 2]> for Vector2 {
-    fn from(from: [f32; 2]) -> Vector2 {
-        Vector2 {
-            x: from[0],
-            y: from[1],
-        }
-    }
+vector_ops!(Vector2, f32, 2, { x: 0, y: 1 });
+signed_vector_ops!(Vector2, { x, y });
+float_vector_ops!(Vector2, { x, y });
+
+swizzles!(Vector2 => Vector2 {
+    yx: (y, x),
+});
+
+// INTEGER VECTORS
+// ================================================================================================
+
+/// A 2-component vector of signed integers, e.g. for pixel offsets or grid coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(C)]
+#[cfg_attr(feature = "serde-impls", derive(Serialize, Deserialize))]
+pub struct IVec2 {
+    pub x: i32,
+    pub y: i32,
 }
 
-impl<'a> From<&'a [f32; 2]> for Vector2 {
-    fn from(from: &'a [f32; 2]) -> Vector2 {
-        Vector2 {
-            x: from[0],
-            y: from[1],
+impl IVec2 {
+    pub const ZERO: IVec2 = IVec2 { x: 0, y: 0 };
+    pub const ONE: IVec2 = IVec2 { x: 1, y: 1 };
+
+    pub fn new(x: i32, y: i32) -> IVec2 {
+        IVec2 {
+            x: x,
+            y: y,
         }
     }
 }
 
-impl<'a> From<&'a [f32]> for Vector2 {
-    fn from(from: &'a [f32]) -> Vector2 {
-        assert_eq!(from.len(), 2, "Wrong number of elements to convert to `Vector2`");
-        Vector2 {
-            x: from[0],
-            y: from[1],
+vector_ops!(IVec2, i32, 2, { x: 0, y: 1 });
+signed_vector_ops!(IVec2, { x, y });
+
+swizzles!(IVec2 => IVec2 {
+    yx: (y, x),
+});
+
+/// A 3-component vector of signed integers, e.g. for voxel coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(C)]
+#[cfg_attr(feature = "serde-impls", derive(Serialize, Deserialize))]
+pub struct IVec3 {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl IVec3 {
+    pub const ZERO: IVec3 = IVec3 { x: 0, y: 0, z: 0 };
+    pub const ONE: IVec3 = IVec3 { x: 1, y: 1, z: 1 };
+
+    pub fn new(x: i32, y: i32, z: i32) -> IVec3 {
+        IVec3 {
+            x: x,
+            y: y,
+            z: z,
         }
     }
 }
 
-impl Lerp for Vector2 {
-    fn lerp(t: f32, from: Vector2, to: Vector2) -> Vector2 {
-        from + (to - from) * t
-    }
+vector_ops!(IVec3, i32, 3, { x: 0, y: 1, z: 2 });
+signed_vector_ops!(IVec3, { x, y, z });
+
+swizzles!(IVec3 => IVec2 {
+    xy: (x, y), xz: (x, z), yx: (y, x), yz: (y, z), zx: (z, x), zy: (z, y),
+});
+
+swizzles!(IVec3 => IVec3 {
+    xzy: (x, z, y), yxz: (y, x, z), yzx: (y, z, x), zxy: (z, x, y), zyx: (z, y, x),
+});
+
+/// A 2-component vector of unsigned integers, e.g. for texture or window dimensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(C)]
+#[cfg_attr(feature = "serde-impls", derive(Serialize, Deserialize))]
+pub struct UVec2 {
+    pub x: u32,
+    pub y: u32,
 }
 
-impl Add for Vector2 {
-    type Output = Vector2;
+impl UVec2 {
+    pub const ZERO: UVec2 = UVec2 { x: 0, y: 0 };
+    pub const ONE: UVec2 = UVec2 { x: 1, y: 1 };
 
-    fn add(self, rhs: Vector2) -> Vector2 {
-        Vector2 {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
+    pub fn new(x: u32, y: u32) -> UVec2 {
+        UVec2 {
+            x: x,
+            y: y,
         }
     }
 }
 
-impl Sub for Vector2 {
-    type Output = Vector2;
+vector_ops!(UVec2, u32, 2, { x: 0, y: 1 });
 
-    fn sub(self, rhs: Vector2) -> Vector2 {
-        Vector2 {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
+swizzles!(UVec2 => UVec2 {
+    yx: (y, x),
+});
+
+/// A 3-component vector of unsigned integers, e.g. for grid dimensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(C)]
+#[cfg_attr(feature = "serde-impls", derive(Serialize, Deserialize))]
+pub struct UVec3 {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+
+impl UVec3 {
+    pub const ZERO: UVec3 = UVec3 { x: 0, y: 0, z: 0 };
+    pub const ONE: UVec3 = UVec3 { x: 1, y: 1, z: 1 };
+
+    pub fn new(x: u32, y: u32, z: u32) -> UVec3 {
+        UVec3 {
+            x: x,
+            y: y,
+            z: z,
         }
     }
 }
 
-impl Mul for Vector2 {
-    type Output = Vector2;
+vector_ops!(UVec3, u32, 3, { x: 0, y: 1, z: 2 });
 
-    fn mul(self, rhs: Vector2) -> Vector2 {
-        Vector2 {
-            x: self.x * rhs.x,
-            y: self.y * rhs.y,
-        }
+swizzles!(UVec3 => UVec2 {
+    xy: (x, y), xz: (x, z), yx: (y, x), yz: (y, z), zx: (z, x), zy: (z, y),
+});
+
+swizzles!(UVec3 => UVec3 {
+    xzy: (x, z, y), yxz: (y, x, z), yzx: (y, z, x), zxy: (z, x, y), zyx: (z, y, x),
+});
+
+// CONVERSIONS
+// ================================================================================================
+
+impl From<IVec2> for Vector2 {
+    fn from(from: IVec2) -> Vector2 {
+        Vector2::new(from.x as f32, from.y as f32)
     }
 }
 
-impl Mul<f32> for Vector2 {
-    type Output = Vector2;
+impl From<UVec2> for Vector2 {
+    fn from(from: UVec2) -> Vector2 {
+        Vector2::new(from.x as f32, from.y as f32)
+    }
+}
 
-    fn mul(self, rhs: f32) -> Vector2 {
-        Vector2 {
-            x: self.x * rhs,
-            y: self.y * rhs,
-        }
+impl From<IVec3> for Vector3 {
+    fn from(from: IVec3) -> Vector3 {
+        Vector3::new(from.x as f32, from.y as f32, from.z as f32)
     }
 }
 
-impl Mul<Vector2> for f32 {
-    type Output = Vector2;
+impl From<UVec3> for Vector3 {
+    fn from(from: UVec3) -> Vector3 {
+        Vector3::new(from.x as f32, from.y as f32, from.z as f32)
+    }
+}
+
+impl Vector2 {
+    /// Converts to an integer vector by rounding each component towards negative infinity.
+    ///
+    /// Use this to find the grid cell or pixel containing a point, since truncating would round
+    /// negative coordinates the wrong way.
+    pub fn floor_to_ivec2(self) -> IVec2 {
+        IVec2::new(self.x.floor() as i32, self.y.floor() as i32)
+    }
+}
 
-    fn mul(self, rhs: Vector2) -> Vector2 {
-        rhs * self
+impl Vector3 {
+    /// Converts to an integer vector by rounding each component towards negative infinity.
+    pub fn floor_to_ivec3(self) -> IVec3 {
+        IVec3::new(self.x.floor() as i32, self.y.floor() as i32, self.z.floor() as i32)
     }
 }
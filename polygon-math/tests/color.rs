@@ -0,0 +1,136 @@
+extern crate polygon_math;
+
+use polygon_math::*;
+use polygon_math::color::{self, ParseHexError};
+
+/// Asserts that each component of two colors is within a tolerance of each other.
+fn assert_color_near(expected: Color, actual: Color) {
+    let expected_array: [f32; 4] = expected.into();
+    let actual_array: [f32; 4] = actual.into();
+    for (expected_value, actual_value) in expected_array.iter().zip(actual_array.iter()) {
+        if (expected_value - actual_value).abs() > 1e-4 {
+            panic!("Colors are not equal\nexpected: {:?}\n  actual: {:?}", expected, actual);
+        }
+    }
+}
+
+fn assert_near(expected: f32, actual: f32) {
+    assert!((expected - actual).abs() < 1e-3, "expected {}, got {}", expected, actual);
+}
+
+#[test]
+fn srgb_reference_values() {
+    assert_eq!(0.0, color::srgb_to_linear(0.0));
+    assert_eq!(1.0, color::srgb_to_linear(1.0));
+    assert_near(0.214_041, color::srgb_to_linear(0.5));
+    assert_near(0.002_428, color::srgb_to_linear(0.031_372_55)); // Linear segment.
+    assert_near(0.735_357, color::linear_to_srgb(0.5));
+
+    // Every 8-bit sRGB value round trips through linear.
+    for byte in 0..256 {
+        let value = byte as f32 / 255.0;
+        let round_trip = color::linear_to_srgb(color::srgb_to_linear(value));
+        assert_eq!(byte, (round_trip * 255.0).round() as u32);
+    }
+}
+
+#[test]
+fn hex_parsing() {
+    assert_eq!(Ok(Color::from_srgba8([0xff, 0x88, 0x00, 0xff])), Color::from_hex("#ff8800"));
+    assert_eq!(Ok(Color::from_srgba8([0xff, 0x88, 0x00, 0xff])), Color::from_hex("FF8800"));
+    assert_eq!(Ok(Color::from_srgba8([0xff, 0x88, 0x00, 0xff])), Color::from_hex("#f80"));
+    assert_eq!(Ok(Color::from_srgba8([0x12, 0x34, 0x56, 0x78])), Color::from_hex("#12345678"));
+    assert_eq!(Ok(Color::from_srgba8([0xaa, 0xbb, 0xcc, 0xdd])), Color::from_hex("#abcd"));
+    assert_eq!(Ok(Color::rgb(1.0, 1.0, 1.0)), "#ffffff".parse());
+
+    assert_eq!(Err(ParseHexError::InvalidLength(5)), Color::from_hex("#12345"));
+    assert_eq!(Err(ParseHexError::InvalidLength(0)), Color::from_hex("#"));
+    assert_eq!(Err(ParseHexError::InvalidLength(9)), Color::from_hex("#123456789"));
+    assert_eq!(Err(ParseHexError::InvalidDigit('g')), Color::from_hex("#ff88g0"));
+}
+
+#[test]
+fn hex_formatting() {
+    assert_eq!("#ff8800", Color::from_hex("#ff8800").unwrap().to_hex());
+    assert_eq!("#12345678", Color::from_hex("#12345678").unwrap().to_hex());
+    assert_eq!("#000000", Color::default().to_hex());
+    assert_eq!("#ffffff", Color::rgb(2.0, 1.5, 1.0).to_hex());
+}
+
+#[test]
+fn packed_formats() {
+    let color = Color::from_packed_srgba8(0xff8800cc);
+    assert_eq!([0xff, 0x88, 0x00, 0xcc], color.to_srgba8());
+    assert_eq!(0xff8800cc, color.to_packed_srgba8());
+    assert_eq!(Color::from_hex("#ff8800cc").unwrap(), color);
+
+    // Half of the sRGB range is much less than half of the linear range.
+    assert_near(0.215_861, Color::from_srgba8([128, 128, 128, 255]).r);
+}
+
+#[test]
+fn hsv() {
+    assert_color_near(Color::rgb(1.0, 0.0, 0.0), Color::from_hsv(0.0, 1.0, 1.0));
+    assert_color_near(Color::rgb(0.0, 1.0, 0.0), Color::from_hsv(120.0, 1.0, 1.0));
+    assert_color_near(Color::rgb(0.0, 0.0, 1.0), Color::from_hsv(240.0, 1.0, 1.0));
+    assert_color_near(Color::rgb(1.0, 0.0, 0.0), Color::from_hsv(360.0, 1.0, 1.0));
+    assert_color_near(Color::rgb(1.0, 0.0, 0.0), Color::from_hsv(-360.0, 1.0, 1.0));
+    assert_color_near(Color::from_srgb(1.0, 0.5, 0.0, 1.0), Color::from_hsv(30.0, 1.0, 1.0));
+    assert_color_near(Color::from_hex("#808080").unwrap(), Color::from_hsv(200.0, 0.0, 0.502));
+
+    let (h, s, v) = Color::from_hex("#336699").unwrap().to_hsv();
+    assert_near(210.0, h);
+    assert_near(0.667, s);
+    assert_near(0.6, v);
+
+    for &(h, s, v) in &[(0.0, 0.5, 0.5), (75.0, 0.2, 0.9), (190.0, 1.0, 0.3), (300.0, 0.8, 1.0)] {
+        let (h2, s2, v2) = Color::from_hsv(h, s, v).to_hsv();
+        assert_near(h, h2);
+        assert_near(s, s2);
+        assert_near(v, v2);
+    }
+}
+
+#[test]
+fn hsl() {
+    assert_color_near(Color::rgb(1.0, 0.0, 0.0), Color::from_hsl(0.0, 1.0, 0.5));
+    assert_color_near(Color::rgb(1.0, 1.0, 1.0), Color::from_hsl(123.0, 0.5, 1.0));
+    assert_color_near(Color::rgb(0.0, 0.0, 0.0), Color::from_hsl(123.0, 0.5, 0.0));
+    assert_color_near(Color::from_hex("#336699").unwrap(), Color::from_hsl(210.0, 0.5, 0.4));
+
+    let (h, s, l) = Color::from_hex("#ff8800").unwrap().to_hsl();
+    assert_near(32.0, h);
+    assert_near(1.0, s);
+    assert_near(0.5, l);
+
+    for &(h, s, l) in &[(10.0, 0.5, 0.5), (75.0, 0.2, 0.9), (190.0, 1.0, 0.3), (300.0, 0.8, 0.6)] {
+        let (h2, s2, l2) = Color::from_hsl(h, s, l).to_hsl();
+        assert_near(h, h2);
+        assert_near(s, s2);
+        assert_near(l, l2);
+    }
+}
+
+#[test]
+fn operators() {
+    let a = Color::new(0.5, 0.25, 1.0, 1.0);
+    let b = Color::new(0.25, 0.25, 0.5, 0.5);
+
+    assert_eq!(Color::new(0.75, 0.5, 1.5, 1.5), a + b);
+    assert_eq!(Color::new(0.25, 0.0, 0.5, 0.5), a - b);
+    assert_eq!(Color::new(0.125, 0.0625, 0.5, 0.5), a * b);
+    assert_eq!(Color::new(1.0, 0.5, 2.0, 2.0), a * 2.0);
+    assert_eq!(Color::new(1.0, 0.5, 2.0, 2.0), 2.0 * a);
+    assert_eq!(Color::new(0.25, 0.125, 0.5, 0.5), a / 2.0);
+    assert_eq!(Color::new(0.375, 0.25, 0.75, 0.75), Color::lerp(0.5, a, b));
+}
+
+#[test]
+fn premultiplied_alpha() {
+    let color = Color::new(0.5, 1.0, 0.25, 0.5);
+    let premultiplied = color.premultiplied();
+    assert_eq!(Color::new(0.25, 0.5, 0.125, 0.5), premultiplied);
+    assert_eq!(color, premultiplied.unpremultiplied());
+
+    assert_eq!(Color::new(0.0, 0.0, 0.0, 0.0), Color::new(1.0, 1.0, 1.0, 0.0).premultiplied().unpremultiplied());
+}
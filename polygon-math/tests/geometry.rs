@@ -0,0 +1,419 @@
+extern crate polygon_math;
+
+use polygon_math::*;
+
+/// The number of random cases to check for each property.
+const CASES: usize = 500;
+
+/// Tolerance used when comparing results that accumulate floating point error.
+const TOLERANCE: f32 = 1e-3;
+
+/// A small xorshift random number generator so that the property tests are reproducible.
+struct Rng(u32);
+
+impl Rng {
+    fn new() -> Rng {
+        Rng(0x9E37_79B9)
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+
+    /// Generates a value in `[min, max)`.
+    fn range(&mut self, min: f32, max: f32) -> f32 {
+        let unit = (self.next_u32() >> 8) as f32 / (1 << 24) as f32;
+        min + (max - min) * unit
+    }
+
+    fn point(&mut self) -> Point {
+        Point::new(self.range(-10.0, 10.0), self.range(-10.0, 10.0), self.range(-10.0, 10.0))
+    }
+
+    fn direction(&mut self) -> Vector3 {
+        loop {
+            let vector = Vector3::new(self.range(-1.0, 1.0), self.range(-1.0, 1.0), self.range(-1.0, 1.0));
+            if vector.magnitude_squared() > 0.01 {
+                return vector.normalized();
+            }
+        }
+    }
+
+    fn aabb(&mut self) -> Aabb {
+        let center = self.point();
+        let extents = Vector3::new(self.range(0.1, 5.0), self.range(0.1, 5.0), self.range(0.1, 5.0));
+        Aabb::from_center_extents(center, extents)
+    }
+
+    fn sphere(&mut self) -> Sphere {
+        Sphere::new(self.point(), self.range(0.1, 5.0))
+    }
+
+    /// Generates a random rotation, scale, and translation.
+    fn transform(&mut self) -> Matrix4 {
+        let rotation = Matrix4::rotation(self.range(-PI, PI), self.range(-PI, PI), self.range(-PI, PI));
+        let scale = Matrix4::scale(self.range(0.5, 2.0), self.range(0.5, 2.0), self.range(0.5, 2.0));
+        Matrix4::from_point(self.point()) * rotation * scale
+    }
+
+    /// Generates a point inside the bounding box.
+    fn point_in(&mut self, aabb: &Aabb) -> Point {
+        Point::new(
+            self.range(aabb.min.x, aabb.max.x),
+            self.range(aabb.min.y, aabb.max.y),
+            self.range(aabb.min.z, aabb.max.z),
+        )
+    }
+}
+
+fn grow(aabb: &Aabb, amount: f32) -> Aabb {
+    Aabb::from_center_extents(aabb.center(), aabb.extents() + Vector3::ONE * amount)
+}
+
+#[test]
+fn aabb_from_points_contains_points() {
+    let mut rng = Rng::new();
+    for _ in 0..CASES {
+        let points = (0..8).map(|_| rng.point()).collect::<Vec<_>>();
+        let aabb = Aabb::from_points(&points).unwrap();
+        for point in &points {
+            assert!(aabb.contains_point(*point), "{:?} does not contain {:?}", aabb, point);
+        }
+    }
+
+    assert_eq!(None, Aabb::from_points(&[]));
+}
+
+#[test]
+fn aabb_merge_contains_both() {
+    let mut rng = Rng::new();
+    for _ in 0..CASES {
+        let (first, second) = (rng.aabb(), rng.aabb());
+        let merged = first.merge(&second);
+        assert!(merged.contains_aabb(&first));
+        assert!(merged.contains_aabb(&second));
+        assert_eq!(merged, second.merge(&first));
+    }
+}
+
+#[test]
+fn aabb_intersection_is_symmetric() {
+    let mut rng = Rng::new();
+    for _ in 0..CASES {
+        let (first, second) = (rng.aabb(), rng.aabb());
+        assert_eq!(first.intersects_aabb(&second), second.intersects_aabb(&first));
+
+        // Any box containing a point of another box intersects it.
+        let point = rng.point_in(&first);
+        let around = Aabb::from_center_extents(point, Vector3::ONE * 0.01);
+        assert!(first.intersects_aabb(&around));
+    }
+}
+
+#[test]
+fn aabb_transform_contains_transformed_corners() {
+    let mut rng = Rng::new();
+    for _ in 0..CASES {
+        let aabb = rng.aabb();
+        let transform = rng.transform();
+        let transformed = grow(&aabb.transform(transform), TOLERANCE);
+        for corner in aabb.corners().iter() {
+            assert!(transformed.contains_point(*corner * transform));
+        }
+    }
+}
+
+#[test]
+fn sphere_from_points_contains_points() {
+    let mut rng = Rng::new();
+    for _ in 0..CASES {
+        let points = (0..8).map(|_| rng.point()).collect::<Vec<_>>();
+        let sphere = Sphere::from_points(&points).unwrap();
+        let sphere = Sphere::new(sphere.center, sphere.radius + TOLERANCE);
+        for point in &points {
+            assert!(sphere.contains_point(*point));
+        }
+    }
+}
+
+#[test]
+fn sphere_merge_contains_both() {
+    let mut rng = Rng::new();
+    for _ in 0..CASES {
+        let (first, second) = (rng.sphere(), rng.sphere());
+        let merged = first.merge(&second);
+        let merged = Sphere::new(merged.center, merged.radius + TOLERANCE);
+        assert!(merged.contains_sphere(&first));
+        assert!(merged.contains_sphere(&second));
+    }
+}
+
+#[test]
+fn sphere_sphere_intersection() {
+    let mut rng = Rng::new();
+    for _ in 0..CASES {
+        let (first, second) = (rng.sphere(), rng.sphere());
+        let expected = first.center.distance(&second.center) <= first.radius + second.radius;
+        assert_eq!(expected, first.intersects_sphere(&second));
+        assert_eq!(expected, second.intersects_sphere(&first));
+    }
+
+    let first = Sphere::new(Point::origin(), 1.0);
+    assert!(first.intersects_sphere(&Sphere::new(Point::new(2.0, 0.0, 0.0), 1.0)));
+    assert!(!first.intersects_sphere(&Sphere::new(Point::new(2.1, 0.0, 0.0), 1.0)));
+}
+
+#[test]
+fn sphere_transform_contains_transformed_points() {
+    let mut rng = Rng::new();
+    for _ in 0..CASES {
+        let sphere = rng.sphere();
+        let transform = rng.transform();
+        let transformed = sphere.transform(transform);
+        let transformed = Sphere::new(transformed.center, transformed.radius + TOLERANCE);
+
+        let surface_point = sphere.center + rng.direction() * sphere.radius;
+        assert!(transformed.contains_point(surface_point * transform));
+    }
+}
+
+#[test]
+fn plane_signed_distance() {
+    let plane = Plane::from_point_normal(Point::new(0.0, 2.0, 0.0), Vector3::UP);
+    assert_eq!(1.0, plane.signed_distance(Point::new(5.0, 3.0, -1.0)));
+    assert_eq!(-2.0, plane.signed_distance(Point::origin()));
+    assert_eq!(Point::new(1.0, 2.0, 1.0), plane.project_point(Point::new(1.0, -4.0, 1.0)));
+
+    assert_eq!(None, Plane::from_points(Point::origin(), Point::new(1.0, 0.0, 0.0), Point::new(2.0, 0.0, 0.0)));
+}
+
+#[test]
+fn plane_transform_keeps_points_on_plane() {
+    let mut rng = Rng::new();
+    for _ in 0..CASES {
+        let plane = Plane::from_point_normal(rng.point(), rng.direction());
+        let transform = rng.transform();
+        let transformed = plane.transform(transform);
+
+        let point = plane.project_point(rng.point());
+        assert!(transformed.signed_distance(point * transform).abs() < TOLERANCE);
+    }
+}
+
+#[test]
+fn ray_triangle_hits_inside() {
+    let mut rng = Rng::new();
+    for _ in 0..CASES {
+        let triangle = Triangle::new(rng.point(), rng.point(), rng.point());
+        if triangle.area() < 1.0 {
+            continue;
+        }
+
+        // Aim at a random point inside the triangle.
+        let weights = [rng.range(0.1, 1.0), rng.range(0.1, 1.0), rng.range(0.1, 1.0)];
+        let total = weights[0] + weights[1] + weights[2];
+        let (u, v) = (weights[1] / total, weights[2] / total);
+        let target = triangle.a + (triangle.b - triangle.a) * u + (triangle.c - triangle.a) * v;
+        let origin = target + rng.direction() * rng.range(1.0, 10.0);
+        let ray = Ray::new(origin, target - origin);
+
+        if triangle.normal().dot(ray.direction).abs() < 0.05 {
+            // Skip rays that barely graze the triangle.
+            continue;
+        }
+
+        let distance = ray.intersect_triangle(&triangle).expect("Ray should hit the triangle");
+        assert!((distance - origin.distance(&target)).abs() < TOLERANCE);
+
+        let (bary_u, bary_v, bary_w) = triangle.barycentric(ray.at(distance)).unwrap();
+        assert!((bary_u + bary_v + bary_w - 1.0).abs() < TOLERANCE);
+        assert!((bary_v - u).abs() < TOLERANCE && (bary_w - v).abs() < TOLERANCE);
+
+        // Pointing the ray the other way misses.
+        let reversed = Ray::new(origin, origin - target);
+        assert_eq!(None, reversed.intersect_triangle(&triangle));
+    }
+}
+
+#[test]
+fn ray_triangle_misses_outside() {
+    let triangle = Triangle::new(
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(1.0, 0.0, 0.0),
+        Point::new(0.0, 1.0, 0.0),
+    );
+
+    let hit = Ray::new(Point::new(0.25, 0.25, 1.0), Vector3::FORWARD);
+    assert_eq!(Some(1.0), hit.intersect_triangle(&triangle));
+
+    let miss = Ray::new(Point::new(0.75, 0.75, 1.0), Vector3::FORWARD);
+    assert_eq!(None, miss.intersect_triangle(&triangle));
+
+    let parallel = Ray::new(Point::new(0.25, 0.25, 1.0), Vector3::RIGHT);
+    assert_eq!(None, parallel.intersect_triangle(&triangle));
+}
+
+#[test]
+fn ray_triangle_hits_small_triangle() {
+    // Half a millimetre across, in metres.
+    let triangle = Triangle::new(
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(0.0005, 0.0, 0.0),
+        Point::new(0.0, 0.0005, 0.0),
+    );
+
+    let hit = Ray::new(Point::new(0.0001, 0.0001, 1.0), Vector3::FORWARD);
+    assert_eq!(Some(1.0), hit.intersect_triangle(&triangle));
+
+    let miss = Ray::new(Point::new(0.0004, 0.0004, 1.0), Vector3::FORWARD);
+    assert_eq!(None, miss.intersect_triangle(&triangle));
+
+    let parallel = Ray::new(Point::new(0.0001, 0.0001, 0.0), Vector3::RIGHT);
+    assert_eq!(None, parallel.intersect_triangle(&triangle));
+
+    let degenerate = Triangle::new(triangle.a, triangle.b, triangle.b);
+    assert_eq!(None, hit.intersect_triangle(&degenerate));
+}
+
+#[test]
+fn ray_aabb_hits_inside() {
+    let mut rng = Rng::new();
+    for _ in 0..CASES {
+        let aabb = rng.aabb();
+        let target = rng.point_in(&aabb);
+        let origin = target + rng.direction() * rng.range(1.0, 20.0);
+        let ray = Ray::new(origin, target - origin);
+
+        let distance = ray.intersect_aabb(&aabb).expect("Ray should hit the box");
+        assert!(distance <= origin.distance(&target) + TOLERANCE);
+        assert!(grow(&aabb, TOLERANCE).contains_point(ray.at(distance)));
+
+        if !aabb.contains_point(origin) {
+            assert_eq!(None, Ray::new(origin, origin - target).intersect_aabb(&aabb));
+        }
+    }
+}
+
+#[test]
+fn ray_sphere() {
+    let sphere = Sphere::new(Point::new(0.0, 0.0, -5.0), 1.0);
+
+    let hit = Ray::new(Point::origin(), Vector3::FORWARD);
+    assert_eq!(Some(4.0), hit.intersect_sphere(&sphere));
+
+    let miss = Ray::new(Point::origin(), Vector3::UP);
+    assert_eq!(None, miss.intersect_sphere(&sphere));
+
+    let inside = Ray::new(Point::new(0.0, 0.0, -5.0), Vector3::UP);
+    assert_eq!(Some(0.0), inside.intersect_sphere(&sphere));
+}
+
+#[test]
+fn frustum_from_identity_is_unit_cube() {
+    let frustum = Frustum::from_matrix(Matrix4::identity());
+    assert!(frustum.contains_point(Point::origin()));
+    assert!(frustum.contains_point(Point::new(1.0, -1.0, 1.0)));
+    assert!(!frustum.contains_point(Point::new(1.1, 0.0, 0.0)));
+    assert!(!frustum.contains_point(Point::new(0.0, 0.0, -1.1)));
+}
+
+#[test]
+fn frustum_aabb() {
+    let mut rng = Rng::new();
+    for _ in 0..CASES {
+        // A view-projection matrix that maps a random box to the unit cube.
+        let volume = rng.aabb();
+        let center = volume.center();
+        let extents = volume.extents();
+        let view_projection =
+            Matrix4::scale(1.0 / extents.x, 1.0 / extents.y, 1.0 / extents.z)
+          * Matrix4::translation(-center.x, -center.y, -center.z);
+        let frustum = Frustum::from_matrix(view_projection);
+
+        let aabb = rng.aabb();
+        let expected = volume.intersects_aabb(&aabb);
+        assert_eq!(expected, frustum.intersects_aabb(&aabb), "volume: {:?}, aabb: {:?}", volume, aabb);
+        assert_eq!(volume.contains_aabb(&aabb), frustum.contains_aabb(&aabb));
+
+        let point = rng.point();
+        assert_eq!(volume.contains_point(point), frustum.contains_point(point));
+    }
+}
+
+#[test]
+fn frustum_sphere() {
+    let frustum = Frustum::from_matrix(Matrix4::identity());
+    assert!(frustum.intersects_sphere(&Sphere::new(Point::new(1.5, 0.0, 0.0), 0.6)));
+    assert!(!frustum.intersects_sphere(&Sphere::new(Point::new(1.5, 0.0, 0.0), 0.4)));
+    assert!(frustum.contains_sphere(&Sphere::new(Point::origin(), 0.5)));
+    assert!(!frustum.contains_sphere(&Sphere::new(Point::origin(), 1.5)));
+}
+
+#[test]
+fn obb_contains_transformed_aabb_points() {
+    let mut rng = Rng::new();
+    for _ in 0..CASES {
+        let aabb = rng.aabb();
+        let transform = rng.transform();
+        let obb = Obb::from_aabb(&aabb).transform(transform);
+
+        let point = rng.point_in(&aabb);
+        assert!(obb.contains_point(point * transform));
+
+        let bounds = grow(&obb.aabb(), TOLERANCE);
+        for corner in obb.corners().iter() {
+            assert!(bounds.contains_point(*corner));
+        }
+    }
+}
+
+#[test]
+fn obb_intersection() {
+    let mut rng = Rng::new();
+    for _ in 0..CASES {
+        let (first, second) = (rng.aabb(), rng.aabb());
+
+        // Axis-aligned oriented boxes behave exactly like bounding boxes.
+        let expected = first.intersects_aabb(&second);
+        assert_eq!(expected, Obb::from_aabb(&first).intersects_aabb(&second));
+
+        // Rotating both boxes by the same rotation doesn't change whether they intersect. Boxes
+        // that are barely touching are skipped since rounding error can go either way.
+        let is_close = grow(&first, 0.01).intersects_aabb(&second) != grow(&first, -0.01).intersects_aabb(&second);
+        if !is_close {
+            let rotation = Matrix4::rotation(rng.range(-PI, PI), rng.range(-PI, PI), rng.range(-PI, PI));
+            let first = Obb::from_aabb(&first).transform(rotation);
+            let second = Obb::from_aabb(&second).transform(rotation);
+            assert_eq!(expected, first.intersects_obb(&second));
+            assert_eq!(expected, second.intersects_obb(&first));
+        }
+    }
+
+    // A box rotated 45 degrees whose bounding box overlaps the unit box but that doesn't
+    // actually touch it.
+    let unit = Obb::from_aabb(&Aabb::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0)));
+    let rotated = Obb::from_aabb(&Aabb::from_center_extents(Point::origin(), Vector3::ONE * 0.5))
+        .transform(Matrix4::translation(1.6, 1.6, 0.0) * Matrix4::rotation(0.0, 0.0, PI / 4.0));
+    assert!(unit.aabb().intersects_aabb(&rotated.aabb()));
+    assert!(!unit.intersects_obb(&rotated));
+    assert!(!rotated.intersects_obb(&unit));
+}
+
+#[test]
+fn triangle_properties() {
+    let triangle = Triangle::new(
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(2.0, 0.0, 0.0),
+        Point::new(0.0, 2.0, 0.0),
+    );
+
+    assert_eq!(2.0, triangle.area());
+    assert_eq!(Vector3::BACK, triangle.normal());
+    assert_eq!(Some((1.0, 0.0, 0.0)), triangle.barycentric(triangle.a));
+    assert_eq!(Aabb::new(Point::new(0.0, 0.0, 0.0), Point::new(2.0, 2.0, 0.0)), triangle.aabb());
+    assert!(!triangle.is_degenerate());
+    assert!(Triangle::new(triangle.a, triangle.b, triangle.b).is_degenerate());
+}
@@ -0,0 +1,137 @@
+//! Checks the math kernels used by the operators against the portable scalar kernels.
+//!
+//! Without the `simd` feature the active kernels are the scalar ones, so these tests only
+//! exercise something interesting when run with `cargo test --features simd`.
+
+extern crate polygon_math;
+
+use polygon_math::*;
+use polygon_math::kernels::{self, scalar};
+
+const CASES: usize = 1000;
+
+/// A small xorshift generator so the tests are deterministic without pulling in `rand`.
+struct Rng(u32);
+
+impl Rng {
+    fn next(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        (self.0 as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+
+    fn range(&mut self, scale: f32) -> f32 {
+        self.next() * scale
+    }
+
+    fn matrix(&mut self) -> Matrix4 {
+        let mut matrix = Matrix4::new();
+        for row in 0..4 {
+            for col in 0..4 {
+                matrix[row][col] = self.range(10.0);
+            }
+        }
+        matrix
+    }
+
+    fn point(&mut self) -> Point {
+        let mut point = Point::new(self.range(100.0), self.range(100.0), self.range(100.0));
+        point.w = self.range(2.0);
+        point
+    }
+
+    fn quaternion(&mut self) -> Quaternion {
+        Quaternion::new(Vector3::new(self.next(), self.next(), self.next()), self.next())
+    }
+}
+
+/// Asserts two values agree to within a tolerance relative to their magnitude.
+fn assert_close(expected: &[f32], actual: &[f32]) {
+    for (&expected_value, &actual_value) in expected.iter().zip(actual.iter()) {
+        let tolerance = 1e-5 * expected_value.abs().max(1.0) * 100.0;
+        if (expected_value - actual_value).abs() > tolerance {
+            panic!("Kernels disagree\nexpected: {:?}\n  actual: {:?}", expected, actual);
+        }
+    }
+}
+
+#[test]
+fn mul_matrix4_matches_scalar() {
+    let mut rng = Rng(0x1234_5678);
+    for _ in 0..CASES {
+        let (first, second) = (rng.matrix(), rng.matrix());
+        let expected = scalar::mul_matrix4(&first, &second);
+        assert_close(expected.raw_data(), kernels::mul_matrix4(&first, &second).raw_data());
+        assert_close(expected.raw_data(), (first * second).raw_data());
+    }
+}
+
+#[test]
+fn transform_matches_scalar() {
+    let mut rng = Rng(0x9e37_79b9);
+    for _ in 0..CASES {
+        let matrix = rng.matrix();
+        let point = rng.point();
+        let vector = [point.x, point.y, point.z, point.w];
+
+        let expected = scalar::transform(&matrix, vector);
+        assert_close(&expected, &kernels::transform(&matrix, vector));
+
+        let transformed = point * matrix;
+        assert_close(&expected, &[transformed.x, transformed.y, transformed.z, transformed.w]);
+    }
+}
+
+#[test]
+fn transform_points_matches_scalar() {
+    let mut rng = Rng(0xdead_beef);
+    let matrix = rng.matrix();
+
+    // Use a count that isn't a multiple of 4 to catch any remainder handling bugs.
+    let points: Vec<Point> = (0..1023).map(|_| rng.point()).collect();
+
+    let mut expected = points.clone();
+    scalar::transform_points(&matrix, &mut expected);
+
+    let mut actual = points.clone();
+    matrix.transform_points(&mut actual);
+
+    assert_close(Point::as_ref(&expected), Point::as_ref(&actual));
+    for (point, transformed) in points.iter().zip(actual.iter()) {
+        let single = *point * matrix;
+        assert_close(single.as_array(), transformed.as_array());
+    }
+
+    // Empty slices are fine.
+    matrix.transform_points(&mut []);
+}
+
+#[test]
+fn mul_quaternion_matches_scalar() {
+    let mut rng = Rng(0x0bad_f00d);
+    for _ in 0..CASES {
+        let (first, second) = (rng.quaternion(), rng.quaternion());
+        let expected = scalar::mul_quaternion(first, second);
+        let actual = first * second;
+
+        assert_close(
+            &[expected.v.x, expected.v.y, expected.v.z, expected.w],
+            &[actual.v.x, actual.v.y, actual.v.z, actual.w]);
+    }
+
+    // Reference value: i * j = k.
+    let i = Quaternion::new(Vector3::RIGHT, 0.0);
+    let j = Quaternion::new(Vector3::UP, 0.0);
+    assert_eq!(Quaternion::new(Vector3::BACK, 0.0), i * j);
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+#[test]
+fn sse2_kernels_are_active() {
+    let mut rng = Rng(42);
+    let (first, second) = (rng.matrix(), rng.matrix());
+    assert_close(
+        kernels::sse2::mul_matrix4(&first, &second).raw_data(),
+        (first * second).raw_data());
+}
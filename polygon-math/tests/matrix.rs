@@ -1,6 +1,6 @@
 extern crate polygon_math;
 
-use polygon_math::matrix::Matrix4;
+use polygon_math::*;
 
 #[test]
 fn matrix_equality()
@@ -59,3 +59,181 @@ fn matrix_translation()
     assert!(translation_2[2][3] == 3.0);
     assert!(translation_2[3][3] == 1.0);
 }
+
+/// Asserts that each element of two matrices is within a tolerance of each other.
+///
+/// Inverting and decomposing matrices accumulates more error than `Matrix4`'s `PartialEq` allows
+/// for, so the tests use a looser comparison.
+fn assert_matrix_near(expected: Matrix4, actual: Matrix4) {
+    for (expected_value, actual_value) in expected.raw_data().iter().zip(actual.raw_data().iter()) {
+        if (expected_value - actual_value).abs() > 1e-4 {
+            panic!("Matrices are not equal\nexpected: {:?}\n  actual: {:?}", expected, actual);
+        }
+    }
+}
+
+/// A selection of affine transforms used by the tests below.
+fn sample_transforms() -> Vec<Matrix4> {
+    vec![
+        Matrix4::identity(),
+        Matrix4::translation(1.0, -2.0, 3.5),
+        Matrix4::rotation(0.3, -1.2, 2.5),
+        Matrix4::scale(2.0, 0.5, 3.0),
+        Matrix4::translation(-4.0, 0.25, 1.0) * Matrix4::rotation(1.0, 0.5, -0.7) * Matrix4::scale(0.5, 1.5, 2.0),
+        Matrix4::from_orientation(Orientation::from_eulers(0.1, 2.0, -1.3)) * Matrix4::scale(-1.0, 2.0, 2.0),
+    ]
+}
+
+#[test]
+fn determinant() {
+    assert_eq!(1.0, Matrix4::identity().determinant());
+    assert_eq!(3.0, Matrix4::scale(2.0, 0.5, 3.0).determinant());
+    assert_eq!(1.0, Matrix4::translation(5.0, 6.0, 7.0).determinant());
+    assert!((Matrix4::rotation(0.3, -1.2, 2.5).determinant() - 1.0).abs() < 1e-5);
+    assert_eq!(0.0, Matrix4::new().determinant());
+
+    let matrix = Matrix4::scale(2.0, 3.0, 4.0) * Matrix4::rotation(0.5, 0.5, 0.5);
+    assert!((Matrix3::from(matrix).determinant() - 24.0).abs() < 1e-4);
+
+    // Swapping two rows negates the determinant.
+    let mut swapped = matrix;
+    swapped[0] = matrix[1];
+    swapped[1] = matrix[0];
+    assert!((swapped.determinant() + matrix.determinant()).abs() < 1e-4);
+}
+
+#[test]
+fn inverse() {
+    for transform in sample_transforms() {
+        let inverse = transform.inverse().expect("Transform should be invertible");
+        assert_matrix_near(Matrix4::identity(), transform * inverse);
+        assert_matrix_near(Matrix4::identity(), inverse * transform);
+    }
+
+    // A projection matrix is invertible but not affine.
+    let projection = Matrix4::perspective(PI / 3.0, 1.5, 0.1, 100.0);
+    let inverse = projection.inverse().unwrap();
+    assert_matrix_near(Matrix4::identity(), projection * inverse);
+
+    // A hand-checked non-affine matrix.
+    let mut matrix = Matrix4::identity();
+    matrix[3][0] = 2.0;
+    let mut expected = Matrix4::identity();
+    expected[3][0] = -2.0;
+    assert_eq!(Some(expected), matrix.inverse());
+}
+
+#[test]
+fn inverse_singular() {
+    assert_eq!(None, Matrix4::new().inverse());
+    assert_eq!(None, Matrix4::scale(1.0, 0.0, 1.0).inverse());
+    assert_eq!(None, Matrix4::scale(1.0, 0.0, 1.0).affine_inverse());
+    assert_eq!(None, Matrix3::from_scale_vector(Vector3::new(1.0, 1.0, 0.0)).inverse());
+
+    let mut duplicate_rows = Matrix4::rotation(0.2, 0.4, 0.6);
+    duplicate_rows[2] = duplicate_rows[1];
+    assert_eq!(None, duplicate_rows.inverse());
+}
+
+#[test]
+fn affine_inverse_matches_inverse() {
+    for transform in sample_transforms() {
+        assert!(transform.is_affine());
+        assert_matrix_near(transform.inverse().unwrap(), transform.affine_inverse().unwrap());
+    }
+
+    assert!(!Matrix4::perspective(PI / 3.0, 1.0, 0.1, 10.0).is_affine());
+}
+
+#[test]
+fn matrix3_inverse() {
+    let matrix = Matrix3::rotation(0.4, 1.1, -0.3) * Matrix3::from_scale_vector(Vector3::new(2.0, 0.5, 4.0));
+    let inverse = matrix.inverse().unwrap();
+    assert_eq!(Matrix3::identity(), matrix * inverse);
+    assert_eq!(Matrix3::identity(), inverse * matrix);
+
+    let rotation = Matrix3::rotation(0.4, 1.1, -0.3);
+    assert_eq!(rotation.transpose(), rotation.inverse().unwrap());
+}
+
+#[test]
+fn decompose() {
+    let translation = Point::new(1.0, -2.0, 3.0);
+    let orientation = Orientation::from_eulers(0.3, -0.8, 1.9);
+    let scale = Vector3::new(2.0, 0.5, 1.5);
+
+    let matrix = Matrix4::from_point(translation) * Matrix4::from(orientation) * Matrix4::from_scale_vector(scale);
+    let (decomposed_translation, decomposed_orientation, decomposed_scale) = matrix.decompose().unwrap();
+
+    assert_eq!(translation, decomposed_translation);
+    assert!((decomposed_scale - scale).magnitude() < 1e-5);
+
+    // `q` and `-q` represent the same rotation, so compare the resulting matrices.
+    assert_matrix_near(Matrix4::from(orientation), Matrix4::from(decomposed_orientation));
+
+    // Every sample transform can be rebuilt from its components.
+    for transform in sample_transforms() {
+        let (translation, orientation, scale) = transform.decompose().unwrap();
+        let rebuilt = Matrix4::from_point(translation) * Matrix4::from(orientation) * Matrix4::from_scale_vector(scale);
+        assert_matrix_near(transform, rebuilt);
+    }
+
+    assert_eq!(None, Matrix4::scale(1.0, 1.0, 0.0).decompose());
+}
+
+#[test]
+fn look_at() {
+    let eye = Point::new(1.0, 2.0, 3.0);
+    let target = Point::new(4.0, 2.0, -1.0);
+    let view = Matrix4::look_at(eye, target, Vector3::UP);
+
+    // The eye ends up at the origin and the target straight ahead of it.
+    assert!((eye * view - Point::origin()).magnitude() < 1e-5);
+    let target_view = target * view;
+    assert!(target_view.x.abs() < 1e-5 && target_view.y.abs() < 1e-5);
+    assert!((target_view.z + 5.0).abs() < 1e-5);
+
+    // Matches the view matrix of a camera oriented to look at the target.
+    let identity_view = Matrix4::look_at(Point::origin(), Point::new(0.0, 0.0, -1.0), Vector3::UP);
+    assert_eq!(Matrix4::identity(), identity_view);
+}
+
+#[test]
+fn perspective() {
+    let (near, far) = (0.5, 50.0);
+    let projection = Matrix4::perspective(PI / 2.0, 2.0, near, far);
+
+    let project = |point: Point| {
+        let clip = projection * Vector4::from(point);
+        Vector3::new(clip.x / clip.w, clip.y / clip.w, clip.z / clip.w)
+    };
+
+    assert!((project(Point::new(0.0, 0.0, -near)).z + 1.0).abs() < 1e-5);
+    assert!((project(Point::new(0.0, 0.0, -far)).z - 1.0).abs() < 1e-4);
+
+    // With a 90 degree field of view the top of the frustum is at `y == -z`.
+    assert!((project(Point::new(0.0, 3.0, -3.0)).y - 1.0).abs() < 1e-5);
+    assert!((project(Point::new(6.0, 0.0, -3.0)).x - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn orthographic() {
+    let projection = Matrix4::orthographic(-2.0, 4.0, -1.0, 3.0, 1.0, 11.0);
+
+    let min = projection * Vector4::new(-2.0, -1.0, -1.0, 1.0);
+    let max = projection * Vector4::new(4.0, 3.0, -11.0, 1.0);
+    assert_eq!(Vector4::new(-1.0, -1.0, -1.0, 1.0), min);
+    assert_eq!(Vector4::new(1.0, 1.0, 1.0, 1.0), max);
+}
+
+#[test]
+fn multiply_vector4() {
+    let matrix = Matrix4::translation(1.0, 2.0, 3.0) * Matrix4::scale(2.0, 2.0, 2.0);
+
+    // Points (w == 1) are translated, directions (w == 0) are not.
+    assert_eq!(Vector4::new(3.0, 4.0, 5.0, 1.0), matrix * Vector4::new(1.0, 1.0, 1.0, 1.0));
+    assert_eq!(Vector4::new(2.0, 2.0, 2.0, 0.0), matrix * Vector4::new(1.0, 1.0, 1.0, 0.0));
+
+    let point = Point::new(0.5, -1.5, 2.0);
+    assert_eq!(Vector4::from(point * matrix), matrix * Vector4::from(point));
+}
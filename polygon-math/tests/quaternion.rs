@@ -0,0 +1,257 @@
+extern crate polygon_math;
+
+use polygon_math::*;
+
+/// Asserts that two floats are within a tolerance of each other.
+macro_rules! assert_near {
+    ($left: expr, $right: expr) => {
+        {
+            let (left, right) = ($left, $right);
+            if (left - right).abs() > 1e-4 {
+                panic!("assertion failed: `(left ~= right)`\n  left: `{:?}`,\n right: `{:?}`", left, right);
+            }
+        }
+    };
+}
+
+/// Asserts that two quaternions represent the same rotation.
+///
+/// `q` and `-q` represent the same rotation, so the comparison ignores the sign.
+fn assert_same_rotation(expected: Quaternion, actual: Quaternion) {
+    let dot = Quaternion::dot(expected, actual).abs();
+    if (dot - 1.0).abs() > 1e-4 {
+        panic!("Rotations are not equal\nexpected: {:?}\n  actual: {:?}", expected, actual);
+    }
+}
+
+fn axis_angle(x: f32, y: f32, z: f32, angle: f32) -> Quaternion {
+    Orientation::axis_angle(Vector3::new(x, y, z).normalized(), angle).0
+}
+
+#[test]
+fn nlerp() {
+    let from = Quaternion::identity();
+    let to = axis_angle(0.0, 1.0, 0.0, PI * 0.5);
+
+    assert_same_rotation(from, Quaternion::nlerp(from, to, 0.0));
+    assert_same_rotation(to, Quaternion::nlerp(from, to, 1.0));
+    assert!(Quaternion::nlerp(from, to, 0.3).is_normalized());
+
+    // Symmetric inputs meet exactly in the middle.
+    assert_same_rotation(axis_angle(0.0, 1.0, 0.0, PI * 0.25), Quaternion::nlerp(from, to, 0.5));
+
+    // The result is the same whether or not the target is negated.
+    assert_same_rotation(Quaternion::nlerp(from, to, 0.3), Quaternion::nlerp(from, to * -1.0, 0.3));
+}
+
+#[test]
+fn slerp_reference_values() {
+    let from = Quaternion::identity();
+    let to = axis_angle(1.0, 2.0, 3.0, 2.0);
+
+    for &t in &[0.0, 0.1, 0.25, 0.5, 0.8, 1.0] {
+        // Slerping from the identity about a single axis scales the angle linearly.
+        assert_same_rotation(axis_angle(1.0, 2.0, 3.0, 2.0 * t), Quaternion::slerp(from, to, t));
+    }
+
+    // Reference value for a slerp between two arbitrary rotations.
+    let from = axis_angle(1.0, 0.0, 0.0, PI * 0.5);
+    let to = axis_angle(0.0, 0.0, 1.0, PI * 0.5);
+    let half = Quaternion::slerp(from, to, 0.5);
+    let expected = Quaternion::new(Vector3::new(0.408_248_3, 0.0, 0.408_248_3), 0.816_496_6);
+    assert_same_rotation(expected, half);
+    assert_near!(Quaternion::angle_between(from, half), Quaternion::angle_between(half, to));
+}
+
+#[test]
+fn slerp_constant_velocity() {
+    let from = axis_angle(0.3, -1.0, 0.5, 0.4);
+    let to = axis_angle(-0.7, 0.2, 1.0, 2.1);
+    let total = Quaternion::angle_between(from, to);
+
+    for step in 0..=10 {
+        let t = step as f32 / 10.0;
+        let result = Quaternion::slerp(from, to, t);
+        assert!(result.is_normalized());
+        assert_near!(total * t, Quaternion::angle_between(from, result));
+    }
+}
+
+#[test]
+fn slerp_shortest_path() {
+    // A 270 degree rotation is the same as a -90 degree one, so halfway should be -45 degrees.
+    let from = Quaternion::identity();
+    let to = axis_angle(0.0, 0.0, 1.0, PI * 1.5);
+    assert_same_rotation(axis_angle(0.0, 0.0, 1.0, -PI * 0.25), Quaternion::slerp(from, to, 0.5));
+
+    let to_negated = to * -1.0;
+    assert_same_rotation(Quaternion::slerp(from, to, 0.3), Quaternion::slerp(from, to_negated, 0.3));
+}
+
+#[test]
+fn slerp_nearly_identical() {
+    let from = axis_angle(0.0, 1.0, 0.0, 1.0);
+    let to = axis_angle(0.0, 1.0, 0.0, 1.0 + 1e-5);
+
+    let result = Quaternion::slerp(from, to, 0.5);
+    assert!(result.is_normalized());
+    assert_same_rotation(from, result);
+}
+
+#[test]
+fn ln_exp_round_trip() {
+    let rotation = axis_angle(0.2, 0.9, -0.4, 1.3);
+    let log = rotation.ln();
+    assert_eq!(0.0, log.w);
+    assert_near!(0.65, log.v.magnitude());
+    assert_same_rotation(rotation, log.exp());
+
+    assert_eq!(Quaternion::identity(), Quaternion::identity().ln().exp());
+}
+
+#[test]
+fn squad_endpoints() {
+    let keys = [
+        axis_angle(1.0, 0.0, 0.0, 0.0),
+        axis_angle(1.0, 1.0, 0.0, 0.8),
+        axis_angle(0.0, 1.0, 1.0, 1.6),
+        axis_angle(1.0, 0.0, 1.0, 2.0),
+    ];
+
+    let first_control = Quaternion::squad_control_point(keys[0], keys[1], keys[2]);
+    let second_control = Quaternion::squad_control_point(keys[1], keys[2], keys[3]);
+
+    assert_same_rotation(keys[1], Quaternion::squad(keys[1], keys[2], first_control, second_control, 0.0));
+    assert_same_rotation(keys[2], Quaternion::squad(keys[1], keys[2], first_control, second_control, 1.0));
+    assert!(Quaternion::squad(keys[1], keys[2], first_control, second_control, 0.4).is_normalized());
+}
+
+#[test]
+fn squad_antipodal_keys() {
+    // `q` and `-q` are the same rotation, but squad interpolates them as given, passing through
+    // the great circle between them.
+    let first = axis_angle(1.0, 2.0, 3.0, 0.7);
+    let second = first * -1.0;
+
+    assert_same_rotation(first, Quaternion::squad(first, second, first, second, 0.0));
+    assert_same_rotation(second, Quaternion::squad(first, second, first, second, 1.0));
+    for &t in &[0.25, 0.5, 0.75] {
+        let result = Quaternion::squad(first, second, first, second, t);
+        assert!(result.is_normalized(), "{:?} isn't normalized at t = {}", result, t);
+    }
+}
+
+#[test]
+fn squad_single_axis() {
+    // When every keyframe rotates about the same axis at an even spacing the control points
+    // coincide with the keyframes and squad reduces to slerp.
+    let keys: Vec<_> = (0..4).map(|index| axis_angle(0.0, 1.0, 0.0, index as f32 * 0.5)).collect();
+    let first_control = Quaternion::squad_control_point(keys[0], keys[1], keys[2]);
+    let second_control = Quaternion::squad_control_point(keys[1], keys[2], keys[3]);
+
+    assert_same_rotation(keys[1], first_control);
+    assert_same_rotation(keys[2], second_control);
+
+    for &t in &[0.25, 0.5, 0.75] {
+        assert_same_rotation(
+            Quaternion::slerp(keys[1], keys[2], t),
+            Quaternion::squad(keys[1], keys[2], first_control, second_control, t));
+    }
+}
+
+#[test]
+fn squad_is_continuous_across_keyframes() {
+    let keys = [
+        Orientation::from_eulers(0.0, 0.0, 0.0),
+        Orientation::from_eulers(0.5, 0.2, 0.0),
+        Orientation::from_eulers(0.6, 1.0, 0.4),
+        Orientation::from_eulers(0.1, 1.5, 1.0),
+        Orientation::from_eulers(-0.3, 1.7, 1.2),
+    ];
+
+    // The angular velocity just before and just after the middle keyframe should match.
+    let dt = 1e-2;
+    let before = Orientation::squad(keys[0], keys[1], keys[2], keys[3], 1.0 - dt);
+    let after = Orientation::squad(keys[1], keys[2], keys[3], keys[4], dt);
+
+    let incoming = keys[2].angle_between(before);
+    let outgoing = keys[2].angle_between(after);
+    assert!((incoming - outgoing).abs() < incoming * 0.1, "{} vs {}", incoming, outgoing);
+}
+
+#[test]
+fn angle_between() {
+    let identity = Quaternion::identity();
+    assert_near!(0.0, Quaternion::angle_between(identity, identity));
+    assert_near!(1.2, Quaternion::angle_between(identity, axis_angle(1.0, 1.0, 0.0, 1.2)));
+    assert_near!(PI, Quaternion::angle_between(identity, axis_angle(0.0, 0.0, 1.0, PI)));
+
+    // The short way around is always reported.
+    assert_near!(PI * 0.5, Quaternion::angle_between(identity, axis_angle(0.0, 0.0, 1.0, PI * 1.5)));
+
+    let first = Orientation::from_eulers(0.3, 0.2, 0.1);
+    let second = first + Orientation::axis_angle(Vector3::UP, 0.7);
+    assert_near!(0.7, first.angle_between(second));
+    assert_near!(0.7, second.angle_between(first));
+}
+
+#[test]
+fn orientation_between() {
+    let pairs = [
+        (Vector3::FORWARD, Vector3::RIGHT),
+        (Vector3::new(1.0, 2.0, 3.0), Vector3::new(-3.0, 0.5, 1.0)),
+        (Vector3::UP, Vector3::UP * 4.0),
+        (Vector3::UP, Vector3::DOWN),
+        (Vector3::RIGHT, Vector3::LEFT),
+        (Vector3::new(1.0, 1.0, 0.0), Vector3::new(-1.0, -1.0, 0.0)),
+    ];
+
+    for &(from, to) in &pairs {
+        let rotation = Orientation::between(from, to);
+        assert!(rotation.0.is_normalized());
+
+        let rotated = rotation * from.normalized();
+        assert!((rotated - to.normalized()).magnitude() < 1e-4, "{:?} -> {:?} gave {:?}", from, to, rotated);
+
+        // The rotation is the smallest one possible, so its angle is the angle between the vectors.
+        let expected_angle = f32::atan2(Vector3::cross(from, to).magnitude(), from.dot(to));
+        assert_near!(expected_angle, rotation.angle_between(Orientation::new()));
+    }
+}
+
+#[test]
+fn rotate_towards() {
+    let from = Orientation::new();
+    let target = Orientation::axis_angle(Vector3::UP, 1.0);
+
+    // A small step moves exactly `max_angle` towards the target.
+    let stepped = from.rotate_towards(target, 0.25);
+    assert_same_rotation(Orientation::axis_angle(Vector3::UP, 0.25).0, stepped.0);
+    assert_near!(0.75, stepped.angle_between(target));
+
+    // A step larger than the remaining angle snaps to the target.
+    assert_eq!(target, from.rotate_towards(target, 2.0));
+    assert_eq!(target, target.rotate_towards(target, 0.1));
+
+    // A negative step rotates away from the target, but never further than the antipode.
+    let away = from.rotate_towards(target, -0.5);
+    assert_near!(1.5, away.angle_between(target));
+    let far_away = from.rotate_towards(target, -10.0);
+    assert_near!(PI, far_away.angle_between(target));
+
+    // Repeated steps converge on the target.
+    let mut current = Orientation::from_eulers(1.0, -2.0, 0.5);
+    let target = Orientation::from_eulers(-0.5, 0.7, 2.0);
+    for _ in 0..100 {
+        current = current.rotate_towards(target, 0.1);
+    }
+    assert_eq!(target, current);
+}
+
+#[test]
+fn orientation_slerp_matches_lerp_trait() {
+    let from = Orientation::from_eulers(0.1, 0.2, 0.3);
+    let to = Orientation::from_eulers(1.0, -0.5, 0.2);
+    assert_eq!(Orientation::slerp(from, to, 0.4), Orientation::lerp(0.4, from, to));
+    assert_same_rotation(Orientation::nlerp(from, to, 0.0).0, from.0);
+}
@@ -0,0 +1,158 @@
+extern crate polygon_math;
+
+use polygon_math::*;
+
+/// Asserts that two points are within a tolerance of each other.
+fn assert_point_near(expected: Point, actual: Point) {
+    if (expected - actual).magnitude() > 1e-4 {
+        panic!("Points are not equal\nexpected: {:?}\n  actual: {:?}", expected, actual);
+    }
+}
+
+fn assert_vector_near(expected: Vector3, actual: Vector3) {
+    if (expected - actual).magnitude() > 1e-4 {
+        panic!("Vectors are not equal\nexpected: {:?}\n  actual: {:?}", expected, actual);
+    }
+}
+
+fn sample_transform() -> Transform {
+    Transform::from_parts(
+        Point::new(1.0, -2.0, 3.0),
+        Orientation::from_eulers(0.4, -1.1, 0.7),
+        Vector3::new(2.0, 0.5, 1.5),
+    )
+}
+
+fn sample_points() -> Vec<Point> {
+    vec![
+        Point::origin(),
+        Point::new(1.0, 0.0, 0.0),
+        Point::new(-3.0, 2.5, 0.25),
+        Point::new(10.0, -7.0, 4.0),
+    ]
+}
+
+#[test]
+fn identity() {
+    let identity = Transform::new();
+    assert_eq!(Matrix4::identity(), identity.matrix());
+    assert_eq!(Transform::default(), identity);
+
+    let point = Point::new(1.0, 2.0, 3.0);
+    assert_eq!(point, identity.transform_point(point));
+    assert_eq!(identity, identity * identity);
+}
+
+#[test]
+fn matches_matrix() {
+    let transform = sample_transform();
+    let matrix = Matrix4::from(transform);
+
+    for point in sample_points() {
+        assert_point_near(point * matrix, transform.transform_point(point));
+        assert_point_near(point * matrix, transform * point);
+        assert_vector_near(point.as_vector3() * matrix, transform.transform_vector(point.as_vector3()));
+    }
+
+    // The inverse matrix undoes the transform exactly, even with non-uniform scale.
+    let inverse = transform.inverse_matrix();
+    for point in sample_points() {
+        assert_point_near(point, (point * matrix) * inverse);
+        assert_point_near(point, transform.inverse_transform_point(transform.transform_point(point)));
+    }
+}
+
+#[test]
+fn from_matrix_round_trip() {
+    let transform = sample_transform();
+    let decomposed = Transform::from_matrix(transform.matrix()).unwrap();
+
+    assert_point_near(transform.position, decomposed.position);
+    assert_vector_near(transform.scale, decomposed.scale);
+    for point in sample_points() {
+        assert_point_near(transform.transform_point(point), decomposed.transform_point(point));
+    }
+
+    assert_eq!(None, Transform::from_matrix(Matrix4::scale(1.0, 0.0, 1.0)));
+}
+
+#[test]
+fn composition() {
+    let parent = Transform::from_parts(
+        Point::new(5.0, 0.0, -1.0),
+        Orientation::axis_angle(Vector3::UP, 0.8),
+        Vector3::new(2.0, 2.0, 2.0),
+    );
+    let child = sample_transform();
+    let combined = parent * child;
+
+    for point in sample_points() {
+        assert_point_near(parent.transform_point(child.transform_point(point)), combined.transform_point(point));
+    }
+
+    let mut accumulated = parent;
+    accumulated *= child;
+    assert_eq!(combined, accumulated);
+}
+
+#[test]
+fn inverse() {
+    // With uniform scale the inverse is exact.
+    let transform = Transform::from_parts(
+        Point::new(1.0, -2.0, 3.0),
+        Orientation::from_eulers(0.4, -1.1, 0.7),
+        Vector3::new(1.5, 1.5, 1.5),
+    );
+    let inverse = transform.inverse();
+
+    for point in sample_points() {
+        assert_point_near(point, inverse.transform_point(transform.transform_point(point)));
+        assert_point_near(point, (transform * inverse).transform_point(point));
+    }
+
+    let product = (inverse * transform).matrix();
+    for (actual, expected) in product.raw_data().iter().zip(Matrix4::identity().raw_data().iter()) {
+        assert!((actual - expected).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn transform_normal() {
+    // A plane tilted 45 degrees is squashed along y, so its normal tilts further towards y.
+    let transform = Transform::from_scale(Vector3::new(1.0, 0.5, 1.0));
+    let normal = Vector3::new(1.0, 1.0, 0.0).normalized();
+    let tangent = Vector3::new(1.0, -1.0, 0.0);
+
+    let transformed_normal = transform.transform_normal(normal);
+    let transformed_tangent = transform.transform_vector(tangent);
+    assert!(transformed_normal.is_normalized());
+    assert!(transformed_normal.dot(transformed_tangent).abs() < 1e-5);
+
+    // Matches the normal matrix.
+    let transform = sample_transform();
+    let expected = (normal * transform.normal_matrix()).normalized();
+    assert_vector_near(expected, transform.transform_normal(normal));
+}
+
+#[test]
+fn interpolation() {
+    let from = Transform::from_parts(Point::new(0.0, 0.0, 0.0), Orientation::new(), Vector3::ONE);
+    let to = Transform::from_parts(
+        Point::new(2.0, 4.0, -6.0),
+        Orientation::axis_angle(Vector3::UP, PI * 0.5),
+        Vector3::new(3.0, 3.0, 1.0),
+    );
+
+    let halfway = Transform::slerp(from, to, 0.5);
+    assert_point_near(Point::new(1.0, 2.0, -3.0), halfway.position);
+    assert_vector_near(Vector3::new(2.0, 2.0, 1.0), halfway.scale);
+    assert!((halfway.orientation.angle_between(Orientation::axis_angle(Vector3::UP, PI * 0.25))).abs() < 1e-4);
+
+    // For symmetric endpoints nlerp and slerp agree at the midpoint.
+    let halfway_nlerp = Transform::lerp(from, to, 0.5);
+    assert!(halfway_nlerp.orientation.angle_between(halfway.orientation) < 1e-4);
+
+    assert_eq!(from, Transform::slerp(from, to, 0.0));
+    assert_point_near(to.position, Transform::slerp(from, to, 1.0).position);
+    assert_eq!(Transform::slerp(from, to, 0.3), Lerp::lerp(0.3, from, to));
+}
@@ -0,0 +1,152 @@
+extern crate polygon_math;
+
+use polygon_math::*;
+
+#[test]
+fn vector2_operators() {
+    let a = Vector2::new(1.0, -2.0);
+    let b = Vector2::new(4.0, 0.5);
+
+    assert_eq!(Vector2::new(5.0, -1.5), a + b);
+    assert_eq!(Vector2::new(-3.0, -2.5), a - b);
+    assert_eq!(Vector2::new(4.0, -1.0), a * b);
+    assert_eq!(Vector2::new(0.25, -4.0), a / b);
+    assert_eq!(Vector2::new(2.0, -4.0), a * 2.0);
+    assert_eq!(Vector2::new(2.0, -4.0), 2.0 * a);
+    assert_eq!(Vector2::new(0.5, -1.0), a / 2.0);
+    assert_eq!(Vector2::new(2.0, -1.0), 2.0 / a);
+    assert_eq!(Vector2::new(-1.0, 2.0), -a);
+    assert_eq!(3.0, a.dot(b));
+
+    let mut c = a;
+    c += b;
+    c -= a;
+    c *= 2.0;
+    c /= b;
+    assert_eq!(Vector2::new(2.0, 2.0), c);
+}
+
+#[test]
+fn vector2_magnitude() {
+    let mut vector = Vector2::new(3.0, 4.0);
+    assert_eq!(5.0, vector.magnitude());
+    assert_eq!(25.0, vector.magnitude_squared());
+
+    assert_eq!(5.0, vector.normalize());
+    assert!(vector.is_normalized());
+    assert_eq!(Vector2::new(0.6, 0.8), vector);
+
+    // Normalizing the zero vector leaves it unchanged.
+    assert_eq!(Vector2::ZERO, Vector2::ZERO.normalized());
+
+    assert_eq!(-2.0, Vector2::cross(Vector2::new(1.0, 2.0), Vector2::new(3.0, 4.0)));
+    assert_eq!(Vector2::up(), Vector2::right().perpendicular());
+}
+
+#[test]
+fn vector3_operators() {
+    let a = Vector3::new(1.0, 2.0, -4.0);
+    let b = Vector3::new(2.0, -1.0, 2.0);
+
+    assert_eq!(Vector3::new(0.5, -2.0, -2.0), a / b);
+    assert_eq!(Vector3::new(2.0, 1.0, -0.5), 2.0 / a);
+
+    let mut c = a;
+    c /= b;
+    assert_eq!(a / b, c);
+
+    assert_eq!(Vector3::new(1.0, 2.0, -4.0), Vector3::from([1.0, 2.0, -4.0]));
+    assert_eq!(Vector3::new(1.0, 2.0, -4.0), Vector3::from((1.0, 2.0, -4.0)));
+    assert_eq!([1.0, 2.0, -4.0], <[f32; 3]>::from(a));
+    assert_eq!((1.0, 2.0, -4.0), a.into());
+}
+
+#[test]
+fn vector4_operators() {
+    let a = Vector4::new(1.0, 2.0, 3.0, 4.0);
+    let b = Vector4::splat(2.0);
+
+    assert_eq!(Vector4::new(3.0, 4.0, 5.0, 6.0), a + b);
+    assert_eq!(Vector4::new(-1.0, 0.0, 1.0, 2.0), a - b);
+    assert_eq!(Vector4::new(0.5, 1.0, 1.5, 2.0), a / b);
+    assert_eq!(Vector4::new(-1.0, -2.0, -3.0, -4.0), -a);
+    assert_eq!(20.0, a.dot(b));
+    assert_eq!(30.0, a.magnitude_squared());
+    assert_eq!(4.0, a[3]);
+
+    assert_eq!(Vector4::new(1.5, 2.0, 2.5, 3.0), Vector4::lerp(0.5, a, b));
+    assert_eq!(Vector4::from_vector3(Vector3::new(1.0, 2.0, 3.0), 4.0), a);
+}
+
+#[test]
+fn component_wise_functions() {
+    let a = Vector3::new(1.5, -2.5, 3.0);
+    let b = Vector3::new(-1.0, 0.5, 3.5);
+
+    assert_eq!(Vector3::new(-1.0, -2.5, 3.0), a.min(b));
+    assert_eq!(Vector3::new(1.5, 0.5, 3.5), a.max(b));
+    assert_eq!(Vector3::new(1.5, 2.5, 3.0), a.abs());
+    assert_eq!(Vector3::new(1.0, -3.0, 3.0), a.floor());
+    assert_eq!(Vector3::new(2.0, -2.0, 3.0), a.ceil());
+    assert_eq!(Vector3::new(2.0, -3.0, 3.0), a.round());
+
+    assert_eq!(IVec3::new(1, -3, 3), a.floor_to_ivec3());
+    assert_eq!(IVec2::new(-1, 0), Vector2::new(-0.5, 0.5).floor_to_ivec2());
+
+    assert_eq!(IVec2::new(-3, 1), IVec2::new(-3, 4).min(IVec2::new(2, 1)));
+    assert_eq!(UVec2::new(2, 4), UVec2::new(0, 4).max(UVec2::new(2, 1)));
+    assert_eq!(IVec2::new(3, 4), IVec2::new(-3, 4).abs());
+}
+
+#[test]
+fn integer_vectors() {
+    let a = IVec2::new(7, -3);
+    let b = IVec2::new(2, 2);
+
+    assert_eq!(IVec2::new(9, -1), a + b);
+    assert_eq!(IVec2::new(5, -5), a - b);
+    assert_eq!(IVec2::new(14, -6), a * b);
+    assert_eq!(IVec2::new(3, -1), a / b);
+    assert_eq!(IVec2::new(-7, 3), -a);
+    assert_eq!(8, a.dot(b));
+
+    let size = UVec2::new(640, 480);
+    assert_eq!(UVec2::new(320, 240), size / 2);
+    assert_eq!(Vector2::new(640.0, 480.0), Vector2::from(size));
+
+    let cell = IVec3::new(1, 2, 3);
+    assert_eq!(IVec3::new(2, 4, 6), cell * 2);
+    assert_eq!(Vector3::new(1.0, 2.0, 3.0), Vector3::from(cell));
+    assert_eq!([1, 2, 3], cell.into_array());
+    assert_eq!(UVec3::new(4, 5, 6), UVec3::from([4, 5, 6]));
+
+    // Integer vectors can be used as map keys.
+    let mut cells = std::collections::HashSet::new();
+    cells.insert(IVec2::new(1, 1));
+    assert!(cells.contains(&IVec2::new(1, 1)));
+}
+
+#[test]
+fn swizzles() {
+    let vector = Vector3::new(1.0, 2.0, 3.0);
+    assert_eq!(Vector2::new(1.0, 3.0), vector.xz());
+    assert_eq!(Vector2::new(3.0, 2.0), vector.zy());
+    assert_eq!(Vector3::new(3.0, 2.0, 1.0), vector.zyx());
+    assert_eq!(Vector3::new(2.0, 3.0, 1.0), vector.yzx());
+
+    let vector = Vector4::new(1.0, 2.0, 3.0, 4.0);
+    assert_eq!(Vector3::new(1.0, 2.0, 3.0), vector.xyz());
+    assert_eq!(Vector2::new(3.0, 4.0), vector.zw());
+    assert_eq!(Vector4::new(4.0, 3.0, 2.0, 1.0), vector.wzyx());
+
+    assert_eq!(Vector2::new(2.0, 1.0), Vector2::new(1.0, 2.0).yx());
+    assert_eq!(IVec2::new(3, 1), IVec3::new(1, 2, 3).zx());
+    assert_eq!(UVec3::new(3, 1, 2), UVec3::new(1, 2, 3).zxy());
+}
+
+#[test]
+#[should_panic]
+fn index_out_of_bounds() {
+    let vector = Vector2::new(1.0, 2.0);
+    let _ = vector[2];
+}
@@ -1,91 +1,107 @@
 use math::*;
 
+/// A position, orientation, and scale in the scene that cameras, lights, and meshes can attach to.
+///
+/// An anchor wraps a `Transform`, which does the actual math. The anchor's transform converts
+/// from the local space of anything attached to it to world space.
 #[derive(Debug)]
 pub struct Anchor {
-    position: Point,
-    orientation: Orientation,
-    scale: Vector3,
+    transform: Transform,
 }
 
 impl Anchor {
     /// Creates a new anchor.
     pub fn new() -> Anchor {
         Anchor {
-            position: Point::origin(),
-            orientation: Orientation::new(),
-            scale: Vector3::ONE
+            transform: Transform::new(),
         }
     }
 
+    /// Creates a new anchor with the given transform.
+    pub fn from_transform(transform: Transform) -> Anchor {
+        Anchor {
+            transform: transform,
+        }
+    }
+
+    /// Gets the current transform of the anchor.
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    /// Sets the transform of the anchor.
+    pub fn set_transform(&mut self, transform: Transform) {
+        self.transform = transform;
+    }
+
+    /// Gets a mutable reference to the anchor's transform.
+    pub fn transform_mut(&mut self) -> &mut Transform {
+        &mut self.transform
+    }
+
     /// Gets the current position of the anchor.
     pub fn position(&self) -> Point {
-        self.position
+        self.transform.position
     }
 
     /// Sets the position of the anchor.
     pub fn set_position(&mut self, position: Point) {
-        self.position = position;
+        self.transform.position = position;
     }
 
     /// Gets the current orientation of the anchor.
     pub fn orientation(&self) -> Orientation {
-        self.orientation
+        self.transform.orientation
     }
 
     /// Sets the orientation of the anchor.
     pub fn set_orientation(&mut self, orientation: Orientation) {
-        self.orientation = orientation;
+        self.transform.orientation = orientation;
     }
 
     /// Gets the current scale of the anchor.
     pub fn scale(&self) -> Vector3 {
-        self.scale
+        self.transform.scale
     }
 
     /// Sets the scale of the anchor.
     pub fn set_scale(&mut self, scale: Vector3) {
-        self.scale = scale;
+        self.transform.scale = scale;
     }
 
     /// Calculates the matrix to convert from object space to world space.
     pub fn matrix(&self) -> Matrix4 {
-        let position = Matrix4::from_point(self.position);
-        let orientation = Matrix4::from(self.orientation);
-        let scale = Matrix4::from_scale_vector(self.scale);
-
-        position * (orientation * scale)
+        self.transform.matrix()
     }
 
     /// Calculates the matrix used to convert normals from object space to world space.
     pub fn normal_matrix(&self) -> Matrix3 {
-        let inv_scale = Matrix3::from_scale_vector(1.0 / self.scale);
-        let orientation: Matrix3 = self.orientation.into();
-        let inv_rotation = orientation.transpose();
-        // let inv_translation = Matrix3::from_point(-self.position);
-
-        let inverse = inv_scale * (inv_rotation);// * inv_translation);
-        inverse.transpose()
+        self.transform.normal_matrix()
     }
 
     /// Calculates the view transform for the camera.
     ///
     /// The view transform the matrix that converts from world coordinates to camera coordinates.
+    /// Cameras ignore the anchor's scale.
     pub fn view_matrix(&self) -> Matrix4 {
-        let inv_orientation = Matrix4::from(self.orientation).transpose();
-        let inv_translation = Matrix4::translation(
-            -self.position.x,
-            -self.position.y,
-            -self.position.z);
-        inv_orientation * inv_translation
+        self.camera_transform().inverse_matrix()
     }
 
     /// Calculates the inverse view matrix.
     pub fn inverse_view_matrix(&self) -> Matrix4 {
-        Matrix4::from_point(self.position) * self.orientation.into()
+        self.camera_transform().matrix()
+    }
+
+    /// Gets the anchor's transform without its scale, as used by cameras.
+    fn camera_transform(&self) -> Transform {
+        Transform::from_parts(self.transform.position, self.transform.orientation, Vector3::ONE)
     }
 }
 
 /// Identifies an achor that has been registered with the renderer.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
-pub struct AnchorId(usize);
-derive_Counter!(AnchorId);
+pub struct AnchorId {
+    index: u32,
+    generation: u32,
+}
+derive_Handle!(AnchorId);
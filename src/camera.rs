@@ -31,16 +31,22 @@ impl Camera
     /// The projection matrix is the matrix that converts from camera space to
     /// clip space. This effectively converts the viewing frustrum into a unit cube.
     pub fn projection_matrix(&self) -> Matrix4 {
-        let height = 2.0 * self.near * (self.fov * 0.5).tan();
-        let width = self.aspect * height;
-
-        let mut projection = Matrix4::new();
-        projection[0][0] = 2.0 * self.near / width;
-        projection[1][1] = 2.0 * self.near / height;
-        projection[2][2] = -(self.far + self.near) / (self.far - self.near);
-        projection[2][3] = -2.0 * self.far * self.near / (self.far - self.near);
-        projection[3][2] = -1.0;
-        projection
+        Matrix4::perspective(self.fov, self.aspect, self.near, self.far)
+    }
+
+    /// Calculates how much of the screen the sphere covers when seen from `camera_position`.
+    ///
+    /// The result is the height of the sphere's projection as a fraction of the viewport's
+    /// height, so a sphere that exactly fills the view vertically has a screen size of 1.0.
+    /// Spheres containing the camera have an infinite screen size. The sphere is treated as if
+    /// it were centered on screen, which slightly underestimates spheres near the edges.
+    pub fn screen_size(&self, sphere: &Sphere, camera_position: Point) -> f32 {
+        let distance = camera_position.distance(&sphere.center);
+        if distance <= sphere.radius {
+            return f32::INFINITY;
+        }
+
+        sphere.radius / (distance * (self.fov * 0.5).tan())
     }
 
     pub fn anchor(&self) -> Option<AnchorId> {
@@ -91,5 +97,8 @@ impl Default for Camera {
 
 /// Identifies an achor that has been registered with the renderer.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
-pub struct CameraId(usize);
-derive_Counter!(CameraId);
+pub struct CameraId {
+    index: u32,
+    generation: u32,
+}
+derive_Handle!(CameraId);
@@ -0,0 +1,623 @@
+//! A compact binary format for caching meshes.
+//!
+//! Parsing text formats like OBJ can be slow for large meshes, so meshes can be written out in a
+//! binary form that is quick to load with `Mesh::write_to()` and read back with
+//! `Mesh::read_from()` or `Mesh::from_bytes()`. The cached mesh is identical to the original,
+//! including its vertex layout, index size, and bounds.
+//!
+//! # Format
+//!
+//! All values are little endian. A file starts with a 64 byte header:
+//!
+//! | Offset | Size | Contents                                             |
+//! |--------|------|------------------------------------------------------|
+//! | 0      | 8    | The magic bytes `MAGIC`.                             |
+//! | 8      | 4    | The format version, currently `VERSION`.             |
+//! | 12     | 4    | Flags, bit 0 is set if the payload is compressed.    |
+//! | 16     | 8    | The size of the payload once decompressed.           |
+//! | 24     | 8    | The size of the payload as stored in the file.       |
+//! | 32     | 4    | The Adler-32 checksum of the stored payload.         |
+//! | 36     | 28   | Reserved, must be zero.                              |
+//!
+//! The payload follows the header and contains the mesh description (vertex count, layout,
+//! index count and size, bounds, attributes, and submeshes), followed by the vertex data and then
+//! the index data. The vertex and index data each start on a 16 byte boundary, so an uncompressed
+//! cache file can be memory mapped and its vertex and index data used in place.
+//!
+//! Compressed payloads use a simple LZ77 scheme in the style of LZ4. Floating point vertex data
+//! doesn't compress especially well, so the savings are modest (around 15% for a typical scanned
+//! mesh) and come at the cost of the data no longer being usable in place.
+
+use geometry::mesh::*;
+use math::*;
+use std::io::{Error as IoError};
+use std::io::prelude::*;
+
+/// The magic bytes at the start of every mesh cache file.
+pub const MAGIC: [u8; 8] = *b"PGNMESH\0";
+
+/// The current version of the mesh cache format.
+///
+/// Files with a different version are rejected, so any change to the format must bump this.
+pub const VERSION: u32 = 2;
+
+/// The size of the header at the start of a cache file.
+pub const HEADER_SIZE: usize = 64;
+
+/// The alignment of the vertex and index data within a cache file.
+pub const DATA_ALIGNMENT: usize = 16;
+
+const FLAG_COMPRESSED: u32 = 1;
+
+const ATTRIBUTE_POSITION: u32 = 0;
+const ATTRIBUTE_NORMAL:   u32 = 1;
+const ATTRIBUTE_TANGENT:  u32 = 2;
+const ATTRIBUTE_COLOR:    u32 = 3;
+const ATTRIBUTE_TEXCOORD: u32 = 4;
+const ATTRIBUTE_CUSTOM:   u32 = 5;
+
+/// The ways that the payload of a cache file can be compressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compression {
+    /// The payload is stored as-is, so that it can be memory mapped.
+    None,
+
+    /// The payload is compressed with LZ77.
+    Lz,
+}
+
+impl Default for Compression {
+    fn default() -> Compression {
+        Compression::None
+    }
+}
+
+/// An error that can occur when reading a cached mesh.
+#[derive(Debug)]
+pub enum Error {
+    IoError(IoError),
+
+    /// Indicates that the data doesn't start with `MAGIC`, so it isn't a mesh cache.
+    BadMagic,
+
+    /// Indicates that the data was written with a different version of the format.
+    UnsupportedVersion(u32),
+
+    /// Indicates that the stored payload doesn't match its checksum.
+    ChecksumMismatch {
+        expected: u32,
+        actual: u32,
+    },
+
+    /// Indicates that the data passed the checksum but still doesn't describe a valid mesh, or
+    /// that it ended early.
+    Corrupt,
+}
+
+impl From<IoError> for Error {
+    fn from(error: IoError) -> Error {
+        Error::IoError(error)
+    }
+}
+
+impl Mesh {
+    /// Writes the mesh to `writer` in the mesh cache format.
+    ///
+    /// See the `geometry::cache` module for details of the format.
+    pub fn write_to<W: Write>(&self, mut writer: W, compression: Compression) -> Result<(), IoError> {
+        let payload = encode(self);
+        let compressed;
+        let (flags, stored) = match compression {
+            Compression::None => (0, &*payload),
+            Compression::Lz => {
+                compressed = compress(&payload);
+                (FLAG_COMPRESSED, &*compressed)
+            }
+        };
+
+        let mut header = Vec::with_capacity(HEADER_SIZE);
+        header.extend(&MAGIC);
+        header.extend(&VERSION.to_le_bytes());
+        header.extend(&flags.to_le_bytes());
+        header.extend(&(payload.len() as u64).to_le_bytes());
+        header.extend(&(stored.len() as u64).to_le_bytes());
+        header.extend(&adler32(stored).to_le_bytes());
+        header.resize(HEADER_SIZE, 0);
+
+        writer.write_all(&header)?;
+        writer.write_all(stored)?;
+        writer.flush()
+    }
+
+    /// Reads a mesh in the mesh cache format from `reader`.
+    ///
+    /// The whole reader is consumed, see `from_bytes()` for reading a mesh that's already in
+    /// memory.
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Mesh, Error> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Mesh::from_bytes(&data)
+    }
+
+    /// Reads a mesh from a buffer in the mesh cache format, e.g. a memory mapped cache file.
+    pub fn from_bytes(data: &[u8]) -> Result<Mesh, Error> {
+        if !data.starts_with(&MAGIC) {
+            return Err(Error::BadMagic);
+        }
+
+        let mut header = Cursor::new(data.get(..HEADER_SIZE).ok_or(Error::Corrupt)?);
+        header.bytes(MAGIC.len())?;
+
+        let version = header.u32()?;
+        if version != VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+
+        let flags = header.u32()?;
+        let payload_size = header.usize()?;
+        let stored_size = header.usize()?;
+        let expected = header.u32()?;
+
+        let stored = data
+            .get(HEADER_SIZE..)
+            .and_then(|stored| stored.get(..stored_size))
+            .ok_or(Error::Corrupt)?;
+        let actual = adler32(stored);
+        if actual != expected {
+            return Err(Error::ChecksumMismatch { expected: expected, actual: actual });
+        }
+
+        if flags & FLAG_COMPRESSED != 0 {
+            decode(&decompress(stored, payload_size)?)
+        } else if stored_size == payload_size {
+            decode(stored)
+        } else {
+            Err(Error::Corrupt)
+        }
+    }
+}
+
+/// Encodes the mesh as an uncompressed payload.
+fn encode(mesh: &Mesh) -> Vec<u8> {
+    let mut attributes = vec![(ATTRIBUTE_POSITION, mesh.position(), "")];
+    attributes.extend(mesh.normal().map(|normal| (ATTRIBUTE_NORMAL, normal, "")));
+    attributes.extend(mesh.tangent().map(|tangent| (ATTRIBUTE_TANGENT, tangent, "")));
+    attributes.extend(mesh.color().map(|color| (ATTRIBUTE_COLOR, color, "")));
+    attributes.extend(mesh.texcoord().iter().map(|&texcoord| (ATTRIBUTE_TEXCOORD, texcoord, "")));
+    attributes.extend(
+        mesh.custom_attributes()
+            .iter()
+            .map(|&(ref name, custom)| (ATTRIBUTE_CUSTOM, custom, &**name)),
+    );
+
+    let (index_size, index_count) = match *mesh.indices() {
+        MeshIndices::U16(ref indices) => (2u32, indices.len()),
+        MeshIndices::U32(ref indices) => (4u32, indices.len()),
+    };
+
+    let layout = match mesh.layout() {
+        VertexLayout::Planar => 0u32,
+        VertexLayout::Interleaved => 1u32,
+    };
+
+    let mut payload = Vec::new();
+    payload.extend(&(mesh.vertex_count() as u64).to_le_bytes());
+    payload.extend(&(mesh.vertex_data().len() as u64).to_le_bytes());
+    payload.extend(&(index_count as u64).to_le_bytes());
+    payload.extend(&index_size.to_le_bytes());
+    payload.extend(&layout.to_le_bytes());
+
+    let bounding_box = mesh.bounding_box();
+    let bounding_sphere = mesh.bounding_sphere();
+    let bounds = [
+        bounding_box.min.x, bounding_box.min.y, bounding_box.min.z,
+        bounding_box.max.x, bounding_box.max.y, bounding_box.max.z,
+        bounding_sphere.center.x, bounding_sphere.center.y, bounding_sphere.center.z,
+        bounding_sphere.radius,
+    ];
+    for &value in &bounds {
+        payload.extend(&value.to_le_bytes());
+    }
+
+    payload.extend(&(attributes.len() as u32).to_le_bytes());
+    for &(kind, attribute, name) in &attributes {
+        payload.extend(&kind.to_le_bytes());
+        payload.extend(&(attribute.elements as u32).to_le_bytes());
+        payload.extend(&(attribute.offset as u64).to_le_bytes());
+        payload.extend(&(attribute.stride as u64).to_le_bytes());
+        payload.extend(&(name.len() as u32).to_le_bytes());
+        payload.extend(name.as_bytes());
+    }
+
+    payload.extend(&(mesh.submeshes().len() as u32).to_le_bytes());
+    for submesh in mesh.submeshes() {
+        payload.extend(&(submesh.start as u64).to_le_bytes());
+        payload.extend(&(submesh.count as u64).to_le_bytes());
+    }
+
+    // The payload starts at an aligned offset in the file, so aligning within the payload also
+    // aligns within the file.
+    align(&mut payload);
+    for &value in mesh.vertex_data() {
+        payload.extend(&value.to_le_bytes());
+    }
+
+    align(&mut payload);
+    match *mesh.indices() {
+        MeshIndices::U16(ref indices) => for &index in indices {
+            payload.extend(&index.to_le_bytes());
+        },
+        MeshIndices::U32(ref indices) => for &index in indices {
+            payload.extend(&index.to_le_bytes());
+        },
+    }
+
+    payload
+}
+
+/// Decodes a mesh from an uncompressed payload, validating it along the way.
+fn decode(payload: &[u8]) -> Result<Mesh, Error> {
+    let mut cursor = Cursor::new(payload);
+
+    let vertex_count = cursor.usize()?;
+    let vertex_data_len = cursor.usize()?;
+    let index_count = cursor.usize()?;
+    let index_size = cursor.u32()?;
+    let layout = match cursor.u32()? {
+        0 => VertexLayout::Planar,
+        1 => VertexLayout::Interleaved,
+        _ => return Err(Error::Corrupt),
+    };
+
+    let mut bounds = [0.0; 10];
+    for value in &mut bounds {
+        *value = cursor.f32()?;
+    }
+    let bounding_box = Aabb {
+        min: Point::new(bounds[0], bounds[1], bounds[2]),
+        max: Point::new(bounds[3], bounds[4], bounds[5]),
+    };
+    let bounding_sphere = Sphere {
+        center: Point::new(bounds[6], bounds[7], bounds[8]),
+        radius: bounds[9],
+    };
+
+    let mut position = None;
+    let mut normal = None;
+    let mut tangent = None;
+    let mut color = None;
+    let mut texcoord = Vec::new();
+    let mut custom = Vec::new();
+
+    let attribute_count = cursor.u32()?;
+    for _ in 0..attribute_count {
+        let kind = cursor.u32()?;
+        let attribute = VertexAttribute {
+            elements: cursor.u32()? as usize,
+            offset: cursor.usize()?,
+            stride: cursor.usize()?,
+        };
+        let name_len = cursor.u32()? as usize;
+        let name = String::from_utf8(cursor.bytes(name_len)?.to_vec()).map_err(|_| Error::Corrupt)?;
+
+        if !attribute_fits(attribute, vertex_count, vertex_data_len) {
+            return Err(Error::Corrupt);
+        }
+
+        let slot = match kind {
+            ATTRIBUTE_POSITION => &mut position,
+            ATTRIBUTE_NORMAL => &mut normal,
+            ATTRIBUTE_TANGENT => &mut tangent,
+            ATTRIBUTE_COLOR => &mut color,
+            ATTRIBUTE_TEXCOORD => {
+                texcoord.push(attribute);
+                continue;
+            }
+            ATTRIBUTE_CUSTOM => {
+                custom.push((name, attribute));
+                continue;
+            }
+            _ => return Err(Error::Corrupt),
+        };
+
+        // Each built-in attribute can only appear once.
+        if slot.replace(attribute).is_some() {
+            return Err(Error::Corrupt);
+        }
+    }
+    let position = position.ok_or(Error::Corrupt)?;
+
+    let submesh_count = cursor.u32()?;
+    let mut submeshes = Vec::new();
+    for _ in 0..submesh_count {
+        let submesh = Submesh::new(cursor.usize()?, cursor.usize()?);
+        if submesh.start.checked_add(submesh.count).map(|end| end > index_count).unwrap_or(true) {
+            return Err(Error::Corrupt);
+        }
+        submeshes.push(submesh);
+    }
+    if submeshes.is_empty() {
+        return Err(Error::Corrupt);
+    }
+
+    cursor.align()?;
+    let vertex_data = cursor
+        .bytes(vertex_data_len.checked_mul(4).ok_or(Error::Corrupt)?)?
+        .chunks(4)
+        .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect::<Vec<_>>();
+
+    cursor.align()?;
+    let index_bytes = cursor.bytes(index_count.checked_mul(index_size as usize).ok_or(Error::Corrupt)?)?;
+    let indices = match index_size {
+        2 => MeshIndices::U16(
+            index_bytes
+                .chunks(2)
+                .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+                .collect(),
+        ),
+        4 => MeshIndices::U32(
+            index_bytes
+                .chunks(4)
+                .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect(),
+        ),
+        _ => return Err(Error::Corrupt),
+    };
+
+    if indices.iter().any(|index| index as usize >= vertex_count) {
+        return Err(Error::Corrupt);
+    }
+
+    Ok(Mesh {
+        vertex_data: vertex_data,
+        vertex_count: vertex_count,
+        layout: layout,
+        indices: indices,
+        submeshes: submeshes,
+
+        position: position,
+        normal: normal,
+        tangent: tangent,
+        color: color,
+        texcoord: texcoord,
+        custom: custom,
+
+        bounding_box: bounding_box,
+        bounding_sphere: bounding_sphere,
+    })
+}
+
+/// Checks that every vertex's value for an attribute is within the vertex data.
+fn attribute_fits(attribute: VertexAttribute, vertex_count: usize, vertex_data_len: usize) -> bool {
+    if attribute.elements < 1 || attribute.elements > 4 {
+        return false;
+    }
+
+    if vertex_count == 0 {
+        return true;
+    }
+
+    let stride = if attribute.stride == 0 { attribute.elements } else { attribute.stride };
+    (vertex_count - 1)
+        .checked_mul(stride)
+        .and_then(|last| last.checked_add(attribute.offset))
+        .and_then(|last| last.checked_add(attribute.elements))
+        .map(|end| end <= vertex_data_len)
+        .unwrap_or(false)
+}
+
+/// Pads the payload with zeros up to the next multiple of `DATA_ALIGNMENT`.
+fn align(payload: &mut Vec<u8>) {
+    while payload.len() % DATA_ALIGNMENT != 0 {
+        payload.push(0);
+    }
+}
+
+/// Reads little endian values from a buffer, failing with `Error::Corrupt` if it runs out.
+struct Cursor<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(data: &'a [u8]) -> Cursor<'a> {
+        Cursor {
+            data: data,
+            position: 0,
+        }
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let end = self.position.checked_add(len).ok_or(Error::Corrupt)?;
+        let bytes = self.data.get(self.position..end).ok_or(Error::Corrupt)?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn usize(&mut self) -> Result<usize, Error> {
+        let bytes = self.bytes(8)?;
+        let mut value = [0; 8];
+        value.copy_from_slice(bytes);
+        let value = u64::from_le_bytes(value);
+        if value > usize::MAX as u64 {
+            return Err(Error::Corrupt);
+        }
+        Ok(value as usize)
+    }
+
+    fn f32(&mut self) -> Result<f32, Error> {
+        self.u32().map(f32::from_bits)
+    }
+
+    /// Skips the padding up to the next multiple of `DATA_ALIGNMENT`.
+    fn align(&mut self) -> Result<(), Error> {
+        let padding = (DATA_ALIGNMENT - self.position % DATA_ALIGNMENT) % DATA_ALIGNMENT;
+        self.bytes(padding).map(|_| ())
+    }
+}
+
+/// Computes the Adler-32 checksum of the data.
+fn adler32(data: &[u8]) -> u32 {
+    const MODULUS: u32 = 65521;
+
+    // The largest number of bytes that can be summed before `b` could overflow.
+    const CHUNK_SIZE: usize = 5552;
+
+    let mut a = 1;
+    let mut b = 0;
+    for chunk in data.chunks(CHUNK_SIZE) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MODULUS;
+        b %= MODULUS;
+    }
+
+    (b << 16) | a
+}
+
+/// The shortest match that compression will encode, as shorter matches don't save any space.
+const MIN_MATCH: usize = 4;
+
+/// The number of bits in the hashes used to find matches while compressing.
+const HASH_BITS: u32 = 16;
+
+/// Compresses the data.
+///
+/// The compressed data is a series of sequences, each made up of a run of literal bytes followed
+/// by a match copying earlier output. Each sequence starts with a token byte holding the literal
+/// length in the high 4 bits and the match length minus `MIN_MATCH` in the low 4 bits. A length
+/// of 15 is followed by extra bytes that are added to it, continuing while the byte is 255.
+/// The literals follow the token (and literal length bytes), then the 2 byte offset back to the
+/// start of the match, then any extra match length bytes. The last sequence has only literals.
+fn compress(data: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(data.len() / 2);
+    let mut table = vec![usize::MAX; 1 << HASH_BITS];
+
+    let mut anchor = 0;
+    let mut position = 0;
+    while position + MIN_MATCH <= data.len() {
+        let sequence = &data[position .. position + MIN_MATCH];
+        let key = u32::from_le_bytes([sequence[0], sequence[1], sequence[2], sequence[3]]);
+        let hash = (key.wrapping_mul(2_654_435_761) >> (32 - HASH_BITS)) as usize;
+
+        let candidate = table[hash];
+        table[hash] = position;
+
+        let is_match = candidate != usize::MAX
+            && position - candidate <= u16::MAX as usize
+            && data[candidate .. candidate + MIN_MATCH] == *sequence;
+        if !is_match {
+            position += 1;
+            continue;
+        }
+
+        let mut match_len = MIN_MATCH;
+        while position + match_len < data.len() && data[candidate + match_len] == data[position + match_len] {
+            match_len += 1;
+        }
+
+        let literals = &data[anchor..position];
+        let match_extra = match_len - MIN_MATCH;
+        output.push(((literals.len().min(15) as u8) << 4) | match_extra.min(15) as u8);
+        write_length(&mut output, literals.len());
+        output.extend(literals);
+        output.extend(&((position - candidate) as u16).to_le_bytes());
+        write_length(&mut output, match_extra);
+
+        position += match_len;
+        anchor = position;
+    }
+
+    let literals = &data[anchor..];
+    output.push((literals.len().min(15) as u8) << 4);
+    write_length(&mut output, literals.len());
+    output.extend(literals);
+
+    output
+}
+
+/// Writes the extra bytes for a length that doesn't fit in 4 bits of a token.
+fn write_length(output: &mut Vec<u8>, length: usize) {
+    if length < 15 {
+        return;
+    }
+
+    let mut remaining = length - 15;
+    while remaining >= 255 {
+        output.push(255);
+        remaining -= 255;
+    }
+    output.push(remaining as u8);
+}
+
+/// Decompresses data produced by `compress()`, which must decompress to exactly `size` bytes.
+fn decompress(data: &[u8], size: usize) -> Result<Vec<u8>, Error> {
+    // Each compressed byte produces at most 255 bytes of output, which is what an extra length
+    // byte adds. The size comes from the header, which the checksum doesn't cover, so anything
+    // larger is corrupt and mustn't be allocated.
+    if size > data.len().saturating_mul(255) {
+        return Err(Error::Corrupt);
+    }
+
+    let mut output = Vec::with_capacity(size);
+    let mut input = Cursor::new(data);
+
+    loop {
+        let token = input.bytes(1)?[0];
+
+        let literal_len = read_length(&mut input, (token >> 4) as usize)?;
+        output.extend(input.bytes(literal_len)?);
+        if output.len() > size {
+            return Err(Error::Corrupt);
+        }
+
+        // The last sequence has no match.
+        if input.position == data.len() {
+            break;
+        }
+
+        let offset = input.bytes(2)?;
+        let offset = u16::from_le_bytes([offset[0], offset[1]]) as usize;
+        let match_len = read_length(&mut input, (token & 0xf) as usize)? + MIN_MATCH;
+        if offset == 0 || offset > output.len() || output.len() + match_len > size {
+            return Err(Error::Corrupt);
+        }
+
+        // The match can overlap the bytes it produces, so it's copied a byte at a time.
+        let start = output.len() - offset;
+        for index in start .. start + match_len {
+            let byte = output[index];
+            output.push(byte);
+        }
+    }
+
+    if output.len() != size {
+        return Err(Error::Corrupt);
+    }
+
+    Ok(output)
+}
+
+/// Reads a length from a token, plus any extra length bytes.
+fn read_length(input: &mut Cursor, length: usize) -> Result<usize, Error> {
+    let mut length = length;
+    if length == 15 {
+        loop {
+            let byte = input.bytes(1)?[0];
+            length = length.checked_add(byte as usize).ok_or(Error::Corrupt)?;
+            if byte != 255 {
+                break;
+            }
+        }
+    }
+
+    Ok(length)
+}
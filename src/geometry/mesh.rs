@@ -1,19 +1,36 @@
 use math::*;
+use std::collections::HashMap;
 
 pub type MeshIndex = u32;
 
+/// The maximum number of texture coordinate sets a mesh can have.
+pub const MAX_TEXCOORDS: usize = 4;
+
 /// The raw data representing a mesh in memory.
 ///
 /// Meshes are represented as list of vertex positions and a list of faces.
 /// Each face is represented as 3 indices into the vertex array.
+///
+/// The faces are divided into one or more submeshes, each a range of the index data, so that
+/// different parts of the mesh can be drawn with different materials. See `Submesh` for more
+/// information.
 #[derive(Debug, Clone)]
 pub struct Mesh {
-    vertex_data: Vec<f32>,
-    indices:     Vec<MeshIndex>,
+    pub(crate) vertex_data:  Vec<f32>,
+    pub(crate) vertex_count: usize,
+    pub(crate) layout:       VertexLayout,
+    pub(crate) indices:      MeshIndices,
+    pub(crate) submeshes:    Vec<Submesh>,
 
-    position: VertexAttribute,
-    normal:   Option<VertexAttribute>,
-    texcoord: Vec<VertexAttribute>,
+    pub(crate) position: VertexAttribute,
+    pub(crate) normal:   Option<VertexAttribute>,
+    pub(crate) tangent:  Option<VertexAttribute>,
+    pub(crate) color:    Option<VertexAttribute>,
+    pub(crate) texcoord: Vec<VertexAttribute>,
+    pub(crate) custom:   Vec<(String, VertexAttribute)>,
+
+    pub(crate) bounding_box:    Aabb,
+    pub(crate) bounding_sphere: Sphere,
 }
 
 impl Mesh {
@@ -21,8 +38,30 @@ impl Mesh {
         &*self.vertex_data
     }
 
-    pub fn indices(&self) -> &[MeshIndex] {
-        &*self.indices
+    /// Gets the index data for the mesh.
+    ///
+    /// Meshes with few enough vertices store their indices as `u16`, see `MeshIndices` for
+    /// more information.
+    pub fn indices(&self) -> &MeshIndices {
+        &self.indices
+    }
+
+    /// Gets the submeshes of the mesh.
+    ///
+    /// Every mesh has at least one submesh, meshes built without any submeshes have a single
+    /// submesh covering all of their indices.
+    pub fn submeshes(&self) -> &[Submesh] {
+        &self.submeshes
+    }
+
+    /// Gets the number of vertices in the mesh.
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    /// Gets the layout of the attributes in the mesh's vertex data.
+    pub fn layout(&self) -> VertexLayout {
+        self.layout
     }
 
     pub fn position(&self) -> VertexAttribute {
@@ -33,9 +72,162 @@ impl Mesh {
         self.normal
     }
 
+    /// Gets the tangent attribute of the mesh, if it has one.
+    ///
+    /// Tangents have 4 elements: The `xyz` components are the tangent direction and `w` is the
+    /// handedness of the tangent basis. The bitangent is reconstructed as
+    /// `cross(normal, tangent.xyz) * tangent.w`, following the MikkTSpace convention.
+    pub fn tangent(&self) -> Option<VertexAttribute> {
+        self.tangent
+    }
+
+    /// Gets the vertex color attribute of the mesh, if it has one.
+    ///
+    /// Vertex colors have 4 elements and are in linear space.
+    pub fn color(&self) -> Option<VertexAttribute> {
+        self.color
+    }
+
+    /// Gets the texture coordinate attributes of the mesh, one for each set of texture
+    /// coordinates.
     pub fn texcoord(&self) -> &[VertexAttribute] {
         &*self.texcoord
     }
+
+    /// Gets the custom attribute with the specified name, if the mesh has one.
+    pub fn custom_attribute(&self, name: &str) -> Option<VertexAttribute> {
+        self.custom
+            .iter()
+            .find(|custom| custom.0 == name)
+            .map(|custom| custom.1)
+    }
+
+    /// Gets all of the custom attributes of the mesh along with their names.
+    pub fn custom_attributes(&self) -> &[(String, VertexAttribute)] {
+        &*self.custom
+    }
+
+    /// Gets the value of an attribute for a single vertex, regardless of the mesh's layout.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `vertex` is out of bounds or `attribute` doesn't describe this mesh's vertex
+    /// data.
+    pub fn attribute_value(&self, attribute: VertexAttribute, vertex: usize) -> &[f32] {
+        let stride = if attribute.stride == 0 { attribute.elements } else { attribute.stride };
+        let start = attribute.offset + vertex * stride;
+        &self.vertex_data[start .. start + attribute.elements]
+    }
+
+    /// Gets the axis-aligned bounding box of the mesh's vertex positions in model space.
+    pub fn bounding_box(&self) -> Aabb {
+        self.bounding_box
+    }
+
+    /// Gets the bounding sphere of the mesh's vertex positions in model space.
+    pub fn bounding_sphere(&self) -> Sphere {
+        self.bounding_sphere
+    }
+}
+
+/// The index data for a mesh.
+///
+/// When a mesh has no more than 65535 vertices `MeshBuilder` stores its indices as `u16` to
+/// halve the size of the index buffer, otherwise it uses `u32` indices. `0xFFFF` is left unused
+/// so that the index data stays valid if primitive restart is enabled, which treats the largest
+/// index value as the restart index. Indices can be read as `MeshIndex` regardless of how they're
+/// stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MeshIndices {
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+
+impl MeshIndices {
+    /// Gets the number of indices.
+    pub fn len(&self) -> usize {
+        match *self {
+            MeshIndices::U16(ref indices) => indices.len(),
+            MeshIndices::U32(ref indices) => indices.len(),
+        }
+    }
+
+    /// Returns `true` if there are no indices.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the index at the specified position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position` is out of bounds.
+    pub fn get(&self, position: usize) -> MeshIndex {
+        match *self {
+            MeshIndices::U16(ref indices) => indices[position] as MeshIndex,
+            MeshIndices::U32(ref indices) => indices[position],
+        }
+    }
+
+    /// Iterates over the indices.
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = MeshIndex> + 'a {
+        (0..self.len()).map(move |position| self.get(position))
+    }
+
+    /// Copies the indices into a `Vec<MeshIndex>`.
+    pub fn to_vec(&self) -> Vec<MeshIndex> {
+        self.iter().collect()
+    }
+}
+
+/// A range of a mesh's index data that is drawn with its own material.
+///
+/// Submeshes let a single mesh, such as a character with skin, cloth, and metal parts, be drawn
+/// with a different material for each part while sharing one vertex buffer. The submeshes of a
+/// mesh are usually contiguous and non-overlapping, but this isn't required.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Submesh {
+    /// The position of the submesh's first index in the mesh's index data.
+    pub start: usize,
+
+    /// The number of indices in the submesh.
+    pub count: usize,
+}
+
+impl Submesh {
+    pub fn new(start: usize, count: usize) -> Submesh {
+        Submesh {
+            start: start,
+            count: count,
+        }
+    }
+
+    /// Gets the position one past the submesh's last index.
+    pub fn end(&self) -> usize {
+        self.start + self.count
+    }
+}
+
+/// The ways that vertex attributes can be arranged in a mesh's vertex data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VertexLayout {
+    /// Each attribute's data is tightly packed in its own block, one block after another.
+    ///
+    /// Attributes in a planar layout have a stride of 0.
+    Planar,
+
+    /// The attributes of each vertex are stored together, one vertex after another.
+    ///
+    /// Attributes in an interleaved layout all have a stride equal to the total number of
+    /// elements in a vertex. Interleaved data is generally more cache-friendly when drawing, as
+    /// all of a vertex's attributes are read from the same part of the buffer.
+    Interleaved,
+}
+
+impl Default for VertexLayout {
+    fn default() -> VertexLayout {
+        VertexLayout::Planar
+    }
 }
 
 /// Represents a single vertex in a mesh with all of its supported attributes.
@@ -43,9 +235,10 @@ impl Mesh {
 pub struct Vertex {
     pub position: Point,
     pub normal: Option<Vector3>,
+    pub tangent: Option<Vector4>,
+    pub color: Option<Color>,
 
-    /// Support an arbitrary number of texture units. The actual maximum is dependent on hardware
-    /// and so is not limited by polygon directly. If the number of
+    /// The texture coordinates for each set of texture coordinates, up to `MAX_TEXCOORDS`.
     pub texcoord: Vec<Vector2>,
 }
 
@@ -54,13 +247,15 @@ impl Vertex {
         Vertex {
             position: position,
             normal: None,
+            tangent: None,
+            color: None,
             texcoord: Vec::new(),
         }
     }
 }
 
 /// A struct describing the single attribute within a mesh's vertex buffer.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct VertexAttribute {
     /// The number of elements in the attribute.
     pub elements: usize,
@@ -79,6 +274,18 @@ pub enum BuildMeshError {
         index: MeshIndex,
     },
 
+    /// Indicates that the number of indices isn't a multiple of 3, so the last triangle is
+    /// incomplete.
+    IncompleteTriangle {
+        index_count: usize,
+    },
+
+    /// Indicates that a submesh extends past the end of the index data.
+    SubmeshOutOfBounds {
+        submesh: usize,
+        index_count: usize,
+    },
+
     /// Indicates that one or more attributes had a count that did not match the total number of
     /// vertices.
     IncorrectAttributeCount {
@@ -86,13 +293,22 @@ pub enum BuildMeshError {
         expected: usize,
         actual: usize,
     },
+
+    /// Indicates that an attribute needed to generate tangents is missing, see
+    /// `MeshBuilder::compute_tangents()`.
+    MissingAttribute {
+        attribute: VertexAttributeType,
+    },
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum VertexAttributeType {
     Position,
     Normal,
+    Tangent,
+    Color,
     Texcoord,
+    Custom,
 }
 
 /// Provides a safe interface for building a mesh from raw vertex data.
@@ -107,13 +323,39 @@ pub enum VertexAttributeType {
 /// - Check for different data count for different attributes (e.g. if the position attribute data
 ///   for a different number of elements than the normal attribute).
 /// - Any of the indicies would be out of bounds for the given vertex data.
+/// - The number of indices isn't a multiple of 3.
+///
+/// Meshes that are missing normals or tangents can have them generated with
+/// `compute_smooth_normals()`, `compute_flat_normals()`, and `compute_tangents()`. The data is
+/// generated by `build()` once the rest of the mesh data has been validated, and may add
+/// vertices where a vertex needs more than one value for the generated attribute. Generating
+/// normals also drops any vertices that aren't used by a face.
+///
+/// In addition to the built-in attributes, meshes can have custom attributes of 1 to 4 floats
+/// each, set with `set_custom_data()`. Custom attributes are matched by name with the
+/// attributes declared by materials.
+///
+/// By default the built mesh has a planar vertex layout, use `set_layout()` to build an
+/// interleaved mesh instead. Index data is stored as `u16` when the vertex count allows it.
+///
+/// The index data can be split into submeshes with `set_submeshes()`, otherwise the built mesh
+/// has a single submesh covering all of its indices.
 #[derive(Debug, Clone)]
 pub struct MeshBuilder {
     position_data: Vec<Point>,
     normal_data: Vec<Vector3>,
-    texcoord_data: Vec<Vector2>,
+    tangent_data: Vec<Vector4>,
+    color_data: Vec<Color>,
+    texcoord_data: Vec<Vec<Vector2>>,
+    custom_data: Vec<CustomData>,
+
+    indices:   Vec<u32>,
+    submeshes: Vec<Submesh>,
+    layout:    VertexLayout,
 
-    indices:  Vec<u32>,
+    /// The attribute data to generate when the mesh is built.
+    computed_normals:  Option<ComputedNormals>,
+    computed_tangents: bool,
 }
 
 impl MeshBuilder {
@@ -121,8 +363,16 @@ impl MeshBuilder {
         MeshBuilder {
             position_data: Vec::new(),
             normal_data:   Vec::new(),
+            tangent_data:  Vec::new(),
+            color_data:    Vec::new(),
             texcoord_data: Vec::new(),
+            custom_data:   Vec::new(),
             indices:       Vec::new(),
+            submeshes:     Vec::new(),
+            layout:        VertexLayout::default(),
+
+            computed_normals:  None,
+            computed_tangents: false,
         }
     }
 
@@ -133,14 +383,33 @@ impl MeshBuilder {
             self.normal_data.push(normal);
         }
 
-        assert!(vertex.texcoord.len() <= 1, "More than one texcoord per vertex is currently not supported");
+        if let Some(tangent) = vertex.tangent {
+            self.tangent_data.push(tangent);
+        }
+
+        if let Some(color) = vertex.color {
+            self.color_data.push(color);
+        }
+
+        assert!(
+            vertex.texcoord.len() <= MAX_TEXCOORDS,
+            "More than {} texcoords per vertex are not supported", MAX_TEXCOORDS);
 
         // Add each texcoord to its corresponding list.
-        if vertex.texcoord.len() > 0 {
-            self.texcoord_data.push(vertex.texcoord[0])
+        for (set, texcoord) in vertex.texcoord.into_iter().enumerate() {
+            if self.texcoord_data.len() <= set {
+                self.texcoord_data.push(Vec::new());
+            }
+            self.texcoord_data[set].push(texcoord);
         }
     }
 
+    /// Sets the layout of the vertex data in the built mesh.
+    pub fn set_layout(mut self, layout: VertexLayout) -> MeshBuilder {
+        self.layout = layout;
+        self
+    }
+
     pub fn add_index(mut self, index: MeshIndex) -> MeshBuilder {
         self.indices.push(index);
         self
@@ -158,9 +427,67 @@ impl MeshBuilder {
         self
     }
 
-    pub fn set_texcoord_data(mut self, texcoord_data: &[Vector2]) -> MeshBuilder {
-        self.texcoord_data.clear();
-        self.texcoord_data.extend(texcoord_data);
+    pub fn set_tangent_data(mut self, tangent_data: &[Vector4]) -> MeshBuilder {
+        self.tangent_data.clear();
+        self.tangent_data.extend(tangent_data);
+        self
+    }
+
+    pub fn set_color_data(mut self, color_data: &[Color]) -> MeshBuilder {
+        self.color_data.clear();
+        self.color_data.extend(color_data);
+        self
+    }
+
+    /// Sets the data for the first set of texture coordinates.
+    pub fn set_texcoord_data(self, texcoord_data: &[Vector2]) -> MeshBuilder {
+        self.set_texcoord_data_at(0, texcoord_data)
+    }
+
+    /// Sets the data for the set of texture coordinates at `index`.
+    ///
+    /// Sets are numbered from 0, and must all be filled in up to the highest set used: a mesh
+    /// with data for set 2 must also have data for sets 0 and 1.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than `MAX_TEXCOORDS`.
+    pub fn set_texcoord_data_at(mut self, index: usize, texcoord_data: &[Vector2]) -> MeshBuilder {
+        assert!(index < MAX_TEXCOORDS, "Texcoord set {} is out of range, max is {}", index, MAX_TEXCOORDS);
+
+        while self.texcoord_data.len() <= index {
+            self.texcoord_data.push(Vec::new());
+        }
+
+        self.texcoord_data[index].clear();
+        self.texcoord_data[index].extend(texcoord_data);
+        self
+    }
+
+    /// Sets the data for a custom attribute, replacing any data already set for `name`.
+    ///
+    /// Each vertex has `elements` floats of data, so `data` should have `elements` floats for
+    /// each vertex.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `elements` isn't between 1 and 4.
+    pub fn set_custom_data(mut self, name: &str, elements: usize, data: &[f32]) -> MeshBuilder {
+        assert!(
+            (1..=4).contains(&elements),
+            "Custom attribute {:?} must have between 1 and 4 elements, but has {}", name, elements);
+
+        let custom = CustomData {
+            name: name.into(),
+            elements: elements,
+            data: data.into(),
+        };
+
+        match self.custom_data.iter().position(|custom| custom.name == name) {
+            Some(index) => self.custom_data[index] = custom,
+            None => self.custom_data.push(custom),
+        }
+
         self
     }
 
@@ -170,7 +497,275 @@ impl MeshBuilder {
         self
     }
 
-    pub fn build(self) -> Result<Mesh, BuildMeshError> {
+    /// Sets the submeshes of the built mesh, replacing any existing submeshes.
+    ///
+    /// Each submesh is a range of the index data, see `Submesh` for more information.
+    pub fn set_submeshes(mut self, submeshes: &[Submesh]) -> MeshBuilder {
+        self.submeshes.clear();
+        self.submeshes.extend(submeshes);
+        self
+    }
+
+    /// Generates smooth normals, replacing any existing normal data.
+    ///
+    /// Each vertex normal is the average of the normals of the faces around it, weighted by the
+    /// angle of each face at that vertex. Faces meeting at an angle greater than
+    /// `hard_edge_angle` (in radians) are not averaged together, leaving a hard edge between
+    /// them; vertices along a hard edge are split so that each side gets its own normal. Pass
+    /// `PI` to smooth across every edge.
+    ///
+    /// Faces are matched by vertex position rather than by index, so vertices that are only
+    /// split for other attributes (e.g. along a texture seam) are still smoothed together. Any
+    /// existing tangent data is discarded, since it would no longer match the new normals.
+    ///
+    /// This replaces any earlier call to `compute_flat_normals()`.
+    pub fn compute_smooth_normals(mut self, hard_edge_angle: f32) -> MeshBuilder {
+        self.computed_normals = Some(ComputedNormals::Smooth { hard_edge_angle: hard_edge_angle });
+        self
+    }
+
+    /// Generates flat normals, replacing any existing normal data.
+    ///
+    /// Every vertex gets the normal of the face it belongs to, so vertices shared between faces
+    /// with different normals are split. Any existing tangent data is discarded, since it would
+    /// no longer match the new normals.
+    ///
+    /// This replaces any earlier call to `compute_smooth_normals()`.
+    pub fn compute_flat_normals(mut self) -> MeshBuilder {
+        self.computed_normals = Some(ComputedNormals::Flat);
+        self
+    }
+
+    /// Generates tangents for normal mapping, replacing any existing tangent data.
+    ///
+    /// Tangents point in the direction of increasing `u` in the first set of texture coordinates,
+    /// and are orthogonalized against the vertex normals. The `w` component holds the handedness
+    /// of the tangent basis, such that the bitangent is `cross(normal, tangent.xyz) * tangent.w`.
+    /// This matches the conventions of MikkTSpace, which most tools bake normal maps with:
+    /// contributions from each face are weighted by the face's angle at the vertex, and vertices
+    /// shared by faces with mirrored texture coordinates are split so that each side gets its
+    /// own handedness.
+    ///
+    /// Tangents are generated after any normals requested with `compute_smooth_normals()` or
+    /// `compute_flat_normals()`. Building the mesh fails with `MissingAttribute` if it doesn't
+    /// have normal or texcoord data to generate them from.
+    pub fn compute_tangents(mut self) -> MeshBuilder {
+        self.computed_tangents = true;
+        self
+    }
+
+    /// Replaces the normal data with smooth normals, see `compute_smooth_normals()`.
+    fn generate_smooth_normals(&mut self, hard_edge_angle: f32) {
+        let face_normals = self.face_normals();
+        let min_dot = hard_edge_angle.min(PI).cos();
+
+        // Find all the face corners that touch each position. Positions are compared bitwise,
+        // which is enough to catch vertices that were duplicated by an exporter.
+        let mut corners_at_position = HashMap::<[u32; 3], Vec<usize>>::new();
+        for (corner, &index) in self.indices.iter().enumerate() {
+            let position = self.position_data[index as usize];
+            corners_at_position
+                .entry(position_key(position))
+                .or_default()
+                .push(corner);
+        }
+
+        let mut corner_normals = Vec::with_capacity(self.indices.len());
+        for (corner, &index) in self.indices.iter().enumerate() {
+            let face_normal = face_normals[corner / 3];
+            let position = self.position_data[index as usize];
+
+            let mut normal = Vector3::ZERO;
+            for &other in &corners_at_position[&position_key(position)] {
+                let other_normal = face_normals[other / 3];
+                if other == corner || face_normal.dot(other_normal) >= min_dot {
+                    normal += other_normal * self.corner_angle(other);
+                }
+            }
+
+            corner_normals.push(if normal.magnitude_squared() == 0.0 { face_normal } else { normal.normalized() });
+        }
+
+        self.split_by_corner_normals(&corner_normals);
+    }
+
+    /// Replaces the normal data with flat normals, see `compute_flat_normals()`.
+    fn generate_flat_normals(&mut self) {
+        let corner_normals = self
+            .face_normals()
+            .iter()
+            .flat_map(|&normal| vec![normal; 3])
+            .collect::<Vec<_>>();
+
+        self.split_by_corner_normals(&corner_normals);
+    }
+
+    /// Replaces the tangent data with generated tangents, see `compute_tangents()`.
+    fn generate_tangents(&mut self) {
+        // Accumulate the tangent contributions for each vertex, keeping faces with opposite
+        // handedness separate.
+        let mut accumulated = HashMap::<(MeshIndex, bool), Vector3>::new();
+        let mut corner_handedness = Vec::with_capacity(self.indices.len());
+        for (face, triangle) in self.indices.chunks(3).enumerate() {
+            let (a, b, c) = (triangle[0] as usize, triangle[1] as usize, triangle[2] as usize);
+            let edge_1 = self.position_data[b] - self.position_data[a];
+            let edge_2 = self.position_data[c] - self.position_data[a];
+            let delta_uv_1 = self.texcoord_data[0][b] - self.texcoord_data[0][a];
+            let delta_uv_2 = self.texcoord_data[0][c] - self.texcoord_data[0][a];
+
+            // A face with degenerate texture coordinates doesn't define a tangent direction, so
+            // it doesn't contribute. It still needs a handedness for its corners.
+            let determinant = delta_uv_1.x * delta_uv_2.y - delta_uv_2.x * delta_uv_1.y;
+            let (tangent, bitangent) = if determinant != 0.0 {
+                (
+                    (edge_1 * delta_uv_2.y - edge_2 * delta_uv_1.y) / determinant,
+                    (edge_2 * delta_uv_1.x - edge_1 * delta_uv_2.x) / determinant,
+                )
+            } else {
+                (Vector3::ZERO, Vector3::ZERO)
+            };
+
+            let face_normal = Vector3::cross(edge_1, edge_2);
+            let right_handed = Vector3::cross(face_normal, tangent).dot(bitangent) >= 0.0;
+
+            for corner in face * 3 .. face * 3 + 3 {
+                let index = self.indices[corner];
+                let normal = self.normal_data[index as usize];
+                let projected = tangent - normal * normal.dot(tangent);
+                let weighted = if projected.magnitude_squared() == 0.0 {
+                    Vector3::ZERO
+                } else {
+                    projected.normalized() * self.corner_angle(corner)
+                };
+
+                *accumulated.entry((index, right_handed)).or_insert(Vector3::ZERO) += weighted;
+                corner_handedness.push(right_handed);
+            }
+        }
+
+        // Assign each vertex the handedness of its first face, duplicating it for any faces with
+        // the opposite handedness.
+        let mut sources = (0..self.position_data.len()).collect::<Vec<_>>();
+        let mut tangent_data = vec![Vector4::ZERO; self.position_data.len()];
+        let mut assigned = vec![None; self.position_data.len()];
+        let mut duplicates = HashMap::<MeshIndex, MeshIndex>::new();
+        for (corner, &right_handed) in corner_handedness.iter().enumerate() {
+            let index = self.indices[corner];
+
+            let target = match assigned[index as usize] {
+                None => {
+                    assigned[index as usize] = Some(right_handed);
+                    index
+                }
+                Some(handedness) if handedness == right_handed => index,
+                Some(_) => *duplicates.entry(index).or_insert_with(|| {
+                    sources.push(index as usize);
+                    tangent_data.push(Vector4::ZERO);
+                    (sources.len() - 1) as MeshIndex
+                }),
+            };
+
+            let normal = self.normal_data[index as usize];
+            let tangent = accumulated[&(index, right_handed)];
+            let tangent = if tangent.magnitude_squared() == 0.0 {
+                perpendicular(normal)
+            } else {
+                tangent.normalized()
+            };
+            let handedness = if right_handed { 1.0 } else { -1.0 };
+            tangent_data[target as usize] = Vector4::from_vector3(tangent, handedness);
+
+            self.indices[corner] = target;
+        }
+
+        self.select_vertices(&sources);
+        self.tangent_data = tangent_data;
+    }
+
+    /// Calculates the unit normal of each face, or the zero vector for degenerate faces.
+    fn face_normals(&self) -> Vec<Vector3> {
+        self.indices
+            .chunks(3)
+            .map(|triangle| {
+                let a = self.position_data[triangle[0] as usize];
+                let b = self.position_data[triangle[1] as usize];
+                let c = self.position_data[triangle[2] as usize];
+                let normal = Vector3::cross(b - a, c - a);
+                if normal.magnitude_squared() == 0.0 { Vector3::ZERO } else { normal.normalized() }
+            })
+            .collect()
+    }
+
+    /// Calculates the interior angle of a face at one of its corners, given as an offset into
+    /// the index data.
+    fn corner_angle(&self, corner: usize) -> f32 {
+        let face = corner - corner % 3;
+        let position = |offset: usize| {
+            self.position_data[self.indices[face + (corner + offset) % 3] as usize]
+        };
+
+        let first = position(1) - position(0);
+        let second = position(2) - position(0);
+        if first.magnitude_squared() == 0.0 || second.magnitude_squared() == 0.0 {
+            return 0.0;
+        }
+
+        first.normalized().dot(second.normalized()).clamp(-1.0, 1.0).acos()
+    }
+
+    /// Replaces the normal data with a normal for each face corner, splitting vertices that need
+    /// more than one normal.
+    fn split_by_corner_normals(&mut self, corner_normals: &[Vector3]) {
+        let mut vertices = HashMap::<(MeshIndex, [u32; 3]), MeshIndex>::new();
+        let mut sources = Vec::with_capacity(self.position_data.len());
+        let mut normal_data = Vec::with_capacity(self.position_data.len());
+        let mut indices = Vec::with_capacity(self.indices.len());
+
+        for (&original, &normal) in self.indices.iter().zip(corner_normals) {
+            let index = *vertices
+                .entry((original, vector_key(normal)))
+                .or_insert_with(|| {
+                    sources.push(original as usize);
+                    normal_data.push(normal);
+                    (sources.len() - 1) as MeshIndex
+                });
+            indices.push(index);
+        }
+
+        self.select_vertices(&sources);
+        self.normal_data = normal_data;
+        self.tangent_data.clear();
+        self.indices = indices;
+    }
+
+    /// Rebuilds the vertex data so that vertex `i` is a copy of vertex `sources[i]`.
+    ///
+    /// Attributes that don't have data for every vertex are left as-is so that `build()` can
+    /// report the error.
+    fn select_vertices(&mut self, sources: &[usize]) {
+        fn select<T: Copy>(data: &mut Vec<T>, elements: usize, vertex_count: usize, sources: &[usize]) {
+            if data.len() == vertex_count * elements {
+                *data = sources
+                    .iter()
+                    .flat_map(|&source| data[source * elements .. (source + 1) * elements].iter().cloned())
+                    .collect();
+            }
+        }
+
+        let vertex_count = self.position_data.len();
+        select(&mut self.position_data, 1, vertex_count, sources);
+        select(&mut self.normal_data, 1, vertex_count, sources);
+        select(&mut self.tangent_data, 1, vertex_count, sources);
+        select(&mut self.color_data, 1, vertex_count, sources);
+        for texcoord_data in &mut self.texcoord_data {
+            select(texcoord_data, 1, vertex_count, sources);
+        }
+        for custom in &mut self.custom_data {
+            select(&mut custom.data, custom.elements, vertex_count, sources);
+        }
+    }
+
+    pub fn build(mut self) -> Result<Mesh, BuildMeshError> {
         // The vertex count is defined by the position data, since position is the only required
         // vertex attribute.
         let vertex_count = self.position_data.len();
@@ -184,14 +779,48 @@ impl MeshBuilder {
             });
         }
 
-        if self.texcoord_data.len() != 0 && self.texcoord_data.len() != vertex_count {
+        if self.tangent_data.len() != 0 && self.tangent_data.len() != vertex_count {
+            return Err(BuildMeshError::IncorrectAttributeCount {
+                attribute: VertexAttributeType::Tangent,
+                expected: vertex_count,
+                actual: self.tangent_data.len(),
+            });
+        }
+
+        if self.color_data.len() != 0 && self.color_data.len() != vertex_count {
             return Err(BuildMeshError::IncorrectAttributeCount {
-                attribute: VertexAttributeType::Texcoord,
+                attribute: VertexAttributeType::Color,
                 expected: vertex_count,
-                actual: self.texcoord_data.len(),
+                actual: self.color_data.len(),
             });
         }
 
+        // Texcoord sets past the last one with data are ignored, but every set before it needs
+        // data for every vertex.
+        while self.texcoord_data.last().map(Vec::is_empty).unwrap_or(false) {
+            self.texcoord_data.pop();
+        }
+
+        for texcoord_data in &self.texcoord_data {
+            if texcoord_data.len() != vertex_count {
+                return Err(BuildMeshError::IncorrectAttributeCount {
+                    attribute: VertexAttributeType::Texcoord,
+                    expected: vertex_count,
+                    actual: texcoord_data.len(),
+                });
+            }
+        }
+
+        for custom in &self.custom_data {
+            if custom.data.len() != vertex_count * custom.elements {
+                return Err(BuildMeshError::IncorrectAttributeCount {
+                    attribute: VertexAttributeType::Custom,
+                    expected: vertex_count,
+                    actual: custom.data.len() / custom.elements,
+                });
+            }
+        }
+
         // Make sure all indices at least point to a valid vertex.
         for index in self.indices.iter().cloned() {
             if index >= vertex_count as MeshIndex {
@@ -202,57 +831,198 @@ impl MeshBuilder {
             }
         }
 
-        // TODO: Check for degenerate triangles? Actually, should that be a failure or a warning?
+        if self.indices.len() % 3 != 0 {
+            return Err(BuildMeshError::IncompleteTriangle {
+                index_count: self.indices.len(),
+            });
+        }
+
+        for (submesh, range) in self.submeshes.iter().enumerate() {
+            if range.start > self.indices.len() || range.count > self.indices.len() - range.start {
+                return Err(BuildMeshError::SubmeshOutOfBounds {
+                    submesh: submesh,
+                    index_count: self.indices.len(),
+                });
+            }
+        }
+
+        // Degenerate triangles are harmless to draw, so they're left for
+        // `geometry::optimize::remove_degenerate_triangles()` rather than failing the build.
+
+        // Generate the requested attribute data now that every index is known to be valid. This
+        // can add and remove vertices, but doesn't change the number of indices.
+        match self.computed_normals {
+            Some(ComputedNormals::Smooth { hard_edge_angle }) => {
+                self.generate_smooth_normals(hard_edge_angle)
+            }
+            Some(ComputedNormals::Flat) => self.generate_flat_normals(),
+            None => {}
+        }
+
+        if self.computed_tangents {
+            if self.normal_data.is_empty() {
+                return Err(BuildMeshError::MissingAttribute {
+                    attribute: VertexAttributeType::Normal,
+                });
+            }
+
+            if self.texcoord_data.is_empty() {
+                return Err(BuildMeshError::MissingAttribute {
+                    attribute: VertexAttributeType::Texcoord,
+                });
+            }
+
+            self.generate_tangents();
+        }
+
+        let vertex_count = self.position_data.len();
 
-        let float_count =
-            self.position_data.len() * 4
-          + self.normal_data.len() * 3
-          + self.texcoord_data.len() * 2;
+        // Calculate the bounds of the mesh, used by the renderer for culling. A mesh with no
+        // vertices gets empty bounds at the origin.
+        let bounding_box = Aabb::from_points(&*self.position_data)
+            .unwrap_or(Aabb::new(Point::origin(), Point::origin()));
+        let bounding_sphere = Sphere::from_points(&*self.position_data)
+            .unwrap_or(Sphere::new(Point::origin(), 0.0));
+
+        // Gather the data for each attribute in the order the attributes are laid out in a
+        // vertex, along with the number of elements per vertex for each one.
+        let color_data = self.color_data
+            .iter()
+            .flat_map(|&color| <[f32; 4]>::from(color).to_vec())
+            .collect::<Vec<f32>>();
+
+        let mut attrib_data: Vec<(usize, &[f32])> = Vec::new();
+        attrib_data.push((4, Point::as_ref(&*self.position_data)));
+        if self.normal_data.len() > 0 {
+            attrib_data.push((3, Vector3::as_ref(&*self.normal_data)));
+        }
+        if self.tangent_data.len() > 0 {
+            attrib_data.push((4, Vector4::as_ref(&*self.tangent_data)));
+        }
+        if color_data.len() > 0 {
+            attrib_data.push((4, &*color_data));
+        }
+        for texcoord_data in &self.texcoord_data {
+            attrib_data.push((2, Vector2::as_ref(texcoord_data)));
+        }
+        for custom in &self.custom_data {
+            attrib_data.push((custom.elements, &*custom.data));
+        }
 
         // Create the mesh.
-        let mut vertex_data = Vec::<f32>::with_capacity(float_count);
+        let vertex_size = attrib_data.iter().map(|&(elements, _)| elements).sum::<usize>();
+        let mut vertex_data = Vec::<f32>::with_capacity(vertex_size * vertex_count);
+        let mut attribs = Vec::with_capacity(attrib_data.len());
+        match self.layout {
+            VertexLayout::Planar => {
+                for &(elements, data) in &attrib_data {
+                    attribs.push(VertexAttribute {
+                        elements: elements,
+                        offset: vertex_data.len(),
+                        stride: 0,
+                    });
+                    vertex_data.extend(data);
+                }
+            }
 
-        // Setup position data.
-        let position_attrib = VertexAttribute {
-            elements: 4,
-            offset: 0,
-            stride: 0,
-        };
-        vertex_data.extend(Point::as_ref(&*self.position_data));
-
-        // Setup normal data.
-        let normal_attrib = if self.normal_data.len() > 0 {
-            let attrib = VertexAttribute {
-                elements: 3,
-                offset: vertex_data.len(),
-                stride: 0,
-            };
-            vertex_data.extend(Vector3::as_ref(&*self.normal_data));
+            VertexLayout::Interleaved => {
+                let mut offset = 0;
+                for &(elements, _) in &attrib_data {
+                    attribs.push(VertexAttribute {
+                        elements: elements,
+                        offset: offset,
+                        stride: vertex_size,
+                    });
+                    offset += elements;
+                }
 
-            Some(attrib)
+                for vertex in 0..vertex_count {
+                    for &(elements, data) in &attrib_data {
+                        vertex_data.extend(&data[vertex * elements .. (vertex + 1) * elements]);
+                    }
+                }
+            }
+        }
+
+        // Hand the attributes back out in the same order they were gathered.
+        let mut attribs = attribs.into_iter();
+        let position_attrib = attribs.next().unwrap();
+        let normal_attrib = if self.normal_data.len() > 0 { attribs.next() } else { None };
+        let tangent_attrib = if self.tangent_data.len() > 0 { attribs.next() } else { None };
+        let color_attrib = if self.color_data.len() > 0 { attribs.next() } else { None };
+        let texcoord_attribs = attribs
+            .by_ref()
+            .take(self.texcoord_data.len())
+            .collect::<Vec<_>>();
+        let custom_attribs = self.custom_data
+            .into_iter()
+            .map(|custom| custom.name)
+            .zip(attribs)
+            .collect::<Vec<_>>();
+
+        // Use 16 bit indices when every vertex can be addressed by one other than `0xFFFF`, see
+        // `MeshIndices`.
+        let indices = if vertex_count <= u16::MAX as usize {
+            MeshIndices::U16(self.indices.iter().map(|&index| index as u16).collect())
         } else {
-            None
+            MeshIndices::U32(self.indices)
         };
 
-        // Setup texcoord data.
-        let mut texcoord_attribs = Vec::new();
-        if self.texcoord_data.len() > 0 {
-            texcoord_attribs.push(VertexAttribute {
-                elements: 2,
-                offset: vertex_data.len(),
-                stride: 0,
-            });
-            vertex_data.extend(Vector2::as_ref(&*self.texcoord_data));
-        }
+        let submeshes = if self.submeshes.is_empty() {
+            vec![Submesh::new(0, indices.len())]
+        } else {
+            self.submeshes
+        };
 
         // By our powers combined! We are! A mesh.
         Ok(Mesh {
             vertex_data: vertex_data,
-            indices: self.indices,
+            vertex_count: vertex_count,
+            layout: self.layout,
+            indices: indices,
+            submeshes: submeshes,
 
             position: position_attrib,
             normal: normal_attrib,
+            tangent: tangent_attrib,
+            color: color_attrib,
             texcoord: texcoord_attribs,
+            custom: custom_attribs,
+
+            bounding_box: bounding_box,
+            bounding_sphere: bounding_sphere,
         })
     }
 }
+
+/// The normals requested from a `MeshBuilder`, see `MeshBuilder::compute_smooth_normals()` and
+/// `MeshBuilder::compute_flat_normals()`.
+#[derive(Debug, Clone, Copy)]
+enum ComputedNormals {
+    Smooth { hard_edge_angle: f32 },
+    Flat,
+}
+
+/// The data for a custom vertex attribute in a `MeshBuilder`.
+#[derive(Debug, Clone)]
+struct CustomData {
+    name: String,
+    elements: usize,
+    data: Vec<f32>,
+}
+
+/// Creates a hashable key from a position by its bit pattern.
+fn position_key(position: Point) -> [u32; 3] {
+    [position.x.to_bits(), position.y.to_bits(), position.z.to_bits()]
+}
+
+/// Creates a hashable key from a vector by its bit pattern.
+fn vector_key(vector: Vector3) -> [u32; 3] {
+    [vector.x.to_bits(), vector.y.to_bits(), vector.z.to_bits()]
+}
+
+/// Picks an arbitrary unit vector perpendicular to `normal`.
+fn perpendicular(normal: Vector3) -> Vector3 {
+    let axis = if normal.x.abs() < 0.9 { Vector3::RIGHT } else { Vector3::UP };
+    Vector3::cross(normal, axis).normalized()
+}
@@ -1 +1,8 @@
+pub mod cache;
 pub mod mesh;
+pub mod obj;
+pub mod optimize;
+pub mod ply;
+pub mod primitives;
+pub mod simplify;
+pub mod stl;
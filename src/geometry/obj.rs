@@ -0,0 +1,674 @@
+//! Loading meshes and materials from Wavefront OBJ and MTL files.
+//!
+//! `load()` reads an OBJ file along with any MTL material libraries it references, producing a
+//! `Mesh` for each object and group in the file. `parse()` and `parse_mtl()` do the same for
+//! source text that has already been loaded, without touching the file system.
+//!
+//! The following OBJ statements are supported:
+//!
+//! - `v x y z [w]` and `v x y z r g b`, the latter giving the vertex a color.
+//! - `vt u [v [w]]` and `vn x y z`.
+//! - `f`, with vertices of the form `v`, `v/vt`, `v//vn`, or `v/vt/vn`. Faces with more than 3
+//!   vertices are triangulated, and negative indices count back from the most recent data.
+//! - `o` and `g`, each of which starts a new object.
+//! - `mtllib` and `usemtl`.
+//!
+//! Any other statements, such as smoothing groups and free-form geometry, are ignored.
+//!
+//! Vertex data is only given to a mesh if every face vertex in the object provides it, e.g. if any
+//! face vertex lacks a texture coordinate the mesh won't have texture coordinates. Objects without
+//! normals have smooth normals generated for them, so that they can still be lit.
+
+use geometry::mesh::*;
+use material::Material;
+use math::*;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Error as IoError;
+use std::io::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// Loads an OBJ file and the material libraries it references.
+///
+/// Material library paths are resolved relative to the directory containing the OBJ file, and
+/// texture paths are resolved relative to the material library that references them.
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Obj, Error> {
+    let path = path.as_ref();
+    let source = read_file(path)?;
+    let mut obj = parse(&source)?;
+
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+    for library in &obj.material_libraries {
+        let library_path = directory.join(library);
+        let materials = load_mtl(&library_path).map_err(|error| Error::MaterialLibrary {
+            path: library_path.clone(),
+            error: Box::new(error),
+        })?;
+        obj.materials.extend(materials);
+    }
+
+    Ok(obj)
+}
+
+/// Parses the contents of an OBJ file.
+///
+/// Material libraries referenced by the source are listed in `Obj::material_libraries` but are
+/// not loaded, so `Obj::materials` will be empty.
+pub fn parse(source: &str) -> Result<Obj, Error> {
+    let mut parser = ObjParser::new();
+
+    for (line, statement) in statements(source) {
+        let mut arguments = statement.split_whitespace();
+        let keyword = match arguments.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+
+        match keyword {
+            "v" => {
+                let numbers = parse_numbers(arguments, line)?;
+                let position = match numbers.len() {
+                    3 | 4 | 6 | 7 => Point::new(numbers[0], numbers[1], numbers[2]),
+                    _ => return Err(ParseError::new(line, ParseErrorKind::BadArgumentCount).into()),
+                };
+                let color = if numbers.len() >= 6 {
+                    Some(Color::rgb(numbers[3], numbers[4], numbers[5]))
+                } else {
+                    None
+                };
+
+                parser.positions.push(position);
+                parser.colors.push(color);
+            }
+
+            "vt" => {
+                let numbers = parse_numbers(arguments, line)?;
+                let texcoord = match numbers.len() {
+                    1 => Vector2::new(numbers[0], 0.0),
+                    2 | 3 => Vector2::new(numbers[0], numbers[1]),
+                    _ => return Err(ParseError::new(line, ParseErrorKind::BadArgumentCount).into()),
+                };
+
+                parser.texcoords.push(texcoord);
+            }
+
+            "vn" => {
+                let numbers = parse_numbers(arguments, line)?;
+                if numbers.len() != 3 {
+                    return Err(ParseError::new(line, ParseErrorKind::BadArgumentCount).into());
+                }
+
+                parser.normals.push(Vector3::new(numbers[0], numbers[1], numbers[2]));
+            }
+
+            "f" => {
+                let mut face = Vec::new();
+                for vertex in arguments {
+                    face.push(parser.parse_face_vertex(vertex, line)?);
+                }
+
+                if face.len() < 3 {
+                    return Err(ParseError::new(line, ParseErrorKind::BadArgumentCount).into());
+                }
+
+                parser.add_face(&face);
+            }
+
+            "o" | "g" => {
+                let name = arguments.collect::<Vec<_>>().join(" ");
+                parser.finish_object()?;
+                parser.name = if name.is_empty() { "default".into() } else { name };
+            }
+
+            "usemtl" => {
+                let name = rest_of_statement(arguments, line)?;
+                parser.finish_object()?;
+                parser.material = Some(name);
+            }
+
+            "mtllib" => {
+                parser.material_libraries.extend(arguments.map(Into::into));
+            }
+
+            // Ignore everything else.
+            _ => {}
+        }
+    }
+
+    parser.finish_object()?;
+
+    Ok(Obj {
+        objects: parser.objects,
+        materials: Vec::new(),
+        material_libraries: parser.material_libraries,
+    })
+}
+
+/// Loads the materials in an MTL file.
+///
+/// Texture paths are resolved relative to the directory containing the MTL file.
+pub fn load_mtl<P: AsRef<Path>>(path: P) -> Result<Vec<ObjMaterial>, Error> {
+    let path = path.as_ref();
+    let source = read_file(path)?;
+    let mut materials = parse_mtl(&source)?;
+
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+    for material in &mut materials {
+        if let Some(ref mut texture) = material.diffuse_texture {
+            *texture = directory.join(&*texture);
+        }
+    }
+
+    Ok(materials)
+}
+
+/// Parses the contents of an MTL file.
+///
+/// Texture paths are left as they appear in the source. Texture options, such as `-s` and
+/// `-clamp`, are not supported.
+pub fn parse_mtl(source: &str) -> Result<Vec<ObjMaterial>, Error> {
+    let mut materials: Vec<ObjMaterial> = Vec::new();
+
+    for (line, statement) in statements(source) {
+        let mut arguments = statement.split_whitespace();
+        let keyword = match arguments.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+
+        if keyword == "newmtl" {
+            materials.push(ObjMaterial::new(rest_of_statement(arguments, line)?));
+            continue;
+        }
+
+        // Every other statement modifies the most recently declared material, so ignore unknown
+        // statements before checking that there is one.
+        match keyword {
+            "Kd" | "Ks" | "Ns" | "map_Kd" => {}
+            _ => continue,
+        }
+
+        let material = materials
+            .last_mut()
+            .ok_or_else(|| ParseError::new(line, ParseErrorKind::NoMaterial))?;
+
+        match keyword {
+            "Kd" => material.diffuse_color = Some(parse_color(arguments, line)?),
+            "Ks" => material.specular_color = Some(parse_color(arguments, line)?),
+
+            "Ns" => {
+                let numbers = parse_numbers(arguments, line)?;
+                if numbers.len() != 1 {
+                    return Err(ParseError::new(line, ParseErrorKind::BadArgumentCount).into());
+                }
+
+                material.specular_exponent = Some(numbers[0]);
+            }
+
+            "map_Kd" => material.diffuse_texture = Some(rest_of_statement(arguments, line)?.into()),
+
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(materials)
+}
+
+/// The contents of an OBJ file.
+#[derive(Debug, Clone)]
+pub struct Obj {
+    /// The objects in the file, in the order they appear.
+    pub objects: Vec<ObjObject>,
+
+    /// The materials from all of the file's material libraries.
+    pub materials: Vec<ObjMaterial>,
+
+    /// The material libraries referenced by the file, as they appear in the file.
+    pub material_libraries: Vec<String>,
+}
+
+impl Obj {
+    /// Gets the material with the specified name, if there is one.
+    pub fn material(&self, name: &str) -> Option<&ObjMaterial> {
+        self.materials.iter().find(|material| material.name == name)
+    }
+}
+
+/// A single object or group from an OBJ file.
+///
+/// If the material changes partway through an object, the faces after each `usemtl` statement
+/// become a separate `ObjObject` with the same name.
+#[derive(Debug, Clone)]
+pub struct ObjObject {
+    /// The name of the object or group, or `"default"` for faces that aren't in one.
+    pub name: String,
+
+    /// The name of the material used by the object, if one was specified.
+    pub material: Option<String>,
+
+    /// The mesh data for the object.
+    pub mesh: Mesh,
+}
+
+/// The parameters of a material from an MTL file.
+///
+/// Parameters that weren't specified in the file are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjMaterial {
+    /// The name of the material.
+    pub name: String,
+
+    /// The diffuse color of the material, from `Kd`.
+    pub diffuse_color: Option<Color>,
+
+    /// The specular color of the material, from `Ks`.
+    pub specular_color: Option<Color>,
+
+    /// The specular exponent of the material, from `Ns`.
+    pub specular_exponent: Option<f32>,
+
+    /// The path to the diffuse texture map, from `map_Kd`.
+    pub diffuse_texture: Option<PathBuf>,
+}
+
+impl ObjMaterial {
+    /// Creates a new material with no parameters set.
+    pub fn new<S: Into<String>>(name: S) -> ObjMaterial {
+        ObjMaterial {
+            name: name.into(),
+            diffuse_color: None,
+            specular_color: None,
+            specular_exponent: None,
+            diffuse_texture: None,
+        }
+    }
+
+    /// Sets the properties of `material` from the parameters of the MTL material.
+    ///
+    /// The properties set are the ones used by the built-in materials: `surface_color`,
+    /// `surface_specular`, and `surface_shininess`. The diffuse texture isn't set, since it
+    /// needs to be loaded and registered with the renderer first; once it has been, set it as the
+    /// `surface_diffuse` property.
+    pub fn apply(&self, material: &mut Material) {
+        if let Some(color) = self.diffuse_color {
+            material.set_color("surface_color", color);
+        }
+
+        if let Some(color) = self.specular_color {
+            material.set_color("surface_specular", color);
+        }
+
+        if let Some(exponent) = self.specular_exponent {
+            material.set_f32("surface_shininess", exponent);
+        }
+    }
+}
+
+/// An error that can occur when loading an OBJ or MTL file.
+#[derive(Debug)]
+pub enum Error {
+    IoError(IoError),
+    ParseError(ParseError),
+    BuildMeshError(BuildMeshError),
+
+    /// Indicates that a material library referenced by an OBJ file couldn't be loaded.
+    MaterialLibrary {
+        path: PathBuf,
+        error: Box<Error>,
+    },
+}
+
+impl From<IoError> for Error {
+    fn from(error: IoError) -> Error {
+        Error::IoError(error)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Error {
+        Error::ParseError(error)
+    }
+}
+
+impl From<BuildMeshError> for Error {
+    fn from(error: BuildMeshError) -> Error {
+        Error::BuildMeshError(error)
+    }
+}
+
+/// An error in the contents of an OBJ or MTL file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError {
+    /// The line the error occurred on, starting from 1.
+    ///
+    /// For statements continued over multiple lines this is the first line of the statement.
+    pub line: usize,
+
+    /// The kind of error.
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    fn new(line: usize, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            line: line,
+            kind: kind,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// Indicates that a statement had the wrong number of arguments.
+    BadArgumentCount,
+
+    /// Indicates that an argument couldn't be parsed as a number.
+    BadNumber,
+
+    /// Indicates that a face vertex wasn't of the form `v`, `v/vt`, `v//vn`, or `v/vt/vn`.
+    BadFaceVertex,
+
+    /// Indicates that a face index was 0 or referred to data that hadn't been declared.
+    IndexOutOfBounds,
+
+    /// Indicates that an MTL statement appeared before the first `newmtl` statement.
+    NoMaterial,
+}
+
+/// The indices of the position, texcoord, and normal for a face vertex.
+type FaceVertex = (usize, Option<usize>, Option<usize>);
+
+/// The state of an OBJ file as it's being parsed.
+struct ObjParser {
+    positions: Vec<Point>,
+    colors: Vec<Option<Color>>,
+    texcoords: Vec<Vector2>,
+    normals: Vec<Vector3>,
+
+    objects: Vec<ObjObject>,
+    material_libraries: Vec<String>,
+
+    // The object currently being parsed.
+    name: String,
+    material: Option<String>,
+    vertices: Vec<FaceVertex>,
+    vertex_indices: HashMap<FaceVertex, MeshIndex>,
+    indices: Vec<MeshIndex>,
+}
+
+impl ObjParser {
+    fn new() -> ObjParser {
+        ObjParser {
+            positions: Vec::new(),
+            colors: Vec::new(),
+            texcoords: Vec::new(),
+            normals: Vec::new(),
+
+            objects: Vec::new(),
+            material_libraries: Vec::new(),
+
+            name: "default".into(),
+            material: None,
+            vertices: Vec::new(),
+            vertex_indices: HashMap::new(),
+            indices: Vec::new(),
+        }
+    }
+
+    /// Parses a face vertex, resolving its indices against the data declared so far.
+    fn parse_face_vertex(&self, vertex: &str, line: usize) -> Result<FaceVertex, ParseError> {
+        let mut parts = vertex.split('/');
+        let position = parts.next().unwrap_or("");
+        let texcoord = parts.next().unwrap_or("");
+        let normal = parts.next().unwrap_or("");
+        if parts.next().is_some() || position.is_empty() {
+            return Err(ParseError::new(line, ParseErrorKind::BadFaceVertex));
+        }
+
+        // Texcoord and normal indices may be left out.
+        let resolve_optional = |index: &str, len: usize| -> Result<Option<usize>, ParseError> {
+            if index.is_empty() {
+                Ok(None)
+            } else {
+                resolve_index(index, len, line).map(Some)
+            }
+        };
+
+        Ok((
+            resolve_index(position, self.positions.len(), line)?,
+            resolve_optional(texcoord, self.texcoords.len())?,
+            resolve_optional(normal, self.normals.len())?,
+        ))
+    }
+
+    /// Triangulates a face and adds it to the current object.
+    fn add_face(&mut self, face: &[FaceVertex]) {
+        let positions = face
+            .iter()
+            .map(|vertex| self.positions[vertex.0])
+            .collect::<Vec<_>>();
+
+        for corner in triangulate(&positions) {
+            let vertex = face[corner];
+            let next_index = self.vertices.len() as MeshIndex;
+            let index = *self.vertex_indices.entry(vertex).or_insert(next_index);
+            if index == next_index {
+                self.vertices.push(vertex);
+            }
+
+            self.indices.push(index);
+        }
+    }
+
+    /// Builds the mesh for the current object, then resets the object's face data.
+    ///
+    /// Objects without any faces are skipped.
+    fn finish_object(&mut self) -> Result<(), BuildMeshError> {
+        let vertices = ::std::mem::take(&mut self.vertices);
+        let indices = ::std::mem::take(&mut self.indices);
+        self.vertex_indices.clear();
+
+        if indices.is_empty() {
+            return Ok(());
+        }
+
+        let positions = vertices
+            .iter()
+            .map(|vertex| self.positions[vertex.0])
+            .collect::<Vec<_>>();
+        let mut builder = MeshBuilder::new()
+            .set_position_data(&positions)
+            .set_indices(&indices);
+
+        let colors = vertices
+            .iter()
+            .map(|vertex| self.colors[vertex.0])
+            .collect::<Option<Vec<_>>>();
+        if let Some(colors) = colors {
+            builder = builder.set_color_data(&colors);
+        }
+
+        let texcoords = vertices
+            .iter()
+            .map(|vertex| vertex.1.map(|index| self.texcoords[index]))
+            .collect::<Option<Vec<_>>>();
+        if let Some(texcoords) = texcoords {
+            builder = builder.set_texcoord_data(&texcoords);
+        }
+
+        let normals = vertices
+            .iter()
+            .map(|vertex| vertex.2.map(|index| self.normals[index]))
+            .collect::<Option<Vec<_>>>();
+        builder = match normals {
+            Some(normals) => builder.set_normal_data(&normals),
+            None => builder.compute_smooth_normals(PI / 3.0),
+        };
+
+        self.objects.push(ObjObject {
+            name: self.name.clone(),
+            material: self.material.clone(),
+            mesh: builder.build()?,
+        });
+
+        Ok(())
+    }
+}
+
+/// Resolves a face index against the `len` items of its kind declared so far, returning the
+/// zero-based index.
+///
+/// Positive indices start from 1, negative indices count back from the most recent item.
+fn resolve_index(index: &str, len: usize, line: usize) -> Result<usize, ParseError> {
+    let index = index
+        .parse::<isize>()
+        .map_err(|_| ParseError::new(line, ParseErrorKind::BadFaceVertex))?;
+
+    let resolved = if index > 0 { index - 1 } else { len as isize + index };
+    if index == 0 || resolved < 0 || resolved >= len as isize {
+        return Err(ParseError::new(line, ParseErrorKind::IndexOutOfBounds));
+    }
+
+    Ok(resolved as usize)
+}
+
+/// Triangulates a polygon using ear clipping, returning the indices of the corners of each
+/// triangle.
+///
+/// The polygon is assumed to be roughly planar. If no ear can be found, as can happen with
+/// self-intersecting or degenerate polygons, the remaining polygon is triangulated as a fan.
+fn triangulate(positions: &[Point]) -> Vec<usize> {
+    let mut triangles = Vec::with_capacity((positions.len() - 2) * 3);
+    if positions.len() == 3 {
+        triangles.extend(&[0, 1, 2]);
+        return triangles;
+    }
+
+    // Find the polygon's normal with Newell's method, which works for concave polygons.
+    let mut normal = Vector3::ZERO;
+    for (index, &current) in positions.iter().enumerate() {
+        let next = positions[(index + 1) % positions.len()];
+        normal.x += (current.y - next.y) * (current.z + next.z);
+        normal.y += (current.z - next.z) * (current.x + next.x);
+        normal.z += (current.x - next.x) * (current.y + next.y);
+    }
+
+    // Returns `true` if `point` is on the inner side of the edge from `start` to `end`.
+    let inside_edge = |start: Point, end: Point, point: Point| {
+        Vector3::cross(end - start, point - start).dot(normal) >= 0.0
+    };
+
+    let mut remaining = (0..positions.len()).collect::<Vec<_>>();
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let ear = (0..count).find(|&corner| {
+            let previous = remaining[(corner + count - 1) % count];
+            let current = remaining[corner];
+            let next = remaining[(corner + 1) % count];
+            let (a, b, c) = (positions[previous], positions[current], positions[next]);
+
+            // The corner must be convex and no other corner may be inside the triangle.
+            Vector3::cross(b - a, c - b).dot(normal) > 0.0
+                && !remaining.iter().any(|&other| {
+                    let point = positions[other];
+                    other != previous && other != current && other != next
+                        && inside_edge(a, b, point)
+                        && inside_edge(b, c, point)
+                        && inside_edge(c, a, point)
+                })
+        });
+
+        match ear {
+            Some(corner) => {
+                triangles.push(remaining[(corner + count - 1) % count]);
+                triangles.push(remaining[corner]);
+                triangles.push(remaining[(corner + 1) % count]);
+                remaining.remove(corner);
+            }
+
+            None => break,
+        }
+    }
+
+    for corner in 1..remaining.len() - 1 {
+        triangles.push(remaining[0]);
+        triangles.push(remaining[corner]);
+        triangles.push(remaining[corner + 1]);
+    }
+
+    triangles
+}
+
+/// Splits source text into statements, pairing each with the line it starts on.
+///
+/// Comments are removed, and lines ending with a backslash are joined with the following line.
+fn statements(source: &str) -> Vec<(usize, String)> {
+    let mut statements = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+
+    for (index, line) in source.lines().enumerate() {
+        let line = match line.find('#') {
+            Some(comment) => &line[..comment],
+            None => line,
+        };
+        let trimmed = line.trim_end();
+        let continued = trimmed.ends_with('\\');
+        let line = if continued { &trimmed[..trimmed.len() - 1] } else { line };
+
+        let mut statement = current.take().unwrap_or_else(|| (index + 1, String::new()));
+        statement.1.push(' ');
+        statement.1.push_str(line);
+
+        if continued {
+            current = Some(statement);
+        } else {
+            statements.push(statement);
+        }
+    }
+
+    statements.extend(current);
+    statements
+}
+
+fn parse_numbers<'a, I>(arguments: I, line: usize) -> Result<Vec<f32>, ParseError>
+    where I: Iterator<Item = &'a str>
+{
+    arguments
+        .map(|argument| {
+            argument
+                .parse::<f32>()
+                .map_err(|_| ParseError::new(line, ParseErrorKind::BadNumber))
+        })
+        .collect()
+}
+
+/// Parses an MTL color, which is either a single gray value or red, green, and blue values.
+fn parse_color<'a, I>(arguments: I, line: usize) -> Result<Color, ParseError>
+    where I: Iterator<Item = &'a str>
+{
+    let numbers = parse_numbers(arguments, line)?;
+    match numbers.len() {
+        1 => Ok(Color::rgb(numbers[0], numbers[0], numbers[0])),
+        3 => Ok(Color::rgb(numbers[0], numbers[1], numbers[2])),
+        _ => Err(ParseError::new(line, ParseErrorKind::BadArgumentCount)),
+    }
+}
+
+/// Joins the remaining arguments of a statement, for names and paths that may contain spaces.
+fn rest_of_statement<'a, I>(arguments: I, line: usize) -> Result<String, ParseError>
+    where I: Iterator<Item = &'a str>
+{
+    let rest = arguments.collect::<Vec<_>>().join(" ");
+    if rest.is_empty() {
+        return Err(ParseError::new(line, ParseErrorKind::BadArgumentCount));
+    }
+
+    Ok(rest)
+}
+
+fn read_file(path: &Path) -> Result<String, IoError> {
+    let mut file = File::open(path)?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    Ok(contents)
+}
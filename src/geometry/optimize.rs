@@ -0,0 +1,471 @@
+//! Optimizing meshes for rendering.
+//!
+//! Meshes loaded from files or generated procedurally are often far from ideal for the GPU: they
+//! can have duplicate vertices, degenerate triangles, and triangles in an order that thrashes the
+//! post-transform vertex cache. The functions in this module each perform one optimization,
+//! returning a new mesh with every vertex attribute and the vertex layout preserved. `optimize()`
+//! runs all of them in the recommended order and reports how much they helped.
+//!
+//! Triangles are never moved from one submesh to another, so each submesh keeps its own
+//! triangles and can still be drawn with its own material.
+//!
+//! The effectiveness of the vertex cache is measured by the average cache miss ratio (ACMR), the
+//! number of vertices that have to be transformed per triangle. The ACMR is between 0.5 (for an
+//! ideal, infinitely large mesh) and 3 (when no vertices are reused at all); lower is better.
+
+use geometry::mesh::*;
+use math::*;
+use std::collections::{HashMap, HashSet};
+
+/// The number of vertices in the post-transform vertex cache that the optimizer targets, and the
+/// cache size `optimize()` uses when measuring ACMR.
+pub const CACHE_SIZE: usize = 32;
+
+/// Statistics about a mesh before and after running `optimize()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Report {
+    pub vertices_before: usize,
+    pub vertices_after: usize,
+    pub triangles_before: usize,
+    pub triangles_after: usize,
+
+    /// The average cache miss ratio for a FIFO cache of `CACHE_SIZE` vertices, see `acmr()`.
+    pub acmr_before: f32,
+    pub acmr_after: f32,
+}
+
+/// Runs every optimization on the mesh, returning the optimized mesh along with a report of the
+/// changes.
+///
+/// The mesh's vertices are welded with `weld_vertices()` using `tolerance`, then degenerate and
+/// duplicate triangles are removed, the triangles are reordered for the vertex cache, and finally
+/// the vertices are reordered for fetch locality.
+pub fn optimize(mesh: &Mesh, tolerance: f32) -> (Mesh, Report) {
+    let welded = weld_vertices(mesh, tolerance);
+    let cleaned = remove_degenerate_triangles(&welded);
+    let ordered = optimize_vertex_cache(&cleaned);
+    let optimized = optimize_vertex_fetch(&ordered);
+
+    let report = Report {
+        vertices_before: mesh.vertex_count(),
+        vertices_after: optimized.vertex_count(),
+        triangles_before: mesh.indices().len() / 3,
+        triangles_after: optimized.indices().len() / 3,
+        acmr_before: acmr(mesh, CACHE_SIZE),
+        acmr_after: acmr(&optimized, CACHE_SIZE),
+    };
+
+    (optimized, report)
+}
+
+/// Merges vertices whose attributes are all within `tolerance` of each other.
+///
+/// Every attribute is compared, not just the position, so vertices along a texture seam or a
+/// hard edge are kept separate. Pass a tolerance of 0 to only merge exact duplicates. Vertices
+/// that aren't used by any triangle are kept.
+///
+/// # Panics
+///
+/// Panics if `tolerance` is negative.
+pub fn weld_vertices(mesh: &Mesh, tolerance: f32) -> Mesh {
+    assert!(tolerance >= 0.0, "Weld tolerance must not be negative: {}", tolerance);
+
+    // Vertices are bucketed into cells by position so that only nearby vertices are compared.
+    // With a tolerance of 0 each distinct position gets its own cell.
+    let cell = |vertex: usize| -> [i64; 3] {
+        let position = mesh.attribute_value(mesh.position(), vertex);
+        let mut cell = [0; 3];
+        for (cell, &value) in cell.iter_mut().zip(position) {
+            *cell = if tolerance > 0.0 {
+                (value / tolerance).floor() as i64
+            } else {
+                (value + 0.0).to_bits() as i64
+            };
+        }
+        cell
+    };
+
+    let mut cells = HashMap::<[i64; 3], Vec<usize>>::new();
+    let mut kept = Vec::new();
+    let mut kept_values = Vec::new();
+    let mut remap = Vec::with_capacity(mesh.vertex_count());
+    for vertex in 0..mesh.vertex_count() {
+        let values = vertex_values(mesh, vertex);
+        let center = cell(vertex);
+
+        // Check the neighboring cells too, since a vertex within tolerance can be just across a
+        // cell boundary.
+        let range = if tolerance > 0.0 { -1..2 } else { 0..1 };
+        let mut existing = None;
+        'search: for x in range.clone() {
+            for y in range.clone() {
+                for z in range.clone() {
+                    let neighbor = [center[0] + x, center[1] + y, center[2] + z];
+                    for &other in cells.get(&neighbor).into_iter().flatten() {
+                        let other_values: &Vec<f32> = &kept_values[other];
+                        let within_tolerance = values
+                            .iter()
+                            .zip(other_values)
+                            .all(|(value, other)| (value - other).abs() <= tolerance);
+                        if within_tolerance {
+                            existing = Some(other);
+                            break 'search;
+                        }
+                    }
+                }
+            }
+        }
+
+        let index = existing.unwrap_or_else(|| {
+            kept.push(vertex);
+            kept_values.push(values);
+            cells.entry(center).or_default().push(kept.len() - 1);
+            kept.len() - 1
+        });
+        remap.push(index as MeshIndex);
+    }
+
+    let indices = mesh.indices()
+        .iter()
+        .map(|index| remap[index as usize])
+        .collect::<Vec<_>>();
+    rebuild(mesh, &kept, &indices, mesh.submeshes())
+}
+
+/// Removes degenerate and duplicate triangles.
+///
+/// A triangle is degenerate if it uses the same vertex more than once or if its corners are
+/// collinear, so that it has no area. A triangle is a duplicate if it uses the same vertices with
+/// the same winding as an earlier triangle in the same submesh; triangles with the opposite
+/// winding are kept, since they face the other way. Vertices are left as-is, even if no
+/// triangles use them anymore.
+pub fn remove_degenerate_triangles(mesh: &Mesh) -> Mesh {
+    let position = |index: MeshIndex| {
+        let value = mesh.attribute_value(mesh.position(), index as usize);
+        Point::new(value[0], value[1], value[2])
+    };
+
+    let (kept, submeshes) = map_submeshes(mesh, |indices| {
+        let mut seen = HashSet::new();
+        let mut kept = Vec::with_capacity(indices.len());
+        for triangle in indices.chunks(3) {
+            let (a, b, c) = (triangle[0], triangle[1], triangle[2]);
+            if a == b || b == c || c == a {
+                continue;
+            }
+
+            let normal = Vector3::cross(position(b) - position(a), position(c) - position(a));
+            if normal.magnitude_squared() == 0.0 {
+                continue;
+            }
+
+            // Rotate the triangle so that it starts with its lowest index, which keeps the winding
+            // while making every rotation of the same triangle compare equal.
+            let key = if a < b && a < c {
+                [a, b, c]
+            } else if b < c {
+                [b, c, a]
+            } else {
+                [c, a, b]
+            };
+            if seen.insert(key) {
+                kept.extend(triangle);
+            }
+        }
+        kept
+    });
+
+    let vertices = (0..mesh.vertex_count()).collect::<Vec<_>>();
+    rebuild(mesh, &vertices, &kept, &submeshes)
+}
+
+/// Reorders the triangles of the mesh to make better use of the post-transform vertex cache.
+///
+/// This uses Tom Forsyth's linear-speed vertex cache optimization, which greedily picks the next
+/// triangle based on how recently its vertices were used and how many unused triangles they
+/// have left. It targets a cache of `CACHE_SIZE` vertices but works well for any cache size.
+pub fn optimize_vertex_cache(mesh: &Mesh) -> Mesh {
+    let vertex_count = mesh.vertex_count();
+    let (indices, submeshes) = map_submeshes(mesh, |indices| forsyth_order(indices, vertex_count));
+
+    let vertices = (0..vertex_count).collect::<Vec<_>>();
+    rebuild(mesh, &vertices, &indices, &submeshes)
+}
+
+/// Orders the triangles in `indices` for the vertex cache, see `optimize_vertex_cache()`.
+fn forsyth_order(indices: &[MeshIndex], vertex_count: usize) -> Vec<MeshIndex> {
+    let triangle_count = indices.len() / 3;
+
+    // Build the list of triangles using each vertex. The list for each vertex is a range in
+    // `adjacency`, with the triangles still to be drawn at the start of the range.
+    let mut remaining = vec![0; vertex_count];
+    for &index in indices {
+        remaining[index as usize] += 1;
+    }
+
+    let mut adjacency_start = Vec::with_capacity(vertex_count);
+    let mut total = 0;
+    for &count in &remaining {
+        adjacency_start.push(total);
+        total += count;
+    }
+
+    let mut adjacency = vec![0; indices.len()];
+    let mut filled = vec![0; vertex_count];
+    for (position, &index) in indices.iter().enumerate() {
+        let vertex = index as usize;
+        adjacency[adjacency_start[vertex] + filled[vertex]] = position / 3;
+        filled[vertex] += 1;
+    }
+
+    let mut cache_position = vec![None; vertex_count];
+    let mut vertex_scores = remaining
+        .iter()
+        .map(|&remaining| forsyth_vertex_score(None, remaining))
+        .collect::<Vec<_>>();
+    let mut drawn = vec![false; triangle_count];
+
+    let mut cache: Vec<usize> = Vec::with_capacity(CACHE_SIZE + 3);
+    let mut output = Vec::with_capacity(indices.len());
+    let mut best_triangle = None;
+    let mut next_undrawn = 0;
+    for _ in 0..triangle_count {
+        // When none of the cached vertices have any triangles left, start again from the next
+        // triangle that hasn't been drawn.
+        let triangle = match best_triangle {
+            Some(triangle) => triangle,
+            None => {
+                while drawn[next_undrawn] {
+                    next_undrawn += 1;
+                }
+                next_undrawn
+            }
+        };
+
+        drawn[triangle] = true;
+        let corners = [
+            indices[triangle * 3] as usize,
+            indices[triangle * 3 + 1] as usize,
+            indices[triangle * 3 + 2] as usize,
+        ];
+        output.extend(&indices[triangle * 3 .. triangle * 3 + 3]);
+
+        // Remove the triangle from the remaining triangles of its vertices.
+        for &vertex in &corners {
+            let start = adjacency_start[vertex];
+            let list = &mut adjacency[start .. start + remaining[vertex]];
+            let position = list.iter().position(|&other| other == triangle).unwrap();
+            let last = list.len() - 1;
+            list.swap(position, last);
+            remaining[vertex] -= 1;
+        }
+
+        // Move the triangle's vertices to the front of the cache, pushing the rest back.
+        let mut new_cache = Vec::with_capacity(CACHE_SIZE + 3);
+        new_cache.extend(&corners);
+        new_cache.extend(cache.iter().filter(|vertex| !corners.contains(vertex)));
+
+        for (position, &vertex) in new_cache.iter().enumerate() {
+            cache_position[vertex] = if position < CACHE_SIZE { Some(position) } else { None };
+            vertex_scores[vertex] = forsyth_vertex_score(cache_position[vertex], remaining[vertex]);
+        }
+
+        // Update the scores of every triangle that uses a vertex whose score changed, and pick
+        // the best of them to draw next.
+        best_triangle = None;
+        let mut best_score = -1.0;
+        for &vertex in &new_cache {
+            let start = adjacency_start[vertex];
+            for &other in &adjacency[start .. start + remaining[vertex]] {
+                let score = indices[other * 3 .. other * 3 + 3]
+                    .iter()
+                    .map(|&index| vertex_scores[index as usize])
+                    .sum::<f32>();
+                if score > best_score {
+                    best_score = score;
+                    best_triangle = Some(other);
+                }
+            }
+        }
+
+        new_cache.truncate(CACHE_SIZE);
+        cache = new_cache;
+    }
+
+    output
+}
+
+/// Reorders the vertices of the mesh to match the order the triangles use them in.
+///
+/// Vertices used by earlier triangles come earlier in the vertex data, which improves the
+/// locality of vertex fetches. This should be done after `optimize_vertex_cache()`, since it
+/// depends on the triangle order. Vertices that aren't used by any triangle are removed.
+pub fn optimize_vertex_fetch(mesh: &Mesh) -> Mesh {
+    let mut remap = vec![None; mesh.vertex_count()];
+    let mut vertices = Vec::with_capacity(mesh.vertex_count());
+    let indices = mesh.indices()
+        .iter()
+        .map(|index| {
+            *remap[index as usize].get_or_insert_with(|| {
+                vertices.push(index as usize);
+                (vertices.len() - 1) as MeshIndex
+            })
+        })
+        .collect::<Vec<_>>();
+
+    rebuild(mesh, &vertices, &indices, mesh.submeshes())
+}
+
+/// Calculates the average cache miss ratio of the mesh for a FIFO vertex cache holding
+/// `cache_size` vertices.
+///
+/// The ACMR is the number of cache misses (and so vertex shader invocations) per triangle. A
+/// mesh with no triangles has an ACMR of 0.
+///
+/// # Panics
+///
+/// Panics if `cache_size` is 0.
+pub fn acmr(mesh: &Mesh, cache_size: usize) -> f32 {
+    assert!(cache_size > 0, "Cache size must not be 0");
+
+    let triangle_count = mesh.indices().len() / 3;
+    if triangle_count == 0 {
+        return 0.0;
+    }
+
+    // Each vertex records the miss count at which it was added to the cache. In a FIFO cache a
+    // vertex stays cached until `cache_size` more vertices have been added after it.
+    let mut added_at = vec![None; mesh.vertex_count()];
+    let mut misses: usize = 0;
+    for index in mesh.indices().iter() {
+        let cached = match added_at[index as usize] {
+            Some(added_at) => misses - added_at < cache_size,
+            None => false,
+        };
+
+        if !cached {
+            misses += 1;
+            added_at[index as usize] = Some(misses);
+        }
+    }
+
+    misses as f32 / triangle_count as f32
+}
+
+/// Forsyth's constants, from "Linear-Speed Vertex Cache Optimisation".
+const CACHE_DECAY_POWER: f32 = 1.5;
+const LAST_TRIANGLE_SCORE: f32 = 0.75;
+const VALENCE_BOOST_SCALE: f32 = 2.0;
+const VALENCE_BOOST_POWER: f32 = 0.5;
+
+/// Scores a vertex by its position in the cache and the number of triangles still using it.
+fn forsyth_vertex_score(cache_position: Option<usize>, remaining: usize) -> f32 {
+    if remaining == 0 {
+        return -1.0;
+    }
+
+    let cache_score = match cache_position {
+        None => 0.0,
+
+        // The vertices of the last triangle drawn get a fixed score, so that the next triangle
+        // doesn't depend on the order they were used in.
+        Some(position) if position < 3 => LAST_TRIANGLE_SCORE,
+
+        Some(position) => {
+            let scale = 1.0 / (CACHE_SIZE - 3) as f32;
+            (1.0 - (position - 3) as f32 * scale).powf(CACHE_DECAY_POWER)
+        }
+    };
+
+    // Boost vertices with few triangles left, so that lone triangles get drawn rather than left
+    // behind.
+    let valence_boost = VALENCE_BOOST_SCALE * (remaining as f32).powf(-VALENCE_BOOST_POWER);
+
+    cache_score + valence_boost
+}
+
+/// Gets the values of every attribute of a vertex.
+fn vertex_values(mesh: &Mesh, vertex: usize) -> Vec<f32> {
+    let mut values = mesh.attribute_value(mesh.position(), vertex).to_vec();
+    let attributes = mesh.normal()
+        .into_iter()
+        .chain(mesh.tangent())
+        .chain(mesh.color())
+        .chain(mesh.texcoord().iter().cloned())
+        .chain(mesh.custom_attributes().iter().map(|custom| custom.1));
+    for attribute in attributes {
+        values.extend(mesh.attribute_value(attribute, vertex));
+    }
+    values
+}
+
+/// Transforms the indices of each submesh of `mesh` with `transform`.
+///
+/// Returns the transformed indices of every submesh one after another, along with the submeshes
+/// describing where each one's indices ended up.
+fn map_submeshes<F>(mesh: &Mesh, mut transform: F) -> (Vec<MeshIndex>, Vec<Submesh>)
+    where F: FnMut(&[MeshIndex]) -> Vec<MeshIndex>
+{
+    let indices = mesh.indices().to_vec();
+    let mut output = Vec::with_capacity(indices.len());
+    let mut submeshes = Vec::with_capacity(mesh.submeshes().len());
+    for submesh in mesh.submeshes() {
+        let start = output.len();
+        output.extend(transform(&indices[submesh.start .. submesh.end()]));
+        submeshes.push(Submesh::new(start, output.len() - start));
+    }
+
+    (output, submeshes)
+}
+
+/// Builds a new mesh from a subset of the vertices of `mesh`, keeping all of its attributes and
+/// its vertex layout.
+///
+/// The new mesh's vertex `n` is the original mesh's vertex `vertices[n]`, and `indices` index
+/// into the new vertices. `submeshes` describe the submeshes of `indices`.
+pub(crate) fn rebuild(mesh: &Mesh, vertices: &[usize], indices: &[MeshIndex], submeshes: &[Submesh]) -> Mesh {
+    let values = |attribute: VertexAttribute| {
+        vertices.iter().map(move |&vertex| mesh.attribute_value(attribute, vertex))
+    };
+
+    let mut builder = MeshBuilder::new()
+        .set_layout(mesh.layout())
+        .set_position_data(&values(mesh.position())
+            .map(|value| Point::new(value[0], value[1], value[2]))
+            .collect::<Vec<_>>());
+
+    if let Some(normal) = mesh.normal() {
+        builder = builder.set_normal_data(&values(normal)
+            .map(|value| Vector3::new(value[0], value[1], value[2]))
+            .collect::<Vec<_>>());
+    }
+
+    if let Some(tangent) = mesh.tangent() {
+        builder = builder.set_tangent_data(&values(tangent)
+            .map(|value| Vector4::new(value[0], value[1], value[2], value[3]))
+            .collect::<Vec<_>>());
+    }
+
+    if let Some(color) = mesh.color() {
+        builder = builder.set_color_data(&values(color)
+            .map(|value| Color::new(value[0], value[1], value[2], value[3]))
+            .collect::<Vec<_>>());
+    }
+
+    for (index, &texcoord) in mesh.texcoord().iter().enumerate() {
+        builder = builder.set_texcoord_data_at(index, &values(texcoord)
+            .map(|value| Vector2::new(value[0], value[1]))
+            .collect::<Vec<_>>());
+    }
+
+    for &(ref name, custom) in mesh.custom_attributes() {
+        let data = values(custom).flat_map(|value| value.iter().cloned()).collect::<Vec<_>>();
+        builder = builder.set_custom_data(name, custom.elements, &data);
+    }
+
+    builder
+        .set_indices(indices)
+        .set_submeshes(submeshes)
+        .build()
+        .expect("Rebuilding a valid mesh should never fail")
+}
@@ -0,0 +1,616 @@
+//! Reading and writing PLY files.
+//!
+//! PLY files can be ASCII or binary, in either byte order. The reader builds a mesh from the
+//! `vertex` and `face` elements, ignoring any other elements. The following vertex properties are
+//! used, and any others are ignored:
+//!
+//! - `x`, `y`, and `z` for the position, which are required.
+//! - `nx`, `ny`, and `nz` for the normal.
+//! - `red`, `green`, `blue`, and optionally `alpha` for the vertex color. Integer colors are
+//!   treated as sRGB encoded and converted to linear, floating point colors are used as-is.
+//! - `s` and `t`, `u` and `v`, or `texture_u` and `texture_v` for the texture coordinates.
+//!
+//! Faces are read from the `vertex_indices` (or `vertex_index`) list property, and faces with more
+//! than 3 vertices are triangulated as a fan.
+//!
+//! Writing includes the mesh's positions, normals, vertex colors, and first set of texture
+//! coordinates, with vertex colors written as 8-bit sRGB values.
+
+use geometry::mesh::*;
+use math::*;
+use std::fs::File;
+use std::io::{BufWriter, Error as IoError};
+use std::io::prelude::*;
+use std::path::Path;
+use std::str;
+
+/// Loads a PLY file.
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Mesh, Error> {
+    let mut file = File::open(path)?;
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)?;
+    parse(&data)
+}
+
+/// Parses the contents of a PLY file.
+pub fn parse(data: &[u8]) -> Result<Mesh, Error> {
+    let (header, body_start) = parse_header(data)?;
+    let mut body = match header.format {
+        Format::Ascii => {
+            let line_count = header.line_count;
+            let text = str::from_utf8(&data[body_start..])
+                .map_err(|_| Error::BadValue { line: line_count + 1 })?;
+            Body::Ascii {
+                tokens: text
+                    .lines()
+                    .enumerate()
+                    .flat_map(|(index, line)| {
+                        line.split_whitespace().map(move |token| (line_count + index + 1, token))
+                    })
+                    .collect(),
+                position: 0,
+            }
+        }
+
+        format => Body::Binary {
+            data: &data[body_start..],
+            position: 0,
+            big_endian: format == Format::BinaryBigEndian,
+        },
+    };
+
+    let mut builder = MeshBuilder::new();
+    let mut indices = Vec::new();
+    for element in &header.elements {
+        // Instances of elements without properties have no data, so there's nothing to read.
+        // Otherwise each instance reads at least one value, so counts that can't fit in the rest
+        // of the body are rejected before anything is allocated for them.
+        let count = if element.properties.is_empty() { 0 } else { element.count };
+        if count > body.remaining() {
+            return Err(Error::UnexpectedEof);
+        }
+
+        let mut instances = Vec::with_capacity(count);
+        for _ in 0..count {
+            let mut values = Vec::with_capacity(element.properties.len());
+            for property in &element.properties {
+                values.push(match property.kind {
+                    PropertyKind::Scalar(scalar) => vec![body.read(scalar)?],
+                    PropertyKind::List { count, item } => {
+                        let count = body.read(count)? as usize;
+                        if count > body.remaining() {
+                            return Err(Error::UnexpectedEof);
+                        }
+
+                        let mut list = Vec::with_capacity(count);
+                        for _ in 0..count {
+                            list.push(body.read(item)?);
+                        }
+                        list
+                    }
+                });
+            }
+            instances.push(values);
+        }
+
+        match &*element.name {
+            "vertex" => builder = read_vertices(builder, element, &instances)?,
+
+            "face" => {
+                let list = element
+                    .property_index("vertex_indices")
+                    .or_else(|| element.property_index("vertex_index"));
+                if let Some(list) = list {
+                    for face in &instances {
+                        let face = &face[list];
+                        if face.iter().any(|&index| index < 0.0) {
+                            return Err(Error::NegativeIndex);
+                        }
+
+                        for corner in 2..face.len() {
+                            indices.push(face[0] as MeshIndex);
+                            indices.push(face[corner - 1] as MeshIndex);
+                            indices.push(face[corner] as MeshIndex);
+                        }
+                    }
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    Ok(builder.set_indices(&indices).build()?)
+}
+
+/// Writes a mesh as a PLY file in the specified format.
+pub fn write<W: Write>(mesh: &Mesh, format: Format, writer: W) -> Result<(), IoError> {
+    let mut writer = BufWriter::new(writer);
+
+    let format_name = match format {
+        Format::Ascii => "ascii",
+        Format::BinaryLittleEndian => "binary_little_endian",
+        Format::BinaryBigEndian => "binary_big_endian",
+    };
+
+    let texcoord = mesh.texcoord().first().cloned();
+
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format {} 1.0", format_name)?;
+    writeln!(writer, "comment Written by polygon")?;
+    writeln!(writer, "element vertex {}", mesh.vertex_count())?;
+    writeln!(writer, "property float x")?;
+    writeln!(writer, "property float y")?;
+    writeln!(writer, "property float z")?;
+    if mesh.normal().is_some() {
+        writeln!(writer, "property float nx")?;
+        writeln!(writer, "property float ny")?;
+        writeln!(writer, "property float nz")?;
+    }
+    if texcoord.is_some() {
+        writeln!(writer, "property float s")?;
+        writeln!(writer, "property float t")?;
+    }
+    if mesh.color().is_some() {
+        writeln!(writer, "property uchar red")?;
+        writeln!(writer, "property uchar green")?;
+        writeln!(writer, "property uchar blue")?;
+        writeln!(writer, "property uchar alpha")?;
+    }
+    writeln!(writer, "element face {}", mesh.indices().len() / 3)?;
+    writeln!(writer, "property list uchar uint vertex_indices")?;
+    writeln!(writer, "end_header")?;
+
+    let mut body = BodyWriter {
+        writer: writer,
+        format: format,
+        first: true,
+    };
+
+    for vertex in 0..mesh.vertex_count() {
+        for &value in &mesh.attribute_value(mesh.position(), vertex)[..3] {
+            body.write_f32(value)?;
+        }
+
+        if let Some(normal) = mesh.normal() {
+            for &value in mesh.attribute_value(normal, vertex) {
+                body.write_f32(value)?;
+            }
+        }
+
+        if let Some(texcoord) = texcoord {
+            for &value in mesh.attribute_value(texcoord, vertex) {
+                body.write_f32(value)?;
+            }
+        }
+
+        if let Some(color) = mesh.color() {
+            let value = mesh.attribute_value(color, vertex);
+            let color = Color::new(value[0], value[1], value[2], value[3]);
+            for &byte in &color.to_srgba8() {
+                body.write_u8(byte)?;
+            }
+        }
+
+        body.end_element()?;
+    }
+
+    let indices = mesh.indices();
+    for triangle in 0..indices.len() / 3 {
+        body.write_u8(3)?;
+        for corner in 0..3 {
+            body.write_u32(indices.get(triangle * 3 + corner))?;
+        }
+        body.end_element()?;
+    }
+
+    body.writer.flush()
+}
+
+/// The formats that a PLY file can be in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+/// An error that can occur when reading a PLY file.
+#[derive(Debug)]
+pub enum Error {
+    IoError(IoError),
+    BuildMeshError(BuildMeshError),
+
+    /// Indicates that the header was malformed on the specified line, starting from 1.
+    BadHeader {
+        line: usize,
+    },
+
+    /// Indicates that a value in an ASCII file couldn't be parsed, on the specified line.
+    BadValue {
+        line: usize,
+    },
+
+    /// Indicates that the file ended before all of the elements declared in the header were read.
+    UnexpectedEof,
+
+    /// Indicates that the vertex element is missing one of the `x`, `y`, or `z` properties.
+    MissingPositions,
+
+    /// Indicates that a face had a negative vertex index.
+    NegativeIndex,
+}
+
+impl From<IoError> for Error {
+    fn from(error: IoError) -> Error {
+        Error::IoError(error)
+    }
+}
+
+impl From<BuildMeshError> for Error {
+    fn from(error: BuildMeshError) -> Error {
+        Error::BuildMeshError(error)
+    }
+}
+
+/// The parsed contents of a PLY header.
+struct Header {
+    format: Format,
+    elements: Vec<Element>,
+
+    /// The number of lines in the header, including the `end_header` line.
+    line_count: usize,
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+impl Element {
+    fn property_index(&self, name: &str) -> Option<usize> {
+        self.properties.iter().position(|property| property.name == name)
+    }
+}
+
+struct Property {
+    name: String,
+    kind: PropertyKind,
+}
+
+#[derive(Clone, Copy)]
+enum PropertyKind {
+    Scalar(Scalar),
+    List {
+        count: Scalar,
+        item: Scalar,
+    },
+}
+
+/// The primitive types that PLY properties can have.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl Scalar {
+    fn from_name(name: &str) -> Option<Scalar> {
+        match name {
+            "char" | "int8" => Some(Scalar::I8),
+            "uchar" | "uint8" => Some(Scalar::U8),
+            "short" | "int16" => Some(Scalar::I16),
+            "ushort" | "uint16" => Some(Scalar::U16),
+            "int" | "int32" => Some(Scalar::I32),
+            "uint" | "uint32" => Some(Scalar::U32),
+            "float" | "float32" => Some(Scalar::F32),
+            "double" | "float64" => Some(Scalar::F64),
+            _ => None,
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+            Scalar::F64 => 8,
+        }
+    }
+
+    /// The largest value of an integer type, used to normalize integer colors.
+    fn max_value(self) -> Option<f64> {
+        match self {
+            Scalar::I8 => Some(i8::MAX as f64),
+            Scalar::U8 => Some(u8::MAX as f64),
+            Scalar::I16 => Some(i16::MAX as f64),
+            Scalar::U16 => Some(u16::MAX as f64),
+            Scalar::I32 => Some(i32::MAX as f64),
+            Scalar::U32 => Some(u32::MAX as f64),
+            Scalar::F32 | Scalar::F64 => None,
+        }
+    }
+}
+
+/// Parses the header, returning it along with the offset of the first byte after it.
+fn parse_header(data: &[u8]) -> Result<(Header, usize), Error> {
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+
+    let mut offset = 0;
+    let mut line_number = 0;
+    loop {
+        line_number += 1;
+        let length = data[offset..]
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .ok_or(Error::BadHeader { line: line_number })?;
+        let line = str::from_utf8(&data[offset .. offset + length])
+            .map_err(|_| Error::BadHeader { line: line_number })?
+            .trim_end_matches('\r');
+        offset += length + 1;
+
+        let bad_header = || Error::BadHeader { line: line_number };
+        let words = line.split_whitespace().collect::<Vec<_>>();
+
+        if line_number == 1 {
+            if words != ["ply"] {
+                return Err(bad_header());
+            }
+            continue;
+        }
+
+        match words.first().cloned() {
+            Some("format") => {
+                format = match words.get(1..) {
+                    Some(["ascii", "1.0"]) => Some(Format::Ascii),
+                    Some(["binary_little_endian", "1.0"]) => Some(Format::BinaryLittleEndian),
+                    Some(["binary_big_endian", "1.0"]) => Some(Format::BinaryBigEndian),
+                    _ => return Err(bad_header()),
+                };
+            }
+
+            Some("element") => {
+                let count = match words.get(1..) {
+                    Some(&[_, count]) => count.parse::<usize>().map_err(|_| bad_header())?,
+                    _ => return Err(bad_header()),
+                };
+
+                elements.push(Element {
+                    name: words[1].into(),
+                    count: count,
+                    properties: Vec::new(),
+                });
+            }
+
+            Some("property") => {
+                let element = match elements.last_mut() {
+                    Some(element) => element,
+                    None => return Err(bad_header()),
+                };
+
+                let (kind, name) = match words.get(1..) {
+                    Some(&["list", count, item, name]) => {
+                        let kind = PropertyKind::List {
+                            count: Scalar::from_name(count).ok_or_else(bad_header)?,
+                            item: Scalar::from_name(item).ok_or_else(bad_header)?,
+                        };
+                        (kind, name)
+                    }
+
+                    Some(&[scalar, name]) => {
+                        (PropertyKind::Scalar(Scalar::from_name(scalar).ok_or_else(bad_header)?), name)
+                    }
+
+                    _ => return Err(bad_header()),
+                };
+
+                element.properties.push(Property {
+                    name: name.into(),
+                    kind: kind,
+                });
+            }
+
+            Some("end_header") => break,
+
+            Some("comment") | Some("obj_info") | None => {}
+
+            Some(_) => return Err(bad_header()),
+        }
+    }
+
+    let format = format.ok_or(Error::BadHeader { line: line_number })?;
+    Ok((Header { format: format, elements: elements, line_count: line_number }, offset))
+}
+
+/// Sets the vertex data for the mesh from the instances of the vertex element.
+fn read_vertices(
+    builder: MeshBuilder,
+    element: &Element,
+    instances: &[Vec<Vec<f64>>],
+) -> Result<MeshBuilder, Error> {
+    // Finds the indices of a group of properties, if the element has all of them.
+    let find = |names: &[&str]| -> Option<Vec<usize>> {
+        names.iter().map(|name| element.property_index(name)).collect()
+    };
+    let values = |properties: &[usize]| {
+        instances
+            .iter()
+            .map(|instance| properties.iter().map(|&property| instance[property][0] as f32).collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    };
+
+    let positions = find(&["x", "y", "z"]).ok_or(Error::MissingPositions)?;
+    let positions = values(&positions)
+        .into_iter()
+        .map(|value| Point::new(value[0], value[1], value[2]))
+        .collect::<Vec<_>>();
+    let mut builder = builder.set_position_data(&positions);
+
+    if let Some(normals) = find(&["nx", "ny", "nz"]) {
+        let normals = values(&normals)
+            .into_iter()
+            .map(|value| Vector3::new(value[0], value[1], value[2]))
+            .collect::<Vec<_>>();
+        builder = builder.set_normal_data(&normals);
+    }
+
+    let texcoords = find(&["s", "t"])
+        .or_else(|| find(&["u", "v"]))
+        .or_else(|| find(&["texture_u", "texture_v"]));
+    if let Some(texcoords) = texcoords {
+        let texcoords = values(&texcoords)
+            .into_iter()
+            .map(|value| Vector2::new(value[0], value[1]))
+            .collect::<Vec<_>>();
+        builder = builder.set_texcoord_data(&texcoords);
+    }
+
+    if let Some(mut colors) = find(&["red", "green", "blue"]) {
+        colors.extend(element.property_index("alpha"));
+
+        let scalar = match element.properties[colors[0]].kind {
+            PropertyKind::Scalar(scalar) => scalar,
+            PropertyKind::List { .. } => return Ok(builder),
+        };
+
+        let colors = values(&colors)
+            .into_iter()
+            .map(|value| {
+                let alpha = value.get(3).cloned();
+                match scalar.max_value() {
+                    Some(max) => {
+                        let max = max as f32;
+                        let alpha = alpha.map(|alpha| alpha / max).unwrap_or(1.0);
+                        Color::from_srgb(value[0] / max, value[1] / max, value[2] / max, alpha)
+                    }
+                    None => Color::new(value[0], value[1], value[2], alpha.unwrap_or(1.0)),
+                }
+            })
+            .collect::<Vec<_>>();
+        builder = builder.set_color_data(&colors);
+    }
+
+    Ok(builder)
+}
+
+/// The body of a PLY file, from which property values are read.
+enum Body<'a> {
+    Ascii {
+        /// Every token in the body, along with the line it's on.
+        tokens: Vec<(usize, &'a str)>,
+        position: usize,
+    },
+
+    Binary {
+        data: &'a [u8],
+        position: usize,
+        big_endian: bool,
+    },
+}
+
+impl<'a> Body<'a> {
+    /// Gets the largest number of values that could still be read.
+    ///
+    /// Every value takes up at least one token in ASCII or one byte in binary, so this is the
+    /// number of tokens or bytes left in the body.
+    fn remaining(&self) -> usize {
+        match *self {
+            Body::Ascii { ref tokens, position } => tokens.len() - position,
+            Body::Binary { data, position, .. } => data.len() - position,
+        }
+    }
+
+    fn read(&mut self, scalar: Scalar) -> Result<f64, Error> {
+        match *self {
+            Body::Ascii { ref tokens, ref mut position } => {
+                let (line, token) = *tokens.get(*position).ok_or(Error::UnexpectedEof)?;
+                *position += 1;
+                token.parse::<f64>().map_err(|_| Error::BadValue { line: line })
+            }
+
+            Body::Binary { data, ref mut position, big_endian } => {
+                let size = scalar.size();
+                let bytes = data.get(*position .. *position + size).ok_or(Error::UnexpectedEof)?;
+                *position += size;
+
+                // Put the bytes in little endian order so they can all be read the same way.
+                let mut buffer = [0; 8];
+                buffer[..size].copy_from_slice(bytes);
+                if big_endian {
+                    buffer[..size].reverse();
+                }
+
+                let value = match scalar {
+                    Scalar::I8 => buffer[0] as i8 as f64,
+                    Scalar::U8 => buffer[0] as f64,
+                    Scalar::I16 => i16::from_le_bytes([buffer[0], buffer[1]]) as f64,
+                    Scalar::U16 => u16::from_le_bytes([buffer[0], buffer[1]]) as f64,
+                    Scalar::I32 => i32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as f64,
+                    Scalar::U32 => u32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as f64,
+                    Scalar::F32 => f32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as f64,
+                    Scalar::F64 => f64::from_le_bytes(buffer),
+                };
+                Ok(value)
+            }
+        }
+    }
+}
+
+/// Writes property values in the body of a PLY file.
+struct BodyWriter<W: Write> {
+    writer: W,
+    format: Format,
+
+    /// Whether the next value is the first of its element, so no separator is needed in ASCII.
+    first: bool,
+}
+
+impl<W: Write> BodyWriter<W> {
+    fn write_f32(&mut self, value: f32) -> Result<(), IoError> {
+        match self.format {
+            Format::Ascii => self.write_ascii(value),
+            Format::BinaryLittleEndian => self.writer.write_all(&value.to_le_bytes()),
+            Format::BinaryBigEndian => self.writer.write_all(&value.to_be_bytes()),
+        }
+    }
+
+    fn write_u8(&mut self, value: u8) -> Result<(), IoError> {
+        match self.format {
+            Format::Ascii => self.write_ascii(value),
+            _ => self.writer.write_all(&[value]),
+        }
+    }
+
+    fn write_u32(&mut self, value: u32) -> Result<(), IoError> {
+        match self.format {
+            Format::Ascii => self.write_ascii(value),
+            Format::BinaryLittleEndian => self.writer.write_all(&value.to_le_bytes()),
+            Format::BinaryBigEndian => self.writer.write_all(&value.to_be_bytes()),
+        }
+    }
+
+    fn write_ascii<T: ::std::fmt::Display>(&mut self, value: T) -> Result<(), IoError> {
+        if !self.first {
+            self.writer.write_all(b" ")?;
+        }
+        self.first = false;
+        write!(self.writer, "{}", value)
+    }
+
+    /// Finishes writing an element, putting the next one on a new line in ASCII.
+    fn end_element(&mut self) -> Result<(), IoError> {
+        self.first = true;
+        match self.format {
+            Format::Ascii => self.writer.write_all(b"\n"),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,539 @@
+//! Procedurally generated meshes for debug and blockout geometry.
+//!
+//! Every primitive is centered on the origin, has normals, tangents, and a single set of texture
+//! coordinates, and uses counter-clockwise winding for front faces. Primitives that have a
+//! natural "up" axis (cylinders, cones, capsules, and tori) are aligned with the y axis, and flat
+//! primitives face up (`plane()` and `grid()`) or towards the viewer (`quad()`). Resolution
+//! parameters control how many segments are used to approximate curved surfaces.
+//!
+//! Curved surfaces duplicate the vertices along their texture seam so that the texture
+//! coordinates can wrap cleanly from 1 back to 0.
+
+use geometry::mesh::{Mesh, MeshBuilder, MeshIndex};
+use math::*;
+use std::collections::HashMap;
+
+/// Creates an axis-aligned cube with sides of length `size`.
+///
+/// Each face has its own vertices so that normals are flat, and each face maps the full `[0, 1]`
+/// texture range.
+pub fn cube(size: f32) -> Mesh {
+    let half = size * 0.5;
+    let mut builder = PrimitiveBuilder::new();
+
+    // Each face is described by its normal and two axes spanning the face, chosen such that
+    // `u_axis x v_axis == normal` so that the faces wind counter-clockwise.
+    let faces = [
+        (Vector3::RIGHT, Vector3::FORWARD, Vector3::UP),
+        (Vector3::LEFT, Vector3::BACK, Vector3::UP),
+        (Vector3::UP, Vector3::RIGHT, Vector3::FORWARD),
+        (Vector3::DOWN, Vector3::RIGHT, Vector3::BACK),
+        (Vector3::BACK, Vector3::RIGHT, Vector3::UP),
+        (Vector3::FORWARD, Vector3::LEFT, Vector3::UP),
+    ];
+
+    for &(normal, u_axis, v_axis) in &faces {
+        let center = Point::origin() + normal * half;
+        builder.face(center, u_axis * half, v_axis * half, 1, 1);
+    }
+
+    builder.build()
+}
+
+/// Creates a flat quad in the xy plane facing the positive z axis.
+///
+/// The quad is `width` units wide along the x axis and `height` units tall along the y axis.
+pub fn quad(width: f32, height: f32) -> Mesh {
+    let mut builder = PrimitiveBuilder::new();
+    builder.face(Point::origin(), Vector3::RIGHT * (width * 0.5), Vector3::UP * (height * 0.5), 1, 1);
+    builder.build()
+}
+
+/// Creates a flat plane in the xz plane facing the positive y axis.
+///
+/// The plane is `width` units along the x axis and `depth` units along the z axis. This is the
+/// same as `grid()` with a single cell.
+pub fn plane(width: f32, depth: f32) -> Mesh {
+    grid(width, depth, 1, 1)
+}
+
+/// Creates a flat plane in the xz plane facing the positive y axis, subdivided into a grid of
+/// `columns` cells along the x axis and `rows` cells along the z axis.
+///
+/// # Panics
+///
+/// Panics if `columns` or `rows` is zero.
+pub fn grid(width: f32, depth: f32, columns: u32, rows: u32) -> Mesh {
+    assert!(columns > 0 && rows > 0, "Grid must have at least one column and one row");
+
+    let mut builder = PrimitiveBuilder::new();
+    builder.face(
+        Point::origin(),
+        Vector3::RIGHT * (width * 0.5),
+        Vector3::FORWARD * (depth * 0.5),
+        columns,
+        rows,
+    );
+    builder.build()
+}
+
+/// Creates a sphere made of `rings` bands of latitude, each with `segments` quads around the
+/// vertical axis.
+///
+/// The texture wraps once around the sphere horizontally and runs from the bottom pole (`v = 0`)
+/// to the top pole (`v = 1`).
+///
+/// # Panics
+///
+/// Panics if `segments` is less than 3 or `rings` is less than 2.
+pub fn uv_sphere(radius: f32, segments: u32, rings: u32) -> Mesh {
+    assert!(segments >= 3, "Sphere must have at least 3 segments");
+    assert!(rings >= 2, "Sphere must have at least 2 rings");
+
+    let profile = (0..rings + 1)
+        .map(|ring| {
+            let theta = PI * ring as f32 / rings as f32;
+            ProfilePoint {
+                radius: theta.sin() * radius,
+                height: theta.cos() * radius,
+                normal: (theta.sin(), theta.cos()),
+                v: 1.0 - ring as f32 / rings as f32,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut builder = PrimitiveBuilder::new();
+    builder.revolve(&profile, segments);
+    builder.build()
+}
+
+/// Creates a sphere by repeatedly subdividing an icosahedron.
+///
+/// Icospheres have much more evenly sized triangles than UV spheres. Each subdivision splits
+/// every triangle into four, so the sphere has `20 * 4^subdivisions` triangles. Texture
+/// coordinates use the same equirectangular mapping as `uv_sphere()`.
+///
+/// # Panics
+///
+/// Panics if `subdivisions` is greater than 8, which would produce over a million triangles.
+pub fn icosphere(radius: f32, subdivisions: u32) -> Mesh {
+    assert!(subdivisions <= 8, "Icosphere subdivision level {} is too high", subdivisions);
+
+    // The 12 vertices of an icosahedron are the corners of three orthogonal golden rectangles.
+    let t = (1.0 + 5.0f32.sqrt()) * 0.5;
+    let mut directions = vec![
+        Vector3::new(-1.0, t, 0.0), Vector3::new(1.0, t, 0.0),
+        Vector3::new(-1.0, -t, 0.0), Vector3::new(1.0, -t, 0.0),
+        Vector3::new(0.0, -1.0, t), Vector3::new(0.0, 1.0, t),
+        Vector3::new(0.0, -1.0, -t), Vector3::new(0.0, 1.0, -t),
+        Vector3::new(t, 0.0, -1.0), Vector3::new(t, 0.0, 1.0),
+        Vector3::new(-t, 0.0, -1.0), Vector3::new(-t, 0.0, 1.0),
+    ];
+    for direction in &mut directions {
+        direction.normalize();
+    }
+
+    let mut triangles: Vec<[MeshIndex; 3]> = vec![
+        [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+        [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+        [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+        [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+    ];
+
+    for _ in 0..subdivisions {
+        // Adjacent triangles share edge midpoints, so cache them to avoid duplicate vertices.
+        let mut midpoints = HashMap::new();
+        let mut midpoint = |first: MeshIndex, second: MeshIndex, directions: &mut Vec<Vector3>| {
+            let key = if first < second { (first, second) } else { (second, first) };
+            *midpoints.entry(key).or_insert_with(|| {
+                let direction = (directions[first as usize] + directions[second as usize]).normalized();
+                directions.push(direction);
+                (directions.len() - 1) as MeshIndex
+            })
+        };
+
+        let mut subdivided = Vec::with_capacity(triangles.len() * 4);
+        for &[a, b, c] in &triangles {
+            let ab = midpoint(a, b, &mut directions);
+            let bc = midpoint(b, c, &mut directions);
+            let ca = midpoint(c, a, &mut directions);
+
+            subdivided.push([a, ab, ca]);
+            subdivided.push([b, bc, ab]);
+            subdivided.push([c, ca, bc]);
+            subdivided.push([ab, bc, ca]);
+        }
+        triangles = subdivided;
+    }
+
+    let mut builder = PrimitiveBuilder::new();
+    for &direction in &directions {
+        let azimuth = f32::atan2(direction.x, direction.z);
+        let texcoord = Vector2::new(
+            if azimuth < 0.0 { azimuth / TAU + 1.0 } else { azimuth / TAU },
+            0.5 + direction.y.clamp(-1.0, 1.0).asin() / PI,
+        );
+        builder.vertex(Point::origin() + direction * radius, direction, texcoord);
+    }
+
+    let mut wrapped = HashMap::new();
+    for &triangle in &triangles {
+        let [a, b, c] = builder.fix_texture_seam(triangle, &mut wrapped);
+        builder.triangle(a, b, c);
+    }
+
+    builder.build()
+}
+
+/// Creates a cylinder aligned with the y axis, with caps on both ends.
+///
+/// The sides and caps use separate vertices so that the edges are sharp. The texture wraps once
+/// around the side, and each cap maps a circle inscribed in the `[0, 1]` texture range.
+///
+/// # Panics
+///
+/// Panics if `segments` is less than 3.
+pub fn cylinder(radius: f32, height: f32, segments: u32) -> Mesh {
+    assert!(segments >= 3, "Cylinder must have at least 3 segments");
+
+    let half_height = height * 0.5;
+    let profile = [
+        ProfilePoint { radius: radius, height: half_height, normal: (1.0, 0.0), v: 1.0 },
+        ProfilePoint { radius: radius, height: -half_height, normal: (1.0, 0.0), v: 0.0 },
+    ];
+
+    let mut builder = PrimitiveBuilder::new();
+    builder.revolve(&profile, segments);
+    builder.cap(radius, half_height, segments, true);
+    builder.cap(radius, -half_height, segments, false);
+    builder.build()
+}
+
+/// Creates a cone aligned with the y axis, with its apex pointing up and a cap on its base.
+///
+/// # Panics
+///
+/// Panics if `segments` is less than 3.
+pub fn cone(radius: f32, height: f32, segments: u32) -> Mesh {
+    assert!(segments >= 3, "Cone must have at least 3 segments");
+
+    // The side normal is perpendicular to the slope, and is the same along the whole length.
+    let slope_length = (radius * radius + height * height).sqrt();
+    let normal = (height / slope_length, radius / slope_length);
+    let half_height = height * 0.5;
+    let profile = [
+        ProfilePoint { radius: 0.0, height: half_height, normal: normal, v: 1.0 },
+        ProfilePoint { radius: radius, height: -half_height, normal: normal, v: 0.0 },
+    ];
+
+    let mut builder = PrimitiveBuilder::new();
+    builder.revolve(&profile, segments);
+    builder.cap(radius, -half_height, segments, false);
+    builder.build()
+}
+
+/// Creates a capsule aligned with the y axis.
+///
+/// A capsule is a cylinder with hemispheres on each end. `height` is the length of the
+/// cylindrical section, so the total height of the capsule is `height + 2 * radius`. Each
+/// hemisphere is made of `rings` bands of latitude. The texture runs from the bottom
+/// (`v = 0`) to the top (`v = 1`), spaced evenly along the capsule's surface.
+///
+/// # Panics
+///
+/// Panics if `segments` is less than 3 or `rings` is zero.
+pub fn capsule(radius: f32, height: f32, segments: u32, rings: u32) -> Mesh {
+    assert!(segments >= 3, "Capsule must have at least 3 segments");
+    assert!(rings > 0, "Capsule must have at least 1 ring per hemisphere");
+
+    let half_height = height * 0.5;
+    let arc_length = PI * 0.5 * radius;
+    let total_length = 2.0 * arc_length + height;
+
+    let mut profile = Vec::with_capacity(2 * (rings as usize + 1));
+    for ring in 0..rings + 1 {
+        let theta = PI * 0.5 * ring as f32 / rings as f32;
+        let distance = arc_length * ring as f32 / rings as f32;
+        profile.push(ProfilePoint {
+            radius: theta.sin() * radius,
+            height: half_height + theta.cos() * radius,
+            normal: (theta.sin(), theta.cos()),
+            v: 1.0 - distance / total_length,
+        });
+    }
+    for ring in 0..rings + 1 {
+        let theta = PI * 0.5 * (1.0 + ring as f32 / rings as f32);
+        let distance = arc_length * (1.0 + ring as f32 / rings as f32) + height;
+        profile.push(ProfilePoint {
+            radius: theta.sin() * radius,
+            height: -half_height + theta.cos() * radius,
+            normal: (theta.sin(), theta.cos()),
+            v: 1.0 - distance / total_length,
+        });
+    }
+
+    let mut builder = PrimitiveBuilder::new();
+    builder.revolve(&profile, segments);
+    builder.build()
+}
+
+/// Creates a torus lying in the xz plane.
+///
+/// `major_radius` is the distance from the center of the torus to the center of the tube, and
+/// `minor_radius` is the radius of the tube. `major_segments` is the number of segments around
+/// the ring and `minor_segments` is the number around the tube. The texture wraps once in each
+/// direction.
+///
+/// # Panics
+///
+/// Panics if either segment count is less than 3.
+pub fn torus(major_radius: f32, minor_radius: f32, major_segments: u32, minor_segments: u32) -> Mesh {
+    assert!(major_segments >= 3, "Torus must have at least 3 major segments");
+    assert!(minor_segments >= 3, "Torus must have at least 3 minor segments");
+
+    // Walk around the tube starting from the top so the profile matches the other revolved
+    // surfaces, which run from top to bottom.
+    let profile = (0..minor_segments + 1)
+        .map(|segment| {
+            let psi = TAU * segment as f32 / minor_segments as f32;
+            ProfilePoint {
+                radius: major_radius + psi.sin() * minor_radius,
+                height: psi.cos() * minor_radius,
+                normal: (psi.sin(), psi.cos()),
+                v: 1.0 - segment as f32 / minor_segments as f32,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut builder = PrimitiveBuilder::new();
+    builder.revolve(&profile, major_segments);
+    builder.build()
+}
+
+/// A point on the 2D profile of a surface of revolution.
+#[derive(Debug, Clone, Copy)]
+struct ProfilePoint {
+    /// The distance from the y axis.
+    radius: f32,
+
+    /// The height along the y axis.
+    height: f32,
+
+    /// The outward normal as `(radial, vertical)` components.
+    normal: (f32, f32),
+
+    /// The texture `v` coordinate for the point.
+    v: f32,
+}
+
+impl ProfilePoint {
+    /// Checks if the point lies on the y axis, allowing for the rounding error in `sin(PI)`.
+    fn is_pole(&self) -> bool {
+        self.radius.abs() <= self.height.abs() * f32::EPSILON
+    }
+}
+
+/// Accumulates vertex data for a primitive and builds it into a `Mesh`.
+#[derive(Debug, Default)]
+struct PrimitiveBuilder {
+    positions: Vec<Point>,
+    normals: Vec<Vector3>,
+    texcoords: Vec<Vector2>,
+    indices: Vec<MeshIndex>,
+}
+
+impl PrimitiveBuilder {
+    fn new() -> PrimitiveBuilder {
+        PrimitiveBuilder::default()
+    }
+
+    fn vertex(&mut self, position: Point, normal: Vector3, texcoord: Vector2) -> MeshIndex {
+        self.positions.push(position);
+        self.normals.push(normal);
+        self.texcoords.push(texcoord);
+        (self.positions.len() - 1) as MeshIndex
+    }
+
+    fn triangle(&mut self, a: MeshIndex, b: MeshIndex, c: MeshIndex) {
+        self.indices.extend(&[a, b, c]);
+    }
+
+    /// Adds a flat rectangular face subdivided into `columns` by `rows` cells.
+    ///
+    /// The face spans `center - u_axis - v_axis` to `center + u_axis + v_axis`, and faces in the
+    /// direction of `u_axis x v_axis`. Texture coordinates run from 0 to 1 along each axis.
+    fn face(&mut self, center: Point, u_axis: Vector3, v_axis: Vector3, columns: u32, rows: u32) {
+        let normal = Vector3::cross(u_axis, v_axis).normalized();
+        let base = self.positions.len() as MeshIndex;
+
+        for row in 0..rows + 1 {
+            let v = row as f32 / rows as f32;
+            for column in 0..columns + 1 {
+                let u = column as f32 / columns as f32;
+                let position = center + u_axis * (u * 2.0 - 1.0) + v_axis * (v * 2.0 - 1.0);
+                self.vertex(position, normal, Vector2::new(u, v));
+            }
+        }
+
+        let stride = columns + 1;
+        for row in 0..rows {
+            for column in 0..columns {
+                let bottom_left = base + row * stride + column;
+                let bottom_right = bottom_left + 1;
+                let top_left = bottom_left + stride;
+                let top_right = top_left + 1;
+
+                self.triangle(bottom_left, bottom_right, top_right);
+                self.triangle(bottom_left, top_right, top_left);
+            }
+        }
+    }
+
+    /// Revolves a profile around the y axis to create a surface of revolution.
+    ///
+    /// The profile should run from top to bottom so that the surface faces outward. The seam is
+    /// at the positive z axis, and `u` increases towards the positive x axis. Profile points
+    /// on the y axis are poles: they get one vertex per segment, with `u` at the middle
+    /// of the segment, and the degenerate triangles touching them are skipped.
+    fn revolve(&mut self, profile: &[ProfilePoint], segments: u32) {
+        let base = self.positions.len() as MeshIndex;
+        let stride = segments + 1;
+
+        for point in profile {
+            let is_pole = point.is_pole();
+            let radius = if is_pole { 0.0 } else { point.radius };
+            for segment in 0..segments + 1 {
+                let u = if is_pole && segment < segments {
+                    (segment as f32 + 0.5) / segments as f32
+                } else {
+                    segment as f32 / segments as f32
+                };
+                let phi = TAU * u;
+                let (sin, cos) = phi.sin_cos();
+
+                let position = Point::new(sin * radius, point.height, cos * radius);
+                let (radial, vertical) = point.normal;
+                let normal = Vector3::new(sin * radial, vertical, cos * radial).normalized();
+                self.vertex(position, normal, Vector2::new(u, point.v));
+            }
+        }
+
+        for ring in 0..profile.len() as u32 - 1 {
+            let top_is_pole = profile[ring as usize].is_pole();
+            let bottom_is_pole = profile[ring as usize + 1].is_pole();
+
+            for segment in 0..segments {
+                let top_left = base + ring * stride + segment;
+                let top_right = top_left + 1;
+                let bottom_left = top_left + stride;
+                let bottom_right = bottom_left + 1;
+
+                if !bottom_is_pole {
+                    self.triangle(top_left, bottom_left, bottom_right);
+                }
+                if !top_is_pole {
+                    self.triangle(top_left, bottom_right, top_right);
+                }
+            }
+        }
+    }
+
+    /// Adds a flat circular cap at `height` facing up (if `up` is true) or down.
+    fn cap(&mut self, radius: f32, height: f32, segments: u32, up: bool) {
+        let normal = if up { Vector3::UP } else { Vector3::DOWN };
+        let center = self.vertex(Point::new(0.0, height, 0.0), normal, Vector2::new(0.5, 0.5));
+
+        let first = self.positions.len() as MeshIndex;
+        for segment in 0..segments {
+            let phi = TAU * segment as f32 / segments as f32;
+            let (sin, cos) = phi.sin_cos();
+
+            // Map the cap so that the texture appears upright when viewed from outside.
+            let v = if up { 0.5 - cos * 0.5 } else { 0.5 + cos * 0.5 };
+            let texcoord = Vector2::new(0.5 + sin * 0.5, v);
+            self.vertex(Point::new(sin * radius, height, cos * radius), normal, texcoord);
+        }
+
+        for segment in 0..segments {
+            let current = first + segment;
+            let next = first + (segment + 1) % segments;
+            if up {
+                self.triangle(center, current, next);
+            } else {
+                self.triangle(center, next, current);
+            }
+        }
+    }
+
+    /// Duplicates vertices as needed so that a triangle crossing the `u = 0` texture seam
+    /// doesn't interpolate across the whole texture.
+    ///
+    /// If the triangle crosses the seam, its vertices on the right side of the texture are
+    /// duplicated with `u` shifted up by one. `wrapped` caches these duplicates so that
+    /// neighboring triangles can share them. Vertices at a pole (whose `u` is meaningless) are
+    /// duplicated with `u` set to the average of the other two vertices.
+    fn fix_texture_seam(
+        &mut self,
+        triangle: [MeshIndex; 3],
+        wrapped: &mut HashMap<MeshIndex, MeshIndex>,
+    ) -> [MeshIndex; 3] {
+        let mut result = triangle;
+        let u = |index: MeshIndex, builder: &PrimitiveBuilder| builder.texcoords[index as usize].x;
+        let is_pole = |index: MeshIndex, builder: &PrimitiveBuilder| {
+            let normal = builder.normals[index as usize];
+            normal.x.abs() < 1e-6 && normal.z.abs() < 1e-6
+        };
+
+        let (min_u, max_u) = triangle
+            .iter()
+            .filter(|&&index| !is_pole(index, self))
+            .map(|&index| u(index, self))
+            .fold((1.0f32, 0.0f32), |(min, max), u| (min.min(u), max.max(u)));
+
+        if max_u - min_u > 0.5 {
+            for corner in &mut result {
+                if !is_pole(*corner, self) && u(*corner, self) < 0.5 {
+                    let original = *corner;
+                    *corner = match wrapped.get(&original) {
+                        Some(&duplicate) => duplicate,
+                        None => {
+                            let mut texcoord = self.texcoords[original as usize];
+                            texcoord.x += 1.0;
+                            let duplicate = self.duplicate(original, texcoord);
+                            wrapped.insert(original, duplicate);
+                            duplicate
+                        }
+                    };
+                }
+            }
+        }
+
+        for corner in 0..3 {
+            if is_pole(result[corner], self) {
+                let others = [result[(corner + 1) % 3], result[(corner + 2) % 3]];
+                let mut texcoord = self.texcoords[result[corner] as usize];
+                texcoord.x = (u(others[0], self) + u(others[1], self)) * 0.5;
+                result[corner] = self.duplicate(result[corner], texcoord);
+            }
+        }
+
+        result
+    }
+
+    fn duplicate(&mut self, index: MeshIndex, texcoord: Vector2) -> MeshIndex {
+        let position = self.positions[index as usize];
+        let normal = self.normals[index as usize];
+        self.vertex(position, normal, texcoord)
+    }
+
+    fn build(self) -> Mesh {
+        MeshBuilder::new()
+            .set_position_data(&self.positions)
+            .set_normal_data(&self.normals)
+            .set_texcoord_data(&self.texcoords)
+            .set_indices(&self.indices)
+            .compute_tangents()
+            .build()
+            .expect("Generated primitive mesh was invalid")
+    }
+}
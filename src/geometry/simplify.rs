@@ -0,0 +1,601 @@
+//! Mesh simplification and levels of detail.
+//!
+//! `simplify()` reduces the number of triangles in a mesh by repeatedly collapsing the edge that
+//! changes the shape of the mesh the least, as measured by the quadric error metric from Garland
+//! and Heckbert's "Surface Simplification Using Quadric Error Metrics". Each edge collapse moves
+//! one vertex onto the other, so the simplified mesh only uses vertices (and so attribute values)
+//! from the original mesh.
+//!
+//! Vertices that share a position but differ in other attributes, like the two sides of a texture
+//! seam, are simplified together so that the seam doesn't tear open. The boundaries between
+//! submeshes are treated as seams too, and triangles stay in their submesh. Vertices on a seam or
+//! on the border of an open mesh can only move along the seam or border, which keeps the outline
+//! of the mesh, the layout of its textures, and the shape of its submeshes intact. Vertices where
+//! seams or borders meet or turn sharply, or where the mesh isn't manifold, are never moved.
+//!
+//! `lod_chain()` builds a series of progressively simpler meshes for use as levels of detail,
+//! see `MeshInstance::add_lod()` for drawing them.
+
+use geometry::mesh::*;
+use geometry::optimize;
+use math::*;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// The weight of the quadrics that keep border edges in place, relative to the faces' quadrics.
+const BORDER_WEIGHT: f64 = 10.0;
+
+/// Border and seam vertices where the border or seam turns by more than this angle (in radians)
+/// are corners, and are never moved.
+const CORNER_ANGLE: f32 = PI / 6.0;
+
+/// Simplifies the mesh until it has no more than `target_triangles` triangles.
+///
+/// Fewer triangles may be removed if collapsing any more edges would tear seams, move borders,
+/// or fold triangles over, so the result can have more than `target_triangles` triangles.
+/// Vertices that are no longer used are removed, and the vertex layout is preserved.
+pub fn simplify(mesh: &Mesh, target_triangles: usize) -> Mesh {
+    let mut simplifier = Simplifier::new(mesh);
+    simplifier.run(target_triangles);
+    simplifier.finish()
+}
+
+/// Builds a chain of simplified meshes, one for each ratio in `ratios`.
+///
+/// Each ratio is the fraction of the mesh's triangles to keep, so `&[0.5, 0.25, 0.125]` produces
+/// three levels of detail with a half, a quarter, and an eighth of the triangles. Each level is
+/// simplified from the one before it, so the levels stay consistent with each other.
+///
+/// # Panics
+///
+/// - If any ratio isn't between 0 and 1.
+pub fn lod_chain(mesh: &Mesh, ratios: &[f32]) -> Vec<Mesh> {
+    let triangle_count = mesh.indices().len() / 3;
+
+    let mut lods: Vec<Mesh> = Vec::with_capacity(ratios.len());
+    for &ratio in ratios {
+        assert!((0.0..=1.0).contains(&ratio), "LOD ratio must be between 0 and 1: {}", ratio);
+
+        let target = (triangle_count as f32 * ratio) as usize;
+        let lod = simplify(lods.last().unwrap_or(mesh), target);
+        lods.push(lod);
+    }
+
+    lods
+}
+
+/// How a vertex is allowed to move when simplifying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VertexKind {
+    /// The vertex is surrounded by triangles and can collapse along any edge.
+    Manifold,
+
+    /// The vertex is on the border of an open mesh and can only collapse along the border.
+    Border,
+
+    /// The vertex is on an attribute seam and can only collapse along the seam.
+    Seam,
+
+    /// The vertex can't be collapsed.
+    Locked,
+}
+
+/// A symmetric 4x4 matrix measuring the squared distance to a set of planes.
+#[derive(Debug, Clone, Copy, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    /// Creates the quadric for the plane with unit normal `normal` passing through `point`.
+    fn from_plane(normal: Vector3, point: Point, weight: f64) -> Quadric {
+        let (a, b, c) = (normal.x as f64, normal.y as f64, normal.z as f64);
+        let d = -(a * point.x as f64 + b * point.y as f64 + c * point.z as f64);
+        Quadric([
+            a * a, a * b, a * c, a * d,
+            b * b, b * c, b * d,
+            c * c, c * d,
+            d * d,
+        ]).scaled(weight)
+    }
+
+    fn scaled(self, scale: f64) -> Quadric {
+        let mut result = self;
+        for value in &mut result.0 {
+            *value *= scale;
+        }
+        result
+    }
+
+    fn add(&mut self, other: &Quadric) {
+        for (value, other) in self.0.iter_mut().zip(other.0.iter()) {
+            *value += other;
+        }
+    }
+
+    /// Gets the weighted sum of squared distances from the point to the quadric's planes.
+    fn error(&self, point: Point) -> f64 {
+        let q = &self.0;
+        let (x, y, z) = (point.x as f64, point.y as f64, point.z as f64);
+        q[0] * x * x + 2.0 * q[1] * x * y + 2.0 * q[2] * x * z + 2.0 * q[3] * x
+            + q[4] * y * y + 2.0 * q[5] * y * z + 2.0 * q[6] * y
+            + q[7] * z * z + 2.0 * q[8] * z
+            + q[9]
+    }
+}
+
+/// A possible collapse of the vertex `from` onto `to`, ordered so that the cheapest collapse is
+/// the greatest.
+#[derive(Debug, Clone, Copy)]
+struct Collapse {
+    cost: f64,
+    from: usize,
+    to: usize,
+
+    /// The versions of the two vertices when the cost was calculated, so that collapses that have
+    /// been made out of date by other collapses can be skipped.
+    from_version: usize,
+    to_version: usize,
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Collapse) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Collapse) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    fn cmp(&self, other: &Collapse) -> Ordering {
+        other.cost.partial_cmp(&self.cost).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// The state of a mesh while it's being simplified.
+///
+/// Mesh vertices that share a position are treated as a single vertex when simplifying, with
+/// each mesh vertex being one "wedge" of it. Unless stated otherwise, "vertex" refers to these
+/// merged vertices, and triangles store the wedges they use.
+struct Simplifier<'a> {
+    mesh: &'a Mesh,
+
+    /// The vertex that each wedge belongs to.
+    wedge_vertex: Vec<usize>,
+
+    positions: Vec<Point>,
+    kinds: Vec<VertexKind>,
+    quadrics: Vec<Quadric>,
+    versions: Vec<usize>,
+    removed: Vec<bool>,
+
+    /// The triangles that use each vertex, which can include triangles that have since been
+    /// removed or no longer use the vertex.
+    vertex_triangles: Vec<Vec<usize>>,
+
+    triangles: Vec<[MeshIndex; 3]>,
+    triangle_submesh: Vec<usize>,
+    triangle_alive: Vec<bool>,
+    live_triangles: usize,
+
+    /// The border and seam edges, stored with the lower vertex first.
+    border_edges: HashSet<(usize, usize)>,
+    seam_edges: HashSet<(usize, usize)>,
+
+    queue: BinaryHeap<Collapse>,
+}
+
+impl<'a> Simplifier<'a> {
+    fn new(mesh: &'a Mesh) -> Simplifier<'a> {
+        // Merge wedges into vertices by position. Adding 0 turns -0 into 0 so that they merge.
+        let mut vertex_by_position = HashMap::new();
+        let mut positions = Vec::new();
+        let mut wedge_vertex = Vec::with_capacity(mesh.vertex_count());
+        for wedge in 0..mesh.vertex_count() {
+            let value = mesh.attribute_value(mesh.position(), wedge);
+            let key = [(value[0] + 0.0).to_bits(), (value[1] + 0.0).to_bits(), (value[2] + 0.0).to_bits()];
+            let vertex = *vertex_by_position.entry(key).or_insert_with(|| {
+                positions.push(Point::new(value[0], value[1], value[2]));
+                positions.len() - 1
+            });
+            wedge_vertex.push(vertex);
+        }
+
+        let vertex_count = positions.len();
+        let indices = mesh.indices().to_vec();
+        let mut triangles = Vec::with_capacity(indices.len() / 3);
+        let mut triangle_submesh = Vec::with_capacity(indices.len() / 3);
+        for (submesh, range) in mesh.submeshes().iter().enumerate() {
+            for triangle in indices[range.start .. range.end()].chunks_exact(3) {
+                // Triangles that are already degenerate would confuse the topology, so drop them.
+                let a = wedge_vertex[triangle[0] as usize];
+                let b = wedge_vertex[triangle[1] as usize];
+                let c = wedge_vertex[triangle[2] as usize];
+                if a != b && b != c && c != a {
+                    triangles.push([triangle[0], triangle[1], triangle[2]]);
+                    triangle_submesh.push(submesh);
+                }
+            }
+        }
+
+        let mut simplifier = Simplifier {
+            mesh: mesh,
+            wedge_vertex: wedge_vertex,
+
+            positions: positions,
+            kinds: vec![VertexKind::Manifold; vertex_count],
+            quadrics: vec![Quadric::default(); vertex_count],
+            versions: vec![0; vertex_count],
+            removed: vec![false; vertex_count],
+
+            vertex_triangles: vec![Vec::new(); vertex_count],
+
+            triangle_alive: vec![true; triangles.len()],
+            live_triangles: triangles.len(),
+            triangles: triangles,
+            triangle_submesh: triangle_submesh,
+
+            border_edges: HashSet::new(),
+            seam_edges: HashSet::new(),
+
+            queue: BinaryHeap::new(),
+        };
+
+        for (triangle, corners) in simplifier.triangles.iter().enumerate() {
+            for &wedge in corners {
+                simplifier.vertex_triangles[simplifier.wedge_vertex[wedge as usize]].push(triangle);
+            }
+        }
+
+        simplifier.classify();
+        simplifier.build_quadrics();
+
+        for vertex in 0..vertex_count {
+            simplifier.queue_collapses(vertex);
+        }
+
+        simplifier
+    }
+
+    /// Finds the border and seam edges and determines how each vertex can move.
+    fn classify(&mut self) {
+        // Gather the wedges and submesh on each side of every edge.
+        let mut edges = HashMap::<(usize, usize), Vec<(MeshIndex, MeshIndex, usize)>>::new();
+        for (triangle, &submesh) in self.triangles.iter().zip(&self.triangle_submesh) {
+            for corner in 0..3 {
+                let (first, second) = (triangle[corner], triangle[(corner + 1) % 3]);
+                let (a, b) = (self.wedge_vertex[first as usize], self.wedge_vertex[second as usize]);
+                let (key, side) = if a < b {
+                    ((a, b), (first, second, submesh))
+                } else {
+                    ((b, a), (second, first, submesh))
+                };
+                edges.entry(key).or_default().push(side);
+            }
+        }
+
+        let vertex_count = self.positions.len();
+        let mut border_neighbors = vec![Vec::new(); vertex_count];
+        let mut seam_neighbors = vec![Vec::new(); vertex_count];
+        let mut submesh_neighbors = vec![Vec::new(); vertex_count];
+        let mut non_manifold = vec![false; vertex_count];
+        for (&(a, b), sides) in &edges {
+            match sides.len() {
+                1 => {
+                    self.border_edges.insert((a, b));
+                    border_neighbors[a].push(b);
+                    border_neighbors[b].push(a);
+                }
+
+                2 => if sides[0] != sides[1] {
+                    self.seam_edges.insert((a, b));
+                    seam_neighbors[a].push(b);
+                    seam_neighbors[b].push(a);
+
+                    if sides[0].2 != sides[1].2 {
+                        submesh_neighbors[a].push(b);
+                        submesh_neighbors[b].push(a);
+                    }
+                },
+
+                _ => {
+                    non_manifold[a] = true;
+                    non_manifold[b] = true;
+                }
+            }
+        }
+
+        // Count the wedges of each vertex that are actually used.
+        let mut wedges = vec![HashSet::new(); vertex_count];
+        for triangle in &self.triangles {
+            for &wedge in triangle {
+                wedges[self.wedge_vertex[wedge as usize]].insert(wedge);
+            }
+        }
+
+        // A vertex with a single wedge can touch a texture seam without being on it, e.g. next to
+        // the pole of a sphere, so texture seams only matter for vertices with more than one
+        // wedge. Collapses that would join a single wedge to both sides of a seam are caught in
+        // `try_collapse()`. Submesh boundaries don't need a change of wedge, so they always count.
+        for vertex in 0..vertex_count {
+            let border = &border_neighbors[vertex];
+            let seam = &seam_neighbors[vertex];
+            let submesh = &submesh_neighbors[vertex];
+            self.kinds[vertex] = match (wedges[vertex].len(), border.len(), seam.len(), submesh.len()) {
+                _ if non_manifold[vertex] => VertexKind::Locked,
+                (1, 0, _, 0) => VertexKind::Manifold,
+                (1, 2, _, 0) if !self.is_corner(vertex, border) => VertexKind::Border,
+                (1, 0, _, 2) if !self.is_corner(vertex, submesh) => VertexKind::Seam,
+                (2, 0, 2, _) if !self.is_corner(vertex, seam) => VertexKind::Seam,
+                _ => VertexKind::Locked,
+            };
+        }
+    }
+
+    /// Checks if the border or seam through `vertex` turns sharply at `vertex`.
+    fn is_corner(&self, vertex: usize, neighbors: &[usize]) -> bool {
+        let position = self.positions[vertex];
+        let incoming = (position - self.positions[neighbors[0]]).normalized();
+        let outgoing = (self.positions[neighbors[1]] - position).normalized();
+        Vector3::dot(incoming, outgoing) < CORNER_ANGLE.cos()
+    }
+
+    /// Builds the error quadric for every vertex from the planes of its triangles and borders.
+    fn build_quadrics(&mut self) {
+        for triangle in 0..self.triangles.len() {
+            let [a, b, c] = self.triangle_vertices(triangle);
+            let (pa, pb, pc) = (self.positions[a], self.positions[b], self.positions[c]);
+            let cross = Vector3::cross(pb - pa, pc - pa);
+            let area = cross.magnitude() * 0.5;
+            if area == 0.0 {
+                continue;
+            }
+
+            let normal = cross.normalized();
+            let quadric = Quadric::from_plane(normal, pa, area as f64);
+            for &vertex in &[a, b, c] {
+                self.quadrics[vertex].add(&quadric);
+            }
+
+            // Border edges get an extra plane perpendicular to the triangle, which penalizes
+            // moving the border away from its original line.
+            for &(from, to) in &[(a, b), (b, c), (c, a)] {
+                if !self.border_edges.contains(&edge_key(from, to)) {
+                    continue;
+                }
+
+                let edge = self.positions[to] - self.positions[from];
+                let border_normal = Vector3::cross(edge, normal);
+                if border_normal.magnitude_squared() == 0.0 {
+                    continue;
+                }
+
+                let weight = BORDER_WEIGHT * edge.magnitude_squared() as f64;
+                let quadric = Quadric::from_plane(border_normal.normalized(), self.positions[from], weight);
+                self.quadrics[from].add(&quadric);
+                self.quadrics[to].add(&quadric);
+            }
+        }
+    }
+
+    /// Gets the vertices used by a triangle.
+    fn triangle_vertices(&self, triangle: usize) -> [usize; 3] {
+        let corners = self.triangles[triangle];
+        [
+            self.wedge_vertex[corners[0] as usize],
+            self.wedge_vertex[corners[1] as usize],
+            self.wedge_vertex[corners[2] as usize],
+        ]
+    }
+
+    /// Gets the live triangles using a vertex.
+    fn live_triangles_of(&self, vertex: usize) -> Vec<usize> {
+        let mut triangles = self.vertex_triangles[vertex]
+            .iter()
+            .cloned()
+            .filter(|&triangle| self.triangle_alive[triangle])
+            .filter(|&triangle| self.triangle_vertices(triangle).contains(&vertex))
+            .collect::<Vec<_>>();
+        triangles.sort_unstable();
+        triangles.dedup();
+        triangles
+    }
+
+    /// Gets the vertices that share an edge with `vertex`.
+    fn neighbors(&self, vertex: usize) -> HashSet<usize> {
+        self.live_triangles_of(vertex)
+            .into_iter()
+            .flat_map(|triangle| self.triangle_vertices(triangle).to_vec())
+            .filter(|&other| other != vertex)
+            .collect()
+    }
+
+    /// Queues collapses in both directions along every edge of the vertex.
+    fn queue_collapses(&mut self, vertex: usize) {
+        for other in self.neighbors(vertex) {
+            for &(from, to) in &[(vertex, other), (other, vertex)] {
+                if !self.can_move_along(from, to) {
+                    continue;
+                }
+
+                let mut quadric = self.quadrics[from];
+                quadric.add(&self.quadrics[to]);
+                self.queue.push(Collapse {
+                    cost: quadric.error(self.positions[to]),
+                    from: from,
+                    to: to,
+                    from_version: self.versions[from],
+                    to_version: self.versions[to],
+                });
+            }
+        }
+    }
+
+    /// Checks whether the vertex `from` is allowed to move along the edge to `to`.
+    fn can_move_along(&self, from: usize, to: usize) -> bool {
+        match self.kinds[from] {
+            VertexKind::Manifold => true,
+            VertexKind::Border => self.border_edges.contains(&edge_key(from, to)),
+            VertexKind::Seam => self.seam_edges.contains(&edge_key(from, to)),
+            VertexKind::Locked => false,
+        }
+    }
+
+    /// Collapses edges until there are no more than `target_triangles` triangles, or no more
+    /// edges can be collapsed.
+    fn run(&mut self, target_triangles: usize) {
+        while self.live_triangles > target_triangles {
+            let collapse = match self.queue.pop() {
+                Some(collapse) => collapse,
+                None => break,
+            };
+
+            let is_current = !self.removed[collapse.from]
+                && !self.removed[collapse.to]
+                && self.versions[collapse.from] == collapse.from_version
+                && self.versions[collapse.to] == collapse.to_version;
+            if is_current {
+                self.try_collapse(collapse.from, collapse.to);
+            }
+        }
+    }
+
+    /// Collapses `from` onto `to` if doing so keeps the mesh valid, returning whether it did.
+    fn try_collapse(&mut self, from: usize, to: usize) -> bool {
+        let triangles = self.live_triangles_of(from);
+        let (shared, others): (Vec<usize>, Vec<usize>) = triangles
+            .iter()
+            .partition(|&&triangle| self.triangle_vertices(triangle).contains(&to));
+
+        // The link condition: the vertices on both sides of the edge must be the only vertices
+        // that `from` and `to` share, otherwise the collapse pinches the mesh.
+        let common = self.neighbors(from).intersection(&self.neighbors(to)).count();
+        if shared.is_empty() || common != shared.len() {
+            return false;
+        }
+
+        // Each wedge of `from` has to be replaced by the matching wedge of `to`, which is the one
+        // it shares an edge with.
+        let mut wedge_map = HashMap::new();
+        for &triangle in &shared {
+            let corners = self.triangles[triangle];
+            let vertices = self.triangle_vertices(triangle);
+            let from_wedge = corners[vertices.iter().position(|&vertex| vertex == from).unwrap()];
+            let to_wedge = corners[vertices.iter().position(|&vertex| vertex == to).unwrap()];
+            if *wedge_map.entry(from_wedge).or_insert(to_wedge) != to_wedge {
+                return false;
+            }
+        }
+
+        // Make sure that no triangle would flip over or collapse to nothing.
+        for &triangle in &others {
+            let corners = self.triangles[triangle];
+            if !wedge_map.contains_key(&corners[self.corner_of(triangle, from)]) {
+                return false;
+            }
+
+            let [a, b, c] = self.triangle_vertices(triangle);
+            let before = self.normal([a, b, c], None);
+            let after = self.normal([a, b, c], Some((from, to)));
+            if after.magnitude_squared() == 0.0 || Vector3::dot(before, after) <= 0.0 {
+                return false;
+            }
+        }
+
+        // Apply the collapse.
+        for &triangle in &shared {
+            self.triangle_alive[triangle] = false;
+            self.live_triangles -= 1;
+        }
+
+        for &triangle in &others {
+            let corner = self.corner_of(triangle, from);
+            let wedge = self.triangles[triangle][corner];
+            self.triangles[triangle][corner] = wedge_map[&wedge];
+            self.vertex_triangles[to].push(triangle);
+        }
+
+        let neighbors = self.neighbors_via(&others, to);
+        for neighbor in neighbors {
+            for edges in &mut [&mut self.border_edges, &mut self.seam_edges] {
+                if edges.remove(&edge_key(from, neighbor)) {
+                    edges.insert(edge_key(to, neighbor));
+                }
+            }
+        }
+        self.border_edges.remove(&edge_key(from, to));
+        self.seam_edges.remove(&edge_key(from, to));
+
+        let quadric = self.quadrics[from];
+        self.quadrics[to].add(&quadric);
+        self.removed[from] = true;
+        self.versions[to] += 1;
+        self.queue_collapses(to);
+
+        true
+    }
+
+    /// Gets the vertices other than `vertex` used by the triangles.
+    fn neighbors_via(&self, triangles: &[usize], vertex: usize) -> HashSet<usize> {
+        triangles
+            .iter()
+            .flat_map(|&triangle| self.triangle_vertices(triangle).to_vec())
+            .filter(|&other| other != vertex)
+            .collect()
+    }
+
+    /// Gets the index of the corner of a triangle that uses `vertex`.
+    fn corner_of(&self, triangle: usize, vertex: usize) -> usize {
+        self.triangle_vertices(triangle)
+            .iter()
+            .position(|&other| other == vertex)
+            .unwrap()
+    }
+
+    /// Gets the unnormalized normal of a triangle, optionally with one vertex moved onto another.
+    fn normal(&self, vertices: [usize; 3], moved: Option<(usize, usize)>) -> Vector3 {
+        let position = |vertex: usize| match moved {
+            Some((from, to)) if vertex == from => self.positions[to],
+            _ => self.positions[vertex],
+        };
+
+        let (a, b, c) = (position(vertices[0]), position(vertices[1]), position(vertices[2]));
+        Vector3::cross(b - a, c - a)
+    }
+
+    /// Builds the simplified mesh, keeping only the wedges that are still used.
+    fn finish(self) -> Mesh {
+        let mut remap = vec![None; self.mesh.vertex_count()];
+        let mut wedges = Vec::new();
+        let mut indices = Vec::with_capacity(self.live_triangles * 3);
+        let mut submeshes = Vec::with_capacity(self.mesh.submeshes().len());
+        for submesh in 0..self.mesh.submeshes().len() {
+            let start = indices.len();
+            for (triangle, corners) in self.triangles.iter().enumerate() {
+                if !self.triangle_alive[triangle] || self.triangle_submesh[triangle] != submesh {
+                    continue;
+                }
+
+                for &wedge in corners {
+                    let index = *remap[wedge as usize].get_or_insert_with(|| {
+                        wedges.push(wedge as usize);
+                        (wedges.len() - 1) as MeshIndex
+                    });
+                    indices.push(index);
+                }
+            }
+            submeshes.push(Submesh::new(start, indices.len() - start));
+        }
+
+        optimize::rebuild(self.mesh, &wedges, &indices, &submeshes)
+    }
+}
+
+/// Gets the key for an edge, with the lower vertex first.
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    if a < b { (a, b) } else { (b, a) }
+}
@@ -0,0 +1,246 @@
+//! Reading and writing STL files.
+//!
+//! Both binary and ASCII STL files can be read, the format is detected from the file contents.
+//! STL stores every triangle separately, so the reader welds together vertices with identical
+//! positions to produce an indexed mesh. The facet normals in the file are ignored; smooth normals
+//! are generated instead, keeping edges sharper than `HARD_EDGE_ANGLE` hard.
+//!
+//! Writing only uses the mesh's positions and indices, since that's all that STL can represent.
+
+use geometry::mesh::*;
+use math::*;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Error as IoError};
+use std::io::prelude::*;
+use std::path::Path;
+use std::str;
+
+/// The angle, in radians, between faces above which the reader leaves a hard edge between them.
+pub const HARD_EDGE_ANGLE: f32 = PI / 6.0;
+
+/// The size of the header at the start of a binary STL file.
+const HEADER_SIZE: usize = 80;
+
+/// The size of each triangle in a binary STL file.
+const TRIANGLE_SIZE: usize = 50;
+
+/// Loads a binary or ASCII STL file.
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Mesh, Error> {
+    let mut file = File::open(path)?;
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)?;
+    parse(&data)
+}
+
+/// Parses the contents of a binary or ASCII STL file.
+pub fn parse(data: &[u8]) -> Result<Mesh, Error> {
+    // ASCII files start with "solid", but so do some binary files, so only treat the data as
+    // ASCII if it isn't exactly the size of a binary file.
+    let binary_size = triangle_count(data).map(|count| HEADER_SIZE + 4 + count * TRIANGLE_SIZE);
+    let positions = if data.starts_with(b"solid") && binary_size != Some(data.len()) {
+        parse_ascii(data)?
+    } else {
+        parse_binary(data)?
+    };
+
+    // Weld together the corners of adjacent triangles.
+    let mut vertices = HashMap::new();
+    let mut position_data = Vec::new();
+    let mut indices = Vec::with_capacity(positions.len());
+    for position in positions {
+        // Adding 0 turns -0 into 0, so that the two are welded together.
+        let key = [
+            (position.x + 0.0).to_bits(),
+            (position.y + 0.0).to_bits(),
+            (position.z + 0.0).to_bits(),
+        ];
+        let index = *vertices.entry(key).or_insert_with(|| {
+            position_data.push(position);
+            (position_data.len() - 1) as MeshIndex
+        });
+        indices.push(index);
+    }
+
+    let mesh = MeshBuilder::new()
+        .set_position_data(&position_data)
+        .set_indices(&indices)
+        .compute_smooth_normals(HARD_EDGE_ANGLE)
+        .build()?;
+    Ok(mesh)
+}
+
+/// Writes a mesh as a binary STL file.
+pub fn write_binary<W: Write>(mesh: &Mesh, writer: W) -> Result<(), IoError> {
+    let mut writer = BufWriter::new(writer);
+
+    let mut header = [0; HEADER_SIZE];
+    let signature = b"polygon binary STL";
+    header[.. signature.len()].copy_from_slice(signature);
+    writer.write_all(&header)?;
+    writer.write_all(&((mesh.indices().len() / 3) as u32).to_le_bytes())?;
+
+    for triangle in triangles(mesh) {
+        let normal = face_normal(triangle);
+        for &value in &[normal.x, normal.y, normal.z] {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+
+        for position in &triangle {
+            for &value in &[position.x, position.y, position.z] {
+                writer.write_all(&value.to_le_bytes())?;
+            }
+        }
+
+        // The attribute byte count, which is unused.
+        writer.write_all(&[0, 0])?;
+    }
+
+    writer.flush()
+}
+
+/// Writes a mesh as an ASCII STL file.
+///
+/// `name` is written as the name of the solid, and should not contain line breaks.
+pub fn write_ascii<W: Write>(mesh: &Mesh, name: &str, writer: W) -> Result<(), IoError> {
+    let mut writer = BufWriter::new(writer);
+
+    writeln!(writer, "solid {}", name)?;
+    for triangle in triangles(mesh) {
+        let normal = face_normal(triangle);
+        writeln!(writer, "  facet normal {:e} {:e} {:e}", normal.x, normal.y, normal.z)?;
+        writeln!(writer, "    outer loop")?;
+        for position in &triangle {
+            writeln!(writer, "      vertex {:e} {:e} {:e}", position.x, position.y, position.z)?;
+        }
+        writeln!(writer, "    endloop")?;
+        writeln!(writer, "  endfacet")?;
+    }
+    writeln!(writer, "endsolid {}", name)?;
+
+    writer.flush()
+}
+
+/// An error that can occur when reading an STL file.
+#[derive(Debug)]
+pub enum Error {
+    IoError(IoError),
+    BuildMeshError(BuildMeshError),
+
+    /// Indicates that a binary file ended before all of its triangles were read.
+    UnexpectedEof,
+
+    /// Indicates that an ASCII file was malformed on the specified line, starting from 1.
+    BadSyntax {
+        line: usize,
+    },
+}
+
+impl From<IoError> for Error {
+    fn from(error: IoError) -> Error {
+        Error::IoError(error)
+    }
+}
+
+impl From<BuildMeshError> for Error {
+    fn from(error: BuildMeshError) -> Error {
+        Error::BuildMeshError(error)
+    }
+}
+
+/// Reads the triangle count from the header of a binary file.
+fn triangle_count(data: &[u8]) -> Option<usize> {
+    let count = data.get(HEADER_SIZE .. HEADER_SIZE + 4)?;
+    Some(u32::from_le_bytes([count[0], count[1], count[2], count[3]]) as usize)
+}
+
+/// Reads the corner positions of every triangle in a binary file.
+fn parse_binary(data: &[u8]) -> Result<Vec<Point>, Error> {
+    let count = triangle_count(data).ok_or(Error::UnexpectedEof)?;
+    let triangle_data = &data[HEADER_SIZE + 4 ..];
+    if triangle_data.len() < count * TRIANGLE_SIZE {
+        return Err(Error::UnexpectedEof);
+    }
+
+    let read_f32 = |bytes: &[u8]| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+
+    let mut positions = Vec::with_capacity(count * 3);
+    for triangle in triangle_data.chunks(TRIANGLE_SIZE).take(count) {
+        // Skip the facet normal at the start of the triangle.
+        for corner in triangle[12 .. 48].chunks(12) {
+            positions.push(Point::new(
+                read_f32(&corner[0..4]),
+                read_f32(&corner[4..8]),
+                read_f32(&corner[8..12]),
+            ));
+        }
+    }
+
+    Ok(positions)
+}
+
+/// Reads the corner positions of every triangle in an ASCII file.
+fn parse_ascii(data: &[u8]) -> Result<Vec<Point>, Error> {
+    let source = str::from_utf8(data).map_err(|error| {
+        // Report the line containing the first invalid byte.
+        let valid = &data[.. error.valid_up_to()];
+        Error::BadSyntax { line: valid.iter().filter(|&&byte| byte == b'\n').count() + 1 }
+    })?;
+
+    let mut positions = Vec::new();
+    let mut last_line = 1;
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        last_line = line_number;
+
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("vertex") => {
+                let coordinates = words
+                    .map(|word| word.parse::<f32>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| Error::BadSyntax { line: line_number })?;
+                if coordinates.len() != 3 {
+                    return Err(Error::BadSyntax { line: line_number });
+                }
+
+                positions.push(Point::new(coordinates[0], coordinates[1], coordinates[2]));
+            }
+
+            // Every facet must have exactly 3 vertices.
+            Some("endloop") => {
+                if positions.len() % 3 != 0 {
+                    return Err(Error::BadSyntax { line: line_number });
+                }
+            }
+
+            Some("solid") | Some("endsolid") | Some("facet") | Some("outer") | Some("endfacet") | None => {}
+
+            Some(_) => return Err(Error::BadSyntax { line: line_number }),
+        }
+    }
+
+    if positions.len() % 3 != 0 {
+        return Err(Error::BadSyntax { line: last_line });
+    }
+
+    Ok(positions)
+}
+
+/// Gets the corner positions of each triangle in a mesh.
+fn triangles<'a>(mesh: &'a Mesh) -> impl Iterator<Item = [Point; 3]> + 'a {
+    let position = mesh.position();
+    let indices = mesh.indices().to_vec();
+    (0..indices.len() / 3).map(move |triangle| {
+        let corner = |offset: usize| {
+            let value = mesh.attribute_value(position, indices[triangle * 3 + offset] as usize);
+            Point::new(value[0], value[1], value[2])
+        };
+        [corner(0), corner(1), corner(2)]
+    })
+}
+
+fn face_normal(triangle: [Point; 3]) -> Vector3 {
+    let normal = Vector3::cross(triangle[1] - triangle[0], triangle[2] - triangle[0]);
+    if normal.magnitude_squared() == 0.0 { Vector3::ZERO } else { normal.normalized() }
+}
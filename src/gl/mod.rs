@@ -1,9 +1,9 @@
 pub extern crate gl_util;
 
-use {BuildMaterialError, Counter, GpuMesh, Renderer};
+use {BuildMaterialError, CullStats, GpuMesh, ResourceError, Renderer};
 use anchor::*;
 use camera::*;
-use geometry::mesh::{Mesh, VertexAttribute};
+use geometry::mesh::{Mesh, MeshIndices, Submesh, VertexAttribute};
 use light::*;
 use material::*;
 use mesh_instance::*;
@@ -17,75 +17,121 @@ use self::gl_util::texture::{
     TextureFormat,
     TextureInternalFormat,
 };
+use self::gl_util::uniform_buffer::{Std140Buffer, UniformBuffer};
+use polygon_material::material_source::{AttributeType, PropertyType};
 use shader::Shader;
+use slot_map::SlotMap;
+use std::cmp::Reverse;
 use std::collections::HashMap;
+use std::iter;
+use std::ops::Range;
 use std::str;
 use texture::*;
 
 static DEFAULT_SHADER_BYTES: &'static [u8] = include_bytes!("../../resources/materials/diffuse_lit.material");
 
+/// The uniform buffer binding point used for the per-frame camera data.
+const CAMERA_BLOCK_BINDING: u32 = 0;
+
+/// The uniform buffer binding point used for the per-frame light data.
+const LIGHT_BLOCK_BINDING: u32 = 1;
+
+/// The maximum number of lights that can be active at once.
+const MAX_LIGHTS: usize = 8;
+
+/// The first attribute location used for the per-instance model transform.
+///
+/// A `mat4` attribute takes up four consecutive locations, one per column.
+const INSTANCE_MODEL_LOCATION: u32 = 8;
+
+/// The first attribute location used for the per-instance normal transform.
+///
+/// A `mat3` attribute takes up three consecutive locations, one per column.
+const INSTANCE_NORMAL_LOCATION: u32 = 12;
+
+/// The number of floats at the start of each instance's data used for the built-in transforms.
+const INSTANCE_TRANSFORM_LEN: usize = 16 + 9;
+
+/// The optional built-in vertex attributes, given as the name of the attribute in the vertex
+/// program, its GLSL type, and the `@vertex` keyword that passes it to the fragment program.
+static OPTIONAL_VERTEX_ATTRIBUTES: &'static [(&'static str, &'static str, &'static str)] = &[
+    ("vertex_color", "vec4", "@vertex.color"),
+    ("vertex_uv1", "vec2", "@vertex.uv1"),
+    ("vertex_uv2", "vec2", "@vertex.uv2"),
+    ("vertex_uv3", "vec2", "@vertex.uv3"),
+];
+
+/// The names of the vertex attributes built in to every material, which custom attributes can't
+/// reuse.
+static BUILT_IN_ATTRIBUTE_NAMES: &'static [&'static str] = &[
+    "position", "normal", "tangent", "color", "uv0", "uv1", "uv2", "uv3",
+];
+
 #[derive(Debug)]
 pub struct GlRender {
     context: Context,
 
-    shared_materials: HashMap<MaterialId, Material>,
-    meshes: HashMap<GpuMesh, MeshData>,
-    textures: HashMap<GpuTexture, GlTexture2d>,
-    mesh_instances: HashMap<MeshInstanceId, MeshInstance>,
-    anchors: HashMap<AnchorId, Anchor>,
-    cameras: HashMap<CameraId, Camera>,
-    lights: HashMap<LightId, Light>,
-    programs: HashMap<Shader, Program>,
-
-    mesh_instances_with_shared_materials: HashMap<MaterialId, Vec<MeshInstanceId>>,
-    mesh_instances_with_owned_material: Vec<MeshInstanceId>,
-
-    material_counter: MaterialId,
-    mesh_counter: GpuMesh,
-    texture_counter: GpuTexture,
-    mesh_instance_counter: MeshInstanceId,
-    anchor_counter: AnchorId,
-    camera_counter: CameraId,
-    light_counter: LightId,
-    shader_counter: Shader,
+    shared_materials: SlotMap<MaterialId, SharedMaterial>,
+    meshes: SlotMap<GpuMesh, MeshData>,
+    textures: SlotMap<GpuTexture, GlTexture2d>,
+    mesh_instances: SlotMap<MeshInstanceId, MeshInstance>,
+    anchors: SlotMap<AnchorId, Anchor>,
+    cameras: SlotMap<CameraId, Camera>,
+    lights: SlotMap<LightId, Light>,
+    programs: SlotMap<Shader, ProgramData>,
+
+    /// The mesh instances with owned materials, along with the index of each owned material.
+    mesh_instances_with_owned_material: Vec<(MeshInstanceId, usize)>,
 
     ambient_color: Color,
 
     default_material: Material,
+
+    camera_block: UniformBuffer,
+    light_block: UniformBuffer,
+
+    /// Scratch buffer used when building the contents of the uniform blocks each frame.
+    block_data: Std140Buffer,
+
+    instance_buffer: InstanceBuffer,
+
+    cull_stats: CullStats,
 }
 
 impl GlRender {
     pub fn new(context: Context) -> Result<GlRender, Error> {
         context.clear();
 
+        let camera_block = UniformBuffer::new(&context);
+        let light_block = UniformBuffer::new(&context);
+        let instance_buffer = InstanceBuffer::new(&context);
+
         let mut renderer = GlRender {
             context: context,
 
-            shared_materials: HashMap::new(),
-            meshes: HashMap::new(),
-            textures: HashMap::new(),
-            mesh_instances: HashMap::new(),
-            anchors: HashMap::new(),
-            cameras: HashMap::new(),
-            lights: HashMap::new(),
-            programs: HashMap::new(),
+            shared_materials: SlotMap::new(),
+            meshes: SlotMap::new(),
+            textures: SlotMap::new(),
+            mesh_instances: SlotMap::new(),
+            anchors: SlotMap::new(),
+            cameras: SlotMap::new(),
+            lights: SlotMap::new(),
+            programs: SlotMap::new(),
 
-            mesh_instances_with_shared_materials: HashMap::new(),
             mesh_instances_with_owned_material: Vec::new(),
 
-            material_counter: MaterialId::initial(),
-            mesh_counter: GpuMesh::initial(),
-            texture_counter: GpuTexture::initial(),
-            mesh_instance_counter: MeshInstanceId::initial(),
-            anchor_counter: AnchorId::initial(),
-            camera_counter: CameraId::initial(),
-            light_counter: LightId::initial(),
-            shader_counter: Shader::initial(),
-
             ambient_color: Color::rgb(0.01, 0.01, 0.01),
 
             // Use temporary value and replace it later.
-            default_material: Material::new(Shader::initial()),
+            default_material: Material::new(Shader::default()),
+
+            camera_block: camera_block,
+            light_block: light_block,
+            block_data: Std140Buffer::new(),
+
+            instance_buffer: instance_buffer,
+
+            cull_stats: CullStats::default(),
         };
 
         // Load source code for the default material.
@@ -103,55 +149,315 @@ impl GlRender {
         Ok(renderer)
     }
 
-    fn render_mesh_instance(
+    /// Determines the location each of the mesh's built-in vertex attributes is bound to.
+    ///
+    /// Position, normal, tangent, and the first set of texcoords have fixed locations in every
+    /// program, so they're bound in the mesh's vertex array. Optional and custom attributes are
+    /// bound when drawing instead, see `named_attributes()`.
+    fn mesh_attributes(&self, mesh: &Mesh) -> Vec<(u32, VertexAttribute)> {
+        let mut attributes = vec![(0, mesh.position())];
+
+        if let Some(normal) = mesh.normal() {
+            attributes.push((1, normal));
+        }
+
+        if let Some(tangent) = mesh.tangent() {
+            attributes.push((3, tangent));
+        }
+
+        if let Some(texcoord) = mesh.texcoord().first().cloned() {
+            attributes.push((2, texcoord));
+        }
+
+        attributes
+    }
+
+    /// Gets the mesh's optional and custom vertex attributes, keyed by the name of the attribute
+    /// in the vertex program.
+    ///
+    /// Programs only get locations for the attributes they read, and the locations differ
+    /// between programs, so these are bound to the program's locations each time the mesh is
+    /// drawn.
+    fn named_attributes(mesh: &Mesh) -> Vec<(String, VertexAttribute)> {
+        let mut attributes = Vec::new();
+        if let Some(color) = mesh.color() {
+            attributes.push(("vertex_color".to_string(), color));
+        }
+        for (set, &texcoord) in mesh.texcoord().iter().enumerate().skip(1) {
+            attributes.push((format!("vertex_uv{}", set), texcoord));
+        }
+        for &(ref name, attrib) in mesh.custom_attributes() {
+            attributes.push((format!("vertex_{}", name), attrib));
+        }
+
+        attributes
+    }
+
+    /// Uploads the mesh's vertex and index data to a new vertex array, binding each attribute to
+    /// its location.
+    fn build_vertex_array(
         &self,
-        mesh_instance: &MeshInstance,
+        mesh: &Mesh,
+        attributes: &[(u32, VertexAttribute)],
+        usage: BufferUsage,
+    ) -> VertexArray {
+        let mut vertex_array = match *mesh.indices() {
+            MeshIndices::U16(ref indices) => VertexArray::with_index_buffer_and_usage(
+                &self.context,
+                mesh.vertex_data(),
+                &**indices,
+                usage,
+            ),
+            MeshIndices::U32(ref indices) => VertexArray::with_index_buffer_and_usage(
+                &self.context,
+                mesh.vertex_data(),
+                &**indices,
+                usage,
+            ),
+        };
+
+        for &(location, attrib) in attributes {
+            vertex_array.set_attrib(AttributeLocation::from_index(location), attrib.into());
+        }
+
+        vertex_array
+    }
+
+    /// Checks that no registered material uses a resource.
+    ///
+    /// The default material is checked first, then the shared materials, then the owned
+    /// materials of each mesh instance. The first material found using the resource is reported
+    /// in the error.
+    fn check_material_users<F>(&self, uses: F) -> Result<(), ResourceError>
+    where F: Fn(&Material) -> bool
+    {
+        if uses(&self.default_material) {
+            return Err(ResourceError::UsedByDefaultMaterial);
+        }
+
+        for (material_id, shared_material) in self.shared_materials.iter() {
+            if uses(&shared_material.material) {
+                return Err(ResourceError::UsedBySharedMaterial(material_id));
+            }
+        }
+
+        for (mesh_instance_id, mesh_instance) in self.mesh_instances.iter() {
+            let uses_owned = mesh_instance.material_types().iter().any(|material_type| {
+                match *material_type {
+                    MaterialType::Owned(ref material) => uses(material),
+                    MaterialType::Shared(_) => false,
+                }
+            });
+
+            if uses_owned {
+                return Err(ResourceError::UsedByMeshInstance(mesh_instance_id));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the per-frame camera and light data into the uniform buffers.
+    ///
+    /// All programs built by `build_material()` read the camera and light uniforms from these
+    /// blocks, so they only need to be uploaded once per frame rather than once per draw.
+    fn update_uniform_blocks(&mut self, frame: &FrameData) {
+        // Upload camera data.
+        self.block_data.clear();
+        self.block_data
+            .push_matrix(GlMatrix {
+                data: frame.view_transform.raw_data(),
+                transpose: true,
+            })
+            .push_matrix(GlMatrix {
+                data: frame.projection_transform.raw_data(),
+                transpose: true,
+            })
+            .push::<[f32; 4]>(*frame.camera_position.as_array())
+            .push::<[f32; 4]>(self.ambient_color.into());
+        self.camera_block.set_data(self.block_data.finish());
+
+        // Gather light data. Slots for lights that aren't active are left with a light type of
+        // 0, indicating that there is no light.
+        let mut light_type = [0i32; MAX_LIGHTS];
+        let mut light_strength = [0.0f32; MAX_LIGHTS];
+        let mut light_color = [Color::rgb(0.0, 0.0, 0.0); MAX_LIGHTS];
+        let mut light_position = [Point::origin(); MAX_LIGHTS];
+        let mut light_position_view = [Point::origin(); MAX_LIGHTS];
+        let mut light_radius = [0.0f32; MAX_LIGHTS];
+        let mut light_direction = [Vector3::ZERO; MAX_LIGHTS];
+        let mut light_direction_view = [Vector3::ZERO; MAX_LIGHTS];
+
+        // TODO: Support having more than 8 lights active at a time. Maybe pick the 8
+        // most relevant lights? Or simply support more lights at once in the shader.
+        for (index, light) in self.lights.values().take(MAX_LIGHTS).enumerate() {
+            // Setup common light data.
+            light_color[index] = light.color;
+            light_strength[index] = light.strength;
+
+            // Setup data specific to the current type of light.
+            match light.data {
+                LightData::Point { radius } => {
+                    // Get the light's anchor. Lights whose anchor has been removed are left
+                    // inactive.
+                    let light_anchor = match light.anchor() {
+                        Some(&anchor_id) => match self.anchors.get(anchor_id) {
+                            Some(anchor) => anchor,
+                            None => continue,
+                        },
+                        None => panic!("Cannot render point light if it's not attached to an anchor"),
+                    };
+
+                    light_type[index] = 1;
+                    light_position[index] = light_anchor.position();
+                    light_position_view[index] = light_anchor.position() * frame.view_transform;
+                    light_radius[index] = radius;
+                },
+
+                LightData::Directional { direction } => {
+                    light_type[index] = 2;
+                    light_direction[index] = direction;
+                    light_direction_view[index] = direction * frame.view_transform;
+                },
+            }
+        }
+
+        // Upload light data. The order here must match the declaration of `LightBlock` in
+        // `BUILT_IN_UNIFORMS`.
+        self.block_data.clear();
+        self.block_data
+            .push_array(&light_type[..])
+            .push_array(Point::as_slice_of_arrays(&light_position))
+            .push_array(Point::as_slice_of_arrays(&light_position_view))
+            .push_array(&light_strength[..])
+            .push_array(Color::as_slice_of_arrays(&light_color))
+            .push_array(&light_radius[..])
+            .push_array(Vector3::as_slice_of_arrays(&light_direction))
+            .push_array(Vector3::as_slice_of_arrays(&light_direction_view));
+        self.light_block.set_data(self.block_data.finish());
+
+        self.camera_block.bind(CAMERA_BLOCK_BINDING);
+        self.light_block.bind(LIGHT_BLOCK_BINDING);
+    }
+
+    /// Picks the level of detail to draw for the mesh instance based on its size on screen.
+    ///
+    /// The mesh instance's own mesh is used if its size can't be determined because its anchor
+    /// or mesh has been removed.
+    fn select_lod(&self, mesh_instance: &MeshInstance, frame: &FrameData) -> GpuMesh {
+        if mesh_instance.lods().is_empty() {
+            return *mesh_instance.mesh();
+        }
+
+        let anchor = mesh_instance.anchor().and_then(|anchor_id| self.anchors.get(anchor_id));
+        let mesh_data = self.meshes.get(*mesh_instance.mesh());
+        let (anchor, mesh_data) = match (anchor, mesh_data) {
+            (Some(anchor), Some(mesh_data)) => (anchor, mesh_data),
+            _ => return *mesh_instance.mesh(),
+        };
+
+        let bounding_sphere = mesh_data.bounding_sphere.transform(anchor.matrix());
+        let screen_size = frame.camera.screen_size(&bounding_sphere, frame.camera_position);
+        mesh_instance.mesh_for_screen_size(screen_size)
+    }
+
+    /// Gets the range of the mesh's submeshes that the mesh instance draws with its material at
+    /// `material_index`.
+    ///
+    /// The range is empty if the mesh has been removed.
+    fn material_submeshes(&self, mesh: GpuMesh, mesh_instance: &MeshInstance, material_index: usize) -> Range<usize> {
+        match self.meshes.get(mesh) {
+            Some(mesh_data) => mesh_instance.material_submeshes(material_index, mesh_data.submeshes.len()),
+            None => 0..0,
+        }
+    }
+
+    /// Draws a batch of mesh instances that share the same mesh and material.
+    ///
+    /// Each submesh in `submeshes` is drawn with a single instanced draw call. Mesh instances that
+    /// are outside of the camera's frustum are culled, and each remaining instance's transforms
+    /// and instance properties are written to the scratch buffer in `state` and uploaded to the
+    /// instance buffer before drawing. Nothing is drawn if the mesh or the material's shader has
+    /// been removed.
+    fn render_batch(
+        &self,
+        mesh: GpuMesh,
+        submeshes: Range<usize>,
+        mesh_instances: &[&MeshInstance],
         material: &Material,
-        camera: &Camera,
-        camera_anchor: &Anchor,
-        has_setup_lights: &mut bool,
-        has_setup_material: &mut bool,
+        frame: &FrameData,
+        state: &mut DrawState,
     ) {
-        let anchor = match mesh_instance.anchor() {
-            Some(anchor_id) => self.anchors.get(&anchor_id).expect("No such anchor exists"),
-            None => return,
+        // Materials past the mesh's last submesh have nothing to draw.
+        if submeshes.start == submeshes.end {
+            return;
+        }
+
+        let (mesh_data, program_data) = match (self.meshes.get(mesh), self.programs.get(*material.shader())) {
+            (Some(mesh_data), Some(program_data)) => (mesh_data, program_data),
+            _ => return,
         };
 
-        let model_transform = anchor.matrix();
-        let normal_transform = anchor.normal_matrix();
+        // Gather the per-instance data for the batch.
+        let DrawState { ref mut instance_data, ref mut cull_stats, .. } = *state;
+        instance_data.clear();
+        let mut instance_count = 0;
+        for mesh_instance in mesh_instances {
+            let anchor = match mesh_instance.anchor().and_then(|anchor_id| self.anchors.get(anchor_id)) {
+                Some(anchor) => anchor,
+                None => continue,
+            };
 
-        let mesh_data = self.meshes.get(mesh_instance.mesh()).expect("Mesh data does not exist for mesh id");
+            // Cull the mesh instance if it's not visible. The sphere test is cheaper so it's done
+            // first, and the box test catches long, thin meshes with loose bounding spheres.
+            let model_transform = anchor.matrix();
+            let is_visible =
+                frame.frustum.intersects_sphere(&mesh_data.bounding_sphere.transform(model_transform))
+                && frame.frustum.intersects_aabb(&mesh_data.bounding_box.transform(model_transform));
+            // Mesh instances with several materials are drawn in several batches, but they're only
+            // counted in the batch for their first submesh.
+            let is_counted = submeshes.start == 0;
+            if !is_visible {
+                cull_stats.culled += is_counted as usize;
+                continue;
+            }
+            cull_stats.visible += is_counted as usize;
+
+            // Matrices are stored in column-major order, which is how GL reads matrix attributes.
+            instance_data.extend_from_slice(model_transform.transpose().raw_data());
+            instance_data.extend_from_slice(anchor.normal_matrix().transpose().raw_data());
+
+            for attrib in &program_data.instance_attribs {
+                match (attrib.property_type, mesh_instance.instance_property(&attrib.name)) {
+                    (PropertyType::Color, Some(&InstanceProperty::Color(color))) => {
+                        instance_data.extend_from_slice(&Into::<[f32; 4]>::into(color));
+                    }
+
+                    (PropertyType::f32, Some(&InstanceProperty::f32(value))) => {
+                        instance_data.push(value);
+                    }
+
+                    (PropertyType::Vector3, Some(&InstanceProperty::Vector3(value))) => {
+                        instance_data.extend_from_slice(&Into::<[f32; 3]>::into(value));
+                    }
+
+                    // Properties that weren't set (or were set with the wrong type) are zero.
+                    (property_type, _) => {
+                        instance_data.extend(iter::repeat(0.0).take(instance_property_len(property_type)));
+                    }
+                }
+            }
 
-        let default_texture = GlTexture2d::empty(&self.context);
+            instance_count += 1;
+        }
 
-        // Calculate the various transforms needed for rendering.
-        let view_transform = camera_anchor.view_matrix();
-        let model_view_transform = view_transform * model_transform;
-        let projection_transform = camera.projection_matrix();
-        let model_view_projection = projection_transform * model_view_transform;
+        if instance_count == 0 {
+            return;
+        }
 
-        let view_normal_transform = {
-            let inverse_model = normal_transform.transpose();
-            let inverse_view = camera_anchor.inverse_view_matrix().into();
-            let inverse_model_view = inverse_model * inverse_view;
-            inverse_model_view.transpose()
-        };
+        self.instance_buffer.set_data(&*instance_data);
 
-        // The data for the light uniforms must be declared before `draw_builder` so they
-        // can outlive it, since they are borrowed when the uniforms are set.
-        let mut light_type = [0i32; 8];
-        let mut light_strength = [0.0f32; 8];
-        let mut light_color = [Color::rgb(0.0, 0.0, 0.0); 8];
-        let mut light_position = [Point::origin(); 8];
-        let mut light_position_view = [Point::origin(); 8];
-        let mut light_radius = [0.0f32; 8];
-        let mut light_direction = [Vector3::ZERO; 8];
-        let mut light_direction_view = [Vector3::ZERO; 8];
-
-        let program = self
-            .programs
-            .get(material.shader())
-            .expect("Material is using a shader that does not exist");
+        let default_texture = GlTexture2d::empty(&self.context);
 
         // Set the shader to use.
         let mut draw_builder = DrawBuilder::new(
@@ -161,71 +467,65 @@ impl GlRender {
         );
 
         draw_builder
-            .program(program)
+            .program(&program_data.program)
             .cull(Face::Back)
-            .depth_test(Comparison::Less);
+            .depth_test(Comparison::Less)
+            .instances(instance_count);
+
+        // Bind the mesh's optional and custom attributes to the locations the program reads them
+        // from. Attributes the mesh doesn't have are left disabled, and attributes the program
+        // doesn't read are skipped.
+        for &(ref name, location) in &program_data.vertex_attribs {
+            if let Some(attrib) = mesh_data.named_attribute(name) {
+                draw_builder.vertex_attrib(location, attrib.into());
+            }
+        }
 
-        // Set uniform transforms.
-        draw_builder
-            .uniform(
-                "model_transform",
-                GlMatrix {
-                    data: model_transform.raw_data(),
-                    transpose: true,
-                },
-            )
-            .uniform(
-                "normal_transform",
-                GlMatrix {
-                    data: normal_transform.raw_data(),
-                    transpose: true,
-                },
-            )
-            .uniform(
-                "view_normal_transform",
-                GlMatrix {
-                    data: view_normal_transform.raw_data(),
-                    transpose: true,
-                },
-            )
-            .uniform(
-                "view_transform",
-                GlMatrix {
-                    data: view_transform.raw_data(),
-                    transpose: true,
-                },
-            )
-            .uniform(
-                "model_view_transform",
-                GlMatrix {
-                    data: model_view_transform.raw_data(),
-                    transpose: true,
-                },
-            )
-            .uniform(
-                "projection_transform",
-                GlMatrix {
-                    data: projection_transform.raw_data(),
-                    transpose: true,
-                },
-            )
-            .uniform(
-                "model_view_projection",
-                GlMatrix {
-                    data: model_view_projection.raw_data(),
-                    transpose: true,
+        // Setup the per-instance transforms, one attribute per matrix column.
+        let stride = program_data.instance_stride;
+        for column in 0..4 {
+            draw_builder.instance_attrib(
+                AttributeLocation::from_index(INSTANCE_MODEL_LOCATION + column as u32),
+                &self.instance_buffer,
+                AttribLayout {
+                    elements: 4,
+                    offset: column * 4,
+                    stride: stride,
                 },
             );
+        }
 
-        // Apply material attributes.
-        if !*has_setup_material {
-            *has_setup_material = true;
+        for column in 0..3 {
+            draw_builder.instance_attrib(
+                AttributeLocation::from_index(INSTANCE_NORMAL_LOCATION + column as u32),
+                &self.instance_buffer,
+                AttribLayout {
+                    elements: 3,
+                    offset: 16 + column * 3,
+                    stride: stride,
+                },
+            );
+        }
 
-            // Set uniform colors.
-            draw_builder.uniform::<[f32; 4]>("global_ambient", self.ambient_color.into());
+        // Setup the material's instance properties. Attributes that aren't used by the program
+        // get optimized out and have no location, but their data is still in the buffer.
+        for attrib in &program_data.instance_attribs {
+            if let Some(location) = attrib.location {
+                draw_builder.instance_attrib(
+                    location,
+                    &self.instance_buffer,
+                    AttribLayout {
+                        elements: instance_property_len(attrib.property_type),
+                        offset: attrib.offset,
+                        stride: stride,
+                    },
+                );
+            }
+        }
 
-            // Other uniforms.
-            draw_builder.uniform("camera_position", *camera_anchor.position().as_array());
+        // Apply material attributes.
+        if !state.has_setup_material {
+            state.has_setup_material = true;
 
             for (name, property) in material.properties() {
                 match *property {
@@ -241,7 +541,7 @@ impl GlRender {
                     MaterialProperty::Texture(ref texture) => {
                         let gl_texture =
                         self.textures
-                        .get(texture)
+                        .get(*texture)
                         .unwrap_or(&default_texture);
                         draw_builder.uniform(name, gl_texture);
                     },
@@ -249,53 +549,9 @@ impl GlRender {
             }
         }
 
-        // Render all lights in a single pass by sending 8 lights at once in arrays.
-        // All light-related uniforms will stay the same for all draws for a given camera,
-        // so we only specify them for the first draw and leave them the same after that.
-        if !*has_setup_lights {
-            *has_setup_lights = true;
-
-            // TODO: Support having more than 8 lights active at a time. Maybe pick the 8
-            // most relevant lights? Or simply support more lights at once in the shader.
-            for (index, light) in self.lights.values().take(8).enumerate() {
-                // Setup common light data.
-                light_color[index] = light.color;
-                light_strength[index] = light.strength;
-
-                // Setup data specific to the current type of light.
-                match light.data {
-                    LightData::Point { radius } => {
-                        // Get the light's anchor.
-                        let light_anchor = match light.anchor() {
-                            Some(anchor_id) => self.anchors.get(&anchor_id).expect("No such anchor exists"),
-                            None => panic!("Cannot render point light if it's not attached to an anchor"),
-                        };
-
-                        light_type[index] = 1;
-                        light_position[index] = light_anchor.position();
-                        light_position_view[index] = light_anchor.position() * view_transform;
-                        light_radius[index] = radius;
-                    },
-
-                    LightData::Directional { direction } => {
-                        light_type[index] = 2;
-                        light_direction[index] = direction;
-                        light_direction_view[index] = direction * view_transform;
-                    },
-                }
-            }
-
-            draw_builder.uniform("light_type", &light_type[..]);
-            draw_builder.uniform("light_strength", &light_strength[..]);
-            draw_builder.uniform("light_color", Color::as_slice_of_arrays(&light_color));
-            draw_builder.uniform("light_position", Point::as_slice_of_arrays(&light_position));
-            draw_builder.uniform("light_position_view", Point::as_slice_of_arrays(&light_position_view));
-            draw_builder.uniform("light_radius", &light_radius[..]);
-            draw_builder.uniform("light_direction", Vector3::as_slice_of_arrays(&light_direction));
-            draw_builder.uniform("light_direction_view", Vector3::as_slice_of_arrays(&light_direction_view));
+        for submesh in &mesh_data.submeshes[submeshes] {
+            draw_builder.range(submesh.start..submesh.end()).draw();
         }
-
-        draw_builder.draw();
     }
 }
 
@@ -305,47 +561,84 @@ impl Renderer for GlRender {
 
         // TODO: Support rendering multiple cameras.
         // TODO: Should we warn if there are no cameras?
-        if let Some(camera) = self.cameras.values().next() {
-            let camera_anchor = match camera.anchor() {
-                Some(ref anchor_id) => self.anchors.get(anchor_id).expect("No such anchor exists"),
-                None => unimplemented!(),
-            };
+        let frame = match self.cameras.values().next() {
+            Some(camera) => {
+                let camera_anchor = match camera.anchor() {
+                    Some(anchor_id) => self.anchors.get(anchor_id),
+                    None => unimplemented!(),
+                };
 
-            let mut has_setup_lights = false;
-
-            // Render shared materials first.
-            for (material_id, mesh_instances) in &self.mesh_instances_with_shared_materials {
-                let material = self.shared_materials.get(material_id).expect("No such material exists");
-                let mut has_setup_material = false;
-
-                for mesh_instance_id in mesh_instances {
-                    let mesh_instance = self.mesh_instances.get(mesh_instance_id).expect("No such mesh instance");
-                    self.render_mesh_instance(
-                        mesh_instance,
-                        material,
-                        camera,
-                        camera_anchor,
-                        &mut has_setup_lights,
-                        &mut has_setup_material,
-                    );
-                }
+                // A camera whose anchor has been removed has nowhere to draw from.
+                camera_anchor.map(|camera_anchor| FrameData::new(camera, camera_anchor))
+            }
+
+            None => None,
+        };
+        let frame = match frame {
+            Some(frame) => frame,
+            None => {
+                self.context.swap_buffers();
+                return;
+            }
+        };
+
+        self.update_uniform_blocks(&frame);
+
+        let mut state = DrawState {
+            instance_data: Vec::new(),
+            cull_stats: CullStats::default(),
+            has_setup_material: false,
+        };
+
+        // Render shared materials first. Mesh instances that use the same mesh and draw the same
+        // submeshes with the material are batched together and drawn with one instanced draw
+        // per submesh.
+        let mut batches = HashMap::<(GpuMesh, Range<usize>), Vec<&MeshInstance>>::new();
+        for shared_material in self.shared_materials.values() {
+            state.has_setup_material = false;
+
+            for batch in batches.values_mut() {
+                batch.clear();
             }
 
-            // Render meshes with unique materials.
-            for mesh_instance_id in &self.mesh_instances_with_owned_material {
+            for &(mesh_instance_id, material_index) in &shared_material.mesh_instances {
                 let mesh_instance = self.mesh_instances.get(mesh_instance_id).expect("No such mesh instance");
-                let material = mesh_instance.material().expect("Mesh instance was in wrong bucket (was in the owned material bucket, had shared material)");
-                self.render_mesh_instance(
-                    mesh_instance,
-                    material,
-                    camera,
-                    camera_anchor,
-                    &mut false,
-                    &mut false,
+                let mesh = self.select_lod(mesh_instance, &frame);
+                let submeshes = self.material_submeshes(mesh, mesh_instance, material_index);
+                batches.entry((mesh, submeshes)).or_insert(Vec::new()).push(mesh_instance);
+            }
+
+            for (&(mesh, ref submeshes), batch) in &batches {
+                self.render_batch(
+                    mesh,
+                    submeshes.clone(),
+                    &*batch,
+                    &shared_material.material,
+                    &frame,
+                    &mut state,
                 );
             }
         }
 
+        // Render meshes with unique materials.
+        for &(mesh_instance_id, material_index) in &self.mesh_instances_with_owned_material {
+            let mesh_instance = self.mesh_instances.get(mesh_instance_id).expect("No such mesh instance");
+            let mesh = self.select_lod(mesh_instance, &frame);
+            let submeshes = self.material_submeshes(mesh, mesh_instance, material_index);
+            let material = mesh_instance.submesh_material(material_index).expect("Mesh instance was in wrong bucket (was in the owned material bucket, had shared material)");
+            state.has_setup_material = false;
+            self.render_batch(
+                mesh,
+                submeshes,
+                &[mesh_instance],
+                material,
+                &frame,
+                &mut state,
+            );
+        }
+
+        self.cull_stats = state.cull_stats;
+
         self.context.swap_buffers();
     }
 
@@ -354,8 +647,6 @@ impl Renderer for GlRender {
     }
 
     fn build_material(&mut self, source: MaterialSource) -> Result<Material, BuildMaterialError> {
-        use polygon_material::material_source::PropertyType;
-
         // COMPILE SHADER SOURCE
         // =====================
 
@@ -382,28 +673,64 @@ impl Renderer for GlRender {
             uniform_declarations
         };
 
+        // Generate the declarations and replacements for the material's instance properties.
+        // Instance properties are read as vertex attributes and then passed along to the
+        // fragment shader unchanged.
+        let mut instance_attrib_declarations = String::new();
+        let mut instance_out_declarations = String::new();
+        let mut instance_in_declarations = String::new();
+        let mut instance_assignments = String::new();
+        let mut instance_replacements = Vec::new();
+        for property in &source.instance_properties {
+            let type_str = match property.property_type {
+                PropertyType::Color => "vec4",
+                PropertyType::f32 => "float",
+                PropertyType::Vector3 => "vec3",
+                PropertyType::Texture2d => return Err(BuildMaterialError),
+            };
+
+            let varying = format!("_instance_{}_", property.name);
+            instance_attrib_declarations.push_str(&format!("in {} instance_{};\n", type_str, property.name));
+            instance_out_declarations.push_str(&format!("flat out {} {};\n", type_str, varying));
+            instance_in_declarations.push_str(&format!("flat in {} {};\n", type_str, varying));
+            instance_assignments.push_str(&format!("{} = instance_{};\n", varying, property.name));
+            instance_replacements.push((format!("@instance.{}", property.name), varying));
+        }
+
+        // Replace longer names first so that a property whose name is a prefix of another
+        // property's name doesn't clobber it.
+        instance_replacements.sort_by_key(|replacement| Reverse(replacement.0.len()));
+        let replace_instance_properties = |source: String| {
+            instance_replacements
+                .iter()
+                .fold(source, |source, (keyword, varying)| source.replace(keyword.as_str(), varying))
+        };
+
+        // NOTE: The declarations in `CameraBlock` and `LightBlock` must match the order in which
+        // `update_uniform_blocks()` writes the block data.
         static BUILT_IN_UNIFORMS: &'static str = r#"
-            uniform mat4 model_transform;
-            uniform mat3 normal_transform;
-            uniform mat4 view_transform;
-            uniform mat3 view_normal_transform;
-            uniform mat4 model_view_transform;
-            uniform mat4 projection_transform;
-            uniform mat4 model_view_projection;
-
-            uniform vec4 global_ambient;
-            uniform vec4 camera_position;
-
-            uniform int light_type[8];
-            uniform vec4 light_position[8];
-            uniform vec4 light_position_view[8];
-            uniform float light_strength[8];
-            uniform vec4 light_color[8];
-            uniform float light_radius[8];
-            uniform vec3 light_direction[8];
-            uniform vec3 light_direction_view[8];
+            layout(std140) uniform CameraBlock {
+                mat4 view_transform;
+                mat4 projection_transform;
+                vec4 camera_position;
+                vec4 global_ambient;
+            };
+
+            layout(std140) uniform LightBlock {
+                int light_type[8];
+                vec4 light_position[8];
+                vec4 light_position_view[8];
+                float light_strength[8];
+                vec4 light_color[8];
+                float light_radius[8];
+                vec3 light_direction[8];
+                vec3 light_direction_view[8];
+            };
         "#;
 
+        // The names of the optional and custom attributes declared in the vertex shader.
+        let mut vertex_attrib_names = Vec::new();
+
         // Generate the GLSL source for the vertex shader.
         let vert_shader = {
             static DEFAULT_VERT_MAIN: &'static str = r#"
@@ -418,19 +745,68 @@ impl Renderer for GlRender {
 
                 @vertex.view_position = model_view_transform * vertex_position;
                 @vertex.view_normal = normalize(view_normal_transform * vertex_normal);
+
+                @vertex.tangent = vertex_tangent;
+                @vertex.bitangent = cross(vertex_normal, vertex_tangent.xyz) * vertex_tangent.w;
+
+                @vertex.view_tangent = mat3(model_view_transform) * vertex_tangent.xyz;
+                @vertex.view_bitangent = cross(@vertex.view_normal, @vertex.view_tangent) * vertex_tangent.w;
             "#;
 
-            // Retrieve source string for the vertex shader.
-            let raw_source =
+            let fragment_source =
                 source
                 .programs
                 .iter()
-                .find(|program_source| program_source.is_vertex())
+                .find(|program_source| program_source.is_fragment())
                 .map(|program_source| program_source.source())
-                .unwrap_or(DEFAULT_VERT_MAIN);
+                .unwrap_or("");
+
+            // Retrieve source string for the vertex shader. The default vertex program only
+            // passes along the optional attributes that the fragment program reads, so that
+            // unused attributes don't take up attribute locations.
+            let raw_source = match source.programs.iter().find(|program_source| program_source.is_vertex()) {
+                Some(program_source) => program_source.source().to_string(),
+                None => {
+                    let mut raw_source = DEFAULT_VERT_MAIN.to_string();
+                    for &(name, _, keyword) in OPTIONAL_VERTEX_ATTRIBUTES {
+                        if fragment_source.contains(keyword) {
+                            raw_source.push_str(&format!("{} = {};\n", keyword, name));
+                        }
+                    }
+                    raw_source
+                }
+            };
+
+            // Declare the optional attributes used by the vertex program and the material's
+            // custom attributes. GL assigns their locations when the program is linked, and they
+            // are looked up afterwards.
+            let mut attrib_declarations = String::new();
+            for &(name, type_str, _) in OPTIONAL_VERTEX_ATTRIBUTES {
+                if raw_source.contains(name) {
+                    attrib_declarations.push_str(&format!("in {} {};\n", type_str, name));
+                    vertex_attrib_names.push(name.to_string());
+                }
+            }
+
+            for attribute in &source.attributes {
+                if BUILT_IN_ATTRIBUTE_NAMES.contains(&&*attribute.name) {
+                    return Err(BuildMaterialError);
+                }
+
+                let type_str = match attribute.attribute_type {
+                    AttributeType::f32 => "float",
+                    AttributeType::Vector2 => "vec2",
+                    AttributeType::Vector3 => "vec3",
+                    AttributeType::Vector4 => "vec4",
+                };
+
+                let name = format!("vertex_{}", attribute.name);
+                attrib_declarations.push_str(&format!("in {} {};\n", type_str, name));
+                vertex_attrib_names.push(name);
+            }
 
             // Perform text replacements for the various keywords.
-            let replaced_source = raw_source
+            let replaced_source = replace_instance_properties(raw_source)
                 .replace("@position", "gl_Position")
                 .replace("@vertex.position", "_vertex_position_")
                 .replace("@vertex.normal", "_vertex_normal_")
@@ -438,7 +814,15 @@ impl Renderer for GlRender {
                 .replace("@vertex.world_position", "_vertex_world_position_")
                 .replace("@vertex.world_normal", "_vertex_world_normal_")
                 .replace("@vertex.view_position", "_vertex_view_position_")
-                .replace("@vertex.view_normal", "_vertex_view_normal_");
+                .replace("@vertex.view_normal", "_vertex_view_normal_")
+                .replace("@vertex.tangent", "_vertex_tangent_")
+                .replace("@vertex.bitangent", "_vertex_bitangent_")
+                .replace("@vertex.view_tangent", "_vertex_view_tangent_")
+                .replace("@vertex.view_bitangent", "_vertex_view_bitangent_")
+                .replace("@vertex.color", "_vertex_color_")
+                .replace("@vertex.uv1", "_vertex_uv1_")
+                .replace("@vertex.uv2", "_vertex_uv2_")
+                .replace("@vertex.uv3", "_vertex_uv3_");
             let replaced_source = format!(r#"
                     #version 330 core
 
@@ -449,6 +833,20 @@ impl Renderer for GlRender {
                     layout(location = 0) in vec4 vertex_position;
                     layout(location = 1) in vec3 vertex_normal;
                     layout(location = 2) in vec2 vertex_uv0;
+                    layout(location = 3) in vec4 vertex_tangent;
+                    {}
+
+                    layout(location = {}) in mat4 instance_model_transform;
+                    layout(location = {}) in mat3 instance_normal_transform;
+                    {}
+
+                    mat4 model_transform;
+                    mat3 normal_transform;
+                    mat3 view_normal_transform;
+                    mat4 model_view_transform;
+                    mat4 model_view_projection;
+
+                    {}
 
                     out vec4 _vertex_position_;
                     out vec3 _vertex_normal_;
@@ -457,13 +855,35 @@ impl Renderer for GlRender {
                     out vec3 _vertex_world_normal_;
                     out vec4 _vertex_view_position_;
                     out vec3 _vertex_view_normal_;
+                    out vec4 _vertex_tangent_;
+                    out vec3 _vertex_bitangent_;
+                    out vec3 _vertex_view_tangent_;
+                    out vec3 _vertex_view_bitangent_;
+                    out vec4 _vertex_color_;
+                    out vec2 _vertex_uv1_;
+                    out vec2 _vertex_uv2_;
+                    out vec2 _vertex_uv3_;
 
                     void main(void) {{
+                        model_transform = instance_model_transform;
+                        normal_transform = instance_normal_transform;
+                        model_view_transform = view_transform * model_transform;
+                        view_normal_transform = mat3(view_transform) * normal_transform;
+                        model_view_projection = projection_transform * model_view_transform;
+
+                        {}
+
                         {}
                     }}
                 "#,
                 BUILT_IN_UNIFORMS,
                 uniform_declarations,
+                attrib_declarations,
+                INSTANCE_MODEL_LOCATION,
+                INSTANCE_NORMAL_LOCATION,
+                instance_attrib_declarations,
+                instance_out_declarations,
+                instance_assignments,
                 replaced_source);
 
             GlShader::new(&self.context, replaced_source, ShaderType::Vertex).map_err(|_err| BuildMaterialError)?
@@ -481,7 +901,7 @@ impl Renderer for GlRender {
                 .ok_or(BuildMaterialError)?;
 
             // Perform text replacements for the various keywords.
-            let replaced_source = raw_source
+            let replaced_source = replace_instance_properties(raw_source.into())
                 .replace("@color", "_fragment_color_")
                 .replace("@vertex.position", "_vertex_position_")
                 .replace("@vertex.normal", "_vertex_normal_")
@@ -489,7 +909,16 @@ impl Renderer for GlRender {
                 .replace("@vertex.world_position", "_vertex_world_position_")
                 .replace("@vertex.world_normal", "_vertex_world_normal_")
                 .replace("@vertex.view_position", "_vertex_view_position_")
-                .replace("@vertex.view_normal", "_vertex_view_normal_");
+                .replace("@vertex.view_normal", "_vertex_view_normal_")
+                .replace("@vertex.tangent", "_vertex_tangent_")
+                .replace("@vertex.bitangent", "_vertex_bitangent_")
+                .replace("@vertex.view_tangent", "_vertex_view_tangent_")
+                .replace("@vertex.view_bitangent", "_vertex_view_bitangent_")
+                .replace("@vertex.view_tbn", "_vertex_view_tbn_")
+                .replace("@vertex.color", "_vertex_color_")
+                .replace("@vertex.uv1", "_vertex_uv1_")
+                .replace("@vertex.uv2", "_vertex_uv2_")
+                .replace("@vertex.uv3", "_vertex_uv3_");
             let replaced_source = format!(r#"
                     #version 330 core
 
@@ -504,24 +933,70 @@ impl Renderer for GlRender {
                     in vec3 _vertex_world_normal_;
                     in vec4 _vertex_view_position_;
                     in vec3 _vertex_view_normal_;
+                    in vec4 _vertex_tangent_;
+                    in vec3 _vertex_bitangent_;
+                    in vec3 _vertex_view_tangent_;
+                    in vec3 _vertex_view_bitangent_;
+                    in vec4 _vertex_color_;
+                    in vec2 _vertex_uv1_;
+                    in vec2 _vertex_uv2_;
+                    in vec2 _vertex_uv3_;
+
+                    {}
 
                     out vec4 _fragment_color_;
 
                     void main(void) {{
+                        // Matrix converting from tangent space to view space, used for normal
+                        // mapping. Interpolation denormalizes the basis vectors, so they're
+                        // renormalized here.
+                        mat3 _vertex_view_tbn_ = mat3(
+                            normalize(_vertex_view_tangent_),
+                            normalize(_vertex_view_bitangent_),
+                            normalize(_vertex_view_normal_));
+
                         {}
                     }}
                 "#,
                 BUILT_IN_UNIFORMS,
                 uniform_declarations,
+                instance_in_declarations,
                 replaced_source);
 
             GlShader::new(&self.context, replaced_source, ShaderType::Fragment).map_err(|_err| BuildMaterialError)?
         };
 
         let program = Program::new(&self.context, &[vert_shader, frag_shader]).map_err(|_err| BuildMaterialError)?;
+        program.bind_uniform_block("CameraBlock", CAMERA_BLOCK_BINDING);
+        program.bind_uniform_block("LightBlock", LIGHT_BLOCK_BINDING);
+
+        // Look up where the optional and custom attributes were placed. Attributes that aren't
+        // used by the program get optimized out and have no location.
+        let vertex_attribs = vertex_attrib_names
+            .into_iter()
+            .filter_map(|name| program.get_attrib(&name).map(|location| (name, location)))
+            .collect();
+
+        // Lay out the instance properties in the instance data, following the built-in
+        // transforms.
+        let mut instance_attribs = Vec::with_capacity(source.instance_properties.len());
+        let mut instance_stride = INSTANCE_TRANSFORM_LEN;
+        for property in &source.instance_properties {
+            instance_attribs.push(InstanceAttrib {
+                name: property.name.clone(),
+                property_type: property.property_type,
+                location: program.get_attrib(&format!("instance_{}", property.name)),
+                offset: instance_stride,
+            });
+            instance_stride += instance_property_len(property.property_type);
+        }
 
-        let program_id = self.shader_counter.next();
-        self.programs.insert(program_id, program);
+        let program_id = self.programs.insert(ProgramData {
+            program: program,
+            vertex_attribs: vertex_attribs,
+            instance_attribs: instance_attribs,
+            instance_stride: instance_stride,
+        });
 
         // BUILD MATERIAL OBJECT
         // =====================
@@ -541,55 +1016,101 @@ impl Renderer for GlRender {
         Ok(material)
     }
 
-    fn register_shared_material(&mut self, material: Material) -> MaterialId {
-        let material_id = self.material_counter.next();
+    fn remove_shader(&mut self, shader: Shader) -> Result<(), ResourceError> {
+        if !self.programs.contains_key(shader) {
+            return Err(ResourceError::NotFound);
+        }
 
-        let old = self.shared_materials.insert(material_id, material);
-        assert!(old.is_none());
+        self.check_material_users(|material| *material.shader() == shader)?;
 
-        // Ensure there's a bucket for the shared material's mesh instances.
-        self.mesh_instances_with_shared_materials.entry(material_id).or_insert(Vec::new());
+        // Dropping the program deletes it from the GPU.
+        self.programs.remove(shader);
+        Ok(())
+    }
 
-        material_id
+    fn register_shared_material(&mut self, material: Material) -> MaterialId {
+        self.shared_materials.insert(SharedMaterial {
+            material: material,
+            mesh_instances: Vec::new(),
+        })
     }
 
     fn get_material(&self, material_id: MaterialId) -> Option<&Material> {
-        self.shared_materials.get(&material_id)
+        self.shared_materials
+            .get(material_id)
+            .map(|shared_material| &shared_material.material)
     }
 
-    fn register_mesh(&mut self, mesh: &Mesh) -> GpuMesh {
-        // Configure vertex attributes.
-        let position = mesh.position();
+    fn remove_shared_material(&mut self, material_id: MaterialId) -> Result<Material, ResourceError> {
+        {
+            let shared_material = self.shared_materials
+                .get(material_id)
+                .ok_or(ResourceError::NotFound)?;
+            if let Some(&(mesh_instance_id, _)) = shared_material.mesh_instances.first() {
+                return Err(ResourceError::UsedByMeshInstance(mesh_instance_id));
+            }
+        }
 
-        let mesh_id = self.mesh_counter.next();
+        Ok(self.shared_materials.remove(material_id).unwrap().material)
+    }
 
-        let mut vertex_array = VertexArray::with_index_buffer(
-            &self.context,
-            mesh.vertex_data(),
-            mesh.indices(),
-        );
-        vertex_array.set_attrib(AttributeLocation::from_index(0), position.into());
+    fn register_mesh(&mut self, mesh: &Mesh) -> GpuMesh {
+        let attributes = self.mesh_attributes(mesh);
+        let vertex_array = self.build_vertex_array(mesh, &attributes, BufferUsage::StaticDraw);
+        self.meshes.insert(MeshData::new(vertex_array, attributes, GlRender::named_attributes(mesh), mesh))
+    }
 
-        if let Some(normal) = mesh.normal() {
-            vertex_array.set_attrib(AttributeLocation::from_index(1), normal.into());
+    fn update_mesh(&mut self, mesh_id: GpuMesh, mesh: &Mesh) -> Result<(), ResourceError> {
+        let old_attributes = match self.meshes.get(mesh_id) {
+            Some(mesh_data) if mesh_data.vertex_array.usage() == BufferUsage::DynamicDraw => {
+                Some(mesh_data.attributes.clone())
+            }
+            Some(_) => None,
+            None => return Err(ResourceError::NotFound),
+        };
+
+        let attributes = self.mesh_attributes(mesh);
+        if old_attributes.as_ref() == Some(&attributes) {
+            // The attribute bindings are unchanged, so the existing buffers can be refilled.
+            let vertex_array = &mut self.meshes.get_mut(mesh_id).unwrap().vertex_array;
+            vertex_array.set_vertex_data(mesh.vertex_data());
+            match *mesh.indices() {
+                MeshIndices::U16(ref indices) => vertex_array.set_index_data(&**indices),
+                MeshIndices::U32(ref indices) => vertex_array.set_index_data(&**indices),
+            }
+        } else {
+            // A mesh that's been updated once will likely be updated again, so it's moved into
+            // dynamic buffers rather than staying in the static ones it was registered with.
+            let vertex_array = self.build_vertex_array(mesh, &attributes, BufferUsage::DynamicDraw);
+            self.meshes.get_mut(mesh_id).unwrap().vertex_array = vertex_array;
         }
 
-        // TODO: Support multiple texcoords.
-        if let Some(texcoord) = mesh.texcoord().first().cloned() {
-            vertex_array.set_attrib(AttributeLocation::from_index(2), texcoord.into());
+        self.meshes.get_mut(mesh_id).unwrap().update(attributes, GlRender::named_attributes(mesh), mesh);
+        Ok(())
+    }
+
+    fn update_mesh_vertices(&mut self, mesh_id: GpuMesh, offset: usize, vertex_data: &[f32]) -> Result<(), ResourceError> {
+        let mesh_data = self.meshes.get_mut(mesh_id).ok_or(ResourceError::NotFound)?;
+        mesh_data.vertex_array.update_vertex_data(offset, vertex_data);
+        Ok(())
+    }
+
+    fn remove_mesh(&mut self, mesh_id: GpuMesh) -> Result<(), ResourceError> {
+        if !self.meshes.contains_key(mesh_id) {
+            return Err(ResourceError::NotFound);
         }
 
-        self.meshes.insert(
-            mesh_id,
-            MeshData {
-                vertex_array: vertex_array,
-                position_attribute: mesh.position(),
-                normal_attribute: mesh.normal(),
-                uv_attribute: None,
-                element_count: mesh.indices().len(),
-            });
+        for (mesh_instance_id, mesh_instance) in self.mesh_instances.iter() {
+            let uses_mesh = *mesh_instance.mesh() == mesh_id
+                || mesh_instance.lods().iter().any(|lod| lod.mesh == mesh_id);
+            if uses_mesh {
+                return Err(ResourceError::UsedByMeshInstance(mesh_instance_id));
+            }
+        }
 
-        mesh_id
+        // Dropping the vertex array deletes its buffers from the GPU.
+        self.meshes.remove(mesh_id);
+        Ok(())
     }
 
     fn register_texture(&mut self, texture: &Texture2d) -> GpuTexture {
@@ -624,43 +1145,60 @@ impl Renderer for GlRender {
         let gl_texture = texture_result.expect("Unable to send texture to GPU");
 
         // Register the mesh internally.
-        let texture_id = self.texture_counter.next();
+        self.textures.insert(gl_texture)
+    }
 
-        let old = self.textures.insert(texture_id, gl_texture);
-        assert!(old.is_none());
+    fn remove_texture(&mut self, texture_id: GpuTexture) -> Result<(), ResourceError> {
+        if !self.textures.contains_key(texture_id) {
+            return Err(ResourceError::NotFound);
+        }
+
+        self.check_material_users(|material| {
+            material.properties().any(|(_, property)| match *property {
+                MaterialProperty::Texture(texture) => texture == texture_id,
+                _ => false,
+            })
+        })?;
 
-        texture_id
+        self.textures.remove(texture_id);
+        Ok(())
     }
 
     fn register_mesh_instance(&mut self, mesh_instance: MeshInstance) -> MeshInstanceId {
-        let mesh_instance_id = self.mesh_instance_counter.next();
-
-        // Add the mesh instance to the right bucket based on its material type.
-        match mesh_instance.material_type() {
-            &MaterialType::Shared(id) => self.mesh_instances_with_shared_materials.get_mut(&id).unwrap().push(mesh_instance_id),
-            &MaterialType::Owned(_) => self.mesh_instances_with_owned_material.push(mesh_instance_id),
+        let mesh_instance_id = self.mesh_instances.insert(mesh_instance);
+        let mesh_instance = self.mesh_instances.get(mesh_instance_id).unwrap();
+
+        // Add each of the mesh instance's materials to the right bucket based on its type. Shared
+        // materials that have been removed have no bucket, and are never drawn.
+        for (index, material) in mesh_instance.material_types().iter().enumerate() {
+            match *material {
+                MaterialType::Shared(id) => {
+                    if let Some(shared_material) = self.shared_materials.get_mut(id) {
+                        shared_material.mesh_instances.push((mesh_instance_id, index));
+                    }
+                }
+                MaterialType::Owned(_) => self.mesh_instances_with_owned_material.push((mesh_instance_id, index)),
+            }
         }
 
-        let old = self.mesh_instances.insert(mesh_instance_id, mesh_instance);
-        assert!(old.is_none());
-
         mesh_instance_id
     }
 
     fn remove_mesh_instance(&mut self, id: MeshInstanceId) -> Option<MeshInstance> {
-        if let Some(mesh_instance) = self.mesh_instances.remove(&id) {
-            // Remove the mesh instance from the bucket based on its material type.
-            match mesh_instance.material_type() {
-                &MaterialType::Shared(material_id) => {
-                    self.mesh_instances_with_shared_materials
-                        .get_mut(&material_id)
-                        .unwrap()
-                        .retain(|&instance_id| instance_id != id);
-                }
-
-                &MaterialType::Owned(_) => {
-                    self.mesh_instances_with_owned_material
-                        .retain(|&instance_id| instance_id != id);
+        if let Some(mesh_instance) = self.mesh_instances.remove(id) {
+            // Remove the mesh instance from the buckets of each of its materials.
+            for material in mesh_instance.material_types() {
+                match *material {
+                    MaterialType::Shared(material_id) => {
+                        if let Some(shared_material) = self.shared_materials.get_mut(material_id) {
+                            shared_material.mesh_instances.retain(|&(instance_id, _)| instance_id != id);
+                        }
+                    }
+
+                    MaterialType::Owned(_) => {
+                        self.mesh_instances_with_owned_material
+                            .retain(|&(instance_id, _)| instance_id != id);
+                    }
                 }
             }
 
@@ -671,79 +1209,68 @@ impl Renderer for GlRender {
     }
 
     fn get_mesh_instance(&self, id: MeshInstanceId) -> Option<&MeshInstance> {
-        self.mesh_instances.get(&id)
+        self.mesh_instances.get(id)
     }
 
     fn get_mesh_instance_mut(&mut self, id: MeshInstanceId) -> Option<&mut MeshInstance> {
-        self.mesh_instances.get_mut(&id)
+        self.mesh_instances.get_mut(id)
     }
 
     fn register_anchor(&mut self, anchor: Anchor) -> AnchorId {
-        let anchor_id = self.anchor_counter.next();
-
-        let old = self.anchors.insert(anchor_id, anchor);
-        assert!(old.is_none());
-
-        anchor_id
+        self.anchors.insert(anchor)
     }
 
     fn remove_anchor(&mut self, id: AnchorId) -> Option<Anchor> {
-        self.anchors.remove(&id)
+        self.anchors.remove(id)
     }
 
     fn get_anchor(&self, anchor_id: AnchorId) -> Option<&Anchor> {
-        self.anchors.get(&anchor_id)
+        self.anchors.get(anchor_id)
     }
 
     fn get_anchor_mut(&mut self, anchor_id: AnchorId) -> Option<&mut Anchor> {
-        self.anchors.get_mut(&anchor_id)
+        self.anchors.get_mut(anchor_id)
     }
 
     fn register_camera(&mut self, camera: Camera) -> CameraId {
-        let camera_id = self.camera_counter.next();
-
-        let old = self.cameras.insert(camera_id, camera);
-        assert!(old.is_none());
-
-        camera_id
+        self.cameras.insert(camera)
     }
 
     fn remove_camera(&mut self, id: CameraId) -> Option<Camera> {
-        self.cameras.remove(&id)
+        self.cameras.remove(id)
     }
 
     fn get_camera(&self, camera_id: CameraId) -> Option<&Camera> {
-        self.cameras.get(&camera_id)
+        self.cameras.get(camera_id)
     }
 
     fn get_camera_mut(&mut self, camera_id: CameraId) -> Option<&mut Camera> {
-        self.cameras.get_mut(&camera_id)
+        self.cameras.get_mut(camera_id)
     }
 
     fn register_light(&mut self, light: Light) -> LightId {
-        let light_id = self.light_counter.next();
-
-        let old = self.lights.insert(light_id, light);
-        assert!(old.is_none());
-
-        light_id
+        self.lights.insert(light)
     }
 
     fn remove_light(&mut self, id: LightId) -> Option<Light> {
-        self.lights.remove(&id)
+        self.lights.remove(id)
     }
 
     fn get_light(&self, light_id: LightId) -> Option<&Light> {
-        self.lights.get(&light_id)
+        self.lights.get(light_id)
     }
 
     fn get_light_mut(&mut self, light_id: LightId) -> Option<&mut Light> {
-        self.lights.get_mut(&light_id)
+        self.lights.get_mut(light_id)
     }
 
     fn set_ambient_light(&mut self, color: Color) {
         self.ambient_color = color;
     }
+
+    fn cull_stats(&self) -> CullStats {
+        self.cull_stats
+    }
 }
 
 unsafe impl Send for GlRender {}
@@ -759,13 +1286,146 @@ impl From<ContextError> for Error {
     }
 }
 
+/// The camera data used for every draw in a frame.
+#[derive(Debug, Clone)]
+struct FrameData {
+    view_transform: Matrix4,
+    projection_transform: Matrix4,
+    camera_position: Point,
+
+    /// The camera's view frustum in world space, used for culling.
+    frustum: Frustum,
+
+    /// The camera the frame is drawn with, used to pick mesh instances' levels of detail.
+    camera: Camera,
+}
+
+impl FrameData {
+    fn new(camera: &Camera, camera_anchor: &Anchor) -> FrameData {
+        let view_transform = camera_anchor.view_matrix();
+        let projection_transform = camera.projection_matrix();
+
+        FrameData {
+            view_transform: view_transform,
+            projection_transform: projection_transform,
+            camera_position: camera_anchor.position(),
+            frustum: Frustum::from_matrix(projection_transform * view_transform),
+            camera: camera.clone(),
+        }
+    }
+}
+
+/// Scratch data and statistics accumulated while drawing a frame.
+#[derive(Debug)]
+struct DrawState {
+    /// Scratch buffer used to build the per-instance data for each batch.
+    instance_data: Vec<f32>,
+
+    cull_stats: CullStats,
+
+    /// Whether the uniforms for the current material have been set, so that batches drawn with
+    /// the same material can skip setting them again.
+    has_setup_material: bool,
+}
+
+/// A compiled shader program along with the layout of its per-instance data.
+#[derive(Debug)]
+struct ProgramData {
+    program: Program,
+
+    /// The locations of the optional and custom vertex attributes read by the program, keyed by
+    /// the attribute's name.
+    vertex_attribs: Vec<(String, AttributeLocation)>,
+
+    instance_attribs: Vec<InstanceAttrib>,
+
+    /// The number of floats of instance data used for each instance.
+    instance_stride: usize,
+}
+
+/// Describes where a material's instance property is stored in the instance data.
+#[derive(Debug)]
+struct InstanceAttrib {
+    name: String,
+    property_type: PropertyType,
+    location: Option<AttributeLocation>,
+
+    /// The offset in floats from the start of the instance's data.
+    offset: usize,
+}
+
+/// Gets the number of floats used to store an instance property of the specified type.
+fn instance_property_len(property_type: PropertyType) -> usize {
+    match property_type {
+        PropertyType::Color => 4,
+        PropertyType::f32 => 1,
+        PropertyType::Vector3 => 3,
+        PropertyType::Texture2d => panic!("Textures cannot be used as instance properties"),
+    }
+}
+
 #[derive(Debug)]
 struct MeshData {
     vertex_array: VertexArray,
-    position_attribute: VertexAttribute,
-    normal_attribute: Option<VertexAttribute>,
-    uv_attribute: Option<VertexAttribute>,
-    element_count: usize,
+    attributes: Vec<(u32, VertexAttribute)>,
+
+    /// The optional and custom attributes, which are bound when drawing. See
+    /// `GlRender::named_attributes()`.
+    named_attributes: Vec<(String, VertexAttribute)>,
+
+    submeshes: Vec<Submesh>,
+    bounding_box: Aabb,
+    bounding_sphere: Sphere,
+}
+
+impl MeshData {
+    fn new(
+        vertex_array: VertexArray,
+        attributes: Vec<(u32, VertexAttribute)>,
+        named_attributes: Vec<(String, VertexAttribute)>,
+        mesh: &Mesh,
+    ) -> MeshData {
+        MeshData {
+            vertex_array: vertex_array,
+            attributes: attributes,
+            named_attributes: named_attributes,
+            submeshes: mesh.submeshes().to_vec(),
+            bounding_box: mesh.bounding_box(),
+            bounding_sphere: mesh.bounding_sphere(),
+        }
+    }
+
+    /// Replaces everything but the vertex array with the data for `mesh`.
+    fn update(
+        &mut self,
+        attributes: Vec<(u32, VertexAttribute)>,
+        named_attributes: Vec<(String, VertexAttribute)>,
+        mesh: &Mesh,
+    ) {
+        self.attributes = attributes;
+        self.named_attributes = named_attributes;
+        self.submeshes = mesh.submeshes().to_vec();
+        self.bounding_box = mesh.bounding_box();
+        self.bounding_sphere = mesh.bounding_sphere();
+    }
+
+    /// Gets an optional or custom attribute by its name in the vertex program.
+    fn named_attribute(&self, name: &str) -> Option<VertexAttribute> {
+        self.named_attributes
+            .iter()
+            .find(|attribute| attribute.0 == name)
+            .map(|attribute| attribute.1)
+    }
+}
+
+/// A shared material along with the mesh instances using it.
+#[derive(Debug)]
+struct SharedMaterial {
+    material: Material,
+
+    /// The mesh instances using the material, along with the index of the material in each mesh
+    /// instance's materials.
+    mesh_instances: Vec<(MeshInstanceId, usize)>,
 }
 
 impl Into<AttribLayout> for VertexAttribute {
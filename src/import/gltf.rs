@@ -0,0 +1,712 @@
+//! Importing scenes from glTF 2.0 files.
+//!
+//! `load()` imports a `.gltf` or `.glb` file, along with any external buffers and images it
+//! references, into a `Scene`. The scene holds everything polygon can represent:
+//!
+//! - Each mesh primitive becomes a `Mesh` with positions, normals, tangents, vertex colors, and
+//!   texture coordinates. Primitives without normals get flat normals, and primitives without
+//!   tangents get generated tangents if their material uses a normal map.
+//! - Each image becomes a `Texture2d`.
+//! - Materials keep their metallic-roughness parameters, see `PbrMaterial`.
+//! - Nodes keep their hierarchy, with both their local and world transforms.
+//! - Perspective cameras become `Camera`s. Orthographic cameras aren't supported and are skipped.
+//! - Lights from the `KHR_lights_punctual` extension become `Light`s. Spot lights are imported as
+//!   point lights, and intensities are used directly as light strengths.
+//!
+//! External resources are only loaded from relative paths and base64 `data:` URIs, nothing is
+//! fetched over the network. Once imported, `Scene::register()` sends the whole scene to a
+//! renderer.
+
+use {GpuMesh, Renderer};
+use anchor::{Anchor, AnchorId};
+use base64;
+use camera::{Camera, CameraId};
+use geometry::mesh::*;
+use gltf;
+use image;
+use light::{Light, LightData, LightId};
+use material::Material;
+use math::*;
+use mesh_instance::{MeshInstance, MeshInstanceId};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Error as IoError;
+use std::io::prelude::*;
+use std::path::Path;
+use texture::{GpuTexture, Texture2d};
+
+/// Imports a `.gltf` or `.glb` file.
+///
+/// External buffers and images are loaded relative to the directory containing the file.
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Scene, Error> {
+    let path = path.as_ref();
+    let data = read_file(path)?;
+    from_slice(&data, path.parent().unwrap_or_else(|| Path::new("")))
+}
+
+/// Imports glTF data that has already been loaded into memory.
+///
+/// `data` may be either glTF JSON or binary glTF. External buffers and images are loaded relative
+/// to `base_path`.
+pub fn from_slice(data: &[u8], base_path: &Path) -> Result<Scene, Error> {
+    let gltf::Gltf { document, mut blob } = gltf::Gltf::from_slice(data)?;
+
+    // Load the data for all of the buffers up front, since any primitive or image may refer to
+    // any buffer.
+    let mut buffers = Vec::new();
+    for buffer in document.buffers() {
+        let data = match buffer.source() {
+            gltf::buffer::Source::Bin => blob.take().ok_or(Error::MissingBuffer(buffer.index()))?,
+            gltf::buffer::Source::Uri(uri) => read_uri(uri, base_path)?,
+        };
+
+        if data.len() < buffer.length() {
+            return Err(Error::MissingBuffer(buffer.index()));
+        }
+
+        buffers.push(data);
+    }
+
+    let mut textures = Vec::new();
+    for image in document.images() {
+        let data = match image.source() {
+            gltf::image::Source::View { view, .. } => {
+                let buffer = &buffers[view.buffer().index()];
+                buffer[view.offset() .. view.offset() + view.length()].to_vec()
+            }
+            gltf::image::Source::Uri { uri, .. } => read_uri(uri, base_path)?,
+        };
+
+        let image = image::load_from_memory(&data).map_err(|_| Error::BadImage(image.index()))?;
+        textures.push(Texture2d::from_image(image));
+    }
+
+    let materials = document.materials().map(PbrMaterial::from_gltf).collect::<Vec<_>>();
+
+    let mut meshes = Vec::new();
+    for mesh in document.meshes() {
+        let mut primitives = Vec::new();
+        for primitive in mesh.primitives() {
+            let material = primitive.material().index();
+            let normal_mapped = material
+                .map(|index| materials[index].normal_texture.is_some())
+                .unwrap_or(false);
+
+            // Primitives drawn as points or lines have no faces, so there's nothing to import.
+            if let Some(mesh) = build_primitive(&mesh, &primitive, &buffers, normal_mapped)? {
+                primitives.push(Primitive {
+                    mesh: mesh,
+                    material: material,
+                });
+            }
+        }
+
+        meshes.push(SceneMesh {
+            name: mesh.name().map(Into::into),
+            primitives: primitives,
+        });
+    }
+
+    // Orthographic cameras are skipped, so nodes refer to cameras through this mapping.
+    let mut cameras = Vec::new();
+    let mut camera_indices = HashMap::new();
+    for camera in document.cameras() {
+        if let gltf::camera::Projection::Perspective(perspective) = camera.projection() {
+            let mut imported = Camera::default();
+            imported.set_fov(perspective.yfov());
+            if let Some(aspect) = perspective.aspect_ratio() {
+                imported.set_aspect(aspect);
+            }
+            if let Some(far) = perspective.zfar() {
+                imported.set_far(far);
+            }
+            imported.set_near(perspective.znear());
+
+            camera_indices.insert(camera.index(), cameras.len());
+            cameras.push(imported);
+        }
+    }
+
+    let mut lights = Vec::new();
+    for light in document.lights().into_iter().flatten() {
+        let [r, g, b] = light.color();
+        let color = Color::rgb(r, g, b);
+        lights.push(match light.kind() {
+            gltf::khr_lights_punctual::Kind::Directional => {
+                Light::directional(Vector3::FORWARD, light.intensity(), color)
+            }
+
+            // Lights without a range have infinite range, which the renderer can't represent.
+            _ => Light::point(light.range().unwrap_or(DEFAULT_LIGHT_RANGE), light.intensity(), color),
+        });
+    }
+
+    let mut nodes = document
+        .nodes()
+        .map(|node| {
+            let (translation, rotation, scale) = node.transform().decomposed();
+            let transform = Transform::from_parts(
+                Point::new(translation[0], translation[1], translation[2]),
+                Orientation(Quaternion::new(
+                    Vector3::new(rotation[0], rotation[1], rotation[2]),
+                    rotation[3],
+                )),
+                scale.into(),
+            );
+
+            Node {
+                name: node.name().map(Into::into),
+                parent: None,
+                children: node.children().map(|child| child.index()).collect(),
+                transform: transform,
+                world_transform: transform,
+                mesh: node.mesh().map(|mesh| mesh.index()),
+                camera: node.camera().and_then(|camera| camera_indices.get(&camera.index()).cloned()),
+                light: node.light().map(|light| light.index()),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    for index in 0..nodes.len() {
+        for child in nodes[index].children.clone() {
+            nodes[child].parent = Some(index);
+        }
+    }
+
+    // Compose the world transforms from the top of the hierarchy down.
+    let mut roots = nodes
+        .iter()
+        .enumerate()
+        .filter(|&(_, node)| node.parent.is_none())
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    let mut pending = roots.clone();
+    while let Some(index) = pending.pop() {
+        let world_transform = nodes[index].world_transform;
+        for child in nodes[index].children.clone() {
+            nodes[child].world_transform = world_transform * nodes[child].transform;
+            pending.push(child);
+        }
+    }
+
+    if let Some(scene) = document.default_scene().or_else(|| document.scenes().next()) {
+        roots = scene.nodes().map(|node| node.index()).collect();
+    }
+
+    Ok(Scene {
+        meshes: meshes,
+        textures: textures,
+        materials: materials,
+        nodes: nodes,
+        roots: roots,
+        cameras: cameras,
+        lights: lights,
+    })
+}
+
+/// The range given to point lights that don't specify one.
+pub const DEFAULT_LIGHT_RANGE: f32 = 100.0;
+
+/// The contents of a glTF file.
+///
+/// Nodes, meshes, materials, and lights are indexed the same as in the glTF file. Cameras are
+/// indexed in the order they appear in the file with orthographic cameras skipped.
+#[derive(Debug)]
+pub struct Scene {
+    pub meshes: Vec<SceneMesh>,
+
+    /// The textures for each image in the file.
+    pub textures: Vec<Texture2d>,
+
+    pub materials: Vec<PbrMaterial>,
+    pub nodes: Vec<Node>,
+
+    /// The nodes at the root of the scene.
+    ///
+    /// These are the root nodes of the file's default scene, or of its first scene if it doesn't
+    /// specify a default. Files without any scenes use every node without a parent.
+    pub roots: Vec<usize>,
+
+    pub cameras: Vec<Camera>,
+
+    /// The lights in the scene.
+    ///
+    /// Directional lights point down the negative z axis, which is the direction they point in
+    /// the space of the nodes that use them.
+    pub lights: Vec<Light>,
+}
+
+impl Scene {
+    /// Registers the scene with a renderer.
+    ///
+    /// All textures and meshes are registered, and every node reachable from the scene's roots
+    /// gets an anchor with its world transform. Mesh instances, cameras, and lights are created
+    /// for each node that uses them.
+    ///
+    /// Each mesh instance gets its own copy of `material`, with the parameters of the
+    /// primitive's `PbrMaterial` applied to it.
+    pub fn register<R: Renderer>(&self, renderer: &mut R, material: &Material) -> RegisteredScene {
+        let textures = self.textures
+            .iter()
+            .map(|texture| renderer.register_texture(texture))
+            .collect::<Vec<_>>();
+
+        let meshes = self.meshes
+            .iter()
+            .map(|mesh| {
+                mesh.primitives
+                    .iter()
+                    .map(|primitive| renderer.register_mesh(&primitive.mesh))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut registered = RegisteredScene {
+            textures: textures,
+            meshes: meshes,
+            anchors: HashMap::new(),
+            mesh_instances: Vec::new(),
+            cameras: Vec::new(),
+            lights: Vec::new(),
+        };
+
+        let mut pending = self.roots.clone();
+        while let Some(index) = pending.pop() {
+            let node = &self.nodes[index];
+            pending.extend(&node.children);
+
+            let anchor_id = renderer.register_anchor(Anchor::from_transform(node.world_transform));
+            registered.anchors.insert(index, anchor_id);
+
+            if let Some(mesh) = node.mesh {
+                for (primitive, &gpu_mesh) in self.meshes[mesh].primitives.iter().zip(&registered.meshes[mesh]) {
+                    let mut instance_material = material.clone();
+                    if let Some(pbr_material) = primitive.material {
+                        self.materials[pbr_material].apply(&mut instance_material, &registered.textures);
+                    }
+
+                    let mut mesh_instance = MeshInstance::with_owned_material(gpu_mesh, instance_material);
+                    mesh_instance.set_anchor(anchor_id);
+                    registered.mesh_instances.push(renderer.register_mesh_instance(mesh_instance));
+                }
+            }
+
+            if let Some(camera) = node.camera {
+                let mut camera = self.cameras[camera].clone();
+                camera.set_anchor(anchor_id);
+                registered.cameras.push(renderer.register_camera(camera));
+            }
+
+            if let Some(light) = node.light {
+                let mut light = self.lights[light];
+                if let LightData::Directional { ref mut direction } = light.data {
+                    *direction = node.world_transform.orientation * *direction;
+                }
+                light.set_anchor(anchor_id);
+                registered.lights.push(renderer.register_light(light));
+            }
+        }
+
+        registered
+    }
+}
+
+/// A mesh from a glTF file.
+///
+/// glTF meshes are made up of primitives, each of which has its own vertex data and material.
+#[derive(Debug)]
+pub struct SceneMesh {
+    pub name: Option<String>,
+    pub primitives: Vec<Primitive>,
+}
+
+/// A single primitive of a glTF mesh.
+#[derive(Debug)]
+pub struct Primitive {
+    pub mesh: Mesh,
+
+    /// The index of the primitive's material, or `None` if it uses the default material.
+    pub material: Option<usize>,
+}
+
+/// A node in a glTF scene.
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub name: Option<String>,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+
+    /// The transform of the node relative to its parent.
+    pub transform: Transform,
+
+    /// The transform of the node relative to the scene.
+    ///
+    /// Shear introduced by non-uniform scale in the hierarchy is lost, see `Transform` for
+    /// details.
+    pub world_transform: Transform,
+
+    pub mesh: Option<usize>,
+    pub camera: Option<usize>,
+    pub light: Option<usize>,
+}
+
+/// The metallic-roughness material parameters of a glTF material.
+///
+/// Texture indices refer to `Scene::textures`. Texture coordinate sets and samplers aren't
+/// imported; all textures are assumed to use the first set of texture coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PbrMaterial {
+    pub name: Option<String>,
+
+    pub base_color: Color,
+    pub base_color_texture: Option<usize>,
+
+    pub metallic: f32,
+    pub roughness: f32,
+    pub metallic_roughness_texture: Option<usize>,
+
+    pub normal_texture: Option<usize>,
+    pub normal_scale: f32,
+
+    pub occlusion_texture: Option<usize>,
+
+    pub emissive: Color,
+    pub emissive_texture: Option<usize>,
+
+    pub double_sided: bool,
+}
+
+impl PbrMaterial {
+    fn from_gltf(material: gltf::Material) -> PbrMaterial {
+        let pbr = material.pbr_metallic_roughness();
+        let [r, g, b, a] = pbr.base_color_factor();
+        let [er, eg, eb] = material.emissive_factor();
+
+        PbrMaterial {
+            name: material.name().map(Into::into),
+
+            base_color: Color::new(r, g, b, a),
+            base_color_texture: pbr.base_color_texture().map(|info| info.texture().source().index()),
+
+            metallic: pbr.metallic_factor(),
+            roughness: pbr.roughness_factor(),
+            metallic_roughness_texture: pbr
+                .metallic_roughness_texture()
+                .map(|info| info.texture().source().index()),
+
+            normal_texture: material.normal_texture().map(|normal| normal.texture().source().index()),
+            normal_scale: material.normal_texture().map(|normal| normal.scale()).unwrap_or(1.0),
+
+            occlusion_texture: material
+                .occlusion_texture()
+                .map(|occlusion| occlusion.texture().source().index()),
+
+            emissive: Color::rgb(er, eg, eb),
+            emissive_texture: material.emissive_texture().map(|info| info.texture().source().index()),
+
+            double_sided: material.double_sided(),
+        }
+    }
+
+    /// Sets the properties of `material` from the glTF material parameters.
+    ///
+    /// The built-in materials use Blinn-Phong shading, so the parameters are converted to the
+    /// properties those materials use:
+    ///
+    /// - `surface_color` is the base color.
+    /// - `surface_specular` blends from a dielectric's 4% reflectance to the base color as the
+    ///   material becomes more metallic.
+    /// - `surface_shininess` is the Blinn-Phong exponent that best matches the roughness.
+    /// - `surface_diffuse` and `surface_normal` are the base color and normal textures, if the
+    ///   material has them.
+    ///
+    /// `textures` are the registered textures for `Scene::textures`, as returned by
+    /// `Scene::register()`.
+    pub fn apply(&self, material: &mut Material, textures: &[GpuTexture]) {
+        let dielectric = Color::rgb(0.04, 0.04, 0.04);
+        let specular = Color::rgb(
+            dielectric.r + (self.base_color.r - dielectric.r) * self.metallic,
+            dielectric.g + (self.base_color.g - dielectric.g) * self.metallic,
+            dielectric.b + (self.base_color.b - dielectric.b) * self.metallic,
+        );
+
+        // Convert the perceptual roughness to the exponent with a matching highlight width.
+        let alpha = self.roughness.max(0.05).powi(2);
+        let shininess = 2.0 / (alpha * alpha) - 2.0;
+
+        material.set_color("surface_color", self.base_color);
+        material.set_color("surface_specular", specular);
+        material.set_f32("surface_shininess", shininess.max(1.0));
+
+        if let Some(texture) = self.base_color_texture {
+            material.set_texture("surface_diffuse", textures[texture]);
+        }
+
+        if let Some(texture) = self.normal_texture {
+            material.set_texture("surface_normal", textures[texture]);
+        }
+    }
+}
+
+/// The renderer resources created by `Scene::register()`.
+#[derive(Debug, Clone)]
+pub struct RegisteredScene {
+    /// The registered textures, indexed the same as `Scene::textures`.
+    pub textures: Vec<GpuTexture>,
+
+    /// The registered meshes for each primitive, indexed the same as `Scene::meshes`.
+    pub meshes: Vec<Vec<GpuMesh>>,
+
+    /// The anchor created for each registered node, keyed by node index.
+    pub anchors: HashMap<usize, AnchorId>,
+
+    pub mesh_instances: Vec<MeshInstanceId>,
+    pub cameras: Vec<CameraId>,
+    pub lights: Vec<LightId>,
+}
+
+/// An error that can occur when importing a glTF file.
+#[derive(Debug)]
+pub enum Error {
+    IoError(IoError),
+    GltfError(gltf::Error),
+    BuildMeshError(BuildMeshError),
+
+    /// Indicates that a URI was neither a relative path nor a base64 `data:` URI.
+    UnsupportedUri(String),
+
+    /// Indicates that the data for the specified buffer was missing or too short.
+    MissingBuffer(usize),
+
+    /// Indicates that the specified image couldn't be decoded.
+    BadImage(usize),
+
+    /// Indicates that a primitive had no vertex positions.
+    MissingPositions {
+        mesh: usize,
+        primitive: usize,
+    },
+
+    /// Indicates that one of a primitive's indices was past the end of its vertex data.
+    IndexOutOfBounds {
+        mesh: usize,
+        primitive: usize,
+        index: u32,
+        vertex_count: usize,
+    },
+
+    /// Indicates that a triangle list primitive's index count wasn't a multiple of 3.
+    IncompleteTriangle {
+        mesh: usize,
+        primitive: usize,
+        index_count: usize,
+    },
+
+    /// Indicates that a primitive had a different number of values for one of its attributes
+    /// than it had vertex positions.
+    IncorrectAttributeCount {
+        mesh: usize,
+        primitive: usize,
+        attribute: VertexAttributeType,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl From<IoError> for Error {
+    fn from(error: IoError) -> Error {
+        Error::IoError(error)
+    }
+}
+
+impl From<gltf::Error> for Error {
+    fn from(error: gltf::Error) -> Error {
+        Error::GltfError(error)
+    }
+}
+
+impl From<BuildMeshError> for Error {
+    fn from(error: BuildMeshError) -> Error {
+        Error::BuildMeshError(error)
+    }
+}
+
+/// Builds the mesh for a primitive, returning `None` for primitives that aren't triangles.
+fn build_primitive(
+    mesh: &gltf::Mesh,
+    primitive: &gltf::Primitive,
+    buffers: &[Vec<u8>],
+    normal_mapped: bool,
+) -> Result<Option<Mesh>, Error> {
+    let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(|data| &**data));
+
+    let positions = reader
+        .read_positions()
+        .ok_or(Error::MissingPositions {
+            mesh: mesh.index(),
+            primitive: primitive.index(),
+        })?
+        .map(|[x, y, z]| Point::new(x, y, z))
+        .collect::<Vec<_>>();
+
+    let indices = match reader.read_indices() {
+        Some(indices) => indices.into_u32().collect::<Vec<_>>(),
+        None => (0..positions.len() as u32).collect(),
+    };
+
+    // The mesh builder checks the mesh data as well, but these checks need to happen here so that
+    // the errors can say which primitive was at fault.
+    if let Some(&index) = indices.iter().find(|&&index| index as usize >= positions.len()) {
+        return Err(Error::IndexOutOfBounds {
+            mesh: mesh.index(),
+            primitive: primitive.index(),
+            index: index,
+            vertex_count: positions.len(),
+        });
+    }
+
+    // Convert strips and fans to lists, keeping the winding of every triangle consistent. Every
+    // index of a strip or fan past the first two adds a triangle, so there's nothing left over.
+    let indices = match primitive.mode() {
+        gltf::mesh::Mode::Triangles => {
+            if indices.len() % 3 != 0 {
+                return Err(Error::IncompleteTriangle {
+                    mesh: mesh.index(),
+                    primitive: primitive.index(),
+                    index_count: indices.len(),
+                });
+            }
+            indices
+        }
+
+        gltf::mesh::Mode::TriangleStrip => {
+            let mut triangles = Vec::new();
+            for (index, strip) in indices.windows(3).enumerate() {
+                if index % 2 == 0 {
+                    triangles.extend(&[strip[0], strip[1], strip[2]]);
+                } else {
+                    triangles.extend(&[strip[1], strip[0], strip[2]]);
+                }
+            }
+            triangles
+        }
+
+        gltf::mesh::Mode::TriangleFan => {
+            let mut triangles = Vec::new();
+            for index in 2..indices.len() {
+                triangles.extend(&[indices[0], indices[index - 1], indices[index]]);
+            }
+            triangles
+        }
+
+        _ => return Ok(None),
+    };
+
+    let check_count = |attribute, actual| if actual == positions.len() {
+        Ok(())
+    } else {
+        Err(Error::IncorrectAttributeCount {
+            mesh: mesh.index(),
+            primitive: primitive.index(),
+            attribute: attribute,
+            expected: positions.len(),
+            actual: actual,
+        })
+    };
+
+    let mut builder = MeshBuilder::new()
+        .set_position_data(&positions)
+        .set_indices(&indices);
+
+    if let Some(colors) = reader.read_colors(0) {
+        let colors = colors
+            .into_rgba_f32()
+            .map(|[r, g, b, a]| Color::new(r, g, b, a))
+            .collect::<Vec<_>>();
+        check_count(VertexAttributeType::Color, colors.len())?;
+        builder = builder.set_color_data(&colors);
+    }
+
+    for set in 0..MAX_TEXCOORDS {
+        let texcoords = match reader.read_tex_coords(set as u32) {
+            Some(texcoords) => texcoords.into_f32().map(Vector2::from).collect::<Vec<_>>(),
+            None => break,
+        };
+        check_count(VertexAttributeType::Texcoord, texcoords.len())?;
+        builder = builder.set_texcoord_data_at(set, &texcoords);
+    }
+
+    // The glTF spec says that primitives without normals should be flat shaded.
+    let has_normals = match reader.read_normals() {
+        Some(normals) => {
+            let normals = normals.map(Vector3::from).collect::<Vec<_>>();
+            check_count(VertexAttributeType::Normal, normals.len())?;
+            builder = builder.set_normal_data(&normals);
+            true
+        }
+        None => {
+            builder = builder.compute_flat_normals();
+            false
+        }
+    };
+
+    match reader.read_tangents() {
+        // Generating flat normals may add vertices, so tangents from the file only line up with
+        // the vertices if the normals came from the file as well.
+        Some(tangents) if has_normals => {
+            let tangents = tangents.map(Vector4::from).collect::<Vec<_>>();
+            check_count(VertexAttributeType::Tangent, tangents.len())?;
+            builder = builder.set_tangent_data(&tangents);
+        }
+
+        _ => if normal_mapped && reader.read_tex_coords(0).is_some() {
+            builder = builder.compute_tangents();
+        },
+    }
+
+    Ok(Some(builder.build()?))
+}
+
+/// Reads the data for a buffer or image URI.
+fn read_uri(uri: &str, base_path: &Path) -> Result<Vec<u8>, Error> {
+    if uri.starts_with("data:") {
+        let data = uri
+            .find(";base64,")
+            .map(|start| &uri[start + ";base64,".len() ..])
+            .ok_or_else(|| Error::UnsupportedUri(uri.into()))?;
+        return base64::decode(data).map_err(|_| Error::UnsupportedUri(uri.into()));
+    }
+
+    // Anything with a scheme would have to be fetched from somewhere, which isn't supported.
+    if uri.contains("://") {
+        return Err(Error::UnsupportedUri(uri.into()));
+    }
+
+    let path = decode_percent(uri).ok_or_else(|| Error::UnsupportedUri(uri.into()))?;
+    Ok(read_file(&base_path.join(path))?)
+}
+
+/// Decodes the percent-encoded characters in a relative URI.
+fn decode_percent(uri: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(uri.len());
+    let mut remaining = uri.as_bytes();
+    while let Some((&byte, rest)) = remaining.split_first() {
+        if byte == b'%' {
+            let hex = rest.get(..2).and_then(|hex| ::std::str::from_utf8(hex).ok())?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            remaining = &rest[2..];
+        } else {
+            bytes.push(byte);
+            remaining = rest;
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, IoError> {
+    let mut file = File::open(path)?;
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents)?;
+    Ok(contents)
+}
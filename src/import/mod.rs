@@ -0,0 +1,6 @@
+//! Importers for scene interchange formats.
+//!
+//! Where the loaders in `geometry` produce mesh data, importers bring in whole scenes: meshes,
+//! textures, materials, node hierarchies, cameras, and lights.
+
+pub mod gltf;
@@ -1,5 +1,7 @@
 extern crate polygon_math;
+extern crate base64;
 extern crate bmp;
+extern crate gltf;
 extern crate image;
 extern crate polygon_material;
 
@@ -12,10 +14,12 @@ pub mod anchor;
 pub mod camera;
 pub mod geometry;
 pub mod gl;
+pub mod import;
 pub mod light;
 pub mod material;
 pub mod mesh_instance;
 pub mod shader;
+mod slot_map;
 pub mod texture;
 
 pub mod math {
@@ -29,12 +33,16 @@ use light::*;
 use material::*;
 use math::Color;
 use mesh_instance::*;
+use shader::Shader;
 use texture::*;
 
 /// Identifies mesh data that has been sent to the GPU.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
-pub struct GpuMesh(usize);
-derive_Counter!(GpuMesh);
+pub struct GpuMesh {
+    index: u32,
+    generation: u32,
+}
+derive_Handle!(GpuMesh);
 
 /// The common interface that all rendering systems must provide.
 pub trait Renderer: 'static + Send {
@@ -47,19 +55,71 @@ pub trait Renderer: 'static + Send {
     /// Parses a material source file and generates a material from it.
     fn build_material(&mut self, source: MaterialSource) -> Result<Material, BuildMaterialError>;
 
+    /// Removes a shader built by `build_material()`, deleting its program from the GPU.
+    ///
+    /// Fails if the default material, a shared material, or an owned material of a registered
+    /// mesh instance uses the shader. Materials that aren't registered with the renderer aren't
+    /// checked, so they must not be registered or used after their shader is removed.
+    fn remove_shader(&mut self, shader: Shader) -> Result<(), ResourceError>;
+
     /// Registers a material to be used as a shared material.
     fn register_shared_material(&mut self, material: Material) -> MaterialId;
 
     /// Gets a registered material.
     fn get_material(&self, material_id: MaterialId) -> Option<&Material>;
 
+    /// Removes a shared material from the renderer, returning it.
+    ///
+    /// Fails if any registered mesh instance uses the material.
+    fn remove_shared_material(&mut self, material_id: MaterialId) -> Result<Material, ResourceError>;
+
     /// Registers mesh data with the renderer, returning a unique id for the mesh.
     fn register_mesh(&mut self, mesh: &Mesh) -> GpuMesh;
 
+    /// Replaces the data of a registered mesh, every mesh instance using the mesh draws the new
+    /// data from then on.
+    ///
+    /// The new data doesn't need to match the old data in size or layout. Meshes that have been
+    /// updated are kept in dynamic buffers, so updating the same mesh repeatedly (e.g. for
+    /// deformable terrain or procedural effects) is cheaper than registering a new mesh each time.
+    ///
+    /// Fails if `mesh_id` doesn't identify a registered mesh.
+    fn update_mesh(&mut self, mesh_id: GpuMesh, mesh: &Mesh) -> Result<(), ResourceError>;
+
+    /// Overwrites part of a registered mesh's vertex data, leaving the rest unchanged.
+    ///
+    /// `offset` and `vertex_data` are in the same layout as `Mesh::vertex_data()` for the mesh
+    /// that was last registered or updated, so `offset` is in elements rather than vertices. The
+    /// mesh's bounds aren't recalculated, so use `update_mesh()` if vertices may move outside of
+    /// them, otherwise instances may be culled while still visible.
+    ///
+    /// Fails if `mesh_id` doesn't identify a registered mesh.
+    ///
+    /// # Panics
+    ///
+    /// - If the updated range extends past the end of the mesh's vertex data.
+    fn update_mesh_vertices(&mut self, mesh_id: GpuMesh, offset: usize, vertex_data: &[f32]) -> Result<(), ResourceError>;
+
+    /// Removes mesh data from the renderer, deleting it from the GPU.
+    ///
+    /// Fails if any registered mesh instance uses the mesh, either as its mesh or as one of its
+    /// LODs.
+    fn remove_mesh(&mut self, mesh_id: GpuMesh) -> Result<(), ResourceError>;
+
     /// Registers texture data with the renderer, returning a unique id for the texture.
     fn register_texture(&mut self, texture: &Texture2d) -> GpuTexture;
 
+    /// Removes texture data from the renderer, deleting it from the GPU.
+    ///
+    /// Fails if the default material, a shared material, or an owned material of a registered
+    /// mesh instance uses the texture.
+    fn remove_texture(&mut self, texture_id: GpuTexture) -> Result<(), ResourceError>;
+
     /// Registers a mesh instance with the renderer, returning a unique id for that mesh instance.
+    ///
+    /// The mesh instance may refer to resources that have already been removed, e.g. an anchor
+    /// removed before the mesh instance was registered. It's still registered, but the parts of
+    /// it that use removed resources aren't drawn.
     fn register_mesh_instance(&mut self, mesh_instance: MeshInstance) -> MeshInstanceId;
 
     /// Removes a mesh instance from the renderer, returning it.
@@ -108,25 +168,74 @@ pub trait Renderer: 'static + Send {
     fn get_light_mut(&mut self, light_id: LightId) -> Option<&mut Light>;
 
     fn set_ambient_light(&mut self, color: Color);
+
+    /// Gets the culling statistics for the most recently drawn frame.
+    fn cull_stats(&self) -> CullStats;
 }
 
-/// Extra special secret trait for keep counter functionality local to this crate.
+/// Extra special secret trait for keep handle functionality local to this crate.
+///
+/// All resources managed by a renderer have an associated handle type used to reference the data
+/// owned by the renderer. Handles are generational: A handle identifies a slot in the renderer's
+/// storage along with the generation of the slot, which changes each time the slot's resource is
+/// removed. A handle that outlives its resource no longer matches its slot's generation, so
+/// looking it up fails rather than finding whatever resource has reused the slot. Slot
+/// generations start at 1, so `Default` handles never identify a registered resource.
 ///
-/// All resources managed by a renderer have an associated ID type used to reference the data
-/// owned by the renderer. The renderers internally keep a counter to generate new ID values, but
-/// the functionality for creating new ID values needs to be kept private to polygon. In order to
-/// avoid having to define all ID types at the root of the crate (which would give the renderers
-/// access to the private methods to create new ID values) we define the private `Counter` trait
-/// and implement it for all ID types using the `derive_Counter!` macro. This allows us to define
-/// various ID types in the most appropriate module while still giving all renderers the ability
-/// to create new ID values.
-trait Counter {
-    /// Creates a new counter with the initial value.
-    fn initial() -> Self;
-
-    /// Returns the next valid ID value, updating the internal counter in the process.
-    fn next(&mut self) -> Self;
+/// The functionality for creating new handles needs to be kept private to polygon. In order to
+/// avoid having to define all handle types at the root of the crate (which would give the
+/// renderers access to the private fields to create new handles) we define the private `Handle`
+/// trait and implement it for all handle types using the `derive_Handle!` macro. This allows us
+/// to define various handle types in the most appropriate module while still giving all renderers
+/// the ability to create new handles.
+trait Handle: Copy {
+    /// Creates a handle for the slot at `index` with the slot's current generation.
+    fn new(index: u32, generation: u32) -> Self;
+
+    /// Gets the index of the slot the handle refers to.
+    fn index(&self) -> usize;
+
+    /// Gets the generation of the slot that the handle is valid for.
+    fn generation(&self) -> u32;
 }
 
 #[derive(Debug)]
 pub struct BuildMaterialError;
+
+/// An error using or removing a resource registered with a renderer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceError {
+    /// The handle doesn't identify a resource registered with the renderer, either because it was
+    /// never registered or because it has already been removed.
+    NotFound,
+
+    /// The resource is still used by the renderer's default material.
+    UsedByDefaultMaterial,
+
+    /// The resource is still used by a shared material.
+    UsedBySharedMaterial(MaterialId),
+
+    /// The resource is still used by a mesh instance, either directly or through one of its
+    /// owned materials.
+    UsedByMeshInstance(MeshInstanceId),
+}
+
+/// Statistics about how many mesh instances were culled during a frame.
+///
+/// Mesh instances are culled when their bounds are entirely outside of the camera's view frustum.
+/// Mesh instances that aren't attached to an anchor are never drawn and aren't counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CullStats {
+    /// The number of mesh instances that were drawn.
+    pub visible: usize,
+
+    /// The number of mesh instances that were skipped because they weren't visible.
+    pub culled: usize,
+}
+
+impl CullStats {
+    /// Gets the total number of mesh instances that were tested for visibility.
+    pub fn total(&self) -> usize {
+        self.visible + self.culled
+    }
+}
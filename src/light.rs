@@ -45,5 +45,8 @@ pub enum LightData {
 
 /// Identifies a light that has been registered with the renderer.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
-pub struct LightId(usize);
-derive_Counter!(LightId);
+pub struct LightId {
+    index: u32,
+    generation: u32,
+}
+derive_Handle!(LightId);
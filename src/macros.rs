@@ -1,17 +1,21 @@
-/// Derives the `Counter` trait for any tuple-like struct declared as `SomeStruct(usize)`.
-///
-/// Note that the inner value doesn't have to be `usize`, it only needs to be an integer type.
-macro_rules! derive_Counter {
+/// Derives the `Handle` trait for any struct declared as
+/// `SomeStruct { index: u32, generation: u32 }`.
+macro_rules! derive_Handle {
     ($type_name: ident) => {
-        impl $crate::Counter for $type_name {
-            fn initial() -> Self {
-                $type_name(0)
+        impl $crate::Handle for $type_name {
+            fn new(index: u32, generation: u32) -> Self {
+                $type_name {
+                    index: index,
+                    generation: generation,
+                }
             }
 
-            fn next(&mut self) -> Self {
-                let next = *self;
-                self.0 += 1;
-                next
+            fn index(&self) -> usize {
+                self.index as usize
+            }
+
+            fn generation(&self) -> u32 {
+                self.generation
             }
         }
     }
@@ -30,7 +30,41 @@
 //!
 //! ## Vertex attributes
 //!
-//! TODO: What are the input and output vertex attributes?
+//! The vertex program reads the mesh's vertex attributes as `vertex_position`, `vertex_normal`,
+//! `vertex_tangent`, `vertex_color`, and `vertex_uv0` through `vertex_uv3`. Values are passed
+//! from the vertex program to the fragment program through the `@vertex` built-ins, which the
+//! default vertex program fills in:
+//!
+//! - `@vertex.position`, `@vertex.world_position`, `@vertex.view_position` - The position of the
+//!   vertex in model, world, and view space.
+//! - `@vertex.normal`, `@vertex.world_normal`, `@vertex.view_normal` - The normal of the vertex in
+//!   model, world, and view space.
+//! - `@vertex.uv0` through `@vertex.uv3` - The sets of texture coordinates.
+//! - `@vertex.color` - The vertex color.
+//! - `@vertex.tangent`, `@vertex.bitangent` - The tangent and bitangent of the vertex in model
+//!   space. The tangent's `w` component holds the handedness of the tangent basis.
+//! - `@vertex.view_tangent`, `@vertex.view_bitangent` - The tangent and bitangent of the vertex
+//!   in view space.
+//!
+//! The fragment program also has `@vertex.view_tbn`, a `mat3` that converts tangent-space
+//! directions (such as those read from a normal map) to view space. Tangents are only available
+//! for meshes that have tangent data, see `MeshBuilder::compute_tangents()`. Attributes that the
+//! mesh doesn't have read as zero (except for `w`, which reads as one).
+//!
+//! ## Custom Attributes
+//!
+//! Materials can also read custom per-vertex data from the mesh, such as wind strength or
+//! blend weights. Custom attributes are declared using the `attribute` keyword:
+//!
+//! ```text
+//! attribute wind: f32;
+//! ```
+//!
+//! Custom attributes are read in the vertex program as `vertex_<name>` (e.g. `vertex_wind`) and
+//! are matched by name with the mesh data set with `MeshBuilder::set_custom_data()`. They can be
+//! `f32`, `Vector2`, `Vector3`, or `Vector4`, and can't reuse the name of a built-in attribute.
+//! Custom attributes are only available in the vertex program, so a custom vertex program needs
+//! to pass them to the fragment program through one of the `@vertex` built-ins.
 //!
 //! ## Built-In Uniforms and Attributes
 //!
@@ -66,6 +100,30 @@
 //!   for directional lights).
 //! - `light_direction_view` - The normalized direction in view space of the current light (only
 //!   for directional lights).
+//!
+//! The view and projection transforms, `camera_position`, `global_ambient`, and all of the
+//! lighting uniforms are the same for every draw in a frame, so the OpenGL renderer provides them
+//! through the `CameraBlock` and `LightBlock` uniform blocks, which are uploaded once per frame.
+//! Materials can still refer to them by name as if they were regular uniforms.
+//!
+//! The per-model transforms (`model_transform`, `normal_transform`, `view_normal_transform`,
+//! `model_view_transform`, and `model_view_projection`) are read from the instance buffer rather
+//! than set as uniforms, so they're only available in the vertex program.
+//!
+//! ## Instance Properties
+//!
+//! Mesh instances that share both a mesh and a shared material are drawn together in a single
+//! instanced draw. Values that need to vary between those instances can be declared as instance
+//! properties using the `instance` keyword:
+//!
+//! ```text
+//! instance tint: Color;
+//! ```
+//!
+//! Instance properties are read in both the vertex and fragment programs as `@instance.<name>`
+//! (e.g. `@instance.tint`) and are set per mesh instance with methods like
+//! `MeshInstance::set_instance_color()`. Instance properties can be `Color`, `f32`, or `Vector3`,
+//! but not `Texture2d`.
 
 use math::*;
 use shader::Shader;
@@ -76,8 +134,11 @@ use texture::GpuTexture;
 pub use polygon_material::material_source::{Error as MaterialSourceError, MaterialSource};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct MaterialId(usize);
-derive_Counter!(MaterialId);
+pub struct MaterialId {
+    index: u32,
+    generation: u32,
+}
+derive_Handle!(MaterialId);
 
 /// Represents combination of a shader and set values for its uniform properties.
 #[derive(Debug, Clone)]
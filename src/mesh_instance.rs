@@ -6,10 +6,28 @@
 //!
 //! * Allowing meshes to be displayed numerous times in the same scene.
 //! * Associating materials with meshes in the scene.
+//!
+//! A mesh instance has one material for each submesh of its mesh, see `with_materials()`, so
+//! that a single mesh can be drawn with several materials. Each submesh is drawn separately.
+//!
+//! Mesh instances can also carry per-instance values for any `instance` properties declared by
+//! their material. Mesh instances that share both a mesh and a shared material are drawn together
+//! in a single instanced draw, so per-instance values are the way to vary the appearance of
+//! otherwise identical instances without breaking them out into separate draws.
+//!
+//! A mesh instance can also have a list of simpler meshes to draw when it's small on screen,
+//! added with `add_lod()`. Each frame the renderer measures how much of the screen the
+//! instance's bounding sphere covers through the active camera and draws the simplest mesh
+//! whose screen size threshold is still larger than that. The LOD meshes can be generated
+//! with `geometry::simplify::lod_chain()`.
 
 use {GpuMesh};
 use anchor::AnchorId;
 use material::*;
+use math::*;
+use std::cmp;
+use std::collections::HashMap;
+use std::ops::Range;
 
 /// Represents an instance of a mesh in the scene.
 ///
@@ -19,26 +37,50 @@ use material::*;
 #[derive(Debug)]
 pub struct MeshInstance {
     mesh: GpuMesh,
-    material: MaterialType,
-    anchor: Option<AnchorId>
+
+    /// The material for each submesh. Never empty.
+    materials: Vec<MaterialType>,
+
+    anchor: Option<AnchorId>,
+    instance_properties: HashMap<String, InstanceProperty>,
+
+    /// Levels of detail sorted from most to least detailed, i.e. by descending screen size.
+    lods: Vec<MeshLod>,
 }
 
 impl MeshInstance {
     /// Creates a new mesh instance sharing the specified material.
+    ///
+    /// The material is used for every submesh of the mesh.
     pub fn with_shared_material(mesh: GpuMesh, material: MaterialId) -> MeshInstance {
-        MeshInstance {
-            mesh: mesh,
-            material: MaterialType::Shared(material),
-            anchor: None,
-        }
+        MeshInstance::with_materials(mesh, vec![MaterialType::Shared(material)])
     }
 
     /// Creates a new mesh instance with its own material.
+    ///
+    /// The material is used for every submesh of the mesh.
     pub fn with_owned_material(mesh: GpuMesh, material: Material) -> MeshInstance {
+        MeshInstance::with_materials(mesh, vec![MaterialType::Owned(material)])
+    }
+
+    /// Creates a new mesh instance with a material for each submesh of the mesh.
+    ///
+    /// Submesh `n` is drawn with `materials[n]`. If the mesh has more submeshes than there are
+    /// materials, the last material is used for the rest of the submeshes, and materials past
+    /// the last submesh are ignored.
+    ///
+    /// # Panics
+    ///
+    /// - If `materials` is empty.
+    pub fn with_materials(mesh: GpuMesh, materials: Vec<MaterialType>) -> MeshInstance {
+        assert!(!materials.is_empty(), "Mesh instance must have at least one material");
+
         MeshInstance {
             mesh: mesh,
-            material: MaterialType::Owned(material),
+            materials: materials,
             anchor: None,
+            instance_properties: HashMap::new(),
+            lods: Vec::new(),
         }
     }
 
@@ -52,35 +94,112 @@ impl MeshInstance {
         &self.mesh
     }
 
-    /// Gets a reference to either the shared material ID or the owned material.
+    /// Adds a level of detail to the mesh instance.
+    ///
+    /// `mesh` is drawn instead of the instance's mesh when the instance's bounding sphere covers
+    /// less than `screen_size` of the screen's height, see `Camera::screen_size()` for details.
+    /// When several levels of detail apply the one with the smallest `screen_size` is drawn.
+    /// The bounding sphere of the instance's main mesh is used for all levels of detail.
+    ///
+    /// # Panics
+    ///
+    /// - If `screen_size` is negative or NaN.
+    pub fn add_lod(&mut self, mesh: GpuMesh, screen_size: f32) {
+        assert!(screen_size >= 0.0, "LOD screen size must not be negative: {}", screen_size);
+
+        let index = self.lods
+            .iter()
+            .position(|lod| lod.screen_size < screen_size)
+            .unwrap_or(self.lods.len());
+        self.lods.insert(index, MeshLod {
+            mesh: mesh,
+            screen_size: screen_size,
+        });
+    }
+
+    /// Gets the levels of detail for the mesh instance, from most to least detailed.
+    pub fn lods(&self) -> &[MeshLod] {
+        &self.lods
+    }
+
+    /// Removes all levels of detail from the mesh instance.
+    pub fn clear_lods(&mut self) {
+        self.lods.clear();
+    }
+
+    /// Gets the mesh to draw when the mesh instance covers `screen_size` of the screen's height.
+    pub fn mesh_for_screen_size(&self, screen_size: f32) -> GpuMesh {
+        self.lods
+            .iter()
+            .rev()
+            .find(|lod| screen_size < lod.screen_size)
+            .map(|lod| lod.mesh)
+            .unwrap_or(self.mesh)
+    }
+
+    /// Gets a reference to either the shared material ID or the owned material of the first
+    /// submesh.
     pub fn material_type(&self) -> &MaterialType {
-        &self.material
+        &self.materials[0]
+    }
+
+    /// Gets the materials of the mesh instance, one for each submesh.
+    pub fn material_types(&self) -> &[MaterialType] {
+        &self.materials
     }
 
-    /// Gets the shared material ID if the mesh instance is using a shared material.
+    /// Gets the shared material ID of the first submesh if it's using a shared material.
     pub fn shared_material(&self) -> Option<MaterialId> {
-        match self.material {
+        match self.materials[0] {
             MaterialType::Shared(id) => Some(id),
             _ => None,
         }
     }
 
-    /// Gets a reference to the material used by the mesh instance if it owns its material.
+    /// Gets a reference to the material of the first submesh if the mesh instance owns it.
     pub fn material(&self) -> Option<&Material> {
-        match self.material {
+        self.submesh_material(0)
+    }
+
+    /// Gets a mutable reference to the material of the first submesh if the mesh instance owns it.
+    pub fn material_mut(&mut self) -> Option<&mut Material> {
+        self.submesh_material_mut(0)
+    }
+
+    /// Gets a reference to the material used for a submesh if the mesh instance owns it.
+    pub fn submesh_material(&self, submesh: usize) -> Option<&Material> {
+        let index = cmp::min(submesh, self.materials.len() - 1);
+        match self.materials[index] {
             MaterialType::Owned(ref material) => Some(material),
             _ => None,
         }
     }
 
-    /// Gets a mutable reference to the material used by the mesh instance if it owns its material.
-    pub fn material_mut(&mut self) -> Option<&mut Material> {
-        match self.material {
+    /// Gets a mutable reference to the material used for a submesh if the mesh instance owns it.
+    ///
+    /// Submeshes past the last material share the last material, so changing it affects all of
+    /// them.
+    pub fn submesh_material_mut(&mut self, submesh: usize) -> Option<&mut Material> {
+        let index = cmp::min(submesh, self.materials.len() - 1);
+        match self.materials[index] {
             MaterialType::Owned(ref mut material) => Some(material),
             _ => None,
         }
     }
 
+    /// Gets the range of submeshes drawn with the material at `index` in `material_types()`,
+    /// for a mesh with `submesh_count` submeshes.
+    ///
+    /// The range is empty if the material isn't used.
+    pub fn material_submeshes(&self, index: usize, submesh_count: usize) -> Range<usize> {
+        let start = cmp::min(index, submesh_count);
+        if index + 1 == self.materials.len() {
+            start..submesh_count
+        } else {
+            start..cmp::min(index + 1, submesh_count)
+        }
+    }
+
     /// Attaches the mesh instance to the specified anchor.
     pub fn set_anchor(&mut self, anchor_id: AnchorId) {
         self.anchor = Some(anchor_id);
@@ -90,8 +209,59 @@ impl MeshInstance {
     pub fn anchor(&self) -> Option<AnchorId> {
         self.anchor
     }
+
+    /// Sets an instance property to be the specified color.
+    pub fn set_instance_color<S: Into<String>>(&mut self, name: S, color: Color) {
+        self.instance_properties.insert(name.into(), InstanceProperty::Color(color));
+    }
+
+    /// Sets an instance property to be the specified `f32` value.
+    pub fn set_instance_f32<S: Into<String>>(&mut self, name: S, value: f32) {
+        self.instance_properties.insert(name.into(), InstanceProperty::f32(value));
+    }
+
+    /// Sets an instance property to be the specified `Vector3` value.
+    pub fn set_instance_vector3<S: Into<String>>(&mut self, name: S, value: Vector3) {
+        self.instance_properties.insert(name.into(), InstanceProperty::Vector3(value));
+    }
+
+    /// Gets the value of an instance property.
+    ///
+    /// Instance properties declared by the material but not set on the mesh instance are zero
+    /// when rendered.
+    pub fn instance_property(&self, name: &str) -> Option<&InstanceProperty> {
+        self.instance_properties.get(name)
+    }
+
+    /// Removes an instance property from the mesh instance.
+    ///
+    /// The existing property is returned if any.
+    pub fn clear_instance_property(&mut self, name: &str) -> Option<InstanceProperty> {
+        self.instance_properties.remove(name)
+    }
+}
+
+/// A level of detail for a mesh instance, see `MeshInstance::add_lod()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeshLod {
+    pub mesh: GpuMesh,
+
+    /// The mesh is drawn when the mesh instance covers less than this much of the screen's height.
+    pub screen_size: f32,
+}
+
+/// Represents a per-instance value that can be read in shader programs with `@instance`.
+#[derive(Debug, Clone, Copy)]
+#[allow(bad_style)]
+pub enum InstanceProperty {
+    Color(Color),
+    f32(f32),
+    Vector3(Vector3),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
-pub struct MeshInstanceId(usize);
-derive_Counter!(MeshInstanceId);
+pub struct MeshInstanceId {
+    index: u32,
+    generation: u32,
+}
+derive_Handle!(MeshInstanceId);
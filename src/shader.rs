@@ -2,5 +2,8 @@
 ///
 /// Shaders are created by the renderer by compiling shader source code.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
-pub struct Shader(usize);
-derive_Counter!(Shader);
+pub struct Shader {
+    index: u32,
+    generation: u32,
+}
+derive_Handle!(Shader);
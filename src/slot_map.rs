@@ -0,0 +1,192 @@
+//! Generational storage for the resources owned by a renderer.
+
+use Handle;
+use std::marker::PhantomData;
+
+/// A collection that gives each inserted value a generational handle.
+///
+/// Removing a value frees its slot to be reused by later insertions, but the slot's generation is
+/// incremented first so that handles to the removed value don't match the value that reuses the
+/// slot. Looking up a stale handle returns `None` rather than finding the wrong value.
+#[derive(Debug)]
+pub(crate) struct SlotMap<K, V> {
+    slots: Vec<Slot<V>>,
+
+    /// The indices of the empty slots that can be reused.
+    free: Vec<u32>,
+
+    _key: PhantomData<K>,
+}
+
+#[derive(Debug)]
+struct Slot<V> {
+    /// The generation of the slot's current value, or of the next value if the slot is empty.
+    ///
+    /// Generations start at 1 so that default handles never match any slot.
+    generation: u32,
+    value: Option<V>,
+}
+
+impl<K: Handle, V> SlotMap<K, V> {
+    pub fn new() -> SlotMap<K, V> {
+        SlotMap {
+            slots: Vec::new(),
+            free: Vec::new(),
+            _key: PhantomData,
+        }
+    }
+
+    /// Adds a value to the map, returning the handle that identifies it.
+    ///
+    /// # Panics
+    ///
+    /// - If the map already has `u32::MAX` slots and none of them are free.
+    pub fn insert(&mut self, value: V) -> K {
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            debug_assert!(slot.value.is_none(), "Free slot {} is occupied", index);
+
+            slot.value = Some(value);
+            return K::new(index, slot.generation);
+        }
+
+        let index = self.slots.len();
+        assert!(index < u32::MAX as usize, "Slot map has run out of slots");
+
+        self.slots.push(Slot {
+            generation: 1,
+            value: Some(value),
+        });
+        K::new(index as u32, 1)
+    }
+
+    /// Removes a value from the map, returning it.
+    ///
+    /// Returns `None` if the handle is stale.
+    pub fn remove(&mut self, key: K) -> Option<V> {
+        let slot = self.slots.get_mut(key.index())?;
+        if slot.generation != key.generation() {
+            return None;
+        }
+
+        let value = slot.value.take()?;
+
+        // A slot whose generation would overflow is retired rather than reused, otherwise old
+        // handles to the slot would become valid again.
+        if slot.generation < u32::MAX {
+            slot.generation += 1;
+            self.free.push(key.index() as u32);
+        }
+
+        Some(value)
+    }
+
+    /// Gets a reference to a value, returning `None` if the handle is stale.
+    pub fn get(&self, key: K) -> Option<&V> {
+        self.slots
+            .get(key.index())
+            .filter(|slot| slot.generation == key.generation())
+            .and_then(|slot| slot.value.as_ref())
+    }
+
+    /// Gets a mutable reference to a value, returning `None` if the handle is stale.
+    pub fn get_mut(&mut self, key: K) -> Option<&mut V> {
+        self.slots
+            .get_mut(key.index())
+            .filter(|slot| slot.generation == key.generation())
+            .and_then(|slot| slot.value.as_mut())
+    }
+
+    /// Checks if a handle identifies a value in the map.
+    pub fn contains_key(&self, key: K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Iterates over the values in the map along with their handles, in slot order.
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = (K, &'a V)> + 'a {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| {
+                slot.value
+                    .as_ref()
+                    .map(|value| (K::new(index as u32, slot.generation), value))
+            })
+    }
+
+    /// Iterates over the values in the map, in slot order.
+    pub fn values<'a>(&'a self) -> impl Iterator<Item = &'a V> + 'a {
+        self.slots.iter().filter_map(|slot| slot.value.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use GpuMesh;
+    use Handle;
+    use super::SlotMap;
+
+    #[test]
+    fn insert_and_get() {
+        let mut map = SlotMap::<GpuMesh, &str>::new();
+        let first = map.insert("first");
+        let second = map.insert("second");
+
+        assert_eq!(Some(&"first"), map.get(first));
+        assert_eq!(Some(&"second"), map.get(second));
+        assert_eq!(vec![(first, &"first"), (second, &"second")], map.iter().collect::<Vec<_>>());
+
+        // Default handles have generation 0, which no slot ever has.
+        assert!(!map.contains_key(GpuMesh::default()));
+        assert_eq!(None, map.get(GpuMesh::new(2, 1)));
+    }
+
+    #[test]
+    fn stale_handles() {
+        let mut map = SlotMap::<GpuMesh, &str>::new();
+        let old = map.insert("old");
+        assert_eq!(Some("old"), map.remove(old));
+
+        // The removed value's slot is reused with the next generation.
+        let new = map.insert("new");
+        assert_eq!(old.index(), new.index());
+        assert_eq!(old.generation() + 1, new.generation());
+
+        assert_eq!(None, map.get(old));
+        assert_eq!(None, map.get_mut(old));
+        assert!(!map.contains_key(old));
+        assert_eq!(None, map.remove(old));
+
+        assert_eq!(Some(&"new"), map.get(new));
+        assert!(map.contains_key(new));
+        *map.get_mut(new).unwrap() = "newer";
+        assert_eq!(vec![&"newer"], map.values().collect::<Vec<_>>());
+
+        // Removing twice doesn't free the slot twice.
+        assert_eq!(Some("newer"), map.remove(new));
+        assert_eq!(None, map.remove(new));
+        let first = map.insert("first");
+        let second = map.insert("second");
+        assert_ne!(first.index(), second.index());
+    }
+
+    #[test]
+    fn retire_exhausted_slot() {
+        let mut map = SlotMap::<GpuMesh, &str>::new();
+        map.insert("last");
+        map.slots[0].generation = u32::MAX;
+
+        let last = GpuMesh::new(0, u32::MAX);
+        assert_eq!(Some(&"last"), map.get(last));
+        assert_eq!(Some("last"), map.remove(last));
+
+        // The slot can't be given a new generation without wrapping back to handles that were
+        // already given out, so it's never reused.
+        let next = map.insert("next");
+        assert_eq!(1, next.index());
+        assert_eq!(1, next.generation());
+        assert_eq!(None, map.get(last));
+        assert_eq!(None, map.get(GpuMesh::new(0, 0)));
+        assert_eq!(vec![(next, &"next")], map.iter().collect::<Vec<_>>());
+    }
+}
@@ -2,8 +2,11 @@ use image::DynamicImage;
 
 /// Represents texture data that has been sent to the GPU.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
-pub struct GpuTexture(usize);
-derive_Counter!(GpuTexture);
+pub struct GpuTexture {
+    index: u32,
+    generation: u32,
+}
+derive_Handle!(GpuTexture);
 
 /// Represents a texture loaded into memory and ready to be sent to the GPU.
 ///
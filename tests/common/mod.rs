@@ -0,0 +1,87 @@
+//! Helpers for building test meshes and reading back their vertex data, shared by the mesh tests.
+
+#![allow(dead_code)]
+
+use polygon::geometry::mesh::*;
+use polygon::math::*;
+
+/// Builds a four-vertex mesh with every kind of attribute, where each value encodes the vertex
+/// it belongs to so that misplaced data is easy to spot. Each of its two triangles is in its own
+/// submesh.
+pub fn test_mesh(layout: VertexLayout) -> Mesh {
+    let vertices = 0..4;
+    let value = |vertex: usize, attribute: usize| (vertex * 100 + attribute * 10) as f32;
+
+    let positions = vertices.clone()
+        .map(|vertex| Point::new(value(vertex, 0), value(vertex, 0) + 1.0, value(vertex, 0) + 2.0))
+        .collect::<Vec<_>>();
+    let normals = vertices.clone()
+        .map(|vertex| Vector3::new(value(vertex, 1), value(vertex, 1) + 1.0, value(vertex, 1) + 2.0))
+        .collect::<Vec<_>>();
+    let colors = vertices.clone()
+        .map(|vertex| Color::new(value(vertex, 2), value(vertex, 2) + 1.0, value(vertex, 2) + 2.0, value(vertex, 2) + 3.0))
+        .collect::<Vec<_>>();
+    let uv0 = vertices.clone()
+        .map(|vertex| Vector2::new(value(vertex, 3), value(vertex, 3) + 1.0))
+        .collect::<Vec<_>>();
+    let uv1 = vertices.clone()
+        .map(|vertex| Vector2::new(value(vertex, 4), value(vertex, 4) + 1.0))
+        .collect::<Vec<_>>();
+    let weight = vertices.clone()
+        .map(|vertex| value(vertex, 5))
+        .collect::<Vec<_>>();
+
+    MeshBuilder::new()
+        .set_layout(layout)
+        .set_position_data(&positions)
+        .set_normal_data(&normals)
+        .set_color_data(&colors)
+        .set_texcoord_data(&uv0)
+        .set_texcoord_data_at(1, &uv1)
+        .set_custom_data("weight", 1, &weight)
+        .set_indices(&[0, 1, 2, 0, 2, 3])
+        .set_submeshes(&[Submesh::new(0, 3), Submesh::new(3, 3)])
+        .build()
+        .unwrap()
+}
+
+pub fn point(mesh: &Mesh, vertex: u32) -> Point {
+    let value = mesh.attribute_value(mesh.position(), vertex as usize);
+    Point::new(value[0], value[1], value[2])
+}
+
+pub fn normal(mesh: &Mesh, vertex: u32) -> Vector3 {
+    let value = mesh.attribute_value(mesh.normal().unwrap(), vertex as usize);
+    Vector3::new(value[0], value[1], value[2])
+}
+
+pub fn tangent(mesh: &Mesh, vertex: u32) -> (Vector3, f32) {
+    let value = mesh.attribute_value(mesh.tangent().unwrap(), vertex as usize);
+    (Vector3::new(value[0], value[1], value[2]), value[3])
+}
+
+pub fn triangle_count(mesh: &Mesh) -> usize {
+    mesh.indices().len() / 3
+}
+
+/// Gets the corner positions of the triangles in a range of the mesh's indices.
+pub fn triangles_in(mesh: &Mesh, submesh: Submesh) -> Vec<[Point; 3]> {
+    let indices = mesh.indices().to_vec();
+    indices[submesh.start .. submesh.end()]
+        .chunks(3)
+        .map(|triangle| [point(mesh, triangle[0]), point(mesh, triangle[1]), point(mesh, triangle[2])])
+        .collect()
+}
+
+/// Gets the corner positions of every triangle in the mesh.
+pub fn triangles(mesh: &Mesh) -> Vec<[Point; 3]> {
+    triangles_in(mesh, Submesh::new(0, mesh.indices().len()))
+}
+
+/// Gets the total surface area of a set of triangles.
+pub fn area(triangles: &[[Point; 3]]) -> f32 {
+    triangles
+        .iter()
+        .map(|&[a, b, c]| Vector3::cross(b - a, c - a).magnitude() * 0.5)
+        .sum()
+}
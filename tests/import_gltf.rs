@@ -0,0 +1,409 @@
+extern crate base64;
+extern crate polygon;
+
+use polygon::camera::Camera;
+use polygon::geometry::mesh::*;
+use polygon::import::gltf::{self, PbrMaterial};
+use polygon::light::LightData;
+use polygon::math::*;
+use std::fs;
+use std::path::Path;
+
+/// Builds the binary buffer used by the fixtures: a unit quad in the xy plane, followed by its
+/// normals, texcoords, and `u16` indices.
+///
+/// | Data      | Offset | Length |
+/// |-----------|--------|--------|
+/// | Positions | 0      | 48     |
+/// | Normals   | 48     | 48     |
+/// | Texcoords | 96     | 32     |
+/// | Indices   | 128    | 12     |
+fn quad_buffer() -> Vec<u8> {
+    let positions = [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0];
+    let normals = [0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0];
+    let texcoords = [0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0];
+    let indices = [0u16, 1, 2, 0, 2, 3];
+
+    let mut buffer = Vec::new();
+    for &value in positions.iter().chain(&normals).chain(&texcoords) {
+        buffer.extend_from_slice(&f32::to_le_bytes(value));
+    }
+    for &index in &indices {
+        buffer.extend_from_slice(&index.to_le_bytes());
+    }
+    buffer
+}
+
+/// The buffer views and accessors for `quad_buffer()`, shared by all of the fixtures.
+static QUAD_ACCESSORS: &'static str = r#"
+    "bufferViews": [
+        { "buffer": 0, "byteOffset": 0, "byteLength": 48 },
+        { "buffer": 0, "byteOffset": 48, "byteLength": 48 },
+        { "buffer": 0, "byteOffset": 96, "byteLength": 32 },
+        { "buffer": 0, "byteOffset": 128, "byteLength": 12 }
+    ],
+    "accessors": [
+        { "bufferView": 0, "componentType": 5126, "count": 4, "type": "VEC3", "min": [0, 0, 0], "max": [1, 1, 0] },
+        { "bufferView": 1, "componentType": 5126, "count": 4, "type": "VEC3" },
+        { "bufferView": 2, "componentType": 5126, "count": 4, "type": "VEC2" },
+        { "bufferView": 3, "componentType": 5123, "count": 6, "type": "SCALAR" }
+    ]
+"#;
+
+/// A scene with a node hierarchy, several kinds of primitives, materials, cameras, and lights.
+/// The buffer is given by `buffer_uri`.
+fn scene_source(buffer_uri: &str) -> String {
+    format!(r#"{{
+    "asset": {{ "version": "2.0" }},
+    "extensionsUsed": ["KHR_lights_punctual"],
+    "buffers": [{{ "byteLength": 140, "uri": "{}" }}],
+    {},
+    "meshes": [
+        {{
+            "name": "quad",
+            "primitives": [{{
+                "attributes": {{ "POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2 }},
+                "indices": 3,
+                "material": 0
+            }}]
+        }},
+        {{
+            "name": "strip",
+            "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "mode": 5, "material": 1 }}]
+        }},
+        {{
+            "name": "points",
+            "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "mode": 0 }}]
+        }}
+    ],
+    "materials": [
+        {{
+            "name": "brushed",
+            "pbrMetallicRoughness": {{
+                "baseColorFactor": [1.0, 0.5, 0.25, 0.75],
+                "metallicFactor": 0.75,
+                "roughnessFactor": 0.5
+            }},
+            "emissiveFactor": [0.1, 0.2, 0.3],
+            "doubleSided": true
+        }},
+        {{}}
+    ],
+    "cameras": [
+        {{ "type": "perspective", "perspective": {{ "yfov": 0.8, "aspectRatio": 1.5, "znear": 0.1, "zfar": 50.0 }} }},
+        {{ "type": "orthographic", "orthographic": {{ "xmag": 1.0, "ymag": 1.0, "znear": 0.1, "zfar": 10.0 }} }},
+        {{ "type": "perspective", "perspective": {{ "yfov": 1.0, "znear": 0.5 }} }}
+    ],
+    "extensions": {{
+        "KHR_lights_punctual": {{
+            "lights": [
+                {{ "type": "directional", "color": [1.0, 0.9, 0.8], "intensity": 2.0 }},
+                {{ "type": "point", "intensity": 5.0, "range": 10.0 }},
+                {{ "type": "spot", "intensity": 3.0, "spot": {{}} }}
+            ]
+        }}
+    }},
+    "nodes": [
+        {{ "name": "root", "translation": [1.0, 2.0, 3.0], "children": [1, 3] }},
+        {{
+            "name": "child",
+            "translation": [0.0, 0.0, -2.0],
+            "rotation": [0.0, 0.70710677, 0.0, 0.70710677],
+            "scale": [2.0, 2.0, 2.0],
+            "mesh": 0,
+            "children": [2]
+        }},
+        {{ "name": "grandchild", "translation": [1.0, 0.0, 0.0], "camera": 2 }},
+        {{ "name": "sun", "extensions": {{ "KHR_lights_punctual": {{ "light": 0 }} }} }},
+        {{ "name": "unused", "mesh": 1, "camera": 1 }},
+        {{ "name": "lamp", "extensions": {{ "KHR_lights_punctual": {{ "light": 1 }} }} }}
+    ],
+    "scenes": [{{ "nodes": [0] }}],
+    "scene": 0
+}}"#, buffer_uri, QUAD_ACCESSORS)
+}
+
+/// A minimal scene with a single quad, whose buffer is given by `buffer`.
+fn quad_source(buffer: &str) -> String {
+    format!(r#"{{
+    "asset": {{ "version": "2.0" }},
+    "buffers": [{}],
+    {},
+    "meshes": [{{
+        "primitives": [{{ "attributes": {{ "POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2 }}, "indices": 3 }}]
+    }}],
+    "nodes": [{{ "mesh": 0, "translation": [0.0, 5.0, 0.0] }}]
+}}"#, buffer, QUAD_ACCESSORS)
+}
+
+/// Packs glTF JSON and a binary buffer into a `.glb` file.
+fn glb(json: &str, bin: &[u8]) -> Vec<u8> {
+    // Chunks are padded to 4 bytes, the JSON with spaces and the binary data with zeros.
+    let mut json = json.as_bytes().to_vec();
+    while json.len() % 4 != 0 {
+        json.push(b' ');
+    }
+    let mut bin = bin.to_vec();
+    while bin.len() % 4 != 0 {
+        bin.push(0);
+    }
+
+    let length = 12 + 8 + json.len() + 8 + bin.len();
+    let mut data = Vec::with_capacity(length);
+    data.extend_from_slice(b"glTF");
+    data.extend_from_slice(&2u32.to_le_bytes());
+    data.extend_from_slice(&(length as u32).to_le_bytes());
+    data.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    data.extend_from_slice(b"JSON");
+    data.extend_from_slice(&json);
+    data.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+    data.extend_from_slice(b"BIN\0");
+    data.extend_from_slice(&bin);
+    data
+}
+
+fn data_uri(data: &[u8]) -> String {
+    format!("data:application/octet-stream;base64,{}", base64::encode(data))
+}
+
+fn assert_near(expected: Point, actual: Point) {
+    assert!((expected - actual).magnitude() < 1e-5, "expected {:?}, got {:?}", expected, actual);
+}
+
+/// Checks that a scene's only mesh is the quad from `quad_buffer()`.
+fn assert_quad(mesh: &Mesh) {
+    assert_eq!(4, mesh.vertex_count());
+    assert_eq!(vec![0, 1, 2, 0, 2, 3], mesh.indices().to_vec());
+    assert_eq!(&[1.0, 1.0, 0.0, 1.0], mesh.attribute_value(mesh.position(), 2));
+    assert_eq!(&[0.0, 0.0, 1.0], mesh.attribute_value(mesh.normal().unwrap(), 2));
+    assert_eq!(&[1.0, 0.0], mesh.attribute_value(mesh.texcoord()[0], 2));
+}
+
+#[test]
+fn embedded_buffer() {
+    let source = scene_source(&data_uri(&quad_buffer()));
+    let scene = gltf::from_slice(source.as_bytes(), Path::new("")).unwrap();
+
+    // Meshes.
+    let names = scene.meshes.iter().map(|mesh| mesh.name.as_deref()).collect::<Vec<_>>();
+    assert_eq!(vec![Some("quad"), Some("strip"), Some("points")], names);
+
+    assert_eq!(1, scene.meshes[0].primitives.len());
+    assert_eq!(Some(0), scene.meshes[0].primitives[0].material);
+    assert_quad(&scene.meshes[0].primitives[0].mesh);
+
+    // The strip's second triangle has its first two corners swapped to keep the strip's winding.
+    // The quad's corners aren't in strip order, so the triangle faces away.
+    let strip = &scene.meshes[1].primitives[0].mesh;
+    let corners = strip.indices()
+        .iter()
+        .map(|index| {
+            let position = strip.attribute_value(strip.position(), index as usize);
+            let normal = strip.attribute_value(strip.normal().unwrap(), index as usize);
+            (position[0], position[1], normal[2])
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            (0.0, 0.0, 1.0), (1.0, 0.0, 1.0), (1.0, 1.0, 1.0),
+            (1.0, 1.0, -1.0), (1.0, 0.0, -1.0), (0.0, 1.0, -1.0),
+        ],
+        corners,
+    );
+
+    // Points have no faces, so the primitive is skipped.
+    assert!(scene.meshes[2].primitives.is_empty());
+
+    // Materials.
+    assert_eq!(
+        PbrMaterial {
+            name: Some("brushed".into()),
+            base_color: Color::new(1.0, 0.5, 0.25, 0.75),
+            base_color_texture: None,
+            metallic: 0.75,
+            roughness: 0.5,
+            metallic_roughness_texture: None,
+            normal_texture: None,
+            normal_scale: 1.0,
+            occlusion_texture: None,
+            emissive: Color::rgb(0.1, 0.2, 0.3),
+            emissive_texture: None,
+            double_sided: true,
+        },
+        scene.materials[0],
+    );
+
+    let default = &scene.materials[1];
+    assert_eq!(Color::new(1.0, 1.0, 1.0, 1.0), default.base_color);
+    assert_eq!((1.0, 1.0), (default.metallic, default.roughness));
+    assert_eq!(Color::rgb(0.0, 0.0, 0.0), default.emissive);
+    assert!(!default.double_sided);
+
+    // Cameras. The orthographic camera is skipped.
+    assert_eq!(2, scene.cameras.len());
+    assert_eq!(Camera::new(0.8, 1.5, 0.1, 50.0).projection_matrix(), scene.cameras[0].projection_matrix());
+
+    // Cameras without an aspect ratio or far plane keep the defaults.
+    let mut expected = Camera::default();
+    expected.set_fov(1.0);
+    expected.set_near(0.5);
+    assert_eq!(expected.projection_matrix(), scene.cameras[1].projection_matrix());
+
+    // Lights.
+    assert_eq!(3, scene.lights.len());
+    match scene.lights[0].data {
+        LightData::Directional { direction } => assert_eq!(Vector3::FORWARD, direction),
+        data => panic!("Expected a directional light, got {:?}", data),
+    }
+    assert_eq!(Color::rgb(1.0, 0.9, 0.8), scene.lights[0].color);
+    assert_eq!(2.0, scene.lights[0].strength);
+
+    match scene.lights[1].data {
+        LightData::Point { radius } => assert_eq!(10.0, radius),
+        data => panic!("Expected a point light, got {:?}", data),
+    }
+    assert_eq!(5.0, scene.lights[1].strength);
+    assert_eq!(Color::rgb(1.0, 1.0, 1.0), scene.lights[1].color);
+
+    // Spot lights become point lights, and lights without a range get the default.
+    match scene.lights[2].data {
+        LightData::Point { radius } => assert_eq!(gltf::DEFAULT_LIGHT_RANGE, radius),
+        data => panic!("Expected a point light, got {:?}", data),
+    }
+}
+
+#[test]
+fn node_hierarchy() {
+    let source = scene_source(&data_uri(&quad_buffer()));
+    let scene = gltf::from_slice(source.as_bytes(), Path::new("")).unwrap();
+
+    // Only the nodes in the default scene are roots, even though other nodes have no parent.
+    assert_eq!(vec![0], scene.roots);
+
+    let names = scene.nodes.iter().map(|node| node.name.as_deref()).collect::<Vec<_>>();
+    assert_eq!(
+        vec![Some("root"), Some("child"), Some("grandchild"), Some("sun"), Some("unused"), Some("lamp")],
+        names,
+    );
+
+    let links = scene.nodes.iter().map(|node| (node.parent, node.children.clone())).collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            (None, vec![1, 3]),
+            (Some(0), vec![2]),
+            (Some(1), vec![]),
+            (Some(0), vec![]),
+            (None, vec![]),
+            (None, vec![]),
+        ],
+        links,
+    );
+
+    // Nodes refer to meshes, cameras, and lights by index. Orthographic cameras are skipped, so
+    // nodes using them have no camera and later cameras are shifted down.
+    assert_eq!(Some(0), scene.nodes[1].mesh);
+    assert_eq!(Some(1), scene.nodes[2].camera);
+    assert_eq!(Some(0), scene.nodes[3].light);
+    assert_eq!((Some(1), None), (scene.nodes[4].mesh, scene.nodes[4].camera));
+    assert_eq!(Some(1), scene.nodes[5].light);
+
+    // The child is rotated a quarter turn around y and doubled in size, so the grandchild's
+    // offset of +x becomes 2 units of -z.
+    let child = &scene.nodes[1];
+    assert_near(Point::new(0.0, 0.0, -2.0), child.transform.position);
+    assert_eq!(Vector3::new(2.0, 2.0, 2.0), child.transform.scale);
+    assert_near(Point::new(1.0, 2.0, 1.0), child.world_transform.position);
+    assert_near(Point::new(1.0, 2.0, -1.0), scene.nodes[2].world_transform.position);
+    assert_near(Point::new(1.0, 2.0, -1.0), scene.nodes[1].world_transform * Point::new(1.0, 0.0, 0.0));
+}
+
+#[test]
+fn binary_gltf() {
+    let json = quad_source(r#"{ "byteLength": 140 }"#);
+    let data = glb(&json, &quad_buffer());
+    let scene = gltf::from_slice(&data, Path::new("")).unwrap();
+
+    assert_eq!(1, scene.meshes.len());
+    assert_eq!(None, scene.meshes[0].name);
+    assert_quad(&scene.meshes[0].primitives[0].mesh);
+    assert_eq!(None, scene.meshes[0].primitives[0].material);
+
+    // Files without scenes use every node without a parent as a root.
+    assert_eq!(vec![0], scene.roots);
+    assert_near(Point::new(0.0, 5.0, 0.0), scene.nodes[0].world_transform.position);
+}
+
+#[test]
+fn external_buffer() {
+    let directory = std::env::temp_dir().join(format!("polygon-gltf-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(directory.join("quad data.bin"), quad_buffer()).unwrap();
+    fs::write(
+        directory.join("quad.gltf"),
+        quad_source(r#"{ "byteLength": 140, "uri": "quad%20data.bin" }"#),
+    ).unwrap();
+
+    let result = gltf::load(directory.join("quad.gltf"));
+    fs::remove_dir_all(&directory).unwrap();
+
+    let scene = result.unwrap();
+    assert_quad(&scene.meshes[0].primitives[0].mesh);
+}
+
+#[test]
+fn errors() {
+    // The buffer is shorter than it claims to be.
+    let source = quad_source(&format!(r#"{{ "byteLength": 140, "uri": "{}" }}"#, data_uri(&quad_buffer()[..100])));
+    match gltf::from_slice(source.as_bytes(), Path::new("")) {
+        Err(gltf::Error::MissingBuffer(0)) => {}
+        result => panic!("Expected MissingBuffer, got {:?}", result),
+    }
+
+    // Binary glTF buffers need a binary chunk.
+    let source = quad_source(r#"{ "byteLength": 140 }"#);
+    match gltf::from_slice(source.as_bytes(), Path::new("")) {
+        Err(gltf::Error::MissingBuffer(0)) => {}
+        result => panic!("Expected MissingBuffer, got {:?}", result),
+    }
+
+    // Nothing is fetched over the network.
+    let source = quad_source(r#"{ "byteLength": 140, "uri": "https://example.com/quad.bin" }"#);
+    match gltf::from_slice(source.as_bytes(), Path::new("")) {
+        Err(gltf::Error::UnsupportedUri(ref uri)) if uri == "https://example.com/quad.bin" => {}
+        result => panic!("Expected UnsupportedUri, got {:?}", result),
+    }
+
+    let buffer = format!(r#"{{ "byteLength": 140, "uri": "{}" }}"#, data_uri(&quad_buffer()));
+
+    // The last index is past the end of the positions, which must be caught before flat normals
+    // are generated.
+    let source = quad_source(&buffer)
+        .replace(r#""POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2"#, r#""POSITION": 0"#)
+        .replace(r#""count": 4, "type": "VEC3", "min""#, r#""count": 3, "type": "VEC3", "min""#);
+    match gltf::from_slice(source.as_bytes(), Path::new("")) {
+        Err(gltf::Error::IndexOutOfBounds { mesh: 0, primitive: 0, index: 3, vertex_count: 3 }) => {}
+        result => panic!("Expected IndexOutOfBounds, got {:?}", result),
+    }
+
+    // A triangle list with a leftover index.
+    let source = quad_source(&buffer)
+        .replace(r#""count": 6, "type": "SCALAR""#, r#""count": 4, "type": "SCALAR""#);
+    match gltf::from_slice(source.as_bytes(), Path::new("")) {
+        Err(gltf::Error::IncompleteTriangle { mesh: 0, primitive: 0, index_count: 4 }) => {}
+        result => panic!("Expected IncompleteTriangle, got {:?}", result),
+    }
+
+    // There are fewer normals than positions.
+    let source = quad_source(&buffer)
+        .replace(r#""bufferView": 1, "componentType": 5126, "count": 4"#, r#""bufferView": 1, "componentType": 5126, "count": 3"#);
+    match gltf::from_slice(source.as_bytes(), Path::new("")) {
+        Err(gltf::Error::IncorrectAttributeCount {
+            mesh: 0,
+            primitive: 0,
+            attribute: VertexAttributeType::Normal,
+            expected: 4,
+            actual: 3,
+        }) => {}
+        result => panic!("Expected IncorrectAttributeCount, got {:?}", result),
+    }
+}
@@ -0,0 +1,131 @@
+extern crate polygon;
+
+mod common;
+
+use polygon::geometry::cache::{self, Compression};
+use polygon::geometry::mesh::*;
+use polygon::geometry::primitives;
+
+use common::*;
+
+/// Asserts that two meshes have identical data and attributes.
+fn assert_meshes_equal(expected: &Mesh, actual: &Mesh) {
+    assert_eq!(expected.vertex_count(), actual.vertex_count());
+    assert_eq!(expected.layout(), actual.layout());
+    assert_eq!(expected.indices(), actual.indices());
+    assert_eq!(expected.submeshes(), actual.submeshes());
+    assert_eq!(expected.bounding_box(), actual.bounding_box());
+    assert_eq!(expected.bounding_sphere(), actual.bounding_sphere());
+
+    let bits = |mesh: &Mesh| mesh.vertex_data().iter().map(|value| value.to_bits()).collect::<Vec<_>>();
+    assert_eq!(bits(expected), bits(actual));
+
+    let describe = |mesh: &Mesh| {
+        let mut attributes = vec![("position".to_string(), mesh.position())];
+        attributes.extend(mesh.normal().map(|normal| ("normal".to_string(), normal)));
+        attributes.extend(mesh.tangent().map(|tangent| ("tangent".to_string(), tangent)));
+        attributes.extend(mesh.color().map(|color| ("color".to_string(), color)));
+        attributes.extend(mesh.texcoord().iter().map(|&texcoord| ("texcoord".to_string(), texcoord)));
+        attributes.extend(mesh.custom_attributes().iter().cloned());
+        attributes
+            .into_iter()
+            .map(|(name, attribute)| (name, attribute.elements, attribute.offset, attribute.stride))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(describe(expected), describe(actual));
+}
+
+#[test]
+fn round_trip() {
+    for &layout in &[VertexLayout::Planar, VertexLayout::Interleaved] {
+        for &compression in &[Compression::None, Compression::Lz] {
+            let mesh = test_mesh(layout);
+
+            let mut data = Vec::new();
+            mesh.write_to(&mut data, compression).unwrap();
+            let read = Mesh::read_from(&*data).unwrap();
+
+            assert_meshes_equal(&mesh, &read);
+        }
+    }
+}
+
+#[test]
+fn round_trip_u32_indices() {
+    let mesh = primitives::grid(1.0, 1.0, 300, 300);
+    assert!(matches!(*mesh.indices(), MeshIndices::U32(_)));
+
+    let mut data = Vec::new();
+    mesh.write_to(&mut data, Compression::Lz).unwrap();
+    assert_meshes_equal(&mesh, &Mesh::from_bytes(&data).unwrap());
+}
+
+#[test]
+fn uncompressed_data_is_aligned() {
+    let mesh = primitives::cube(1.0);
+    let mut data = Vec::new();
+    mesh.write_to(&mut data, Compression::None).unwrap();
+
+    // Find the vertex data in the file and check that it starts at an aligned offset.
+    let vertex_bytes = mesh.vertex_data()
+        .iter()
+        .flat_map(|value| value.to_le_bytes().to_vec())
+        .collect::<Vec<_>>();
+    let offset = data
+        .windows(vertex_bytes.len())
+        .position(|window| window == &*vertex_bytes)
+        .expect("Vertex data should be stored as-is");
+    assert_eq!(0, offset % cache::DATA_ALIGNMENT);
+}
+
+#[test]
+fn compression_shrinks_data() {
+    let mesh = primitives::grid(10.0, 10.0, 64, 64);
+
+    let mut uncompressed = Vec::new();
+    mesh.write_to(&mut uncompressed, Compression::None).unwrap();
+    let mut compressed = Vec::new();
+    mesh.write_to(&mut compressed, Compression::Lz).unwrap();
+
+    assert!(compressed.len() < uncompressed.len());
+}
+
+#[test]
+fn errors() {
+    let mesh = primitives::cube(1.0);
+    let mut data = Vec::new();
+    mesh.write_to(&mut data, Compression::Lz).unwrap();
+
+    match Mesh::from_bytes(b"not a mesh") {
+        Err(cache::Error::BadMagic) => {}
+        result => panic!("Expected BadMagic, got {:?}", result),
+    }
+
+    let mut bad_version = data.clone();
+    bad_version[8] = 99;
+    match Mesh::from_bytes(&bad_version) {
+        Err(cache::Error::UnsupportedVersion(99)) => {}
+        result => panic!("Expected UnsupportedVersion, got {:?}", result),
+    }
+
+    let mut flipped = data.clone();
+    let last = flipped.len() - 1;
+    flipped[last] ^= 0x40;
+    match Mesh::from_bytes(&flipped) {
+        Err(cache::Error::ChecksumMismatch { .. }) => {}
+        result => panic!("Expected ChecksumMismatch, got {:?}", result),
+    }
+
+    match Mesh::from_bytes(&data[.. data.len() - 1]) {
+        Err(cache::Error::Corrupt) => {}
+        result => panic!("Expected Corrupt, got {:?}", result),
+    }
+
+    // The checksum doesn't cover the header, so a corrupt payload size can't be caught by it.
+    let mut huge_payload = data.clone();
+    huge_payload[16..24].copy_from_slice(&(1u64 << 60).to_le_bytes());
+    match Mesh::from_bytes(&huge_payload) {
+        Err(cache::Error::Corrupt) => {}
+        result => panic!("Expected Corrupt, got {:?}", result),
+    }
+}
@@ -0,0 +1,410 @@
+extern crate polygon;
+
+use polygon::geometry::mesh::*;
+use polygon::geometry::{obj, ply, primitives, stl};
+use polygon::math::*;
+
+/// Gets the corner positions of every triangle in a mesh, in index order.
+fn triangle_positions(mesh: &Mesh) -> Vec<[f32; 3]> {
+    mesh.indices()
+        .iter()
+        .map(|index| {
+            let value = mesh.attribute_value(mesh.position(), index as usize);
+            [value[0], value[1], value[2]]
+        })
+        .collect()
+}
+
+fn assert_slice_near(expected: &[f32], actual: &[f32]) {
+    assert_eq!(expected.len(), actual.len());
+    for (expected_value, actual_value) in expected.iter().zip(actual.iter()) {
+        assert!(
+            (expected_value - actual_value).abs() < 1e-4,
+            "expected {:?}, got {:?}", expected, actual,
+        );
+    }
+}
+
+#[test]
+fn stl_binary_round_trip() {
+    let cube = primitives::cube(2.0);
+
+    let mut data = Vec::new();
+    stl::write_binary(&cube, &mut data).unwrap();
+    assert_eq!(80 + 4 + 12 * 50, data.len());
+
+    let mesh = stl::parse(&data).unwrap();
+    assert_eq!(triangle_positions(&cube), triangle_positions(&mesh));
+    assert!(mesh.normal().is_some());
+}
+
+#[test]
+fn stl_ascii_round_trip() {
+    let sphere = primitives::icosphere(1.0, 2);
+
+    let mut data = Vec::new();
+    stl::write_ascii(&sphere, "sphere", &mut data).unwrap();
+    assert!(data.starts_with(b"solid sphere"));
+
+    let mesh = stl::parse(&data).unwrap();
+    assert_eq!(triangle_positions(&sphere), triangle_positions(&mesh));
+}
+
+#[test]
+fn stl_welds_vertices() {
+    // The grid is flat, so welding should recover its shared vertices exactly.
+    let grid = primitives::grid(4.0, 4.0, 4, 4);
+
+    let mut data = Vec::new();
+    stl::write_binary(&grid, &mut data).unwrap();
+    let mesh = stl::parse(&data).unwrap();
+
+    assert_eq!(25, mesh.vertex_count());
+    assert_eq!(grid.indices().len(), mesh.indices().len());
+}
+
+#[test]
+fn stl_binary_starting_with_solid() {
+    // Binary files whose header starts with "solid" are still detected by their size.
+    let mut data = Vec::new();
+    stl::write_binary(&primitives::cube(1.0), &mut data).unwrap();
+    data[..5].copy_from_slice(b"solid");
+
+    let mesh = stl::parse(&data).unwrap();
+    assert_eq!(36, mesh.indices().len());
+}
+
+#[test]
+fn stl_errors() {
+    let mut data = Vec::new();
+    stl::write_binary(&primitives::cube(1.0), &mut data).unwrap();
+    data.truncate(data.len() - 10);
+    match stl::parse(&data) {
+        Err(stl::Error::UnexpectedEof) => {}
+        result => panic!("Expected UnexpectedEof, got {:?}", result),
+    }
+
+    let source = "solid bad\n  facet normal 0 0 1\n    outer loop\n      vertex 0 0 zero\n";
+    match stl::parse(source.as_bytes()) {
+        Err(stl::Error::BadSyntax { line: 4 }) => {}
+        result => panic!("Expected BadSyntax on line 4, got {:?}", result),
+    }
+}
+
+/// Builds a mesh with every attribute that PLY can store.
+fn attribute_mesh() -> Mesh {
+    MeshBuilder::new()
+        .set_position_data(&[
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(1.0, 1.0, 0.0),
+            Point::new(0.0, 1.0, -0.5),
+        ])
+        .set_normal_data(&[Vector3::FORWARD; 4])
+        .set_texcoord_data(&[
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(1.0, 1.0),
+            Vector2::new(0.0, 1.0),
+        ])
+        .set_color_data(&[
+            Color::from_srgba8([255, 0, 0, 255]),
+            Color::from_srgba8([0, 128, 0, 255]),
+            Color::from_srgba8([0, 0, 255, 64]),
+            Color::from_srgba8([12, 34, 56, 78]),
+        ])
+        .set_indices(&[0, 1, 2, 0, 2, 3])
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn ply_round_trip() {
+    let original = attribute_mesh();
+
+    for &format in &[ply::Format::Ascii, ply::Format::BinaryLittleEndian, ply::Format::BinaryBigEndian] {
+        let mut data = Vec::new();
+        ply::write(&original, format, &mut data).unwrap();
+        let mesh = ply::parse(&data).unwrap();
+
+        assert_eq!(original.vertex_count(), mesh.vertex_count());
+        assert_eq!(original.indices().to_vec(), mesh.indices().to_vec());
+
+        for vertex in 0..mesh.vertex_count() {
+            assert_slice_near(
+                original.attribute_value(original.position(), vertex),
+                mesh.attribute_value(mesh.position(), vertex),
+            );
+            assert_slice_near(
+                original.attribute_value(original.normal().unwrap(), vertex),
+                mesh.attribute_value(mesh.normal().unwrap(), vertex),
+            );
+            assert_slice_near(
+                original.attribute_value(original.texcoord()[0], vertex),
+                mesh.attribute_value(mesh.texcoord()[0], vertex),
+            );
+            assert_slice_near(
+                original.attribute_value(original.color().unwrap(), vertex),
+                mesh.attribute_value(mesh.color().unwrap(), vertex),
+            );
+        }
+    }
+}
+
+#[test]
+fn ply_without_colors() {
+    let mut data = Vec::new();
+    ply::write(&primitives::cube(1.0), ply::Format::BinaryBigEndian, &mut data).unwrap();
+    let mesh = ply::parse(&data).unwrap();
+    assert_eq!(36, mesh.indices().len());
+    assert!(mesh.color().is_none());
+}
+
+#[test]
+fn ply_ascii_quads_and_extra_elements() {
+    let source = "\
+ply
+format ascii 1.0
+comment A quad with float colors and an unused element
+element vertex 4
+property double x
+property double y
+property double z
+property float red
+property float green
+property float blue
+property int flags
+element face 1
+property list uchar int vertex_index
+element edge 1
+property int vertex1
+property int vertex2
+end_header
+0 0 0 1 0 0 7
+1 0 0 0 1 0 7
+1 1 0 0 0 1 7
+0 1 0 1 1 1 7
+4 0 1 2 3
+0 1
+";
+
+    let mesh = ply::parse(source.as_bytes()).unwrap();
+    assert_eq!(4, mesh.vertex_count());
+    assert_eq!(vec![0, 1, 2, 0, 2, 3], mesh.indices().to_vec());
+    assert_slice_near(&[0.0, 1.0, 0.0, 1.0], mesh.attribute_value(mesh.color().unwrap(), 1));
+}
+
+#[test]
+fn ply_errors() {
+    match ply::parse(b"ply\nformat ascii 2.0\nend_header\n") {
+        Err(ply::Error::BadHeader { line: 2 }) => {}
+        result => panic!("Expected BadHeader on line 2, got {:?}", result),
+    }
+
+    let source = "ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nproperty float y\nend_header\n0 0\n";
+    match ply::parse(source.as_bytes()) {
+        Err(ply::Error::MissingPositions) => {}
+        result => panic!("Expected MissingPositions, got {:?}", result),
+    }
+
+    let source = "ply\nformat ascii 1.0\nelement vertex 2\nproperty float x\nproperty float y\nproperty float z\nend_header\n0 0 0\n0 zero 0\n";
+    match ply::parse(source.as_bytes()) {
+        Err(ply::Error::BadValue { line: 9 }) => {}
+        result => panic!("Expected BadValue on line 9, got {:?}", result),
+    }
+
+    let source = "ply\nformat binary_little_endian 1.0\nelement vertex 1\nproperty float x\nproperty float y\nproperty float z\nend_header\n\0\0\0\0";
+    match ply::parse(source.as_bytes()) {
+        Err(ply::Error::UnexpectedEof) => {}
+        result => panic!("Expected UnexpectedEof, got {:?}", result),
+    }
+
+    // Element and list counts far larger than the body are rejected rather than allocated.
+    let sources: [&[u8]; 4] = [
+        b"ply\nformat ascii 1.0\nelement vertex 4000000000000\nproperty float x\nproperty float y\nproperty float z\nend_header\n0 0 0\n",
+        b"ply\nformat binary_little_endian 1.0\nelement vertex 18446744073709551615\nproperty float x\nproperty float y\nproperty float z\nend_header\n\0\0\0\0",
+        b"ply\nformat ascii 1.0\nelement face 1\nproperty list uint int vertex_indices\nend_header\n4000000000 0 1 2\n",
+        b"ply\nformat binary_big_endian 1.0\nelement face 1\nproperty list uint int vertex_indices\nend_header\n\xff\xff\xff\xff",
+    ];
+    for source in &sources {
+        match ply::parse(source) {
+            Err(ply::Error::UnexpectedEof) => {}
+            result => panic!("Expected UnexpectedEof, got {:?}", result),
+        }
+    }
+}
+
+/// Gets the signed area of each triangle in a mesh, as seen looking down the z axis.
+fn triangle_areas(mesh: &Mesh) -> Vec<f32> {
+    triangle_positions(mesh)
+        .chunks(3)
+        .map(|corners| {
+            let (a, b, c) = (corners[0], corners[1], corners[2]);
+            ((b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1])) / 2.0
+        })
+        .collect()
+}
+
+fn assert_obj_error(source: &str, line: usize, kind: obj::ParseErrorKind) {
+    match obj::parse(source) {
+        Err(obj::Error::ParseError(error)) => assert_eq!(obj::ParseError { line: line, kind: kind }, error),
+        result => panic!("Expected {:?} on line {}, got {:?}", kind, line, result),
+    }
+}
+
+fn assert_mtl_error(source: &str, line: usize, kind: obj::ParseErrorKind) {
+    match obj::parse_mtl(source) {
+        Err(obj::Error::ParseError(error)) => assert_eq!(obj::ParseError { line: line, kind: kind }, error),
+        result => panic!("Expected {:?} on line {}, got {:?}", kind, line, result),
+    }
+}
+
+#[test]
+fn obj_objects_and_materials() {
+    let source = "\
+# Three objects sharing the same vertices.
+mtllib scene.mtl
+v 0 0 0 1 0 0
+v 1 0 0 0 1 0
+v 1 1 0 0 0 1
+v 0 1 0 1 1 1
+vt 0 0
+vt 1 0
+vt 1 1
+vn 0 0 1
+
+o first
+usemtl red
+f 1/1/1 2/2/1 \\
+  3/3/1
+g second group
+f 1//1 3//1 4//1
+usemtl blue
+f 1 2 4 # No normals.
+";
+
+    let obj = obj::parse(source).unwrap();
+    assert_eq!(vec!["scene.mtl".to_string()], obj.material_libraries);
+    assert!(obj.materials.is_empty());
+
+    let objects = obj.objects
+        .iter()
+        .map(|object| (&*object.name, object.material.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![("first", Some("red")), ("second group", Some("red")), ("second group", Some("blue"))],
+        objects,
+    );
+
+    // The face continued onto a second line has all of its vertices.
+    let first = &obj.objects[0].mesh;
+    assert_eq!(3, first.vertex_count());
+    assert_eq!(vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]], triangle_positions(first));
+    assert_slice_near(&[1.0, 0.0], first.attribute_value(first.texcoord()[0], 1));
+    assert_slice_near(&[0.0, 0.0, 1.0], first.attribute_value(first.normal().unwrap(), 1));
+    assert_slice_near(&[0.0, 1.0, 0.0, 1.0], first.attribute_value(first.color().unwrap(), 1));
+
+    // Data is only kept if every face vertex has it, and missing normals are generated.
+    let second = &obj.objects[1].mesh;
+    assert!(second.texcoord().is_empty());
+    assert_slice_near(&[0.0, 0.0, 1.0], second.attribute_value(second.normal().unwrap(), 0));
+
+    let third = &obj.objects[2].mesh;
+    assert_eq!(vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]], triangle_positions(third));
+    assert_slice_near(&[0.0, 0.0, 1.0], third.attribute_value(third.normal().unwrap(), 0));
+}
+
+#[test]
+fn obj_polygons() {
+    // A concave quad and a concave pentagon, where a fan from the first corner would give a
+    // triangle that's wound the wrong way. The pentagon uses negative indices.
+    let source = "\
+v 0 0 0
+v 2 1 0
+v 4 0 0
+v 2 3 0
+o quad
+f 1 2 3 4
+
+v 0 0 0
+v 2 0 0
+v 2 2 0
+v 1 0.5 0
+v 0 2 0
+o pentagon
+f -5 -4 -3 -2 -1
+";
+
+    let obj = obj::parse(source).unwrap();
+    assert_eq!(2, obj.objects.len());
+
+    for &(object, triangles, area) in &[(0, 2, 4.0), (1, 3, 2.5)] {
+        let areas = triangle_areas(&obj.objects[object].mesh);
+        assert_eq!(triangles, areas.len());
+        assert!(areas.iter().all(|&area| area > 0.0), "{} has a flipped triangle: {:?}", obj.objects[object].name, areas);
+        assert!((area - areas.iter().sum::<f32>()).abs() < 1e-5);
+    }
+
+    // The pentagon's negative indices refer to its own vertices.
+    let pentagon = &obj.objects[1].mesh;
+    assert_eq!(5, pentagon.vertex_count());
+    assert!(triangle_positions(pentagon).contains(&[1.0, 0.5, 0.0]));
+}
+
+#[test]
+fn obj_errors() {
+    use polygon::geometry::obj::ParseErrorKind::*;
+
+    assert_obj_error("v 0 0 0\nv 1 2\n", 2, BadArgumentCount);
+    assert_obj_error("v 0 0 0\nv 0 0 0\nf 1 2\n", 3, BadArgumentCount);
+    assert_obj_error("usemtl\n", 1, BadArgumentCount);
+    assert_obj_error("v 0 0 0\nvn 0 zero 1\n", 2, BadNumber);
+    assert_obj_error("v 0 0 0\nf 1/1/1/1 1 1\n", 2, BadFaceVertex);
+    assert_obj_error("v 0 0 0\nf /1 1 1\n", 2, BadFaceVertex);
+    assert_obj_error("v 0 0 0\nf one 1 1\n", 2, BadFaceVertex);
+    assert_obj_error("v 0 0 0\nf 1 1 0\n", 2, IndexOutOfBounds);
+    assert_obj_error("v 0 0 0\nf 1 1 -2\n", 2, IndexOutOfBounds);
+    assert_obj_error("v 0 0 0\nf 1/1 1/1 1/1\n", 2, IndexOutOfBounds);
+
+    // Errors in continued statements are reported on the statement's first line.
+    assert_obj_error("v 0 0 0\n\nf 1 \\\n 1 \\\n 5\n", 3, IndexOutOfBounds);
+
+    assert_mtl_error("# No material yet\nKd 1 0 0\n", 2, NoMaterial);
+    assert_mtl_error("newmtl red\nKd 1 0\n", 2, BadArgumentCount);
+    assert_mtl_error("newmtl red\nNs 1 2\n", 2, BadArgumentCount);
+    assert_mtl_error("newmtl red\nNs shiny\n", 2, BadNumber);
+    assert_mtl_error("newmtl\n", 1, BadArgumentCount);
+}
+
+#[test]
+fn obj_mtl() {
+    let source = "\
+# Unknown statements before the first material are ignored.
+illum 2
+
+newmtl red brick
+Kd 1 0 0
+Ks 0.5
+Ns 32
+map_Kd textures/red \\
+    brick.png
+d 1
+
+newmtl blank
+";
+
+    let materials = obj::parse_mtl(source).unwrap();
+    assert_eq!(
+        vec![
+            obj::ObjMaterial {
+                name: "red brick".into(),
+                diffuse_color: Some(Color::rgb(1.0, 0.0, 0.0)),
+                specular_color: Some(Color::rgb(0.5, 0.5, 0.5)),
+                specular_exponent: Some(32.0),
+                diffuse_texture: Some("textures/red brick.png".into()),
+            },
+            obj::ObjMaterial::new("blank"),
+        ],
+        materials,
+    );
+}
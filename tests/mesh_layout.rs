@@ -0,0 +1,109 @@
+extern crate polygon;
+
+mod common;
+
+use polygon::geometry::mesh::*;
+use polygon::math::*;
+
+use common::*;
+
+/// Gets each attribute of the mesh in the order the builder lays them out.
+fn attributes(mesh: &Mesh) -> Vec<VertexAttribute> {
+    let mut attributes = vec![mesh.position(), mesh.normal().unwrap(), mesh.color().unwrap()];
+    attributes.extend(mesh.texcoord());
+    attributes.push(mesh.custom_attribute("weight").unwrap());
+    attributes
+}
+
+/// A mesh with `vertex_count` vertices, whose only triangle uses the first and last vertex.
+fn mesh_with_vertices(vertex_count: usize) -> Mesh {
+    let positions = (0..vertex_count)
+        .map(|vertex| Point::new(vertex as f32, 0.0, 0.0))
+        .collect::<Vec<_>>();
+    let last = vertex_count as u32 - 1;
+
+    MeshBuilder::new()
+        .set_position_data(&positions)
+        .set_indices(&[0, last, last])
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn interleaved_layout() {
+    let mesh = test_mesh(VertexLayout::Interleaved);
+    assert_eq!(VertexLayout::Interleaved, mesh.layout());
+
+    // Position (4), normal (3), color (4), two sets of texcoords (2 each), then the weight (1).
+    let expected = [(4, 0), (3, 4), (4, 7), (2, 11), (2, 13), (1, 15)];
+    let actual = attributes(&mesh)
+        .iter()
+        .map(|attribute| {
+            assert_eq!(16, attribute.stride);
+            (attribute.elements, attribute.offset)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(&expected[..], &*actual);
+    assert_eq!(16 * 4, mesh.vertex_data().len());
+
+    // Each vertex's data is contiguous, in the same order as the attributes.
+    let second_vertex = &mesh.vertex_data()[16..32];
+    assert_eq!(
+        &[
+            100.0, 101.0, 102.0, 1.0,
+            110.0, 111.0, 112.0,
+            120.0, 121.0, 122.0, 123.0,
+            130.0, 131.0,
+            140.0, 141.0,
+            150.0,
+        ],
+        second_vertex,
+    );
+}
+
+#[test]
+fn planar_layout() {
+    let mesh = test_mesh(VertexLayout::Planar);
+    assert_eq!(VertexLayout::Planar, mesh.layout());
+
+    // Each attribute's block follows the previous one.
+    let expected = [(4, 0), (3, 16), (4, 28), (2, 44), (2, 52), (1, 60)];
+    let actual = attributes(&mesh)
+        .iter()
+        .map(|attribute| {
+            assert_eq!(0, attribute.stride);
+            (attribute.elements, attribute.offset)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(&expected[..], &*actual);
+    assert_eq!(16 * 4, mesh.vertex_data().len());
+}
+
+#[test]
+fn layouts_have_the_same_values() {
+    let planar = test_mesh(VertexLayout::Planar);
+    let interleaved = test_mesh(VertexLayout::Interleaved);
+
+    for (&planar_attribute, &interleaved_attribute) in attributes(&planar).iter().zip(&attributes(&interleaved)) {
+        for vertex in 0..4 {
+            assert_eq!(
+                planar.attribute_value(planar_attribute, vertex),
+                interleaved.attribute_value(interleaved_attribute, vertex),
+            );
+        }
+    }
+}
+
+#[test]
+fn index_width() {
+    let small = mesh_with_vertices(3);
+    assert_eq!(&MeshIndices::U16(vec![0, 2, 2]), small.indices());
+
+    // The largest mesh that can use 16 bit indices without using the primitive restart index.
+    let largest_u16 = mesh_with_vertices(65535);
+    assert_eq!(&MeshIndices::U16(vec![0, 65534, 65534]), largest_u16.indices());
+
+    let smallest_u32 = mesh_with_vertices(65536);
+    assert_eq!(&MeshIndices::U32(vec![0, 65535, 65535]), smallest_u32.indices());
+    assert_eq!(vec![0, 65535, 65535], smallest_u32.indices().to_vec());
+}
@@ -0,0 +1,202 @@
+extern crate polygon;
+
+mod common;
+
+use polygon::geometry::mesh::*;
+use polygon::geometry::primitives;
+use polygon::math::*;
+
+use common::*;
+
+/// Gets a builder with only the positions and indices of `mesh`.
+fn positions_only(mesh: &Mesh) -> MeshBuilder {
+    let positions = (0..mesh.vertex_count() as u32).map(|vertex| point(mesh, vertex)).collect::<Vec<_>>();
+    MeshBuilder::new()
+        .set_position_data(&positions)
+        .set_indices(&mesh.indices().to_vec())
+}
+
+#[test]
+fn smooth_normals_keep_hard_edges() {
+    // The faces of a cube meet at right angles, so they stay separate below that threshold.
+    let cube = positions_only(&primitives::cube(1.0))
+        .compute_smooth_normals(PI / 3.0)
+        .build()
+        .unwrap();
+
+    assert_eq!(24, cube.vertex_count());
+    for vertex in 0..cube.vertex_count() as u32 {
+        let normal = normal(&cube, vertex);
+        let axis_components = [normal.x, normal.y, normal.z]
+            .iter()
+            .filter(|component| (component.abs() - 1.0).abs() < 1e-5)
+            .count();
+        assert_eq!(1, axis_components, "Normal {:?} isn't axis aligned", normal);
+    }
+
+    // Smoothing across every edge averages the three faces at each corner.
+    let rounded = positions_only(&primitives::cube(1.0))
+        .compute_smooth_normals(PI)
+        .build()
+        .unwrap();
+
+    for vertex in 0..rounded.vertex_count() as u32 {
+        let normal = normal(&rounded, vertex);
+        let diagonal = (point(&rounded, vertex) - Point::origin()).normalized();
+        assert!(Vector3::dot(normal, diagonal) > 0.9999, "Normal {:?} isn't along {:?}", normal, diagonal);
+    }
+}
+
+#[test]
+fn smooth_normals_on_sphere() {
+    let sphere = primitives::icosphere(1.0, 2);
+    let smoothed = positions_only(&sphere)
+        .compute_smooth_normals(PI / 3.0)
+        .build()
+        .unwrap();
+
+    // No vertices are split, and each normal points away from the center. Vertices that no
+    // triangle uses are dropped.
+    let mut used = sphere.indices().to_vec();
+    used.sort();
+    used.dedup();
+    assert_eq!(used.len(), smoothed.vertex_count());
+    for vertex in 0..smoothed.vertex_count() as u32 {
+        let outward = point(&smoothed, vertex) - Point::origin();
+        assert!(Vector3::dot(normal(&smoothed, vertex), outward) > 0.99);
+    }
+}
+
+#[test]
+fn flat_normals() {
+    let icosahedron = positions_only(&primitives::icosphere(1.0, 0))
+        .compute_flat_normals()
+        .build()
+        .unwrap();
+
+    // No two faces of an icosahedron are parallel, so no vertices are shared.
+    assert_eq!(60, icosahedron.vertex_count());
+
+    let indices = icosahedron.indices().to_vec();
+    for triangle in indices.chunks(3) {
+        let (a, b, c) = (point(&icosahedron, triangle[0]), point(&icosahedron, triangle[1]), point(&icosahedron, triangle[2]));
+        let face_normal = Vector3::cross(b - a, c - a).normalized();
+        for &vertex in triangle {
+            assert!(Vector3::dot(normal(&icosahedron, vertex), face_normal) > 0.9999);
+        }
+    }
+}
+
+#[test]
+fn tangents() {
+    // A strip of two quads facing up, with the texture mirrored on the right quad so that `u`
+    // increases towards x = 0 from both sides. The vertices along x = 0 are shared.
+    let mut positions = Vec::new();
+    let mut texcoords = Vec::new();
+    for &x in &[-1.0, 0.0, 1.0] {
+        for &z in &[0.0, 1.0] {
+            positions.push(Point::new(x, 0.0, z));
+            texcoords.push(Vector2::new(1.0 - f32::abs(x), z));
+        }
+    }
+
+    let mut indices = Vec::new();
+    for column in 0..2 {
+        let (a, b, c, d) = (column * 2, column * 2 + 1, column * 2 + 3, column * 2 + 2);
+        indices.extend_from_slice(&[a, b, c, a, c, d]);
+    }
+
+    let mesh = MeshBuilder::new()
+        .set_position_data(&positions)
+        .set_normal_data(&[Vector3::UP; 6])
+        .set_texcoord_data(&texcoords)
+        .set_indices(&indices)
+        .compute_tangents()
+        .build()
+        .unwrap();
+
+    assert_eq!(4, mesh.tangent().unwrap().elements);
+
+    // The shared vertices are split so each side gets its own handedness.
+    assert_eq!(8, mesh.vertex_count());
+
+    let indices = mesh.indices().to_vec();
+    for triangle in indices.chunks(3) {
+        let mirrored = triangle.iter().any(|&vertex| point(&mesh, vertex).x > 0.5);
+        for &vertex in triangle {
+            let normal = normal(&mesh, vertex);
+            let (tangent, handedness) = tangent(&mesh, vertex);
+
+            assert!((tangent.magnitude() - 1.0).abs() < 1e-5);
+            assert!(Vector3::dot(tangent, normal).abs() < 1e-5);
+
+            // Tangents follow increasing `u`, and the bitangent follows increasing `v` (+z) on
+            // both sides of the mirror.
+            let expected_tangent = if mirrored { Vector3::new(-1.0, 0.0, 0.0) } else { Vector3::new(1.0, 0.0, 0.0) };
+            assert!(Vector3::dot(tangent, expected_tangent) > 0.9999, "Tangent {:?} for vertex {}", tangent, vertex);
+
+            let bitangent = Vector3::cross(normal, tangent) * handedness;
+            assert!(bitangent.z > 0.9999, "Bitangent {:?} for vertex {}", bitangent, vertex);
+        }
+    }
+}
+
+#[test]
+fn invalid_indices_fail_the_build() {
+    let positions = [Point::new(0.0, 0.0, 0.0), Point::new(1.0, 0.0, 0.0), Point::new(0.0, 1.0, 0.0)];
+    let texcoords = [Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0), Vector2::new(0.0, 1.0)];
+    let builder = MeshBuilder::new()
+        .set_position_data(&positions)
+        .set_texcoord_data(&texcoords);
+
+    // The normals and tangents aren't generated until the indices have been checked.
+    let out_of_bounds = builder.clone()
+        .set_indices(&[0, 1, 3])
+        .compute_smooth_normals(PI)
+        .compute_tangents()
+        .build();
+    match out_of_bounds {
+        Err(BuildMeshError::IndexOutOfBounds { vertex_count: 3, index: 3 }) => {}
+        result => panic!("Expected IndexOutOfBounds, got {:?}", result),
+    }
+
+    let incomplete = builder.clone()
+        .set_indices(&[0, 1, 2, 0])
+        .compute_flat_normals()
+        .build();
+    match incomplete {
+        Err(BuildMeshError::IncompleteTriangle { index_count: 4 }) => {}
+        result => panic!("Expected IncompleteTriangle, got {:?}", result),
+    }
+
+    let without_normals = builder.clone()
+        .set_indices(&[0, 1, 2])
+        .compute_tangents()
+        .build();
+    match without_normals {
+        Err(BuildMeshError::MissingAttribute { attribute: VertexAttributeType::Normal }) => {}
+        result => panic!("Expected MissingAttribute, got {:?}", result),
+    }
+
+    let without_texcoords = MeshBuilder::new()
+        .set_position_data(&positions)
+        .set_indices(&[0, 1, 2])
+        .compute_tangents()
+        .compute_flat_normals()
+        .build();
+    match without_texcoords {
+        Err(BuildMeshError::MissingAttribute { attribute: VertexAttributeType::Texcoord }) => {}
+        result => panic!("Expected MissingAttribute, got {:?}", result),
+    }
+
+    // Tangents are generated from the requested normals, regardless of the order they're asked
+    // for in.
+    let mesh = builder
+        .set_indices(&[0, 1, 2])
+        .compute_tangents()
+        .compute_flat_normals()
+        .build()
+        .unwrap();
+    assert_eq!(Vector3::new(0.0, 0.0, 1.0), normal(&mesh, 0));
+    assert_eq!((Vector3::new(1.0, 0.0, 0.0), 1.0), tangent(&mesh, 0));
+}
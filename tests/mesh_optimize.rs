@@ -0,0 +1,169 @@
+extern crate polygon;
+
+use polygon::geometry::mesh::*;
+use polygon::geometry::optimize::{self, CACHE_SIZE};
+use polygon::geometry::primitives;
+use polygon::math::*;
+
+/// Gets the corner positions of each triangle, rotated to start with the smallest corner and
+/// sorted, so that meshes with the same triangles in any order compare equal.
+fn triangle_set(mesh: &Mesh) -> Vec<[[u32; 3]; 3]> {
+    let indices = mesh.indices().to_vec();
+    let mut triangles = indices
+        .chunks(3)
+        .map(|triangle| {
+            let mut corners = [[0; 3]; 3];
+            for (corner, &index) in corners.iter_mut().zip(triangle) {
+                let value = mesh.attribute_value(mesh.position(), index as usize);
+                *corner = [value[0].to_bits(), value[1].to_bits(), value[2].to_bits()];
+            }
+            let first = (0..3).min_by_key(|&corner| corners[corner]).unwrap();
+            [corners[first], corners[(first + 1) % 3], corners[(first + 2) % 3]]
+        })
+        .collect::<Vec<_>>();
+    triangles.sort();
+    triangles
+}
+
+/// Splits every triangle of the mesh into its own vertices, like an STL file, and shuffles the
+/// triangles so that the vertex cache is used poorly.
+fn unwelded(mesh: &Mesh, offset: f32) -> Mesh {
+    let mut indices = mesh.indices().to_vec();
+
+    // Shuffle the triangles with a simple LCG so that the test is deterministic.
+    let triangle_count = indices.len() / 3;
+    let mut state = 12345u32;
+    for triangle in (1..triangle_count).rev() {
+        state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        let other = (state >> 8) as usize % (triangle + 1);
+        for corner in 0..3 {
+            indices.swap(triangle * 3 + corner, other * 3 + corner);
+        }
+    }
+
+    let positions = indices
+        .iter()
+        .enumerate()
+        .map(|(corner, &index)| {
+            let value = mesh.attribute_value(mesh.position(), index as usize);
+
+            // Nudge alternating corners so that welding needs a tolerance.
+            let nudge = if corner % 2 == 0 { offset } else { 0.0 };
+            Point::new(value[0] + nudge, value[1], value[2])
+        })
+        .collect::<Vec<_>>();
+
+    MeshBuilder::new()
+        .set_position_data(&positions)
+        .set_indices(&(0..indices.len() as u32).collect::<Vec<_>>())
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn weld_exact() {
+    let grid = primitives::grid(4.0, 4.0, 8, 8);
+    let welded = optimize::weld_vertices(&unwelded(&grid, 0.0), 0.0);
+
+    assert_eq!(81, welded.vertex_count());
+    assert_eq!(triangle_set(&grid), triangle_set(&welded));
+}
+
+#[test]
+fn weld_within_tolerance() {
+    let grid = primitives::grid(4.0, 4.0, 8, 8);
+    let mesh = unwelded(&grid, 1e-5);
+
+    assert_eq!(81, optimize::weld_vertices(&mesh, 1e-4).vertex_count());
+    assert!(optimize::weld_vertices(&mesh, 1e-6).vertex_count() > 81);
+}
+
+#[test]
+fn weld_keeps_attribute_seams() {
+    // The two vertices share a position but have different normals, so they must stay separate.
+    let mesh = MeshBuilder::new()
+        .set_position_data(&[Point::origin(), Point::origin(), Point::new(1.0, 0.0, 0.0)])
+        .set_normal_data(&[Vector3::UP, Vector3::RIGHT, Vector3::UP])
+        .set_indices(&[0, 1, 2])
+        .build()
+        .unwrap();
+
+    assert_eq!(3, optimize::weld_vertices(&mesh, 0.01).vertex_count());
+}
+
+#[test]
+fn remove_degenerate_triangles() {
+    let mesh = MeshBuilder::new()
+        .set_position_data(&[
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(0.0, 1.0, 0.0),
+            Point::new(2.0, 0.0, 0.0),
+        ])
+        .set_indices(&[
+            0, 1, 2,
+            0, 0, 1, // Repeated vertex.
+            1, 2, 0, // Rotation of the first triangle.
+            2, 1, 0, // Opposite winding, kept.
+            0, 1, 3, // Collinear.
+        ])
+        .build()
+        .unwrap();
+
+    let cleaned = optimize::remove_degenerate_triangles(&mesh);
+    assert_eq!(vec![0, 1, 2, 2, 1, 0], cleaned.indices().to_vec());
+    assert_eq!(4, cleaned.vertex_count());
+}
+
+#[test]
+fn vertex_cache_lowers_acmr() {
+    let grid = primitives::grid(4.0, 4.0, 32, 32);
+    let mesh = optimize::weld_vertices(&unwelded(&grid, 0.0), 0.0);
+    let before = optimize::acmr(&mesh, CACHE_SIZE);
+
+    let ordered = optimize::optimize_vertex_cache(&mesh);
+    let after = optimize::acmr(&ordered, CACHE_SIZE);
+
+    assert!(after < before, "ACMR went from {} to {}", before, after);
+    assert!(after < 0.8, "ACMR after optimizing should be near 0.5, but is {}", after);
+    assert_eq!(triangle_set(&mesh), triangle_set(&ordered));
+}
+
+#[test]
+fn acmr_bounds() {
+    // Every vertex is a miss when there's no reuse.
+    let mesh = unwelded(&primitives::cube(1.0), 0.0);
+    assert_eq!(3.0, optimize::acmr(&mesh, CACHE_SIZE));
+
+    let empty = MeshBuilder::new().build().unwrap();
+    assert_eq!(0.0, optimize::acmr(&empty, CACHE_SIZE));
+}
+
+#[test]
+fn vertex_fetch_order() {
+    let mesh = optimize::optimize_vertex_fetch(&primitives::uv_sphere(1.0, 16, 8));
+
+    // Each index is at most one past the largest index used so far.
+    let mut next = 0;
+    for index in mesh.indices().iter() {
+        assert!(index <= next);
+        if index == next {
+            next += 1;
+        }
+    }
+    assert_eq!(next as usize, mesh.vertex_count());
+}
+
+#[test]
+fn optimize_pipeline() {
+    let sphere = primitives::icosphere(1.0, 3);
+    let mesh = unwelded(&sphere, 0.0);
+
+    let (optimized, report) = optimize::optimize(&mesh, 0.0);
+    assert_eq!(mesh.vertex_count(), report.vertices_before);
+    assert_eq!(optimized.vertex_count(), report.vertices_after);
+    assert!(report.vertices_after < report.vertices_before);
+    assert_eq!(report.triangles_before, report.triangles_after);
+    assert!(report.acmr_after < report.acmr_before);
+    assert_eq!(triangle_set(&mesh), triangle_set(&optimized));
+}
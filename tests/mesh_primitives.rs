@@ -0,0 +1,128 @@
+extern crate polygon;
+
+mod common;
+
+use polygon::geometry::mesh::*;
+use polygon::geometry::primitives;
+use polygon::math::*;
+
+use common::*;
+
+/// Every primitive, along with its name for assertion messages.
+fn all_primitives() -> Vec<(&'static str, Mesh)> {
+    vec![
+        ("cube", primitives::cube(2.0)),
+        ("quad", primitives::quad(2.0, 1.0)),
+        ("plane", primitives::plane(2.0, 1.0)),
+        ("grid", primitives::grid(2.0, 1.0, 4, 3)),
+        ("uv_sphere", primitives::uv_sphere(1.5, 12, 8)),
+        ("icosphere", primitives::icosphere(1.5, 2)),
+        ("cylinder", primitives::cylinder(1.0, 2.0, 12)),
+        ("cone", primitives::cone(1.0, 2.0, 12)),
+        ("capsule", primitives::capsule(0.5, 1.0, 12, 4)),
+        ("torus", primitives::torus(2.0, 0.5, 16, 8)),
+    ]
+}
+
+#[test]
+fn counts() {
+    let cube = primitives::cube(1.0);
+    assert_eq!(24, cube.vertex_count());
+    assert_eq!(12, triangle_count(&cube));
+
+    let grid = primitives::grid(1.0, 1.0, 4, 3);
+    assert_eq!(5 * 4, grid.vertex_count());
+    assert_eq!(2 * 4 * 3, triangle_count(&grid));
+
+    for subdivisions in 0..4 {
+        let icosphere = primitives::icosphere(1.0, subdivisions);
+        assert_eq!(20 * 4usize.pow(subdivisions), triangle_count(&icosphere));
+    }
+
+    let torus = primitives::torus(2.0, 0.5, 16, 8);
+    assert_eq!(2 * 16 * 8, triangle_count(&torus));
+}
+
+#[test]
+fn normals_are_unit_length() {
+    for (name, mesh) in all_primitives() {
+        for vertex in 0..mesh.vertex_count() as u32 {
+            let normal = normal(&mesh, vertex);
+            assert!(
+                (normal.magnitude() - 1.0).abs() < 1e-4,
+                "{} has a normal of length {} at vertex {}",
+                name,
+                normal.magnitude(),
+                vertex,
+            );
+        }
+    }
+}
+
+#[test]
+fn normals_point_outward() {
+    let spheres = [
+        ("uv_sphere", primitives::uv_sphere(1.5, 12, 8)),
+        ("icosphere", primitives::icosphere(1.5, 2)),
+    ];
+    for &(name, ref mesh) in &spheres {
+        for vertex in 0..mesh.vertex_count() as u32 {
+            let outward = point(mesh, vertex) - Point::origin();
+            assert!(Vector3::dot(normal(mesh, vertex), outward) > 0.0, "{} vertex {} faces inward", name, vertex);
+        }
+    }
+
+    // The torus' normals point away from the center of the tube rather than the origin.
+    let torus = primitives::torus(2.0, 0.5, 16, 8);
+    for vertex in 0..torus.vertex_count() as u32 {
+        let position = point(&torus, vertex);
+        let ring = Vector3::new(position.x, 0.0, position.z).normalized() * 2.0;
+        let outward = position - (Point::origin() + ring);
+        assert!(Vector3::dot(normal(&torus, vertex), outward) > 0.0, "torus vertex {} faces inward", vertex);
+    }
+}
+
+#[test]
+fn winding_matches_normals() {
+    for (name, mesh) in all_primitives() {
+        let indices = mesh.indices().to_vec();
+        for triangle in indices.chunks(3) {
+            let (a, b, c) = (point(&mesh, triangle[0]), point(&mesh, triangle[1]), point(&mesh, triangle[2]));
+            let face_normal = Vector3::cross(b - a, c - a);
+
+            // Triangles collapsed onto a pole have no facing.
+            if face_normal.magnitude() < 1e-6 {
+                continue;
+            }
+
+            let vertex_normals = normal(&mesh, triangle[0]) + normal(&mesh, triangle[1]) + normal(&mesh, triangle[2]);
+            assert!(
+                Vector3::dot(face_normal, vertex_normals) > 0.0,
+                "{} triangle {:?} is wound against its normals",
+                name,
+                triangle,
+            );
+        }
+    }
+}
+
+#[test]
+fn texcoords_in_range() {
+    for (name, mesh) in all_primitives() {
+        // The icosphere's vertices don't line up with its texture seam, so triangles crossing the
+        // seam use duplicate vertices with `u` shifted past 1 and rely on the texture wrapping.
+        let max_u = if name == "icosphere" { 1.5 } else { 1.0 };
+
+        let texcoord = mesh.texcoord()[0];
+        for vertex in 0..mesh.vertex_count() {
+            let value = mesh.attribute_value(texcoord, vertex);
+            assert!(
+                value[0] >= 0.0 && value[0] <= max_u && value[1] >= 0.0 && value[1] <= 1.0,
+                "{} has texcoord {:?} at vertex {}",
+                name,
+                value,
+                vertex,
+            );
+        }
+    }
+}
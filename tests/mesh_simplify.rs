@@ -0,0 +1,149 @@
+extern crate polygon;
+
+mod common;
+
+use polygon::camera::Camera;
+use polygon::geometry::mesh::*;
+use polygon::geometry::primitives;
+use polygon::geometry::simplify;
+use polygon::math::*;
+use std::collections::{HashMap, HashSet};
+
+use common::*;
+
+type Position = [u32; 3];
+
+fn position(mesh: &Mesh, vertex: u32) -> Position {
+    let value = mesh.attribute_value(mesh.position(), vertex as usize);
+    [value[0].to_bits(), value[1].to_bits(), value[2].to_bits()]
+}
+
+/// Counts how many triangles use each edge, identifying edges by the positions of their ends.
+fn edge_counts(mesh: &Mesh) -> HashMap<(Position, Position), usize> {
+    let indices = mesh.indices().to_vec();
+    let mut counts = HashMap::new();
+    for triangle in indices.chunks(3) {
+        for corner in 0..3 {
+            let from = position(mesh, triangle[corner]);
+            let to = position(mesh, triangle[(corner + 1) % 3]);
+            *counts.entry((from.min(to), from.max(to))).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Builds a flat 8x8 grid with a texture seam down the middle, splitting it into two UV charts.
+fn seamed_grid() -> Mesh {
+    let mut positions = Vec::new();
+    let mut texcoords = Vec::new();
+    let mut indices = Vec::new();
+
+    for &(first_column, u_offset) in &[(0, 0.0), (4, 1.0)] {
+        let base = positions.len() as u32;
+        for row in 0..9 {
+            for column in first_column..first_column + 5 {
+                positions.push(Point::new(column as f32 - 4.0, 0.0, row as f32 - 4.0));
+                texcoords.push(Vector2::new(column as f32 / 8.0 + u_offset, row as f32 / 8.0));
+            }
+        }
+
+        for row in 0..8 {
+            for column in 0..4 {
+                let vertex = base + row * 5 + column;
+                indices.extend_from_slice(&[vertex, vertex + 5, vertex + 1]);
+                indices.extend_from_slice(&[vertex + 1, vertex + 5, vertex + 6]);
+            }
+        }
+    }
+
+    MeshBuilder::new()
+        .set_position_data(&positions)
+        .set_normal_data(&vec![Vector3::UP; positions.len()])
+        .set_texcoord_data(&texcoords)
+        .set_indices(&indices)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn closed_mesh_stays_closed() {
+    let sphere = primitives::icosphere(1.0, 3);
+    let original = (0..sphere.vertex_count() as u32)
+        .map(|vertex| position(&sphere, vertex))
+        .collect::<HashSet<_>>();
+
+    let simplified = simplify::simplify(&sphere, 100);
+    assert!(triangle_count(&simplified) <= 100);
+    assert!(edge_counts(&simplified).values().all(|&count| count == 2));
+
+    // Simplifying only moves vertices onto other vertices.
+    for vertex in 0..simplified.vertex_count() as u32 {
+        assert!(original.contains(&position(&simplified, vertex)));
+    }
+    assert!(area(&triangles(&simplified)) > area(&triangles(&sphere)) * 0.9);
+}
+
+#[test]
+fn borders_keep_their_outline() {
+    let grid = primitives::grid(4.0, 4.0, 8, 8);
+    let simplified = simplify::simplify(&grid, 0);
+
+    assert!(triangle_count(&simplified) < triangle_count(&grid));
+    assert_eq!(grid.bounding_box(), simplified.bounding_box());
+    assert!((area(&triangles(&simplified)) - 16.0).abs() < 1e-4);
+}
+
+#[test]
+fn seams_dont_tear() {
+    let grid = seamed_grid();
+    let simplified = simplify::simplify(&grid, 0);
+    assert!(triangle_count(&simplified) < triangle_count(&grid));
+    assert_eq!(grid.bounding_box(), simplified.bounding_box());
+
+    // Edges along the seam are used once by each chart, every other inner edge is shared within
+    // a chart.
+    let open_edges = edge_counts(&simplified)
+        .into_iter()
+        .filter(|&(_, count)| count == 1)
+        .map(|(edge, _)| edge)
+        .collect::<Vec<_>>();
+    let bounds = grid.bounding_box();
+    for (from, to) in open_edges {
+        let on_outline = |position: Position| {
+            let (x, z) = (f32::from_bits(position[0]), f32::from_bits(position[2]));
+            x == bounds.min.x || x == bounds.max.x || z == bounds.min.z || z == bounds.max.z
+        };
+        assert!(
+            on_outline(from) && on_outline(to),
+            "Seam tore open between {:?} and {:?}",
+            from,
+            to,
+        );
+    }
+}
+
+#[test]
+fn lod_chain() {
+    let sphere = primitives::icosphere(1.0, 3);
+    let lods = simplify::lod_chain(&sphere, &[0.5, 0.25, 0.1]);
+
+    assert_eq!(3, lods.len());
+    let mut previous = triangle_count(&sphere);
+    for (lod, &ratio) in lods.iter().zip(&[0.5, 0.25, 0.1]) {
+        let triangles = triangle_count(lod);
+        assert!(triangles < previous);
+        assert!(triangles <= (triangle_count(&sphere) as f32 * ratio) as usize);
+        previous = triangles;
+    }
+}
+
+#[test]
+fn camera_screen_size() {
+    // A 90 degree field of view shows 2 units vertically at a distance of 1.
+    let camera = Camera::new(PI / 2.0, 1.0, 0.1, 100.0);
+    let sphere = Sphere::new(Point::new(0.0, 0.0, -10.0), 1.0);
+
+    assert!((camera.screen_size(&sphere, Point::origin()) - 0.1).abs() < 1e-5);
+    assert!((camera.screen_size(&sphere, Point::new(0.0, 0.0, 10.0)) - 0.05).abs() < 1e-5);
+    assert_eq!(f32::INFINITY, camera.screen_size(&sphere, Point::new(0.0, 0.0, -10.5)));
+}
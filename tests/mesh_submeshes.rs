@@ -0,0 +1,105 @@
+extern crate polygon;
+
+mod common;
+
+use polygon::geometry::mesh::*;
+use polygon::geometry::optimize;
+use polygon::geometry::primitives;
+use polygon::geometry::simplify;
+use polygon::math::*;
+
+use common::*;
+
+/// Builds a 4x4 grid centered on the origin with the triangles on each side of x = 0 in their
+/// own submesh. The submeshes share the vertices along x = 0.
+fn split_grid() -> Mesh {
+    let grid = primitives::grid(4.0, 4.0, 8, 8);
+    let indices = grid.indices().to_vec();
+
+    let mut left = Vec::<MeshIndex>::new();
+    let mut right = Vec::<MeshIndex>::new();
+    for triangle in indices.chunks(3) {
+        let center_x = triangle.iter().map(|&index| point(&grid, index).x).sum::<f32>();
+        if center_x < 0.0 {
+            left.extend(triangle);
+        } else {
+            right.extend(triangle);
+        }
+    }
+
+    let positions = (0..grid.vertex_count() as u32).map(|vertex| point(&grid, vertex)).collect::<Vec<_>>();
+    let submeshes = [Submesh::new(0, left.len()), Submesh::new(left.len(), right.len())];
+    left.extend(right);
+
+    MeshBuilder::new()
+        .set_position_data(&positions)
+        .set_indices(&left)
+        .set_submeshes(&submeshes)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn default_submesh() {
+    let cube = primitives::cube(1.0);
+    assert_eq!(&[Submesh::new(0, cube.indices().len())], cube.submeshes());
+
+    let empty = MeshBuilder::new().build().unwrap();
+    assert_eq!(&[Submesh::new(0, 0)], empty.submeshes());
+}
+
+#[test]
+fn submesh_out_of_bounds() {
+    let result = MeshBuilder::new()
+        .set_position_data(&[Point::origin(), Point::new(1.0, 0.0, 0.0), Point::new(0.0, 1.0, 0.0)])
+        .set_indices(&[0, 1, 2])
+        .set_submeshes(&[Submesh::new(0, 3), Submesh::new(3, 3)])
+        .build();
+
+    match result {
+        Err(BuildMeshError::SubmeshOutOfBounds { submesh: 1, index_count: 3 }) => {}
+        result => panic!("Expected SubmeshOutOfBounds, got {:?}", result),
+    }
+}
+
+#[test]
+fn optimize_keeps_submeshes() {
+    let mesh = split_grid();
+    let (optimized, _) = optimize::optimize(&mesh, 0.0);
+
+    // Each submesh has the same triangles, though possibly in a different order.
+    let triangle_set = |mesh: &Mesh, submesh| {
+        let mut triangles = triangles_in(mesh, mesh.submeshes()[submesh])
+            .iter()
+            .map(|triangle| {
+                let mut corners = triangle.iter().map(|point| [point.x.to_bits(), point.z.to_bits()]).collect::<Vec<_>>();
+                corners.sort();
+                corners
+            })
+            .collect::<Vec<_>>();
+        triangles.sort();
+        triangles
+    };
+
+    assert_eq!(mesh.submeshes(), optimized.submeshes());
+    for submesh in 0..2 {
+        assert_eq!(triangle_set(&mesh, submesh), triangle_set(&optimized, submesh));
+    }
+}
+
+#[test]
+fn simplify_keeps_submesh_boundaries() {
+    let mesh = split_grid();
+    let simplified = simplify::simplify(&mesh, 0);
+
+    assert_eq!(2, simplified.submeshes().len());
+    assert!(simplified.indices().len() < mesh.indices().len());
+
+    // Each half keeps its triangles on its own side of the boundary, and still covers its side.
+    let left = triangles_in(&simplified, simplified.submeshes()[0]);
+    let right = triangles_in(&simplified, simplified.submeshes()[1]);
+    assert!(left.iter().flat_map(|triangle| triangle.iter()).all(|point| point.x <= 0.0));
+    assert!(right.iter().flat_map(|triangle| triangle.iter()).all(|point| point.x >= 0.0));
+    assert!((area(&left) - 8.0).abs() < 1e-4);
+    assert!((area(&right) - 8.0).abs() < 1e-4);
+}